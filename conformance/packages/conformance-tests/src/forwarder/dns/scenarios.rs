@@ -1,10 +1,12 @@
 use std::net::Ipv4Addr;
 
 use dns_test::{
-    FQDN, Forwarder, Network, Resolver, Result,
-    client::{Client, DigSettings},
-    name_server::{Graph, NameServer, Sign},
+    FQDN, ForwardPolicy, Forwarder, Network, Resolver, Result,
+    client::{Client, DigOutput, DigSettings},
+    name_server::{Graph, NameServer, Running, Sign},
     record::{Record, RecordType},
+    tshark::Direction,
+    zone_file::Root,
 };
 
 #[test]
@@ -73,3 +75,121 @@ fn nxdomain() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn forwarder_does_not_contact_root() -> Result<()> {
+    let network = Network::new()?;
+
+    let expected_ipv4_addr = Ipv4Addr::new(1, 2, 3, 4);
+    let mut leaf_ns = NameServer::new(&dns_test::PEER, FQDN::TEST_DOMAIN, &network)?;
+    leaf_ns.add(Record::a(FQDN::EXAMPLE_SUBDOMAIN, expected_ipv4_addr));
+
+    let Graph {
+        nameservers: _nameservers,
+        root,
+        ..
+    } = Graph::build(leaf_ns, Sign::No)?;
+
+    let resolver = Resolver::new(&network, root).start_with_subject(&dns_test::PEER)?;
+    let client = Client::new(&network)?;
+    let settings = *DigSettings::default().recurse();
+
+    // warm up the resolver's cache
+    let output = client.dig(
+        settings,
+        resolver.ipv4_addr(),
+        RecordType::A,
+        &FQDN::EXAMPLE_SUBDOMAIN,
+    )?;
+    assert!(output.status.is_noerror(), "{output:#?}");
+
+    let forwarder = Forwarder::new(&network, &resolver).start()?;
+    let mut tshark = forwarder.eavesdrop()?;
+
+    let output = client.dig(
+        settings,
+        forwarder.ipv4_addr(),
+        RecordType::A,
+        &FQDN::EXAMPLE_SUBDOMAIN,
+    )?;
+
+    tshark.wait_for_capture()?;
+    let captures = tshark.terminate()?;
+
+    assert!(output.status.is_noerror(), "{output:#?}");
+    let [answer] = output.answer.try_into().unwrap();
+    assert_eq!(expected_ipv4_addr, answer.try_into_a().unwrap().ipv4_addr);
+
+    for capture in captures {
+        if let Direction::Outgoing { destination } = capture.direction {
+            if destination != client.ipv4_addr() {
+                assert_eq!(
+                    resolver.ipv4_addr(),
+                    destination,
+                    "the forwarder sent a query to a server other than its resolver: {capture:#?}"
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn forward_only_does_not_fall_back() -> Result<()> {
+    let (output, _nameservers) = broken_resolver_fixture(false)?;
+
+    assert!(output.status.is_servfail(), "{output:#?}");
+
+    Ok(())
+}
+
+#[test]
+#[ignore = "hickory does not support forward first"]
+fn forward_first_falls_back_to_recursion() -> Result<()> {
+    let (output, _nameservers) = broken_resolver_fixture(true)?;
+
+    assert!(output.status.is_noerror(), "{output:#?}");
+    let [answer] = output.answer.try_into().unwrap();
+    assert_eq!(
+        Ipv4Addr::new(1, 2, 3, 4),
+        answer.try_into_a().unwrap().ipv4_addr
+    );
+
+    Ok(())
+}
+
+/// Queries a forwarder whose resolver SERVFAILs because its root hint points at an address where
+/// no DNS server listens; `forward_first` gives the forwarder a working root hint to fall back on
+fn broken_resolver_fixture(forward_first: bool) -> Result<(DigOutput, Vec<NameServer<Running>>)> {
+    let network = Network::new()?;
+    let client = Client::new(&network)?;
+
+    let mut leaf_ns = NameServer::new(&dns_test::PEER, FQDN::TEST_DOMAIN, &network)?;
+    leaf_ns.add(Record::a(
+        FQDN::EXAMPLE_SUBDOMAIN,
+        Ipv4Addr::new(1, 2, 3, 4),
+    ));
+
+    let Graph {
+        nameservers, root, ..
+    } = Graph::build(leaf_ns, Sign::No)?;
+
+    let unreachable_root = Root::new(root.ns.clone(), client.ipv4_addr());
+    let resolver = Resolver::new(&network, unreachable_root).start_with_subject(&dns_test::PEER)?;
+    let mut forwarder = Forwarder::new(&network, &resolver);
+    if forward_first {
+        forwarder.policy(ForwardPolicy::First { root });
+    }
+    let forwarder = forwarder.start()?;
+
+    let settings = *DigSettings::default().recurse().timeout(7);
+    let output = client.dig(
+        settings,
+        forwarder.ipv4_addr(),
+        RecordType::A,
+        &FQDN::EXAMPLE_SUBDOMAIN,
+    )?;
+
+    Ok((output, nameservers))
+}
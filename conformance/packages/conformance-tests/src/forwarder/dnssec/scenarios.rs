@@ -136,3 +136,115 @@ fn nxdomain_nsec() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+#[ignore = "hickory forwarders do not pass the DO and CD bits upstream"]
+fn chain_passes_ad_through() -> Result<()> {
+    let network = Network::new()?;
+
+    let expected_ipv4_addr = Ipv4Addr::new(1, 2, 3, 4);
+    let mut leaf_ns = NameServer::new(&dns_test::PEER, FQDN::TEST_DOMAIN, &network)?;
+    leaf_ns.add(Record::a(FQDN::EXAMPLE_SUBDOMAIN, expected_ipv4_addr));
+
+    let Graph {
+        nameservers: _nameservers,
+        root,
+        trust_anchor,
+    } = Graph::build(
+        leaf_ns,
+        Sign::Yes {
+            settings: SignSettings::default(),
+        },
+    )?;
+    let trust_anchor = trust_anchor.unwrap();
+
+    // only the recursive resolver validates; the forwarders must relay its AD bit
+    let resolver = Resolver::new(&network, root)
+        .trust_anchor(&trust_anchor)
+        .start_with_subject(&dns_test::PEER)?;
+    let forwarders = Forwarder::new(&network, &resolver).start_chain(2)?;
+    let entry_point = forwarders.last().unwrap();
+    let client = Client::new(&network)?;
+
+    let settings = *DigSettings::default().recurse().dnssec();
+    let output = client.dig(
+        settings,
+        entry_point.ipv4_addr(),
+        RecordType::A,
+        &FQDN::EXAMPLE_SUBDOMAIN,
+    )?;
+
+    assert!(output.status.is_noerror(), "{:?}", output.status);
+    assert!(output.flags.authenticated_data);
+
+    let [a, rrsig] = output.answer.try_into().unwrap();
+    let a = a.try_into_a().unwrap();
+    let rrsig = rrsig.try_into_rrsig().unwrap();
+
+    assert_eq!(a.ipv4_addr, expected_ipv4_addr);
+    assert_eq!(rrsig.type_covered, RecordType::A);
+
+    Ok(())
+}
+
+#[test]
+#[ignore = "hickory forwarders do not pass the DO and CD bits upstream"]
+fn chain_passes_cd_through() -> Result<()> {
+    let network = Network::new()?;
+
+    let expected_ipv4_addr = Ipv4Addr::new(1, 2, 3, 4);
+    let mut leaf_ns = NameServer::new(&dns_test::PEER, FQDN::TEST_DOMAIN, &network)?;
+    leaf_ns.add(Record::a(FQDN::EXAMPLE_SUBDOMAIN, expected_ipv4_addr));
+
+    let Graph {
+        nameservers: _nameservers,
+        root,
+        ..
+    } = Graph::build(
+        leaf_ns,
+        Sign::Yes {
+            settings: SignSettings::default(),
+        },
+    )?;
+
+    // the key of a root zone that is never served makes every answer bogus to the resolver
+    let wrong_trust_anchor = NameServer::new(&dns_test::PEER, FQDN::ROOT, &network)?
+        .sign(SignSettings::default())?
+        .trust_anchor();
+
+    let resolver = Resolver::new(&network, root)
+        .trust_anchor(&wrong_trust_anchor)
+        .start_with_subject(&dns_test::PEER)?;
+    let forwarders = Forwarder::new(&network, &resolver).start_chain(2)?;
+    let entry_point = forwarders.last().unwrap();
+    let client = Client::new(&network)?;
+
+    let settings = *DigSettings::default().recurse();
+    let output = client.dig(
+        settings,
+        entry_point.ipv4_addr(),
+        RecordType::A,
+        &FQDN::EXAMPLE_SUBDOMAIN,
+    )?;
+
+    assert!(output.status.is_servfail(), "{:?}", output.status);
+
+    let settings = *DigSettings::default().recurse().checking_disabled();
+    let output = client.dig(
+        settings,
+        entry_point.ipv4_addr(),
+        RecordType::A,
+        &FQDN::EXAMPLE_SUBDOMAIN,
+    )?;
+
+    assert!(output.status.is_noerror(), "{:?}", output.status);
+    assert!(output.flags.checking_disabled);
+    assert!(!output.flags.authenticated_data);
+
+    let [answer] = output.answer.try_into().unwrap();
+    let a = answer.try_into_a().unwrap();
+
+    assert_eq!(a.ipv4_addr, expected_ipv4_addr);
+
+    Ok(())
+}
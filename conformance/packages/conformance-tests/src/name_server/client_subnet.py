@@ -0,0 +1,73 @@
+#!/usr/bin/env python3
+# This server answers every A query with an address that depends on the EDNS
+# CLIENT-SUBNET option (RFC7871) of the query, and echoes the option back with
+# the scope of the answer.
+import ipaddress
+import struct
+
+from dnslib import A, EDNS0, QTYPE, RR, DNSRecord, EDNSOption
+from dnslib.server import BaseResolver, DNSHandler, DNSServer
+
+ECS_OPTION_CODE = 8
+
+# (subnet, answer); the scope of an answer is the prefix length of its subnet
+SUBNETS = [
+    (ipaddress.ip_network("192.0.2.0/24"), "10.0.0.1"),
+    (ipaddress.ip_network("2001:db8::/32"), "10.0.0.2"),
+]
+DEFAULT_ANSWER = "10.0.0.3"
+
+
+def parse_ecs(request: DNSRecord):
+    for rr in request.ar:
+        if rr.rtype != QTYPE.OPT:
+            continue
+
+        for option in rr.rdata:
+            if option.code != ECS_OPTION_CODE:
+                continue
+
+            family, source, _scope = struct.unpack("!HBB", option.data[:4])
+            address = option.data[4:]
+            if family == 1:
+                address = ipaddress.IPv4Address(address.ljust(4, b"\0"))
+            else:
+                address = ipaddress.IPv6Address(address.ljust(16, b"\0"))
+            return family, source, address
+
+    return None
+
+
+class Resolver(BaseResolver):
+    def resolve(self, request: DNSRecord, _handler: DNSHandler) -> DNSRecord:
+        reply = request.reply()
+        ecs = parse_ecs(request)
+
+        answer = DEFAULT_ANSWER
+        scope = 0
+        if ecs is not None:
+            family, source, address = ecs
+            for subnet, subnet_answer in SUBNETS:
+                if address in subnet and source >= subnet.prefixlen:
+                    answer = subnet_answer
+                    scope = subnet.prefixlen
+                    break
+
+        if request.q.qtype == QTYPE.A:
+            reply.add_answer(RR(request.q.qname, QTYPE.A, rdata=A(answer), ttl=300))
+
+        opts = []
+        if ecs is not None:
+            family, source, address = ecs
+            prefix_bytes = (source + 7) // 8
+            payload = struct.pack("!HBB", family, source, scope) + address.packed[:prefix_bytes]
+            opts.append(EDNSOption(ECS_OPTION_CODE, payload))
+        reply.add_ar(EDNS0(udp_len=1232, opts=opts))
+
+        return reply
+
+
+if __name__ == "__main__":
+    resolver = Resolver()
+    server = DNSServer(resolver, address="0.0.0.0", port=53)
+    server.start()
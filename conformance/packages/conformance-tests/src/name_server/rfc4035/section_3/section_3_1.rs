@@ -1 +1,2 @@
 mod section_3_1_1;
+mod section_3_1_3;
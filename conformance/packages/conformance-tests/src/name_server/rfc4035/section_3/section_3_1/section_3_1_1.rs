@@ -59,5 +59,142 @@ fn rrsig_in_authority_section() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn dnssec_records_depend_on_do_bit() -> Result<()> {
+    let network = Network::new()?;
+
+    let ns = NameServer::new(&dns_test::SUBJECT, FQDN::ROOT, &network)?
+        .sign(SignSettings::default())?
+        .start()?;
+
+    let client = Client::new(&network)?;
+    let ns_fqdn = ns.fqdn();
+    let nonexistent = FQDN::TEST_TLD.push_label("does-not-exist");
+    let dnssec_types = [
+        RecordType::RRSIG,
+        RecordType::NSEC,
+        RecordType::NSEC3,
+        RecordType::DNSKEY,
+    ];
+
+    // positive answer
+    let ans = client.dig(
+        DigSettings::default(),
+        ns.ipv4_addr(),
+        RecordType::A,
+        ns_fqdn,
+    )?;
+    assert!(ans.status.is_noerror());
+    assert!(!ans.dnssec_ok_flag());
+    assert!(ans.contains_record_type(RecordType::A));
+    for record_type in &dnssec_types {
+        assert!(!ans.contains_record_type(record_type.clone()), "{ans:#?}");
+    }
+
+    let ans = client.dig(
+        *DigSettings::default().dnssec(),
+        ns.ipv4_addr(),
+        RecordType::A,
+        ns_fqdn,
+    )?;
+    assert!(ans.status.is_noerror());
+    assert!(ans.dnssec_ok_flag());
+    assert!(ans.contains_record_type(RecordType::A));
+    assert!(ans.contains_record_type(RecordType::RRSIG), "{ans:#?}");
+
+    // denial of existence
+    let ans = client.dig(
+        DigSettings::default(),
+        ns.ipv4_addr(),
+        RecordType::A,
+        &nonexistent,
+    )?;
+    assert!(ans.status.is_nxdomain());
+    for record_type in &dnssec_types {
+        assert!(!ans.contains_record_type(record_type.clone()), "{ans:#?}");
+    }
+
+    let ans = client.dig(
+        *DigSettings::default().dnssec(),
+        ns.ipv4_addr(),
+        RecordType::A,
+        &nonexistent,
+    )?;
+    assert!(ans.status.is_nxdomain());
+    assert!(ans.contains_record_type(RecordType::RRSIG), "{ans:#?}");
+    assert!(ans.contains_record_type(RecordType::NSEC3), "{ans:#?}");
+
+    Ok(())
+}
+
+#[test]
+#[ignore = "hickory re-signs the zone file with its own key"]
+fn rrsigs_of_all_algorithms_in_answer_section() -> Result<()> {
+    let network = Network::new()?;
+
+    let ns = NameServer::new(&dns_test::SUBJECT, FQDN::ROOT, &network)?
+        .sign_with_additional_algorithms(
+            SignSettings::ecdsap256sha256(),
+            &[SignSettings::rsasha256()],
+        )?
+        .start()?;
+
+    let client = Client::new(&network)?;
+    let ns_fqdn = ns.fqdn();
+    let ans = client.dig(
+        *DigSettings::default().dnssec(),
+        ns.ipv4_addr(),
+        RecordType::A,
+        ns_fqdn,
+    )?;
+
+    assert!(ans.status.is_noerror());
+    let mut algorithms = ans.rrsig_algorithms(ns_fqdn, RecordType::A);
+    algorithms.sort_unstable();
+    assert_eq!(vec![RSASHA256, ECDSAP256SHA256], algorithms, "{ans:#?}");
+
+    Ok(())
+}
+
+#[test]
+#[ignore = "hickory re-signs the zone file with its own key"]
+fn rrsigs_of_removed_algorithm_not_in_answer_section() -> Result<()> {
+    let network = Network::new()?;
+
+    let mut ns = NameServer::new(&dns_test::SUBJECT, FQDN::ROOT, &network)?
+        .sign_with_additional_algorithms(
+            SignSettings::ecdsap256sha256(),
+            &[SignSettings::rsasha256()],
+        )?;
+
+    // downgrade: only the signatures made with the primary algorithm remain
+    let records = &mut ns.signed_zone_file_mut().records;
+    let count = records.len();
+    records
+        .retain(|record| !matches!(record, Record::RRSIG(rrsig) if rrsig.algorithm == RSASHA256));
+    assert_ne!(count, records.len(), "no RRSIG records were removed");
+
+    let ns = ns.start()?;
+
+    let client = Client::new(&network)?;
+    let ns_fqdn = ns.fqdn();
+    let ans = client.dig(
+        *DigSettings::default().dnssec(),
+        ns.ipv4_addr(),
+        RecordType::A,
+        ns_fqdn,
+    )?;
+
+    assert!(ans.status.is_noerror());
+    let algorithms = ans.rrsig_algorithms(ns_fqdn, RecordType::A);
+    assert_eq!(vec![ECDSAP256SHA256], algorithms, "{ans:#?}");
+    assert!(!algorithms.contains(&RSASHA256));
+
+    Ok(())
+}
+
+const RSASHA256: u8 = 8;
+const ECDSAP256SHA256: u8 = 13;
+
 // TODO Additional section
 // TODO TC bit
@@ -0,0 +1,49 @@
+use dns_test::client::{Client, DigSettings};
+use dns_test::name_server::NameServer;
+use dns_test::nsec::NSECRecords;
+use dns_test::record::RecordType;
+use dns_test::zone_file::{Nsec, SignSettings};
+use dns_test::{FQDN, Network, Result};
+
+// section 3.1.3.2 Including NSEC RRs: Name Error Response
+#[test]
+#[ignore = "hickory-dns signs zones itself and always uses NSEC3"]
+fn nsec_in_name_error_response() -> Result<()> {
+    let network = Network::new()?;
+    let qname = FQDN::TEST_DOMAIN.push_label("unicorn");
+    let wildcard = FQDN::TEST_DOMAIN.push_label("*");
+
+    let ns = NameServer::new(&dns_test::SUBJECT, FQDN::TEST_DOMAIN, &network)?
+        .sign(SignSettings::default().nsec(Nsec::_1))?;
+    let zone_nsecs = NSECRecords::new(ns.signed_zone_file())?;
+    let ns = ns.start()?;
+
+    let client = Client::new(&network)?;
+    let ans = client.dig(
+        *DigSettings::default().dnssec(),
+        ns.ipv4_addr(),
+        RecordType::A,
+        &qname,
+    )?;
+
+    assert!(ans.status.is_nxdomain(), "{ans:#?}");
+    let response_nsecs = NSECRecords::from_records(
+        ans.authority
+            .into_iter()
+            .filter_map(|record| record.try_into_nsec().ok()),
+    )?;
+
+    // the NSEC RR proving that there is no exact match for the QNAME, and the one proving that
+    // there's no wildcard that could have been expanded to match it; these may be the same RR
+    for name in [&qname, &wildcard] {
+        let expected = zone_nsecs
+            .find_cover(name)?
+            .unwrap_or_else(|| panic!("no NSEC RR in the zone covers {name}"));
+        let actual = response_nsecs
+            .find_cover(name)?
+            .unwrap_or_else(|| panic!("no NSEC RR in the response covers {name}"));
+        assert_eq!(expected, actual);
+    }
+
+    Ok(())
+}
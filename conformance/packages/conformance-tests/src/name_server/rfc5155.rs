@@ -77,6 +77,10 @@ fn name_error_response() -> Result<()> {
             (wildcard_rr, "No RR in the response covers the wildcard"),
         ],
     );
+    assert_nsec3_proof_exactly(
+        &nsec3_rrs_response,
+        &[closest_encloser_rr, next_closer_name_rr, wildcard_rr],
+    );
 
     Ok(())
 }
@@ -108,6 +112,7 @@ fn no_data_response_not_ds() -> Result<()> {
         &nsec3_rrs_response,
         [(qname_rr, "No RR in the response matches QNAME")],
     );
+    assert_nsec3_proof_exactly(&nsec3_rrs_response, &[qname_rr]);
 
     Ok(())
 }
@@ -139,6 +144,7 @@ fn no_data_response_ds_match() -> Result<()> {
         &nsec3_rrs_response,
         [(qname_rr, "No RR in the response matches QNAME")],
     );
+    assert_nsec3_proof_exactly(&nsec3_rrs_response, &[qname_rr]);
 
     Ok(())
 }
@@ -189,20 +195,23 @@ fn no_data_response_ds_no_match() -> Result<()> {
             ),
         ],
     );
+    assert_nsec3_proof_exactly(
+        &nsec3_rrs_response,
+        &[closest_encloser_rr, next_closer_name_rr],
+    );
 
     Ok(())
 }
 
 // This test checks that name servers produce a wildcard no data response compliant with section 7.2.5.
 #[test]
-#[ignore]
 fn wildcard_no_data_response() -> Result<()> {
     let wildcard_fqdn = FQDN(WILDCARD_FQDN)?;
     // The queried name
     let qname = FQDN(NON_EXISTENT_FQDN)?;
 
     let (nsec3_rrs, status, nsec3_rrs_response) = query_nameserver(
-        [Record::a(wildcard_fqdn, Ipv4Addr::new(1, 2, 3, 4))],
+        [Record::a(wildcard_fqdn.clone(), Ipv4Addr::new(1, 2, 3, 4))],
         &qname,
         RecordType::MX,
     )?;
@@ -234,19 +243,10 @@ fn wildcard_no_data_response() -> Result<()> {
         .find_match(WILDCARD_HASH)
         .expect("No RR in the zonefile matches the wildcard");
 
-    find_records(
+    assert_wildcard_nodata_proof(&nsec3_rrs_response, &qname, &wildcard_fqdn);
+    assert_nsec3_proof_exactly(
         &nsec3_rrs_response,
-        [
-            (
-                closest_encloser_rr,
-                "No RR in the response matches the closest encloser",
-            ),
-            (
-                next_closer_name_rr,
-                "No RR in the response covers the next closer name",
-            ),
-            (wildcard_rr, "No RR in the response covers the wildcard"),
-        ],
+        &[closest_encloser_rr, next_closer_name_rr, wildcard_rr],
     );
 
     Ok(())
@@ -287,6 +287,84 @@ fn wildcard_answer_response() -> Result<()> {
             "No RR in the response covers the next closer name",
         )],
     );
+    assert_nsec3_proof_exactly(&nsec3_rrs_response, &[next_closer_name_rr]);
+
+    Ok(())
+}
+
+// This test checks that a CNAME synthesized from a wildcard comes with the same proof as other
+// wildcard answers (section 7.2.6): the NSEC3 RR that covers the next closer name.
+#[test]
+fn wildcard_cname_answer_response() -> Result<()> {
+    let wildcard_fqdn = FQDN(WILDCARD_FQDN)?;
+    let target_fqdn = FQDN(TLD_FQDN)?.push_label("target");
+    // The queried name
+    let qname = FQDN(NON_EXISTENT_FQDN)?;
+
+    let network = Network::new()?;
+    let mut ns = NameServer::new(&dns_test::SUBJECT, FQDN::ROOT, &network)?;
+    ns.add(Record::cname(wildcard_fqdn, target_fqdn.clone()))
+        .add(Record::a(target_fqdn.clone(), Ipv4Addr::new(1, 2, 3, 4)));
+    let ns = ns.sign(SignSettings::default())?;
+    let nsec3_rrs = NSEC3Records::new(ns.signed_zone_file());
+    let ns = ns.start()?;
+
+    let client = Client::new(&network)?;
+    let output = client.dig(
+        *DigSettings::default().dnssec().authentic_data(),
+        ns.ipv4_addr(),
+        RecordType::A,
+        &qname,
+    )?;
+
+    assert!(output.status.is_noerror(), "{output:#?}");
+
+    // the CNAME is synthesized at QNAME and its RRSIG reveals the wildcard it came from: the
+    // wildcard owner has one label fewer than QNAME, not counting the `*` label (RFC4034
+    // section 3.1.3)
+    let cname = output
+        .answer
+        .iter()
+        .find_map(|record| record.clone().try_into_cname().ok())
+        .expect("No CNAME RR in the answer");
+    assert_eq!(qname, cname.fqdn);
+    assert_eq!(target_fqdn, cname.target);
+
+    let cname_rrsig = output
+        .answer
+        .iter()
+        .find_map(|record| {
+            record
+                .clone()
+                .try_into_rrsig()
+                .ok()
+                .filter(|rrsig| rrsig.type_covered == RecordType::CNAME)
+        })
+        .expect("No RRSIG RR covering the CNAME in the answer");
+    assert_eq!(qname.num_labels() - 1, usize::from(cname_rrsig.labels));
+
+    assert!(
+        output
+            .answer
+            .iter()
+            .any(|record| matches!(record, Record::A(a) if a.fqdn == target_fqdn)),
+        "{output:#?}"
+    );
+
+    // The next closer name of a name is the name one label longer than its closest encloser. In
+    // this scenario, the closest encloser is `alice.com.` which means that the next closer name is `charlie.alice.com.`
+
+    // If this panics, it probably means that the precomputed hashes must be recomputed.
+    let next_closer_name_rr = nsec3_rrs
+        .find_cover(NON_EXISTENT_HASH)
+        .expect("No RR in the zonefile covers the next closer name");
+
+    let nsec3_rrs_response = output
+        .authority
+        .into_iter()
+        .filter_map(|rr| rr.try_into_nsec3().ok())
+        .collect::<Vec<_>>();
+    assert_nsec3_proof_exactly(&nsec3_rrs_response, &[next_closer_name_rr]);
 
     Ok(())
 }
@@ -326,10 +404,180 @@ fn nsec3_owner_name() -> Result<()> {
     Ok(())
 }
 
+// The owner names of the NSEC3 RRs depend on the zone's salt and iterations so this checks that
+// name servers serve a valid name error proof with parameters other than the default ones.
+#[test]
+#[ignore = "hickory computes its NSEC3 chain with its own salt and iterations"]
+fn name_error_response_with_salt_and_iterations() -> Result<()> {
+    let alice_fqdn = FQDN(TLD_FQDN)?;
+    let qname = FQDN(NON_EXISTENT_FQDN)?;
+    // the parameters of the example in appendix A of RFC5155
+    let salt = "aabbccdd";
+    let iterations = 12;
+
+    let sign_settings = SignSettings::default()
+        .nsec3_salt(Some(vec![0xaa, 0xbb, 0xcc, 0xdd]))
+        .nsec3_iterations(iterations);
+    let (nsec3_rrs, status, nsec3_rrs_response) = query_signed_nameserver(
+        [Record::a(alice_fqdn.clone(), Ipv4Addr::new(1, 2, 3, 4))],
+        &qname,
+        RecordType::A,
+        sign_settings,
+    )?;
+
+    assert!(status.is_nxdomain());
+
+    assert!(nsec3_rrs.salt().unwrap().eq_ignore_ascii_case(salt));
+    assert_eq!(Some(iterations), nsec3_rrs.iterations());
+    for nsec3_rr in &nsec3_rrs_response {
+        assert!(nsec3_rr.salt.eq_ignore_ascii_case(salt), "{nsec3_rr:?}");
+        assert_eq!(iterations, nsec3_rr.iterations, "{nsec3_rr:?}");
+    }
+
+    let closest_encloser_hash = nsec3_rrs.hash(&alice_fqdn)?;
+    let next_closer_name_hash = nsec3_rrs.hash(&qname)?;
+    let wildcard_hash = nsec3_rrs.hash(&FQDN(WILDCARD_FQDN)?)?;
+    assert_ne!(TLD_HASH, closest_encloser_hash);
+
+    let (closest_encloser_rr, next_closer_name_rr) = nsec3_rrs
+        .closest_encloser_proof(&closest_encloser_hash, &next_closer_name_hash)
+        .expect("Cannot find a closest encloser proof in the zonefile");
+    let wildcard_rr = nsec3_rrs
+        .find_cover(&wildcard_hash)
+        .expect("No RR in the zonefile covers the wildcard");
+
+    find_records(
+        &nsec3_rrs_response,
+        [
+            (
+                closest_encloser_rr,
+                "No RR in the response matches the closest encloser",
+            ),
+            (
+                next_closer_name_rr,
+                "No RR in the response covers the next closer name",
+            ),
+            (wildcard_rr, "No RR in the response covers the wildcard"),
+        ],
+    );
+
+    Ok(())
+}
+
+// With opt-out (section 6), unsigned delegations don't get an NSEC3 RR of their own so a referral
+// to one is proven with a closest encloser proof whose covering NSEC3 RR has the opt-out bit set, as
+// per section 7.2.7.
+#[test]
+#[ignore = "hickory does not produce opt-out NSEC3 records"]
+fn opt_out_referral_to_unsigned_delegation() -> Result<()> {
+    let insecure_zone = FQDN("insecure.")?;
+    let secure_zone = FQDN("secure.")?;
+    let qname = insecure_zone.push_label("host");
+
+    // the name servers of both zones are in-bailiwick so they need glue
+    let insecure_ns = insecure_zone.push_label("ns");
+    let secure_ns = secure_zone.push_label("ns");
+    let records = [
+        Record::a(FQDN(TLD_FQDN)?, Ipv4Addr::new(1, 2, 3, 4)),
+        Record::ns(insecure_zone.clone(), insecure_ns.clone()),
+        Record::a(insecure_ns, Ipv4Addr::new(1, 2, 3, 5)),
+        Record::ns(secure_zone.clone(), secure_ns.clone()),
+        Record::a(secure_ns, Ipv4Addr::new(1, 2, 3, 6)),
+        Record::DS(
+            "secure. 86400 IN DS 12345 8 2 A8B1B2C3D4E5F60718293A4B5C6D7E8F90A1B2C3D4E5F60718293A4B5C6D7E8F"
+                .parse()?,
+        ),
+    ];
+    let sign_settings = SignSettings::default().nsec3_opt_out(true);
+    let (nsec3_rrs, status, nsec3_rrs_response) =
+        query_signed_nameserver(records, &qname, RecordType::A, sign_settings)?;
+
+    // a referral
+    assert!(status.is_noerror());
+
+    assert!(!nsec3_rrs_response.is_empty());
+    let proof = NSEC3Records::from_records(nsec3_rrs_response);
+
+    // only the secure delegation is individually proven
+    assert!(
+        nsec3_rrs
+            .find_match(&nsec3_rrs.hash(&insecure_zone)?)
+            .is_none()
+    );
+    assert!(
+        nsec3_rrs
+            .find_match(&nsec3_rrs.hash(&secure_zone)?)
+            .is_some()
+    );
+
+    // the closest provable encloser is the apex and the next closer name is the delegation
+    proof
+        .find_match(&proof.hash(&FQDN::ROOT)?)
+        .expect("No RR in the response matches the closest provable encloser");
+    let cover = proof
+        .find_cover(&proof.hash(&insecure_zone)?)
+        .expect("No RR in the response covers the next closer name");
+    assert!(cover.is_opt_out(), "{cover:?}");
+
+    Ok(())
+}
+
+// This test checks that the NSEC3 RRs of a signed zone form a single closed chain (section 7.1
+// of RFC5155) over the hashes of all the owner names in the zone, empty non-terminals included.
+#[test]
+fn nsec3_chain_is_closed_loop() -> Result<()> {
+    let alice_fqdn = FQDN(TLD_FQDN)?;
+    let bob_fqdn = alice_fqdn.push_label("bob");
+
+    let network = Network::new()?;
+    let mut ns = NameServer::new(&dns_test::SUBJECT, FQDN::ROOT, &network)?;
+    ns.add(Record::a(alice_fqdn.clone(), Ipv4Addr::new(1, 2, 3, 4)))
+        .add(Record::a(bob_fqdn.clone(), Ipv4Addr::new(1, 2, 3, 5)));
+    let ns = ns.sign(SignSettings::default())?;
+
+    let nsec3_rrs = NSEC3Records::new(ns.signed_zone_file());
+    assert!(!nsec3_rrs.is_empty());
+
+    // `com.` is an empty non-terminal
+    for name in [FQDN::ROOT, FQDN("com.")?, alice_fqdn, bob_fqdn] {
+        let name_hash = nsec3_rrs.hash(&name)?;
+        assert!(
+            nsec3_rrs.find_match(&name_hash).is_some(),
+            "no NSEC3 RR matches {name}"
+        );
+    }
+
+    // each RR points to the next one, in hash order, and the last one back to the first
+    let owner_hashes = nsec3_rrs
+        .iter()
+        .map(|nsec3| nsec3.fqdn.last_label().to_uppercase())
+        .collect::<Vec<_>>();
+    assert_eq!(nsec3_rrs.len(), owner_hashes.len());
+    for (index, nsec3) in nsec3_rrs.iter().enumerate() {
+        let expected = &owner_hashes[(index + 1) % owner_hashes.len()];
+        assert_eq!(
+            expected, &nsec3.next_hashed_owner_name,
+            "the chain is broken after {}",
+            nsec3.fqdn
+        );
+    }
+
+    Ok(())
+}
+
 fn query_nameserver(
     records: impl IntoIterator<Item = Record>,
     qname: &FQDN,
     qtype: RecordType,
+) -> Result<(NSEC3Records, DigStatus, Vec<NSEC3>)> {
+    query_signed_nameserver(records, qname, qtype, SignSettings::default())
+}
+
+fn query_signed_nameserver(
+    records: impl IntoIterator<Item = Record>,
+    qname: &FQDN,
+    qtype: RecordType,
+    sign_settings: SignSettings,
 ) -> Result<(NSEC3Records, DigStatus, Vec<NSEC3>)> {
     let network = Network::new()?;
     let mut ns = NameServer::new(&dns_test::SUBJECT, FQDN::ROOT, &network)?;
@@ -338,8 +586,6 @@ fn query_nameserver(
         ns.add(record);
     }
 
-    let sign_settings = SignSettings::default();
-
     let ns = ns.sign(sign_settings)?;
 
     let nsec3_rrs = NSEC3Records::new(ns.signed_zone_file());
@@ -376,3 +622,66 @@ fn find_records<'a>(
         records.iter().find(|&rr| rr == record).expect(err_msg);
     }
 }
+
+/// Asserts that the NSEC3 records in the response prove, as per section 7.2.5, that `qname` is
+/// matched by `wildcard` but that the wildcard has no RRset of the queried type
+///
+/// The hashes are computed using the parameters of the NSEC3 records in the response
+#[track_caller]
+fn assert_wildcard_nodata_proof(records: &[NSEC3], qname: &FQDN, wildcard: &FQDN) {
+    assert_eq!("*", wildcard.last_label(), "{wildcard} is not a wildcard");
+    let closest_encloser = wildcard.parent().unwrap();
+
+    // the ancestor of `qname` that is one label longer than the closest encloser
+    let mut next_closer_name = qname.clone();
+    while next_closer_name.num_labels() > closest_encloser.num_labels() + 1 {
+        next_closer_name = next_closer_name.parent().unwrap();
+    }
+    assert_eq!(
+        Some(&closest_encloser),
+        next_closer_name.parent().as_ref(),
+        "{qname} is not a descendant of {closest_encloser}"
+    );
+
+    let proof = NSEC3Records::from_records(records.iter().cloned());
+    let hash = |name: &FQDN| proof.hash(name).expect("No NSEC3 RR in the response");
+
+    proof
+        .find_match(&hash(&closest_encloser))
+        .expect("No RR in the response matches the closest encloser");
+    proof
+        .find_cover(&hash(&next_closer_name))
+        .expect("No RR in the response covers the next closer name");
+    proof
+        .find_match(&hash(wildcard))
+        .expect("No RR in the response matches the wildcard");
+}
+
+/// Asserts that the NSEC3 records in the response are exactly the `expected` proof, in any order
+///
+/// Besides missing records, this catches servers that include superfluous NSEC3 records, which
+/// needlessly leak the contents of the zone
+#[track_caller]
+fn assert_nsec3_proof_exactly(records: &[NSEC3], expected: &[&NSEC3]) {
+    // the same NSEC3 RR can play more than one role in a proof
+    let mut proof: Vec<&NSEC3> = vec![];
+    for record in expected {
+        if !proof.contains(record) {
+            proof.push(record);
+        }
+    }
+
+    let missing = proof
+        .iter()
+        .filter(|record| !records.contains(record))
+        .collect::<Vec<_>>();
+    let extra = records
+        .iter()
+        .filter(|record| !proof.contains(record))
+        .collect::<Vec<_>>();
+
+    assert!(
+        missing.is_empty() && extra.is_empty(),
+        "NSEC3 records in the response don't match the expected proof\nmissing: {missing:#?}\nextra: {extra:#?}"
+    );
+}
@@ -23,7 +23,7 @@ fn test_8_2_1_minimal_edns() -> Result<()> {
     assert_eq!(output.answer.len(), 1);
     assert!(output.answer[0].is_soa());
     assert!(output.opt);
-    assert_eq!(output.edns_version, Some(0));
+    assert_eq!(output.edns_version(), Some(0));
     assert!(output.flags.authoritative_answer);
     assert!(!output.flags.authenticated_data);
 
@@ -45,7 +45,7 @@ fn test_8_2_2_edns_version_negotiation() -> Result<()> {
     assert_eq!(output.status, DigStatus::BADVERS);
     assert!(output.answer.is_empty());
     assert!(output.opt);
-    assert_eq!(output.edns_version, Some(0));
+    assert_eq!(output.edns_version(), Some(0));
     assert!(!output.flags.authoritative_answer);
     assert!(!output.flags.authenticated_data);
 
@@ -69,7 +69,7 @@ fn test_8_2_3_unknown_edns_options() -> Result<()> {
     assert!(output.answer[0].is_soa());
     assert!(output.opt);
     assert!(output.options.is_empty());
-    assert_eq!(output.edns_version, Some(0));
+    assert_eq!(output.edns_version(), Some(0));
     assert!(output.flags.authoritative_answer);
     assert!(!output.flags.authenticated_data);
 
@@ -92,8 +92,8 @@ fn test_8_2_4_unknown_edns_flags() -> Result<()> {
     assert_eq!(output.answer.len(), 1);
     assert!(output.answer[0].is_soa());
     assert!(output.opt);
-    assert!(!output.edns_must_be_zero);
-    assert_eq!(output.edns_version, Some(0));
+    assert!(!output.edns_must_be_zero());
+    assert_eq!(output.edns_version(), Some(0));
     assert!(output.options.is_empty());
     assert!(output.flags.authoritative_answer);
     assert!(!output.flags.authenticated_data);
@@ -120,8 +120,8 @@ fn test_8_2_5_edns_version_negotiation_with_unknown_edns_flags() -> Result<()> {
     assert_eq!(output.status, DigStatus::BADVERS);
     assert!(output.answer.is_empty());
     assert!(output.opt);
-    assert!(!output.edns_must_be_zero);
-    assert_eq!(output.edns_version, Some(0));
+    assert!(!output.edns_must_be_zero());
+    assert_eq!(output.edns_version(), Some(0));
     assert!(!output.flags.authoritative_answer);
     assert!(!output.flags.authenticated_data);
 
@@ -148,7 +148,7 @@ fn test_8_2_6_edns_version_negotiation_with_unknown_edns_options() -> Result<()>
     assert!(output.answer.is_empty());
     assert!(output.opt);
     assert!(output.options.is_empty());
-    assert_eq!(output.edns_version, Some(0));
+    assert_eq!(output.edns_version(), Some(0));
     assert!(!output.flags.authoritative_answer);
     assert!(!output.flags.authenticated_data);
 
@@ -176,7 +176,7 @@ fn test_8_2_7_truncated_responses() -> Result<()> {
 
     assert_eq!(output.status, DigStatus::NOERROR);
     assert!(output.opt);
-    assert_eq!(output.edns_version, Some(0));
+    assert_eq!(output.edns_version(), Some(0));
 
     Ok(())
 }
@@ -197,10 +197,15 @@ fn test_8_2_8_do_1() -> Result<()> {
     assert!(!output.answer.is_empty());
     assert!(output.answer[0].is_soa());
     assert!(output.opt);
-    assert!(output.dnssec_ok_flag);
-    assert_eq!(output.edns_version, Some(0));
+    assert!(output.dnssec_ok_flag());
+    assert_eq!(output.edns_version(), Some(0));
     assert!(output.flags.authoritative_answer);
 
+    let edns = output.edns.expect("OPT pseudo-record");
+    assert!(edns.dnssec_ok, "{edns:?}");
+    assert_eq!(0, edns.version, "{edns:?}");
+    assert_eq!(0, edns.must_be_zero, "{edns:?}");
+
     Ok(())
 }
 
@@ -225,9 +230,9 @@ fn test_8_2_9_edns_version_negotiation_with_do_1() -> Result<()> {
     assert!(output.opt);
     if !dns_test::SUBJECT.is_unbound() {
         // unbound does not set DO=1 in the BADVERS response
-        assert!(output.dnssec_ok_flag);
+        assert!(output.dnssec_ok_flag());
     }
-    assert_eq!(output.edns_version, Some(0));
+    assert_eq!(output.edns_version(), Some(0));
     assert!(!output.flags.authoritative_answer);
 
     Ok(())
@@ -249,7 +254,7 @@ fn test_8_2_10_multiple_defined_edns_options() -> Result<()> {
     assert_eq!(output.answer.len(), 1);
     assert!(output.answer[0].is_soa());
     assert!(output.opt);
-    assert_eq!(output.edns_version, Some(0));
+    assert_eq!(output.edns_version(), Some(0));
     assert!(output.flags.authoritative_answer);
     assert!(!output.flags.authenticated_data);
 
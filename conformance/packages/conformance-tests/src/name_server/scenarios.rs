@@ -1,7 +1,14 @@
-use dns_test::client::{Client, DigSettings};
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::Duration;
+
+use dns_test::bench::{BenchSettings, Dnsperf};
+use dns_test::client::{Client, DigOutput, DigSettings, DigStatus, ExpectedOutput, IxfrResponse};
 use dns_test::name_server::NameServer;
-use dns_test::record::RecordType;
-use dns_test::{FQDN, Network, Result};
+use dns_test::record::{CDNSKEY, CDS, Class, PTR, Record, RecordType, SvcParam};
+use dns_test::tshark::{Capture, Direction};
+use dns_test::zone_file::SignSettings;
+use dns_test::{FQDN, Implementation, Network, Result};
 
 #[test]
 fn authoritative_answer() -> Result<()> {
@@ -21,3 +28,967 @@ fn authoritative_answer() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn answers_over_tcp() -> Result<()> {
+    let network = &Network::new()?;
+    let needle_fqdn = FQDN::EXAMPLE_SUBDOMAIN;
+    let expected_ipv4_addr = Ipv4Addr::new(1, 2, 3, 4);
+
+    let mut ns = NameServer::new(&dns_test::SUBJECT, FQDN::TEST_DOMAIN, network)?;
+    ns.add(Record::a(needle_fqdn.clone(), expected_ipv4_addr));
+    let ns = ns.start()?;
+
+    let client = Client::new(network)?;
+    let settings = *DigSettings::default().dnssec().tcp();
+    let ans = client.dig(settings, ns.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+
+    assert!(ans.status.is_noerror(), "{ans:#?}");
+    assert!(ans.flags.authoritative_answer);
+    let [answer] = ans.answer.try_into().unwrap();
+    assert_eq!(expected_ipv4_addr, answer.try_into_a().unwrap().ipv4_addr);
+
+    let ans = client.dig(
+        settings,
+        ns.ipv4_addr(),
+        RecordType::A,
+        &FQDN::TEST_DOMAIN.push_label("nonexistent"),
+    )?;
+
+    assert!(ans.status.is_nxdomain(), "{ans:#?}");
+    let [authority] = ans.authority.try_into().unwrap();
+    assert!(authority.is_soa());
+
+    Ok(())
+}
+
+#[test]
+fn truncated_udp_answer_is_complete_over_tcp() -> Result<()> {
+    const NUM_TXT_RECORDS: usize = 8;
+
+    let network = &Network::new()?;
+    let needle_fqdn = FQDN::EXAMPLE_SUBDOMAIN;
+
+    let mut ns = NameServer::new(&dns_test::SUBJECT, FQDN::TEST_DOMAIN, network)?;
+    for index in 0..NUM_TXT_RECORDS {
+        ns.add(Record::txt(
+            needle_fqdn.clone(),
+            &index.to_string().repeat(250),
+        ));
+    }
+    let ns = ns.start()?;
+
+    let client = Client::new(network)?;
+    let settings = *DigSettings::default().bufsize(512).ignore();
+    let ans = client.dig(settings, ns.ipv4_addr(), RecordType::TXT, &needle_fqdn)?;
+
+    assert!(ans.status.is_noerror(), "{ans:#?}");
+    assert!(ans.flags.truncation);
+    assert!(ans.answer.len() < NUM_TXT_RECORDS);
+
+    let settings = *DigSettings::default().tcp();
+    let ans = client.dig(settings, ns.ipv4_addr(), RecordType::TXT, &needle_fqdn)?;
+
+    assert!(ans.status.is_noerror(), "{ans:#?}");
+    assert!(!ans.flags.truncation);
+    assert_eq!(NUM_TXT_RECORDS, ans.answer.len());
+
+    Ok(())
+}
+
+#[test]
+fn txt_character_strings_are_preserved() -> Result<()> {
+    let network = &Network::new()?;
+    let needle_fqdn = FQDN::EXAMPLE_SUBDOMAIN;
+    let long = "x".repeat(255);
+    let expected = Record::txt_character_strings(needle_fqdn.clone(), &[&long, ""]);
+
+    let mut ns = NameServer::new(&dns_test::SUBJECT, FQDN::TEST_DOMAIN, network)?;
+    ns.add(expected.clone());
+    let ns = ns.start()?;
+
+    let client = Client::new(network)?;
+    let ans = client.dig(
+        DigSettings::default(),
+        ns.ipv4_addr(),
+        RecordType::TXT,
+        &needle_fqdn,
+    )?;
+
+    ans.assert_matches(&ExpectedOutput {
+        status: Some(DigStatus::NOERROR),
+        answer: Some(vec![expected]),
+        ..ExpectedOutput::default()
+    });
+
+    Ok(())
+}
+
+#[test]
+#[ignore = "hickory signs the zone with its own keys and does not publish CDS/CDNSKEY records"]
+fn cds_matches_parent_ds() -> Result<()> {
+    let network = &Network::new()?;
+
+    let ns = NameServer::new(&dns_test::SUBJECT, FQDN::TEST_DOMAIN, network)?
+        .sign(SignSettings::default().publish_cds(true))?;
+    let parent_ds = ns.ds().ksk.clone();
+    let ns = ns.start()?;
+
+    let client = Client::new(network)?;
+    let settings = *DigSettings::default().dnssec();
+
+    let ans = client.dig(
+        settings,
+        ns.ipv4_addr(),
+        RecordType::CDS,
+        &FQDN::TEST_DOMAIN,
+    )?;
+    assert!(ans.status.is_noerror(), "{ans:#?}");
+    let [cds, rrsig] = ans.answer.try_into().unwrap();
+    let cds = cds.try_into_cds().unwrap();
+    assert!(
+        cds.matches(&parent_ds),
+        "{cds:?} does not match {parent_ds:?}"
+    );
+    assert_eq!(
+        RecordType::CDS,
+        rrsig.try_into_rrsig().unwrap().type_covered
+    );
+
+    let ans = client.dig(
+        settings,
+        ns.ipv4_addr(),
+        RecordType::CDNSKEY,
+        &FQDN::TEST_DOMAIN,
+    )?;
+    assert!(ans.status.is_noerror(), "{ans:#?}");
+    let [cdnskey, _rrsig] = ans.answer.try_into().unwrap();
+    let cdnskey = cdnskey.try_into_cdnskey().unwrap();
+    assert_eq!(parent_ds.key_tag, cdnskey.rdata.calculate_key_tag());
+
+    Ok(())
+}
+
+#[test]
+fn cds_delete() -> Result<()> {
+    let network = &Network::new()?;
+
+    let mut ns = NameServer::new(&dns_test::SUBJECT, FQDN::TEST_DOMAIN, network)?;
+    ns.add(CDS::delete(FQDN::TEST_DOMAIN))
+        .add(CDNSKEY::delete(FQDN::TEST_DOMAIN));
+    let ns = ns.start()?;
+
+    let client = Client::new(network)?;
+    for record_type in [RecordType::CDS, RecordType::CDNSKEY] {
+        let ans = client.dig(
+            DigSettings::default(),
+            ns.ipv4_addr(),
+            record_type,
+            &FQDN::TEST_DOMAIN,
+        )?;
+
+        assert!(ans.status.is_noerror(), "{ans:#?}");
+        let [answer] = ans.answer.try_into().unwrap();
+        let is_delete = match &answer {
+            Record::CDS(cds) => cds.is_delete(),
+            Record::CDNSKEY(cdnskey) => cdnskey.is_delete(),
+            _ => false,
+        };
+        assert!(is_delete, "{answer:?}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn zone_transfer() -> Result<()> {
+    let network = &Network::new()?;
+    let client = Client::new(network)?;
+
+    let mut ns = NameServer::new(&dns_test::SUBJECT, FQDN::TEST_DOMAIN, network)?;
+    ns.add(Record::a(
+        FQDN::EXAMPLE_SUBDOMAIN,
+        Ipv4Addr::new(1, 2, 3, 4),
+    ))
+    .add(Record::txt(FQDN::EXAMPLE_SUBDOMAIN, "hello"))
+    .add(Record::cname(
+        FQDN::TEST_DOMAIN.push_label("alias"),
+        FQDN::EXAMPLE_SUBDOMAIN,
+    ))
+    .allow_transfer(client.ipv4_addr());
+    let ns = ns.start()?;
+
+    let records = client.axfr(ns.ipv4_addr(), &FQDN::TEST_DOMAIN)?;
+
+    let (Some(Record::SOA(first)), Some(Record::SOA(last))) = (records.first(), records.last())
+    else {
+        panic!("transfer is not delimited by SOA records: {records:#?}");
+    };
+    assert_eq!(ns.zone_file().soa, *first);
+    assert_eq!(first, last);
+
+    let transferred = &records[1..records.len() - 1];
+    let expected = &ns.zone_file().records;
+    assert_eq!(expected.len(), transferred.len(), "{transferred:#?}");
+    for record in expected {
+        assert!(
+            transferred.contains(record),
+            "{record:?} is missing from {transferred:#?}"
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn zone_transfer_refused_by_default() -> Result<()> {
+    let network = &Network::new()?;
+    let client = Client::new(network)?;
+    let ns = NameServer::new(&dns_test::SUBJECT, FQDN::TEST_DOMAIN, network)?.start()?;
+
+    let result = client.axfr(ns.ipv4_addr(), &FQDN::TEST_DOMAIN);
+
+    assert!(result.is_err(), "{result:#?}");
+
+    Ok(())
+}
+
+#[test]
+#[ignore = "hickory-dns does not support reloading zones"]
+fn incremental_zone_transfer() -> Result<()> {
+    let network = &Network::new()?;
+    let client = Client::new(network)?;
+    let unchanged = Record::a(FQDN::EXAMPLE_SUBDOMAIN, Ipv4Addr::new(1, 2, 3, 4));
+    let removed = Record::txt(FQDN::EXAMPLE_SUBDOMAIN, "old");
+    let added = Record::txt(FQDN::EXAMPLE_SUBDOMAIN, "new");
+
+    let mut ns = NameServer::new(&dns_test::SUBJECT, FQDN::TEST_DOMAIN, network)?;
+    ns.add(unchanged.clone())
+        .add(removed.clone())
+        .set_serial(1)
+        .allow_transfer(client.ipv4_addr());
+    let mut ns = ns.start()?;
+    ns.wait_for_serial(1, Duration::from_secs(5))?;
+
+    let serial = ns.update_zone(|zone_file| {
+        zone_file.records.retain(|record| *record != removed);
+        zone_file.add(added.clone());
+    })?;
+    ns.wait_for_serial(serial, Duration::from_secs(5))?;
+
+    match client.ixfr(ns.ipv4_addr(), &FQDN::TEST_DOMAIN, 1)? {
+        IxfrResponse::Incremental(deltas) => {
+            let [delta] = deltas.try_into().unwrap();
+            assert_eq!((1, serial), (delta.from_serial, delta.to_serial));
+            assert_eq!(vec![removed], delta.removed);
+            assert_eq!(vec![added], delta.added);
+        }
+
+        // servers may always send the entire zone (RFC1995 section 4)
+        IxfrResponse::Full(records) => {
+            println!("{:?} fell back to AXFR", *dns_test::SUBJECT);
+            assert!(records.contains(&unchanged));
+            assert!(records.contains(&added));
+            assert!(!records.contains(&removed));
+        }
+
+        response @ IxfrResponse::UpToDate(_) => panic!("unexpected response: {response:?}"),
+    }
+
+    let response = client.ixfr(ns.ipv4_addr(), &FQDN::TEST_DOMAIN, serial)?;
+    assert!(
+        matches!(&response, IxfrResponse::UpToDate(soa) if soa.settings.serial == serial),
+        "{response:?}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn caa_records() -> Result<()> {
+    let network = &Network::new()?;
+    let records = [
+        Record::caa(FQDN::TEST_DOMAIN, 0, "issue", "letsencrypt.org"),
+        Record::caa(
+            FQDN::TEST_DOMAIN,
+            128,
+            "iodef",
+            "mailto:security@hickory-dns.testing",
+        ),
+    ];
+
+    let mut ns = NameServer::new(&dns_test::SUBJECT, FQDN::TEST_DOMAIN, network)?;
+    for record in &records {
+        ns.add(record.clone());
+    }
+    let ns = ns.start()?;
+
+    let client = Client::new(network)?;
+    let ans = client.dig(
+        DigSettings::default(),
+        ns.ipv4_addr(),
+        RecordType::CAA,
+        &FQDN::TEST_DOMAIN,
+    )?;
+
+    ans.assert_matches(&ExpectedOutput {
+        status: Some(DigStatus::NOERROR),
+        answer: Some(records.to_vec()),
+        ..ExpectedOutput::default()
+    });
+
+    Ok(())
+}
+
+#[test]
+fn https_records() -> Result<()> {
+    let network = &Network::new()?;
+    let alias_fqdn = FQDN::TEST_DOMAIN.push_label("alias");
+    let service_fqdn = FQDN::TEST_DOMAIN.push_label("www");
+    let alias = Record::https(alias_fqdn.clone(), 0, service_fqdn.clone(), vec![]);
+    let service = Record::https(
+        service_fqdn.clone(),
+        1,
+        FQDN::ROOT,
+        vec![
+            SvcParam::Alpn(vec!["h2".to_string(), "h3".to_string()]),
+            SvcParam::Ipv4Hint(vec![Ipv4Addr::new(192, 0, 2, 1)]),
+        ],
+    );
+
+    let mut ns = NameServer::new(&dns_test::SUBJECT, FQDN::TEST_DOMAIN, network)?;
+    ns.add(alias.clone()).add(service.clone());
+    let ns = ns.start()?;
+
+    let client = Client::new(network)?;
+    for (fqdn, expected) in [(alias_fqdn, alias), (service_fqdn, service)] {
+        let ans = client.dig(
+            DigSettings::default(),
+            ns.ipv4_addr(),
+            RecordType::HTTPS,
+            &fqdn,
+        )?;
+
+        ans.assert_matches(&ExpectedOutput {
+            status: Some(DigStatus::NOERROR),
+            answer: Some(vec![expected]),
+            ..ExpectedOutput::default()
+        });
+    }
+
+    Ok(())
+}
+
+#[test]
+fn naptr_record() -> Result<()> {
+    let network = &Network::new()?;
+    let fqdn = FQDN::TEST_DOMAIN.push_label("sip");
+    // the backslashes and the double quote must be escaped in the zone file
+    let expected = Record::naptr(
+        fqdn.clone(),
+        100,
+        10,
+        "u",
+        "E2U+sip",
+        r#"!^\+1800555(.*)$!sip:\1@"example".com!"#,
+        FQDN::ROOT,
+    );
+
+    let mut ns = NameServer::new(&dns_test::SUBJECT, FQDN::TEST_DOMAIN, network)?;
+    ns.add(expected.clone());
+    let ns = ns.start()?;
+
+    let client = Client::new(network)?;
+    let ans = client.dig(
+        DigSettings::default(),
+        ns.ipv4_addr(),
+        RecordType::NAPTR,
+        &fqdn,
+    )?;
+
+    ans.assert_matches(&ExpectedOutput {
+        status: Some(DigStatus::NOERROR),
+        answer: Some(vec![expected]),
+        ..ExpectedOutput::default()
+    });
+
+    Ok(())
+}
+
+#[test]
+fn srv_records() -> Result<()> {
+    let network = &Network::new()?;
+    let fqdn = FQDN::TEST_DOMAIN.push_label("_tcp").push_label("_sip");
+    let expected = [
+        (10, 60, "primary"),
+        (10, 40, "secondary"),
+        (20, 0, "backup"),
+    ]
+    .map(|(priority, weight, host)| {
+        Record::srv(
+            fqdn.clone(),
+            priority,
+            weight,
+            5060,
+            FQDN::TEST_DOMAIN.push_label(host),
+        )
+    });
+
+    let mut ns = NameServer::new(&dns_test::SUBJECT, FQDN::TEST_DOMAIN, network)?;
+    for record in &expected {
+        ns.add(record.clone());
+    }
+    let ns = ns.start()?;
+
+    let client = Client::new(network)?;
+    let ans = client.dig(
+        DigSettings::default(),
+        ns.ipv4_addr(),
+        RecordType::SRV,
+        &fqdn,
+    )?;
+
+    ans.assert_matches(&ExpectedOutput {
+        status: Some(DigStatus::NOERROR),
+        answer: Some(expected.to_vec()),
+        ..ExpectedOutput::default()
+    });
+
+    Ok(())
+}
+
+#[test]
+fn reverse_lookup() -> Result<()> {
+    let network = &Network::new()?;
+    let ipv4_addr: IpAddr = "192.0.2.1".parse()?;
+    let ipv6_addr: IpAddr = "2001:db8::1".parse()?;
+    let ipv4_target = FQDN::TEST_DOMAIN.push_label("v4");
+    let ipv6_target = FQDN::TEST_DOMAIN.push_label("v6");
+
+    let records =
+        [(ipv4_addr, ipv4_target), (ipv6_addr, ipv6_target)].map(|(ip_addr, target)| PTR {
+            fqdn: FQDN::reverse(ip_addr),
+            ttl: 86400,
+            target,
+        });
+
+    let mut ns = NameServer::new(&dns_test::SUBJECT, FQDN("arpa.")?, network)?;
+    for record in &records {
+        ns.add(record.clone());
+    }
+    let ns = ns.start()?;
+
+    let client = Client::new(network)?;
+    for (ip_addr, record) in [ipv4_addr, ipv6_addr].into_iter().zip(records) {
+        let ans = client.dig_reverse(DigSettings::default(), ns.ipv4_addr(), ip_addr)?;
+
+        ans.assert_matches(&ExpectedOutput {
+            status: Some(DigStatus::NOERROR),
+            answer: Some(vec![record.into()]),
+            ..ExpectedOutput::default()
+        });
+    }
+
+    Ok(())
+}
+
+#[test]
+#[ignore = "hickory-dns does not support NSID"]
+fn nsid_identifies_server() -> Result<()> {
+    let network = &Network::new()?;
+    let needle_fqdn = FQDN::EXAMPLE_SUBDOMAIN;
+    let expected_ipv4_addr = Ipv4Addr::new(1, 2, 3, 4);
+
+    // two servers with identical content for the same zone
+    let nameservers = [0, 1]
+        .map(|_| -> Result<_> {
+            let mut ns = NameServer::new(&dns_test::SUBJECT, FQDN::TEST_DOMAIN, network)?;
+            ns.add(Record::a(needle_fqdn.clone(), expected_ipv4_addr));
+            ns.start()
+        })
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+    assert_ne!(nameservers[0].nsid(), nameservers[1].nsid());
+
+    let client = Client::new(network)?;
+    let settings = *DigSettings::default().nsid();
+    for ns in &nameservers {
+        let output = client.dig(settings, ns.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+
+        assert!(output.status.is_noerror());
+        output.assert_answered_by(ns);
+    }
+
+    Ok(())
+}
+
+#[test]
+#[ignore = "hickory-dns does not support NSID"]
+fn configured_nsid_is_reported() -> Result<()> {
+    let network = &Network::new()?;
+
+    let nameservers = ["backend-a", "backend-b"]
+        .map(|id| -> Result<_> {
+            let mut ns = NameServer::new(&dns_test::SUBJECT, FQDN::TEST_DOMAIN, network)?;
+            ns.set_nsid(id);
+            ns.start()
+        })
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+
+    let client = Client::new(network)?;
+    let settings = *DigSettings::default().nsid();
+    for (ns, id) in nameservers.iter().zip(["backend-a", "backend-b"]) {
+        assert_eq!(id, ns.nsid());
+
+        let output = client.dig(
+            settings,
+            ns.ipv4_addr(),
+            RecordType::SOA,
+            &FQDN::TEST_DOMAIN,
+        )?;
+
+        assert!(output.status.is_noerror());
+        assert_eq!(Some(id), output.nsid.as_deref());
+    }
+
+    Ok(())
+}
+
+#[test]
+#[ignore = "hickory-dns does not support DNS cookies"]
+fn echoes_client_cookie() -> Result<()> {
+    let network = &Network::new()?;
+    let ns = NameServer::new(&dns_test::SUBJECT, FQDN::TEST_DOMAIN, network)?.start()?;
+    let client = Client::new(network)?;
+
+    let client_cookie = [1, 2, 3, 4, 5, 6, 7, 8];
+    let settings = *DigSettings::default().cookie(client_cookie);
+    let ans = client.dig(
+        settings,
+        ns.ipv4_addr(),
+        RecordType::SOA,
+        &FQDN::TEST_DOMAIN,
+    )?;
+    assert!(ans.status.is_noerror(), "{ans:#?}");
+
+    let Some(cookie) = ans.cookie else {
+        panic!("response has no COOKIE option: {ans:#?}");
+    };
+    let (echoed, server_cookie) = cookie.split_at(client_cookie.len().min(cookie.len()));
+    assert_eq!(client_cookie, echoed);
+    // RFC7873 section 4.2
+    assert!(
+        (8..=32).contains(&server_cookie.len()),
+        "server cookie has invalid length: {server_cookie:?}"
+    );
+
+    Ok(())
+}
+
+/// Exercises the EDNS CLIENT-SUBNET (RFC7871) support of the client against a dnslib server that
+/// tailors its answers to the client subnet, something the name servers under test don't do
+#[test]
+fn client_subnet_selects_answer() -> Result<()> {
+    let network = &Network::new()?;
+    let needle_fqdn = FQDN::EXAMPLE_SUBDOMAIN;
+
+    let ns = NameServer::new(&Implementation::Dnslib, FQDN::TEST_DOMAIN, network)?;
+    let script = fs::read_to_string("src/name_server/client_subnet.py")?;
+    ns.cp("/script.py", &script)?;
+    let ns = ns.start()?;
+
+    let client = Client::new(network)?;
+    let cases = [
+        (Some("192.0.2.0/24"), Ipv4Addr::new(10, 0, 0, 1), Some(24)),
+        (
+            Some("2001:db8:1::/48"),
+            Ipv4Addr::new(10, 0, 0, 2),
+            Some(32),
+        ),
+        (Some("198.51.100.0/24"), Ipv4Addr::new(10, 0, 0, 3), Some(0)),
+        (None, Ipv4Addr::new(10, 0, 0, 3), None),
+    ];
+    for (subnet, expected_ipv4_addr, expected_scope) in cases {
+        let mut settings = DigSettings::default();
+        if let Some(subnet) = subnet {
+            settings.subnet(subnet.parse()?);
+        }
+
+        let ans = client.dig(settings, ns.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+
+        assert!(ans.status.is_noerror(), "{ans:#?}");
+        let [answer] = ans.answer.try_into().unwrap();
+        assert_eq!(expected_ipv4_addr, answer.try_into_a().unwrap().ipv4_addr);
+        assert_eq!(expected_scope, ans.ecs_scope, "subnet: {subnet:?}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn qps_above_floor() -> Result<()> {
+    // deliberately conservative, as CI runners are noisy; this is meant to catch severe
+    // performance regressions rather than to measure the subject's peak throughput
+    const MIN_QPS: f64 = 500.;
+    const MAX_P99_LATENCY: Duration = Duration::from_millis(100);
+
+    let network = &Network::new()?;
+    let fqdn = FQDN::TEST_DOMAIN.push_label("www");
+    let mut ns = NameServer::new(&dns_test::SUBJECT, FQDN::TEST_DOMAIN, network)?;
+    ns.add(Record::a(fqdn.clone(), Ipv4Addr::new(1, 2, 3, 4)));
+    let ns = ns.start()?;
+
+    let dnsperf = Dnsperf::new(network)?;
+    let settings = *BenchSettings::default()
+        .duration(Duration::from_secs(5))
+        .clients(4);
+    let report = dnsperf.bench(
+        settings,
+        ns.ipv4_addr(),
+        &[(RecordType::A, fqdn), (RecordType::SOA, FQDN::TEST_DOMAIN)],
+    )?;
+
+    dbg!(
+        report.queries_sent,
+        report.queries_completed,
+        report.queries_lost,
+        report.qps,
+        report.latency_percentile(50.),
+        report.latency_percentile(99.),
+    );
+
+    assert!(report.qps >= MIN_QPS, "QPS fell below {MIN_QPS}");
+    let p99 = report.latency_percentile(99.).expect("no query completed");
+    assert!(
+        p99 <= MAX_P99_LATENCY,
+        "p99 latency exceeded {MAX_P99_LATENCY:?}"
+    );
+
+    Ok(())
+}
+
+#[test]
+#[ignore = "hickory-dns does not support response rate limiting"]
+fn response_rate_limit() -> Result<()> {
+    const RESPONSES_PER_SECOND: u32 = 5;
+    const DURATION: Duration = Duration::from_secs(3);
+    // well above the rate limit, but low enough that the name server answers every query it's
+    // allowed to
+    const QPS: u32 = 100;
+
+    let network = &Network::new()?;
+    let fqdn = FQDN::TEST_DOMAIN.push_label("www");
+    let mut ns = NameServer::new(&dns_test::SUBJECT, FQDN::TEST_DOMAIN, network)?;
+    ns.add(Record::a(fqdn.clone(), Ipv4Addr::new(1, 2, 3, 4)))
+        .response_rate_limit(RESPONSES_PER_SECOND);
+    let ns = ns.start()?;
+
+    let mut tshark = ns.eavesdrop()?;
+
+    // identical queries, all from the same client IP address
+    let dnsperf = Dnsperf::new(network)?;
+    let settings = *BenchSettings::default()
+        .duration(DURATION)
+        .max_qps(QPS)
+        .timeout(Duration::from_secs(1));
+    let report = dnsperf.bench(settings, ns.ipv4_addr(), &[(RecordType::A, fqdn.clone())])?;
+
+    // dnsperf can't tell truncated responses apart from full ones so look at what the name
+    // server sent instead
+    let queries_received =
+        |captures: &[Capture]| Capture::count_incoming_queries_for(captures, &fqdn) as u64;
+    tshark.wait_until(
+        |captures| queries_received(captures) >= report.queries_sent,
+        Duration::from_secs(10),
+    )?;
+    let captures = tshark.terminate()?;
+
+    let full_responses = captures
+        .iter()
+        .filter(|capture| {
+            matches!(capture.direction, Direction::Outgoing { .. })
+                && capture.message.is_response()
+                && !capture.message.is_tc_flag_set()
+        })
+        .count() as u64;
+
+    // one second of slack as the flood may straddle one more second than `DURATION`
+    let max_full_responses = u64::from(RESPONSES_PER_SECOND) * (DURATION.as_secs() + 1);
+    assert!(
+        full_responses <= max_full_responses,
+        "sent {full_responses} untruncated responses to {} queries; expected at most {max_full_responses}",
+        report.queries_sent,
+    );
+    // the limit was hit: the rest of the queries were either dropped or answered with TC=1
+    assert!(
+        report.queries_sent > max_full_responses,
+        "only {} queries were sent",
+        report.queries_sent
+    );
+
+    Ok(())
+}
+
+#[test]
+#[ignore = "hickory-dns always includes additional section records"]
+fn minimal_responses_omit_additional_records() -> Result<()> {
+    let output = minimal_responses_fixture(true)?;
+
+    assert!(output.status.is_noerror(), "{output:#?}");
+    assert_eq!(1, output.answer.len(), "{output:#?}");
+    assert!(output.additional.is_empty(), "{output:#?}");
+
+    Ok(())
+}
+
+#[test]
+fn additional_records_without_minimal_responses() -> Result<()> {
+    let output = minimal_responses_fixture(false)?;
+
+    assert!(output.status.is_noerror(), "{output:#?}");
+    let [answer] = output.answer.try_into().unwrap();
+    let mx = answer.try_into_mx().unwrap();
+
+    let glue = output
+        .additional
+        .into_iter()
+        .filter_map(|record| record.try_into_a().ok())
+        .find(|a| a.fqdn == mx.exchange);
+    assert_eq!(
+        Some(Ipv4Addr::new(1, 2, 3, 4)),
+        glue.map(|a| a.ipv4_addr),
+        "no A record for {} in the additional section",
+        mx.exchange
+    );
+
+    Ok(())
+}
+
+/// Queries the MX record of a zone whose mail exchange has an in-zone A record
+fn minimal_responses_fixture(minimal_responses: bool) -> Result<DigOutput> {
+    let network = &Network::new()?;
+    let exchange = FQDN::TEST_DOMAIN.push_label("mail");
+
+    let mut ns = NameServer::new(&dns_test::SUBJECT, FQDN::TEST_DOMAIN, network)?;
+    ns.add(Record::mx(FQDN::TEST_DOMAIN, 10, exchange.clone()))
+        .add(Record::a(exchange, Ipv4Addr::new(1, 2, 3, 4)))
+        .minimal_responses(minimal_responses);
+    let ns = ns.start()?;
+
+    let client = Client::new(network)?;
+    client.dig(
+        DigSettings::default(),
+        ns.ipv4_addr(),
+        RecordType::MX,
+        &FQDN::TEST_DOMAIN,
+    )
+}
+
+#[test]
+fn formerr_on_compression_pointer_loop() -> Result<()> {
+    const ID: [u8; 2] = [0x12, 0x34];
+    const FORMERR: u8 = 1;
+
+    let network = &Network::new()?;
+    let ns = NameServer::new(&dns_test::SUBJECT, FQDN::ROOT, network)?.start()?;
+    let client = Client::new(network)?;
+
+    let mut query = vec![];
+    // header: ID, no flags, QDCOUNT=1
+    query.extend(ID);
+    query.extend([0, 0, 0, 1, 0, 0, 0, 0, 0, 0]);
+    // QNAME: a compression pointer to itself, at offset 12
+    query.extend([0xc0, 12]);
+    // QTYPE=A, QCLASS=IN
+    query.extend([0, 1, 0, 1]);
+
+    let response = client.send_raw(ns.ipv4_addr(), &query)?;
+
+    assert!(response.len() >= 12, "{response:02x?}");
+    assert_eq!(ID, response[..2], "{response:02x?}");
+    let is_response = response[2] & 0x80 != 0;
+    assert!(is_response, "{response:02x?}");
+    assert_eq!(FORMERR, response[3] & 0x0f, "{response:02x?}");
+
+    // the name server is still up
+    let output = client.dig(
+        DigSettings::default(),
+        ns.ipv4_addr(),
+        RecordType::SOA,
+        &FQDN::ROOT,
+    )?;
+    assert!(output.status.is_noerror(), "{output:#?}");
+
+    Ok(())
+}
+
+#[test]
+fn pipelined_tcp_queries() -> Result<()> {
+    let network = &Network::new()?;
+    let exchange = FQDN::TEST_DOMAIN.push_label("mail");
+    let ipv4_addr = Ipv4Addr::new(1, 2, 3, 4);
+
+    let mut ns = NameServer::new(&dns_test::SUBJECT, FQDN::TEST_DOMAIN, network)?;
+    ns.add(Record::a(FQDN::TEST_DOMAIN, ipv4_addr))
+        .add(Record::mx(FQDN::TEST_DOMAIN, 10, exchange.clone()));
+    let ns = ns.start()?;
+
+    let client = Client::new(network)?;
+    let [a_output, mx_output] = client
+        .dig_pipeline(
+            ns.ipv4_addr(),
+            &[
+                (RecordType::A, FQDN::TEST_DOMAIN),
+                (RecordType::MX, FQDN::TEST_DOMAIN),
+            ],
+        )?
+        .try_into()
+        .unwrap();
+
+    assert!(a_output.status.is_noerror(), "{a_output:#?}");
+    let [answer] = a_output.answer.try_into().unwrap();
+    assert_eq!(ipv4_addr, answer.try_into_a().unwrap().ipv4_addr);
+
+    assert!(mx_output.status.is_noerror(), "{mx_output:#?}");
+    let [answer] = mx_output.answer.try_into().unwrap();
+    let mx = answer.try_into_mx().unwrap();
+    assert_eq!(10, mx.preference);
+    assert_eq!(exchange, mx.exchange);
+
+    Ok(())
+}
+
+#[test]
+fn a_record_wire_encoding() -> Result<()> {
+    let network = &Network::new()?;
+    let ipv4_addr = Ipv4Addr::new(1, 2, 3, 4);
+
+    let mut ns = NameServer::new(&dns_test::SUBJECT, FQDN::TEST_DOMAIN, network)?;
+    ns.add(Record::a(FQDN::TEST_DOMAIN, ipv4_addr));
+    let ns = ns.start()?;
+
+    let client = Client::new(network)?;
+    let output = client.dig_wire(ns.ipv4_addr(), RecordType::A, &FQDN::TEST_DOMAIN)?;
+
+    assert!(output.status.is_noerror(), "{output:#?}");
+    let raw = output.raw.unwrap();
+
+    // the answer follows the header and the question (QNAME, QTYPE and QCLASS)
+    const HEADER_LEN: usize = 12;
+    let qname_len = FQDN::TEST_DOMAIN.wire_format()?.len();
+    let answer = &raw[HEADER_LEN + qname_len + 4..];
+
+    // the owner is a compression pointer to the QNAME
+    assert_eq!([0xc0, HEADER_LEN as u8], answer[..2], "{raw:02x?}");
+    // TYPE=A, CLASS=IN
+    assert_eq!([0, 1, 0, 1], answer[2..6], "{raw:02x?}");
+    // RDLENGTH and RDATA, after the 4-byte TTL
+    assert_eq!([0, 4], answer[10..12], "{raw:02x?}");
+    assert_eq!(ipv4_addr.octets(), answer[12..16], "{raw:02x?}");
+
+    Ok(())
+}
+
+#[test]
+fn authoritative_only_does_not_recurse() -> Result<()> {
+    let network = &Network::new()?;
+    let ns = NameServer::new(&dns_test::SUBJECT, FQDN::TEST_DOMAIN, network)?.start()?;
+
+    let client = Client::new(network)?;
+    let settings = *DigSettings::default().recurse();
+
+    let output = client.dig(
+        settings,
+        ns.ipv4_addr(),
+        RecordType::SOA,
+        &FQDN::TEST_DOMAIN,
+    )?;
+    assert!(output.status.is_noerror(), "{output:#?}");
+    assert!(output.flags.authoritative_answer);
+    assert!(!output.flags.recursion_available);
+
+    // a name outside the server's zone, which it could only answer by recursing
+    let needle = FQDN::COM_TLD.push_label("example");
+    let output = client.dig(settings, ns.ipv4_addr(), RecordType::A, &needle)?;
+    assert!(!output.flags.recursion_available);
+    assert!(output.answer.is_empty(), "{output:#?}");
+
+    Ok(())
+}
+
+#[test]
+fn allow_recursion_sets_recursion_available() -> Result<()> {
+    let expected_ipv4_addr = Ipv4Addr::new(1, 2, 3, 4);
+    let needle_fqdn = FQDN::COM_TLD.push_label("example");
+    let network = &Network::new()?;
+
+    let mut root_ns = NameServer::new(&Implementation::Bind, FQDN::ROOT, network)?;
+    let mut com_ns = NameServer::new(&Implementation::Bind, FQDN::COM_TLD, network)?;
+    com_ns.add(Record::a(needle_fqdn.clone(), expected_ipv4_addr));
+    root_ns.referral_nameserver(&com_ns);
+
+    let mut ns = NameServer::new(&Implementation::Bind, FQDN::TEST_DOMAIN, network)?;
+    ns.allow_recursion(root_ns.root_hint());
+
+    let _root_ns = root_ns.start()?;
+    let _com_ns = com_ns.start()?;
+    let ns = ns.start()?;
+
+    let client = Client::new(network)?;
+    let settings = *DigSettings::default().recurse();
+    let output = client.dig(
+        settings,
+        ns.ipv4_addr(),
+        RecordType::SOA,
+        &FQDN::TEST_DOMAIN,
+    )?;
+
+    assert!(output.status.is_noerror(), "{output:#?}");
+    assert!(output.flags.authoritative_answer);
+    assert!(output.flags.recursion_available);
+
+    // a name outside the server's zone, which it resolves from the test network's root
+    let output = client.dig(settings, ns.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+
+    assert!(output.status.is_noerror(), "{output:#?}");
+    assert!(!output.flags.authoritative_answer);
+    assert!(output.flags.recursion_available);
+    let [answer] = output.answer.try_into().unwrap();
+    assert_eq!(expected_ipv4_addr, answer.try_into_a().unwrap().ipv4_addr);
+
+    Ok(())
+}
+
+#[test]
+#[ignore = "hickory does not answer CHAOS class queries"]
+fn chaos_class_queries() -> Result<()> {
+    const VERSION: &str = "conformance-test-1.0";
+
+    let network = &Network::new()?;
+    let mut ns = NameServer::new(&dns_test::SUBJECT, FQDN::ROOT, network)?;
+    ns.set_version(VERSION);
+    let ns = ns.start()?;
+
+    let client = Client::new(network)?;
+    let settings = *DigSettings::default().class_chaos();
+
+    let version_bind = FQDN("version.bind.")?;
+    let output = client.dig(settings, ns.ipv4_addr(), RecordType::TXT, &version_bind)?;
+    assert!(output.status.is_noerror(), "{output:#?}");
+    let [answer] = output.answer.try_into().unwrap();
+    let txt = answer.try_into_txt().unwrap();
+    assert_eq!(version_bind, txt.zone);
+    assert_eq!(Class::CH, txt.class);
+    assert_eq!(vec![VERSION.to_string()], txt.character_strings);
+
+    for qname in ["hostname.bind.", "id.server."] {
+        let output = client.dig(settings, ns.ipv4_addr(), RecordType::TXT, &FQDN(qname)?)?;
+        assert!(output.status.is_noerror(), "{output:#?}");
+        let [answer] = output.answer.try_into().unwrap();
+        let txt = answer.try_into_txt().unwrap();
+        assert!(!txt.character_strings.concat().is_empty(), "{txt:?}");
+    }
+
+    Ok(())
+}
@@ -33,7 +33,7 @@ fn test_8_2_1_minimal_edns() -> Result<()> {
     assert_eq!(output.answer.len(), 1);
     assert!(output.answer[0].is_soa());
     assert!(output.opt);
-    assert_eq!(output.edns_version, Some(0));
+    assert_eq!(output.edns_version(), Some(0));
     assert!(!output.flags.authoritative_answer);
     assert!(!output.flags.authenticated_data);
 
@@ -59,7 +59,7 @@ fn test_8_2_2_edns_version_negotiation() -> Result<()> {
     assert_eq!(output.status, DigStatus::BADVERS);
     assert!(output.answer.is_empty());
     assert!(output.opt);
-    assert_eq!(output.edns_version, Some(0));
+    assert_eq!(output.edns_version(), Some(0));
     assert!(!output.flags.authoritative_answer);
     assert!(!output.flags.authenticated_data);
 
@@ -83,7 +83,7 @@ fn test_8_2_3_unknown_edns_options() -> Result<()> {
     assert!(output.answer[0].is_soa());
     assert!(output.opt);
     assert!(output.options.is_empty());
-    assert_eq!(output.edns_version, Some(0));
+    assert_eq!(output.edns_version(), Some(0));
     assert!(!output.flags.authoritative_answer);
     assert!(!output.flags.authenticated_data);
 
@@ -109,8 +109,8 @@ fn test_8_2_4_unknown_edns_flags() -> Result<()> {
     assert_eq!(output.answer.len(), 1);
     assert!(output.answer[0].is_soa());
     assert!(output.opt);
-    assert!(!output.edns_must_be_zero);
-    assert_eq!(output.edns_version, Some(0));
+    assert!(!output.edns_must_be_zero());
+    assert_eq!(output.edns_version(), Some(0));
     assert!(output.options.is_empty());
     assert!(!output.flags.authoritative_answer);
     assert!(!output.flags.authenticated_data);
@@ -138,8 +138,8 @@ fn test_8_2_5_edns_version_negotiation_with_unknown_edns_flags() -> Result<()> {
     assert_eq!(output.status, DigStatus::BADVERS);
     assert!(output.answer.is_empty());
     assert!(output.opt);
-    assert!(!output.edns_must_be_zero);
-    assert_eq!(output.edns_version, Some(0));
+    assert!(!output.edns_must_be_zero());
+    assert_eq!(output.edns_version(), Some(0));
     assert!(!output.flags.authoritative_answer);
     assert!(!output.flags.authenticated_data);
 
@@ -167,7 +167,7 @@ fn test_8_2_6_edns_version_negotiation_with_unknown_edns_options() -> Result<()>
     assert!(output.answer.is_empty());
     assert!(output.opt);
     assert!(output.options.is_empty());
-    assert_eq!(output.edns_version, Some(0));
+    assert_eq!(output.edns_version(), Some(0));
     assert!(!output.flags.authoritative_answer);
     assert!(!output.flags.authenticated_data);
 
@@ -196,7 +196,7 @@ fn test_8_2_7_truncated_responses() -> Result<()> {
 
     assert_eq!(output.status, DigStatus::NOERROR);
     assert!(output.opt);
-    assert_eq!(output.edns_version, Some(0));
+    assert_eq!(output.edns_version(), Some(0));
 
     Ok(())
 }
@@ -221,8 +221,8 @@ fn test_8_2_8_do_1() -> Result<()> {
     assert!(!output.answer.is_empty());
     assert!(output.answer[0].is_soa());
     assert!(output.opt);
-    assert!(output.dnssec_ok_flag);
-    assert_eq!(output.edns_version, Some(0));
+    assert!(output.dnssec_ok_flag());
+    assert_eq!(output.edns_version(), Some(0));
     assert!(!output.flags.authoritative_answer);
 
     Ok(())
@@ -248,8 +248,8 @@ fn test_8_2_9_edns_version_negotiation_with_do_1() -> Result<()> {
     assert_eq!(output.status, DigStatus::BADVERS);
     assert!(output.answer.is_empty());
     assert!(output.opt);
-    assert!(output.dnssec_ok_flag);
-    assert_eq!(output.edns_version, Some(0));
+    assert!(output.dnssec_ok_flag());
+    assert_eq!(output.edns_version(), Some(0));
     assert!(!output.flags.authoritative_answer);
 
     Ok(())
@@ -275,7 +275,7 @@ fn test_8_2_10_multiple_defined_edns_options() -> Result<()> {
     assert_eq!(output.answer.len(), 1);
     assert!(output.answer[0].is_soa());
     assert!(output.opt);
-    assert_eq!(output.edns_version, Some(0));
+    assert_eq!(output.edns_version(), Some(0));
     assert!(!output.flags.authoritative_answer);
     assert!(!output.flags.authenticated_data);
 
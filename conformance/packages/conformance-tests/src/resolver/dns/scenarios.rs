@@ -1,13 +1,36 @@
 use std::net::Ipv4Addr;
+use std::time::Duration;
 
+use dns_test::bench::{BenchSettings, Dnsperf};
 use dns_test::client::{Client, DigSettings};
 use dns_test::name_server::{Graph, NameServer, Sign};
 use dns_test::record::{Record, RecordType};
 use dns_test::tshark::{Capture, Direction};
 use dns_test::{FQDN, Network, Resolver, Result};
 
+mod alias;
 mod bad_referral;
+mod cached_ttl;
+mod cname_and_other_data;
+mod dns64;
+mod edns_negotiation;
+mod encrypted_transport;
+mod glue;
+mod ipv6;
+mod negative_ttl;
 mod packet_loss;
+mod partition;
+mod prefetch;
+mod qname_minimization;
+mod reverse_lookup;
+mod serve_stale;
+mod servfail_cache;
+mod sibling_ns;
+mod slow_upstream;
+mod spoofing;
+mod upstream_ede;
+mod upstream_retry;
+mod zero_ttl;
 
 #[test]
 fn can_resolve() -> Result<()> {
@@ -34,6 +57,8 @@ fn can_resolve() -> Result<()> {
     let output = client.dig(settings, resolver_ip_addr, RecordType::A, &needle_fqdn)?;
 
     assert!(output.status.is_noerror());
+    assert!(output.flags.recursion_available);
+    assert!(!output.flags.authoritative_answer);
 
     let [answer] = output.answer.try_into().unwrap();
     let a = answer.try_into_a().unwrap();
@@ -129,3 +154,122 @@ fn recursion_desired_flag() -> Result<()> {
 
     Ok(())
 }
+
+// the leaf zone's name server answers authoritatively but the resolver's answer to the same
+// query is not authoritative (RFC1035 section 4.1.1)
+#[test]
+fn authoritative_answer_flag() -> Result<()> {
+    let needle_fqdn = FQDN::EXAMPLE_SUBDOMAIN;
+
+    let network = Network::new()?;
+
+    let mut leaf_ns = NameServer::new(&dns_test::PEER, FQDN::TEST_DOMAIN, &network)?;
+    leaf_ns.add(Record::a(needle_fqdn.clone(), Ipv4Addr::new(1, 2, 3, 4)));
+
+    let Graph {
+        nameservers, root, ..
+    } = Graph::build(leaf_ns, Sign::No)?;
+    let leaf_ns = &nameservers[0];
+    assert_eq!(&FQDN::TEST_DOMAIN, leaf_ns.zone());
+
+    let resolver = Resolver::new(&network, root).start()?;
+    let client = Client::new(&network)?;
+    let settings = *DigSettings::default().recurse();
+
+    let output = client.dig(settings, leaf_ns.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+    assert!(output.status.is_noerror(), "{output:#?}");
+    assert!(output.flags.authoritative_answer, "{output:#?}");
+
+    let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+    assert!(output.status.is_noerror(), "{output:#?}");
+    assert!(!output.flags.authoritative_answer, "{output:#?}");
+
+    Ok(())
+}
+
+#[test]
+fn concurrent_identical_queries_are_deduplicated() -> Result<()> {
+    const NUM_QUERIES: usize = 50;
+    // queries that arrive slightly staggered may each miss the cache before the first upstream
+    // query completes; allow a few of those
+    const MAX_UPSTREAM_QUERIES: usize = 3;
+
+    let needle_fqdn = FQDN::EXAMPLE_SUBDOMAIN;
+
+    let network = Network::new()?;
+
+    let mut leaf_ns = NameServer::new(&dns_test::PEER, FQDN::TEST_DOMAIN, &network)?;
+    leaf_ns.add(Record::a(needle_fqdn.clone(), Ipv4Addr::new(1, 2, 3, 4)));
+
+    let Graph {
+        nameservers, root, ..
+    } = Graph::build(leaf_ns, Sign::No)?;
+    let leaf_ns = &nameservers[0];
+    assert_eq!(&FQDN::TEST_DOMAIN, leaf_ns.zone());
+
+    let resolver = Resolver::new(&network, root).start()?;
+
+    let mut tshark = leaf_ns.eavesdrop()?;
+
+    // all queries are sent back to back, without waiting for responses
+    let dnsperf = Dnsperf::new(&network)?;
+    let settings = *BenchSettings::default()
+        .max_runs(1)
+        .duration(Duration::from_secs(10));
+    let queries = vec![(RecordType::A, needle_fqdn.clone()); NUM_QUERIES];
+    let report = dnsperf.bench(settings, resolver.ipv4_addr(), &queries)?;
+
+    assert_eq!(NUM_QUERIES as u64, report.queries_completed, "{report:?}");
+
+    let upstream_queries =
+        |captures: &[Capture]| Capture::count_incoming_queries_for(captures, &needle_fqdn);
+    tshark.wait_until(
+        |captures| upstream_queries(captures) > 0,
+        Duration::from_secs(10),
+    )?;
+    let captures = tshark.terminate()?;
+
+    let upstream_queries = upstream_queries(&captures);
+    assert!(
+        upstream_queries <= MAX_UPSTREAM_QUERIES,
+        "the name server received {upstream_queries} queries for {needle_fqdn}; expected at most {MAX_UPSTREAM_QUERIES}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn no_traffic_leaves_test_network() -> Result<()> {
+    let expected_ipv4_addr = Ipv4Addr::new(1, 2, 3, 4);
+    let needle_fqdn = FQDN::EXAMPLE_SUBDOMAIN;
+
+    // a misconfigured resolver could reach the real root servers from this network
+    let network = Network::with_internet_access()?;
+
+    let mut leaf_ns = NameServer::new(&dns_test::PEER, FQDN::TEST_DOMAIN, &network)?;
+    leaf_ns.add(Record::a(needle_fqdn.clone(), expected_ipv4_addr));
+
+    let Graph {
+        nameservers: _nameservers,
+        root,
+        ..
+    } = Graph::build(leaf_ns, Sign::No)?;
+
+    let resolver = Resolver::new(&network, root).start()?;
+    let mut tshark = resolver.eavesdrop()?;
+
+    let client = Client::new(&network)?;
+    let settings = *DigSettings::default().recurse();
+    let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+
+    assert!(output.status.is_noerror());
+    let [answer] = output.answer.try_into().unwrap();
+    assert_eq!(expected_ipv4_addr, answer.try_into_a().unwrap().ipv4_addr);
+
+    tshark.wait_for_capture()?;
+    let captures = tshark.terminate()?;
+
+    network.assert_no_leaks(&captures);
+
+    Ok(())
+}
@@ -0,0 +1,114 @@
+//! Test that resolvers follow aliases: CNAME (RFC1034 section 3.6.2) and DNAME (RFC6672)
+
+use std::net::Ipv4Addr;
+
+use dns_test::client::{Client, DigSettings};
+use dns_test::name_server::{Graph, NameServer, Sign};
+use dns_test::record::{Record, RecordType};
+use dns_test::{FQDN, Network, Resolver, Result};
+
+const TARGET_ADDR: Ipv4Addr = Ipv4Addr::new(1, 2, 3, 4);
+
+#[test]
+fn cname_chain() -> Result<()> {
+    let alias_fqdn = FQDN::TEST_DOMAIN.push_label("a");
+    let intermediate_fqdn = FQDN::TEST_DOMAIN.push_label("b");
+    let target_fqdn = FQDN::TEST_DOMAIN.push_label("c");
+
+    let network = Network::new()?;
+
+    let mut leaf_ns = NameServer::new(&dns_test::PEER, FQDN::TEST_DOMAIN, &network)?;
+    leaf_ns
+        .add(Record::cname(alias_fqdn.clone(), intermediate_fqdn.clone()))
+        .add(Record::cname(
+            intermediate_fqdn.clone(),
+            target_fqdn.clone(),
+        ))
+        .add(Record::a(target_fqdn.clone(), TARGET_ADDR));
+
+    let Graph {
+        nameservers: _nameservers,
+        root,
+        ..
+    } = Graph::build(leaf_ns, Sign::No)?;
+
+    let resolver = Resolver::new(&network, root).start()?;
+    let client = Client::new(&network)?;
+    let settings = *DigSettings::default().recurse();
+    let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &alias_fqdn)?;
+
+    assert!(output.status.is_noerror(), "{output:#?}");
+
+    let chain = output
+        .cname_chain(&alias_fqdn)
+        .into_iter()
+        .map(|cname| (cname.fqdn.clone(), cname.target.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        [
+            (alias_fqdn, intermediate_fqdn.clone()),
+            (intermediate_fqdn, target_fqdn.clone()),
+        ],
+        chain.as_slice()
+    );
+
+    let addrs = output
+        .answer
+        .iter()
+        .filter_map(|record| record.clone().try_into_a().ok())
+        .map(|a| (a.fqdn, a.ipv4_addr))
+        .collect::<Vec<_>>();
+    assert_eq!([(target_fqdn, TARGET_ADDR)], addrs.as_slice());
+
+    Ok(())
+}
+
+#[test]
+fn dname_synthesizes_cname() -> Result<()> {
+    let dname_owner = FQDN::TEST_DOMAIN.push_label("old");
+    let dname_target = FQDN::TEST_DOMAIN.push_label("new");
+    let needle_fqdn = dname_owner.push_label("host");
+    let target_fqdn = dname_target.push_label("host");
+
+    let network = Network::new()?;
+
+    let mut leaf_ns = NameServer::new(&dns_test::PEER, FQDN::TEST_DOMAIN, &network)?;
+    leaf_ns
+        .add(Record::dname(dname_owner.clone(), dname_target.clone()))
+        .add(Record::a(target_fqdn.clone(), TARGET_ADDR));
+
+    let Graph {
+        nameservers: _nameservers,
+        root,
+        ..
+    } = Graph::build(leaf_ns, Sign::No)?;
+
+    let resolver = Resolver::new(&network, root).start()?;
+    let client = Client::new(&network)?;
+    let settings = *DigSettings::default().recurse();
+    let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+
+    assert!(output.status.is_noerror(), "{output:#?}");
+
+    let dname = output
+        .answer
+        .iter()
+        .find_map(|record| record.clone().try_into_dname().ok())
+        .expect("DNAME record in the answer section");
+    assert_eq!(dname_owner, dname.fqdn);
+    assert_eq!(dname_target, dname.target);
+
+    // RFC6672 section 2.2: the CNAME is synthesized by substituting the DNAME owner with its target
+    let [synthesized] = output.cname_chain(&needle_fqdn).try_into().unwrap();
+    assert_eq!(target_fqdn, synthesized.target);
+
+    assert!(
+        output
+            .answer
+            .iter()
+            .any(|record| matches!(record, Record::A(a) if a.fqdn == target_fqdn && a.ipv4_addr == TARGET_ADDR)),
+        "{output:#?}"
+    );
+
+    Ok(())
+}
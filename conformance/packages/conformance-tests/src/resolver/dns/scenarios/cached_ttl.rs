@@ -0,0 +1,96 @@
+//! Test that resolvers count down the TTL of cached records (RFC1035 section 3.2.1) and answer
+//! from their cache
+
+use std::net::Ipv4Addr;
+use std::thread;
+use std::time::Duration;
+
+use dns_test::client::{Client, DigSettings};
+use dns_test::name_server::{Graph, NameServer, Sign};
+use dns_test::record::{Record, RecordType};
+use dns_test::{FQDN, Network, Resolver, Result};
+
+const TTL: u32 = 5;
+/// Long enough for the TTL to tick down but short enough for the record to stay cached
+const WAIT: Duration = Duration::from_secs(2);
+/// Added to every packet sent to the leaf zone's name server
+const UPSTREAM_DELAY: Duration = Duration::from_millis(500);
+
+#[test]
+fn cached_ttl_counts_down() -> Result<()> {
+    let needle_fqdn = FQDN::EXAMPLE_SUBDOMAIN;
+
+    let network = Network::new()?;
+
+    let mut leaf_ns = NameServer::new(&dns_test::PEER, FQDN::TEST_DOMAIN, &network)?;
+    leaf_ns.add(Record::a(needle_fqdn.clone(), Ipv4Addr::new(1, 2, 3, 4)).with_ttl(TTL));
+
+    let Graph {
+        nameservers: _nameservers,
+        root,
+        ..
+    } = Graph::build(leaf_ns, Sign::No)?;
+
+    let resolver = Resolver::new(&network, root).start()?;
+    let client = Client::new(&network)?;
+    let settings = *DigSettings::default().recurse();
+
+    let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+    assert!(output.status.is_noerror(), "{output:#?}");
+    let [answer] = output.answer.try_into().unwrap();
+    let first_ttl = answer.ttl();
+    assert!(first_ttl <= TTL, "{answer:?}");
+
+    thread::sleep(WAIT);
+
+    let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+    assert!(output.status.is_noerror(), "{output:#?}");
+    let [answer] = output.answer.try_into().unwrap();
+    let second_ttl = answer.ttl();
+
+    assert!(
+        second_ttl < first_ttl,
+        "TTL went from {first_ttl} to {second_ttl} after {WAIT:?}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn cached_response_is_faster() -> Result<()> {
+    let needle_fqdn = FQDN::EXAMPLE_SUBDOMAIN;
+
+    let network = Network::new()?;
+
+    let mut leaf_ns = NameServer::new(&dns_test::PEER, FQDN::TEST_DOMAIN, &network)?;
+    leaf_ns.add(Record::a(needle_fqdn.clone(), Ipv4Addr::new(1, 2, 3, 4)));
+
+    let Graph {
+        nameservers, root, ..
+    } = Graph::build(leaf_ns, Sign::No)?;
+    let leaf_ns = &nameservers[0];
+    assert_eq!(&FQDN::TEST_DOMAIN, leaf_ns.zone());
+
+    let resolver = Resolver::new(&network, root).start()?;
+    let client = Client::new(&network)?;
+    let settings = *DigSettings::default().recurse().timeout(10);
+
+    network.set_impairment(&leaf_ns.ipv4_addr(), UPSTREAM_DELAY, 0.)?;
+
+    let query_time = || -> Result<Duration> {
+        let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+        assert!(output.status.is_noerror(), "{output:#?}");
+        Ok(output.query_time.expect("dig reports the query time"))
+    };
+
+    let uncached = query_time()?;
+    assert!(
+        uncached >= UPSTREAM_DELAY,
+        "uncached query took {uncached:?}"
+    );
+
+    let cached = query_time()?;
+    assert!(cached < UPSTREAM_DELAY, "cached query took {cached:?}");
+
+    Ok(())
+}
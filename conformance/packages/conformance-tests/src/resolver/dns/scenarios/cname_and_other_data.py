@@ -0,0 +1,16 @@
+#!/usr/bin/env python3
+# This server answers queries straight from the zone file. Unlike BIND and NSD,
+# dnslib loads zones that contain a CNAME next to other data at the same name
+# and includes both in its answers.
+from dnslib.server import DNSServer
+from dnslib.zoneresolver import ZoneResolver
+
+ZONE_FILE_PATH = "/etc/zones/main.zone"
+
+
+if __name__ == "__main__":
+    with open(ZONE_FILE_PATH) as zone_file:
+        resolver = ZoneResolver(zone_file.read())
+
+    server = DNSServer(resolver, address="0.0.0.0", port=53)
+    server.start()
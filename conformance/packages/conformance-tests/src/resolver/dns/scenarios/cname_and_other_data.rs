@@ -0,0 +1,120 @@
+//! Test how resolvers handle a zone where a CNAME shares its owner name with other data, which
+//! RFC1034 section 3.6.2 forbids
+//!
+//! This is a common misconfiguration. BIND and NSD refuse to load such a zone so the authoritative
+//! server for it is a dnslib one
+
+use std::fs;
+use std::net::Ipv4Addr;
+
+use dns_test::client::{Client, DigOutput, DigSettings};
+use dns_test::name_server::NameServer;
+use dns_test::record::{Record, RecordType};
+use dns_test::{FQDN, Implementation, Network, PEER, Resolver, Result};
+
+const TARGET_ADDR: Ipv4Addr = Ipv4Addr::new(1, 2, 3, 4);
+const SIBLING_ADDR: Ipv4Addr = Ipv4Addr::new(5, 6, 7, 8);
+
+/// What the resolver made of an answer that contains a CNAME and an A record owned by the same name
+#[derive(Debug)]
+enum Behavior {
+    /// The CNAME was followed and the other data at its owner name was ignored
+    FollowsCname,
+    /// The CNAME was ignored and the other data was returned as the answer
+    UsesOtherData,
+    /// The zone was treated as broken
+    Servfail,
+}
+
+impl Behavior {
+    fn classify(output: &DigOutput, needle_fqdn: &FQDN, target_fqdn: &FQDN) -> Option<Self> {
+        if output.status.is_servfail() {
+            return Some(Self::Servfail);
+        }
+
+        if !output.status.is_noerror() {
+            return None;
+        }
+
+        let mut cnames = vec![];
+        let mut addrs = vec![];
+        for record in &output.answer {
+            match record {
+                Record::CNAME(cname) => cnames.push((cname.fqdn.clone(), cname.target.clone())),
+                Record::A(a) => addrs.push((a.fqdn.clone(), a.ipv4_addr)),
+                _ => return None,
+            }
+        }
+
+        if cnames == [(needle_fqdn.clone(), target_fqdn.clone())]
+            && addrs == [(target_fqdn.clone(), TARGET_ADDR)]
+        {
+            Some(Self::FollowsCname)
+        } else if cnames.is_empty() && addrs == [(needle_fqdn.clone(), SIBLING_ADDR)] {
+            Some(Self::UsesOtherData)
+        } else {
+            None
+        }
+    }
+}
+
+#[test]
+fn cname_and_other_data_at_same_name() -> Result<()> {
+    let needle_fqdn = FQDN::TEST_TLD.push_label("alias");
+    let target_fqdn = FQDN::TEST_TLD.push_label("target");
+    let network = Network::new()?;
+
+    let mut root_ns = NameServer::new(&PEER, FQDN::ROOT, &network)?;
+    let mut leaf_ns = NameServer::new(&Implementation::Dnslib, FQDN::TEST_TLD, &network)?;
+    let script = fs::read_to_string("src/resolver/dns/scenarios/cname_and_other_data.py")?;
+    leaf_ns.cp("/script.py", &script)?;
+
+    // the zone file API doesn't enforce CNAME exclusivity so the illegal combination can be
+    // authored directly
+    leaf_ns
+        .add(Record::cname(needle_fqdn.clone(), target_fqdn.clone()))
+        .add(Record::a(needle_fqdn.clone(), SIBLING_ADDR))
+        .add(Record::a(target_fqdn.clone(), TARGET_ADDR));
+
+    root_ns.referral_nameserver(&leaf_ns);
+
+    let resolver = Resolver::new(&network, root_ns.root_hint()).start()?;
+    let client = Client::new(&network)?;
+
+    let _root_ns = root_ns.start()?;
+    let leaf_ns = leaf_ns.start()?;
+
+    // PRE-CONDITION the authoritative server serves both the CNAME and the other data
+    let output = client.dig(
+        DigSettings::default(),
+        leaf_ns.ipv4_addr(),
+        RecordType::A,
+        &needle_fqdn,
+    )?;
+    assert!(output.status.is_noerror(), "{output:#?}");
+    assert!(
+        output
+            .answer
+            .iter()
+            .any(|record| matches!(record, Record::CNAME(_))),
+        "{output:#?}"
+    );
+    assert!(
+        output
+            .answer
+            .iter()
+            .any(|record| matches!(record, Record::A(a) if a.fqdn == needle_fqdn)),
+        "{output:#?}"
+    );
+
+    let settings = *DigSettings::default().recurse();
+    let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+
+    // any of these is defensible but the resolver must not mix them, e.g. return the CNAME along
+    // with the A record that it was supposed to replace
+    let behavior = Behavior::classify(&output, &needle_fqdn, &target_fqdn);
+    assert!(behavior.is_some(), "{output:#?}");
+    println!("{:?}: {behavior:?}", *dns_test::SUBJECT);
+
+    Ok(())
+}
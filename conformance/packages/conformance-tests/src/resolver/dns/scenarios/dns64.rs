@@ -0,0 +1,95 @@
+//! Test that resolvers configured for DNS64 (RFC6147) synthesize AAAA records from A records
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use dns_test::client::{Client, DigOutput, DigSettings};
+use dns_test::name_server::{Graph, NameServer, Sign};
+use dns_test::record::{Record, RecordType};
+use dns_test::{FQDN, Network, Resolver, Result};
+
+const IPV4_ADDR: Ipv4Addr = Ipv4Addr::new(1, 2, 3, 4);
+/// `64:ff9b::/96` (RFC6052 section 2.1)
+const WELL_KNOWN_PREFIX: Ipv6Addr = Ipv6Addr::new(0x64, 0xff9b, 0, 0, 0, 0, 0, 0);
+const CUSTOM_PREFIX: Ipv6Addr = Ipv6Addr::new(0x2001, 0xdb8, 0x64, 0, 0, 0, 0, 0);
+
+#[test]
+#[ignore = "hickory does not support DNS64"]
+fn synthesizes_aaaa_with_well_known_prefix() -> Result<()> {
+    let output = fixture(WELL_KNOWN_PREFIX, None)?;
+
+    assert_eq!(vec![synthesized(WELL_KNOWN_PREFIX)], aaaa_addrs(&output));
+
+    Ok(())
+}
+
+#[test]
+#[ignore = "hickory does not support DNS64"]
+fn synthesizes_aaaa_with_custom_prefix() -> Result<()> {
+    let output = fixture(CUSTOM_PREFIX, None)?;
+
+    assert_eq!(vec![synthesized(CUSTOM_PREFIX)], aaaa_addrs(&output));
+
+    Ok(())
+}
+
+#[test]
+#[ignore = "hickory does not support DNS64"]
+fn native_aaaa_is_not_synthesized() -> Result<()> {
+    let native = Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1);
+    let output = fixture(WELL_KNOWN_PREFIX, Some(native))?;
+
+    assert_eq!(vec![native], aaaa_addrs(&output));
+
+    Ok(())
+}
+
+/// The AAAA record a DNS64 resolver synthesizes from `IPV4_ADDR` with a /96 `prefix`
+fn synthesized(prefix: Ipv6Addr) -> Ipv6Addr {
+    Ipv6Addr::from(u128::from(prefix) | u128::from(u32::from(IPV4_ADDR)))
+}
+
+fn aaaa_addrs(output: &DigOutput) -> Vec<Ipv6Addr> {
+    assert!(output.status.is_noerror(), "{output:#?}");
+
+    output
+        .answer
+        .iter()
+        .filter_map(|record| match record {
+            Record::AAAA(aaaa) => Some(aaaa.ipv6_addr),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Queries the AAAA records of a name that has an A record, and optionally a `native_aaaa`
+/// record, through a resolver that uses the /96 `prefix` for DNS64
+fn fixture(prefix: Ipv6Addr, native_aaaa: Option<Ipv6Addr>) -> Result<DigOutput> {
+    let needle_fqdn = FQDN::EXAMPLE_SUBDOMAIN;
+
+    let network = Network::new()?;
+
+    let mut leaf_ns = NameServer::new(&dns_test::PEER, FQDN::TEST_DOMAIN, &network)?;
+    leaf_ns.add(Record::a(needle_fqdn.clone(), IPV4_ADDR));
+    if let Some(ipv6_addr) = native_aaaa {
+        leaf_ns.add(Record::aaaa(needle_fqdn.clone(), ipv6_addr));
+    }
+
+    let Graph {
+        nameservers: _nameservers,
+        root,
+        ..
+    } = Graph::build(leaf_ns, Sign::No)?;
+
+    let resolver = Resolver::new(&network, root)
+        .dns64(format!("{prefix}/96").parse()?)
+        .start()?;
+    let client = Client::new(&network)?;
+    let settings = *DigSettings::default().recurse();
+
+    client.dig(
+        settings,
+        resolver.ipv4_addr(),
+        RecordType::AAAA,
+        &needle_fqdn,
+    )
+}
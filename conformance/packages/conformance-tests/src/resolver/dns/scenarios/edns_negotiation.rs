@@ -0,0 +1,134 @@
+//! Test the EDNS UDP payload sizes (RFC6891 section 6.2.3) used on both hops of a resolution:
+//! client to resolver and resolver to authoritative server
+
+use std::time::Duration;
+
+use dns_test::client::{Client, DigSettings};
+use dns_test::name_server::{Graph, NameServer, Sign};
+use dns_test::record::{Record, RecordType};
+use dns_test::tshark::{Capture, Direction};
+use dns_test::{FQDN, Network, Resolver, Result};
+
+const CLIENT_BUFSIZE: u16 = 1400;
+const RESOLVER_BUFSIZE: u16 = 1232;
+/// Enough 250-byte TXT records to exceed both buffer sizes
+const NUM_TXT_RECORDS: usize = 8;
+
+#[test]
+#[ignore = "hickory-recursor does not support configuring its EDNS buffer size"]
+fn buffer_sizes_are_negotiated_per_hop() -> Result<()> {
+    let needle_fqdn = FQDN::EXAMPLE_SUBDOMAIN;
+
+    let network = Network::new()?;
+
+    let mut leaf_ns = NameServer::new(&dns_test::PEER, FQDN::TEST_DOMAIN, &network)?;
+    for index in 0..NUM_TXT_RECORDS {
+        let value = index.to_string().repeat(250);
+        leaf_ns.add(Record::txt(needle_fqdn.clone(), &value));
+    }
+
+    let Graph {
+        nameservers, root, ..
+    } = Graph::build(leaf_ns, Sign::No)?;
+    let leaf_ns = &nameservers[0];
+    assert_eq!(&FQDN::TEST_DOMAIN, leaf_ns.zone());
+
+    let resolver = Resolver::new(&network, root)
+        .edns_buffer_size(RESOLVER_BUFSIZE)
+        .start()?;
+    let client = Client::new(&network)?;
+
+    let mut resolver_tshark = resolver.eavesdrop()?;
+    let mut leaf_tshark = leaf_ns.eavesdrop()?;
+
+    // dig retries over TCP when the response is truncated, which tshark does not capture
+    let settings = *DigSettings::default().recurse().bufsize(CLIENT_BUFSIZE);
+    let output = client.dig(
+        settings,
+        resolver.ipv4_addr(),
+        RecordType::TXT,
+        &needle_fqdn,
+    )?;
+    assert!(output.status.is_noerror(), "{output:#?}");
+    assert_eq!(NUM_TXT_RECORDS, output.answer.len());
+
+    let is_response_about_needle = |capture: &Capture| {
+        capture.message.is_response()
+            && capture.message.query_name() == Some(needle_fqdn.as_str().trim_end_matches('.'))
+    };
+
+    leaf_tshark.wait_until(
+        |captures| captures.iter().any(is_response_about_needle),
+        Duration::from_secs(5),
+    )?;
+    resolver_tshark.wait_until(
+        |captures| {
+            captures.iter().any(|capture| {
+                is_response_about_needle(capture)
+                    && capture.direction.peer_addr() == client.ipv4_addr()
+            })
+        },
+        Duration::from_secs(5),
+    )?;
+    let leaf_captures = leaf_tshark.terminate()?;
+    let resolver_captures = resolver_tshark.terminate()?;
+
+    // client -> resolver
+    let client_queries = resolver_captures
+        .iter()
+        .filter(|capture| {
+            capture.is_incoming_query_for(&needle_fqdn)
+                && capture.direction.peer_addr() == client.ipv4_addr()
+        })
+        .collect::<Vec<_>>();
+    assert!(!client_queries.is_empty());
+    for capture in &client_queries {
+        assert_eq!(Some(CLIENT_BUFSIZE), capture.message.udp_payload_size());
+    }
+
+    // resolver -> authoritative server
+    let upstream_queries = resolver_captures
+        .iter()
+        .filter(|capture| {
+            matches!(capture.direction, Direction::Outgoing { .. })
+                && !capture.message.is_response()
+        })
+        .collect::<Vec<_>>();
+    assert!(!upstream_queries.is_empty());
+    for capture in &upstream_queries {
+        assert_eq!(
+            Some(RESOLVER_BUFSIZE),
+            capture.message.udp_payload_size(),
+            "{:#?}",
+            capture.message
+        );
+    }
+
+    // the complete answer exceeds both buffer sizes so it's truncated on both hops and then
+    // retrieved over TCP
+    let leaf_responses = leaf_captures
+        .iter()
+        .filter(|capture| is_response_about_needle(capture))
+        .collect::<Vec<_>>();
+    assert!(
+        leaf_responses
+            .iter()
+            .all(|capture| capture.message.is_tc_flag_set()),
+        "{leaf_responses:#?}"
+    );
+
+    let client_responses = resolver_captures
+        .iter()
+        .filter(|capture| {
+            is_response_about_needle(capture) && capture.direction.peer_addr() == client.ipv4_addr()
+        })
+        .collect::<Vec<_>>();
+    assert!(
+        client_responses
+            .iter()
+            .all(|capture| capture.message.is_tc_flag_set()),
+        "{client_responses:#?}"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+//! Test that resolvers answer queries sent over encrypted transports like they answer plain DNS
+//! ones
+
+use std::net::Ipv4Addr;
+
+use dns_test::client::{Client, DigSettings};
+use dns_test::name_server::{Graph, NameServer, Sign};
+use dns_test::record::{Record, RecordType};
+use dns_test::{FQDN, Network, Resolver, Result};
+
+#[test]
+#[ignore = "the hickory-dns image is not built with DNS-over-TLS support"]
+fn can_resolve_over_tls() -> Result<()> {
+    fixture(Transport::Tls)
+}
+
+#[test]
+#[ignore = "the hickory-dns image is not built with DNS-over-HTTPS support"]
+fn can_resolve_over_https() -> Result<()> {
+    fixture(Transport::Https)
+}
+
+enum Transport {
+    Tls,
+    Https,
+}
+
+fn fixture(transport: Transport) -> Result<()> {
+    let expected_ipv4_addr = Ipv4Addr::new(1, 2, 3, 4);
+    let needle_fqdn = FQDN::EXAMPLE_SUBDOMAIN;
+
+    let network = Network::new()?;
+
+    let mut leaf_ns = NameServer::new(&dns_test::PEER, FQDN::TEST_DOMAIN, &network)?;
+    leaf_ns.add(Record::a(needle_fqdn.clone(), expected_ipv4_addr));
+
+    let Graph {
+        nameservers: _nameservers,
+        root,
+        ..
+    } = Graph::build(leaf_ns, Sign::No)?;
+
+    let mut resolver = Resolver::new(&network, root);
+    let mut settings = DigSettings::default();
+    settings.recurse();
+    match transport {
+        Transport::Tls => {
+            resolver.tls();
+            settings.tls();
+        }
+        Transport::Https => {
+            resolver.https();
+            settings.https();
+        }
+    }
+    let resolver = resolver.start()?;
+    let client = Client::new(&network)?;
+
+    let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+
+    assert!(output.status.is_noerror(), "{output:#?}");
+    let [answer] = output.answer.try_into().unwrap();
+    let a = answer.try_into_a().unwrap();
+    assert_eq!(needle_fqdn, a.fqdn);
+    assert_eq!(expected_ipv4_addr, a.ipv4_addr);
+
+    // plain DNS keeps working
+    let settings = *DigSettings::default().recurse();
+    let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+    assert!(output.status.is_noerror(), "{output:#?}");
+
+    Ok(())
+}
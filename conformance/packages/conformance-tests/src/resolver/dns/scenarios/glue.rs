@@ -0,0 +1,80 @@
+//! Glue records are only good enough to follow a referral. When a client asks for the glued
+//! address record, the resolver must fetch it from the zone that's authoritative for it
+//! (RFC2181 section 5.4.1)
+
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use dns_test::client::{Client, DigSettings};
+use dns_test::name_server::NameServer;
+use dns_test::record::{Record, RecordType};
+use dns_test::tshark::Capture;
+use dns_test::{FQDN, Network, PEER, Resolver, Result};
+
+#[test]
+fn glue_is_not_served_as_answer() -> Result<()> {
+    let expected_ipv4_addr = Ipv4Addr::new(1, 2, 3, 4);
+    let needle_fqdn = FQDN::EXAMPLE_SUBDOMAIN;
+    // in-bailiwick so the parent zone must provide glue for it
+    let nameserver_fqdn = FQDN::TEST_DOMAIN.push_label("ns");
+
+    let network = Network::new()?;
+
+    let mut leaf_ns = NameServer::builder(PEER.clone(), FQDN::TEST_DOMAIN, network.clone())
+        .nameserver_fqdn(nameserver_fqdn.clone())
+        .build()?;
+    leaf_ns.add(Record::a(needle_fqdn.clone(), expected_ipv4_addr));
+
+    let mut tld_ns = NameServer::new(&PEER, FQDN::TEST_TLD, &network)?;
+    tld_ns.referral_nameserver(&leaf_ns);
+
+    let mut root_ns = NameServer::new(&PEER, FQDN::ROOT, &network)?;
+    root_ns.referral_nameserver(&tld_ns);
+
+    let resolver = Resolver::new(&network, root_ns.root_hint()).start()?;
+    let client = Client::new(&network)?;
+    let settings = *DigSettings::default().recurse();
+
+    let _root_ns = root_ns.start()?;
+    let _tld_ns = tld_ns.start()?;
+    let leaf_ns = leaf_ns.start()?;
+
+    let mut tshark = leaf_ns.eavesdrop()?;
+
+    // following the referral puts the glue in the resolver's cache
+    let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+    assert!(output.status.is_noerror(), "{output:#?}");
+    let [answer] = output.answer.try_into().unwrap();
+    assert_eq!(expected_ipv4_addr, answer.try_into_a().unwrap().ipv4_addr);
+
+    let output = client.dig(
+        settings,
+        resolver.ipv4_addr(),
+        RecordType::A,
+        &nameserver_fqdn,
+    )?;
+    assert!(output.status.is_noerror(), "{output:#?}");
+    let [answer] = output.answer.try_into().unwrap();
+    let a = answer.try_into_a().unwrap();
+    assert_eq!(nameserver_fqdn, a.fqdn);
+    assert_eq!(leaf_ns.ipv4_addr(), a.ipv4_addr);
+
+    // the resolver may have fetched the authoritative record while following the referral, and
+    // answered the second query from its cache, so it's enough that it was fetched at some point
+    let authoritative_lookups =
+        |captures: &[Capture]| Capture::count_incoming_queries_for(captures, &nameserver_fqdn);
+    let fetched = tshark
+        .wait_until(
+            |captures| authoritative_lookups(captures) > 0,
+            Duration::from_secs(5),
+        )
+        .is_ok();
+    let captures = tshark.terminate()?;
+
+    assert!(
+        fetched,
+        "the resolver answered with the glue from the parent zone: {captures:#?}"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+//! Test resolution over an IPv6 network, using AAAA glue and root hints
+
+use std::net::Ipv6Addr;
+
+use dns_test::client::{Client, DigSettings};
+use dns_test::name_server::{Graph, NameServer, Sign};
+use dns_test::record::{Record, RecordType};
+use dns_test::{FQDN, Network, Resolver, Result};
+
+#[test]
+#[ignore = "the hickory resolver can't be restricted to IPv6"]
+fn resolves_over_ipv6_only() -> Result<()> {
+    let expected_ipv6_addr = Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1);
+    let needle_fqdn = FQDN::EXAMPLE_SUBDOMAIN;
+
+    let network = Network::with_ipv6()?;
+
+    let mut leaf_ns = NameServer::new(&dns_test::PEER, FQDN::TEST_DOMAIN, &network)?;
+    leaf_ns.add(Record::aaaa(needle_fqdn.clone(), expected_ipv6_addr));
+
+    let Graph {
+        nameservers: _nameservers,
+        root,
+        ..
+    } = Graph::build(leaf_ns, Sign::No)?;
+    assert!(root.ipv6_addr.is_some());
+
+    let resolver = Resolver::new(&network, root).ipv6_only().start()?;
+    let client = Client::new(&network)?;
+
+    // only sees IPv4 packets
+    let capture = network.capture()?;
+
+    let settings = *DigSettings::default().recurse();
+    let output = client.dig(
+        settings,
+        resolver.ipv6_addr().unwrap(),
+        RecordType::AAAA,
+        &needle_fqdn,
+    )?;
+
+    assert!(output.status.is_noerror(), "{output:#?}");
+    let [answer] = output.answer.try_into().unwrap();
+    assert_eq!(
+        expected_ipv6_addr,
+        answer.try_into_aaaa().unwrap().ipv6_addr
+    );
+
+    let ipv4_packets = capture.packets()?;
+    assert!(ipv4_packets.is_empty(), "{ipv4_packets:#?}");
+
+    Ok(())
+}
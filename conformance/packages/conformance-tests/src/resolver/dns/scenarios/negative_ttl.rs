@@ -0,0 +1,69 @@
+//! Test that resolvers cache negative answers for no longer than the SOA minimum allows
+//! (RFC2308 section 5)
+
+use std::thread;
+use std::time::Duration;
+
+use dns_test::client::{Client, DigSettings};
+use dns_test::name_server::{Graph, NameServer, Sign, SoaOverrides};
+use dns_test::record::{Record, RecordType};
+use dns_test::{FQDN, Network, Resolver, Result};
+
+const SOA_MINIMUM: u32 = 300;
+/// Long enough for the TTL of the cached negative answer to tick down
+const WAIT: Duration = Duration::from_secs(2);
+
+#[test]
+fn negative_ttl_is_soa_minimum() -> Result<()> {
+    let needle_fqdn = FQDN::TEST_DOMAIN.push_label("unicorn");
+
+    let network = Network::new()?;
+
+    let mut leaf_ns = NameServer::new(&dns_test::PEER, FQDN::TEST_DOMAIN, &network)?;
+    leaf_ns.set_soa_settings(SoaOverrides {
+        minimum: Some(SOA_MINIMUM),
+        ..SoaOverrides::default()
+    });
+
+    let Graph {
+        nameservers: _nameservers,
+        root,
+        ..
+    } = Graph::build(leaf_ns, Sign::No)?;
+
+    let resolver = Resolver::new(&network, root).start()?;
+    let client = Client::new(&network)?;
+    let settings = *DigSettings::default().recurse();
+
+    let negative_ttl = || -> Result<u32> {
+        let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+        assert!(output.status.is_nxdomain(), "{output:#?}");
+
+        let soa = output
+            .authority
+            .into_iter()
+            .find_map(|record| match record {
+                Record::SOA(soa) => Some(soa),
+                _ => None,
+            })
+            .expect("negative answer lacks an SOA record");
+        assert_eq!(SOA_MINIMUM, soa.settings.minimum);
+
+        Ok(soa.ttl)
+    };
+
+    // the SOA record's own TTL is longer than the SOA minimum
+    let first_ttl = negative_ttl()?;
+    assert!(first_ttl <= SOA_MINIMUM, "{first_ttl}");
+
+    thread::sleep(WAIT);
+
+    // answered from the cache
+    let second_ttl = negative_ttl()?;
+    assert!(
+        second_ttl < first_ttl,
+        "TTL went from {first_ttl} to {second_ttl} after {WAIT:?}"
+    );
+
+    Ok(())
+}
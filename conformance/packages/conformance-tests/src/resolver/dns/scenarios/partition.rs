@@ -0,0 +1,74 @@
+//! Test that the resolver fails over to the reachable authoritative server of a zone when it is
+//! partitioned from the other one
+
+use std::net::Ipv4Addr;
+
+use dns_test::client::{Client, DigSettings};
+use dns_test::name_server::NameServer;
+use dns_test::record::{Record, RecordType};
+use dns_test::{FQDN, Network, PEER, Resolver, Result};
+
+#[test]
+fn fails_over_to_reachable_server() -> Result<()> {
+    let expected_ipv4_addr = Ipv4Addr::new(1, 2, 3, 4);
+    let priming_fqdn = FQDN::TEST_TLD.push_label("priming");
+    let needle_fqdn = FQDN::TEST_TLD.push_label("needle");
+    let network = Network::new()?;
+
+    let mut root_ns = NameServer::new(&PEER, FQDN::ROOT, &network)?;
+    let mut unreachable_ns = NameServer::new(&PEER, FQDN::TEST_TLD, &network)?;
+    let mut reachable_ns = NameServer::new(&PEER, FQDN::TEST_TLD, &network)?;
+
+    for fqdn in [&priming_fqdn, &needle_fqdn] {
+        unreachable_ns.add(Record::a(fqdn.clone(), expected_ipv4_addr));
+        reachable_ns.add(Record::a(fqdn.clone(), expected_ipv4_addr));
+    }
+
+    root_ns
+        .referral_nameserver(&unreachable_ns)
+        .referral_nameserver(&reachable_ns);
+
+    let resolver = Resolver::new(&network, root_ns.root_hint()).start()?;
+    let client = Client::new(&network)?;
+
+    let _root_ns = root_ns.start()?;
+    let unreachable_ns = unreachable_ns.start()?;
+    let reachable_ns = reachable_ns.start()?;
+
+    let settings = *DigSettings::default().recurse().timeout(10);
+    let resolve = |fqdn: &FQDN| -> Result<()> {
+        let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, fqdn)?;
+
+        assert!(output.status.is_noerror(), "{output:#?}");
+        let [answer] = output.answer.try_into().unwrap();
+        assert_eq!(expected_ipv4_addr, answer.try_into_a().unwrap().ipv4_addr);
+
+        Ok(())
+    };
+
+    // prime the resolver so that the server it is about to be partitioned from is the only one
+    // it has seen answer; resolvers prefer the servers with the lowest round-trip time so it is
+    // the one the resolver tries first afterwards
+    network.partition(&resolver.ipv4_addr(), &reachable_ns.ipv4_addr())?;
+    resolve(&priming_fqdn)?;
+    network.heal(&resolver.ipv4_addr(), &reachable_ns.ipv4_addr())?;
+
+    network.partition(&resolver.ipv4_addr(), &unreachable_ns.ipv4_addr())?;
+    let tshark = unreachable_ns.eavesdrop()?;
+
+    resolve(&needle_fqdn)?;
+
+    // the resolver tried to reach the partitioned server ...
+    let dropped = network.dropped_packets(&resolver.ipv4_addr(), &unreachable_ns.ipv4_addr())?;
+    assert_ne!(0, dropped);
+
+    // ... but its query never made it there
+    let captures = tshark.terminate()?;
+    let leaked = captures
+        .iter()
+        .filter(|capture| capture.is_incoming_query_for(&needle_fqdn))
+        .collect::<Vec<_>>();
+    assert!(leaked.is_empty(), "{leaked:#?}");
+
+    Ok(())
+}
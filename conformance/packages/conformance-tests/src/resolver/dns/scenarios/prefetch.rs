@@ -0,0 +1,87 @@
+//! Test that resolvers refresh popular records before they expire from the cache
+
+use std::net::Ipv4Addr;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use dns_test::client::{Client, DigSettings};
+use dns_test::name_server::{Graph, NameServer, Sign};
+use dns_test::record::{A, RecordType};
+use dns_test::tshark::Capture;
+use dns_test::{FQDN, Network, Resolver, Result};
+
+#[test]
+#[ignore = "hickory-recursor does not support prefetching"]
+fn refreshes_record_before_expiry() -> Result<()> {
+    // long enough to be eligible for prefetching in both BIND (>= 9s) and unbound; unbound
+    // prefetches in the last 10% of the original TTL, BIND in the last 2 seconds
+    const TTL: u32 = 30;
+    const QUERY_INTERVAL: Duration = Duration::from_millis(250);
+
+    let needle_fqdn = FQDN::EXAMPLE_SUBDOMAIN;
+
+    let network = Network::new()?;
+
+    let mut leaf_ns = NameServer::new(&dns_test::PEER, FQDN::TEST_DOMAIN, &network)?;
+    leaf_ns.add(A {
+        fqdn: needle_fqdn.clone(),
+        ttl: TTL,
+        ipv4_addr: Ipv4Addr::new(1, 2, 3, 4),
+    });
+
+    let Graph {
+        nameservers, root, ..
+    } = Graph::build(leaf_ns, Sign::No)?;
+    let leaf_ns = &nameservers[0];
+
+    let resolver = Resolver::new(&network, root).prefetch(true).start()?;
+    let client = Client::new(&network)?;
+    let settings = *DigSettings::default().recurse();
+
+    let mut tshark = leaf_ns.eavesdrop()?;
+
+    // the first query is a cache miss; the record cannot expire from the resolver's cache before
+    // `expiry` because the resolver fetched it after `start`
+    let start = Instant::now();
+    let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+    assert!(output.status.is_noerror());
+    let expiry = start + Duration::from_secs(TTL.into());
+
+    let mut previous_ttl = TTL;
+    let mut refreshed_at = None;
+    while Instant::now() < expiry + Duration::from_secs(5) {
+        thread::sleep(QUERY_INTERVAL);
+
+        let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+        assert!(output.status.is_noerror());
+        let [answer] = output.answer.try_into().unwrap();
+        let ttl = answer.try_into_a().unwrap().ttl;
+
+        // a fresh copy of the record has been fetched from upstream
+        if ttl > previous_ttl {
+            refreshed_at = Some(Instant::now());
+            break;
+        }
+        previous_ttl = ttl;
+    }
+
+    let refreshed_at = refreshed_at.expect("record was never refreshed");
+    assert!(
+        refreshed_at < expiry,
+        "record was refreshed {:?} after it expired",
+        refreshed_at - expiry
+    );
+
+    let upstream_queries =
+        |captures: &[Capture]| Capture::count_incoming_queries_for(captures, &needle_fqdn);
+    tshark.wait_until(
+        |captures| upstream_queries(captures) >= 2,
+        Duration::from_secs(5),
+    )?;
+    let captures = tshark.terminate()?;
+
+    // the initial cache miss plus the prefetch
+    assert_eq!(2, upstream_queries(&captures));
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+//! Test what resolvers reveal of the query name to the root and TLD name servers, with and
+//! without QNAME minimization (RFC9156)
+
+use std::net::Ipv4Addr;
+
+use dns_test::client::{Client, DigSettings};
+use dns_test::name_server::{Graph, NameServer, Sign};
+use dns_test::record::{Record, RecordType};
+use dns_test::tshark::{Capture, Direction};
+use dns_test::{FQDN, Network, Resolver, Result};
+
+#[test]
+fn minimized_queries_to_root_and_tld() -> Result<()> {
+    let (needle_fqdn, upstream_queries) = fixture(true)?;
+
+    assert!(!upstream_queries.is_empty());
+    for capture in &upstream_queries {
+        assert!(
+            !capture.message.is_query_for(&needle_fqdn),
+            "the full query name was sent to {}: {capture:#?}",
+            capture.direction.peer_addr()
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+#[ignore = "hickory always minimizes query names"]
+fn full_query_name_without_minimization() -> Result<()> {
+    let (needle_fqdn, upstream_queries) = fixture(false)?;
+
+    assert!(
+        upstream_queries
+            .iter()
+            .any(|capture| capture.message.is_query_for(&needle_fqdn)),
+        "{upstream_queries:#?}"
+    );
+
+    Ok(())
+}
+
+/// Resolves a name four labels deep and returns it along with the queries the resolver sent to
+/// the root and TLD name servers
+fn fixture(qname_minimization: bool) -> Result<(FQDN, Vec<Capture>)> {
+    let needle_fqdn = FQDN::TEST_DOMAIN.push_label("b").push_label("a");
+
+    let network = Network::new()?;
+
+    let mut leaf_ns = NameServer::new(&dns_test::PEER, FQDN::TEST_DOMAIN, &network)?;
+    leaf_ns.add(Record::a(needle_fqdn.clone(), Ipv4Addr::new(1, 2, 3, 4)));
+
+    let Graph {
+        nameservers, root, ..
+    } = Graph::build(leaf_ns, Sign::No)?;
+
+    let upstream_addrs = nameservers
+        .iter()
+        .filter(|ns| ns.zone() == &FQDN::ROOT || ns.zone() == &FQDN::TEST_TLD)
+        .map(|ns| ns.ipv4_addr())
+        .collect::<Vec<_>>();
+
+    let resolver = Resolver::new(&network, root)
+        .qname_minimization(qname_minimization)
+        .start()?;
+    let mut tshark = resolver.eavesdrop()?;
+
+    let client = Client::new(&network)?;
+    let settings = *DigSettings::default().recurse();
+    let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+    assert!(output.status.is_noerror(), "{output:#?}");
+
+    tshark.wait_for_capture()?;
+    let upstream_queries = tshark
+        .terminate()?
+        .into_iter()
+        .filter(|capture| {
+            matches!(capture.direction, Direction::Outgoing { .. })
+                && !capture.message.is_response()
+                && upstream_addrs.contains(&capture.direction.peer_addr())
+        })
+        .collect();
+
+    Ok((needle_fqdn, upstream_queries))
+}
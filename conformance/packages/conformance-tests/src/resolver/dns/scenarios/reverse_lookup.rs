@@ -0,0 +1,45 @@
+//! Test that resolvers follow delegations into the reverse zones (`in-addr.arpa.` and `ip6.arpa.`)
+
+use std::net::IpAddr;
+
+use dns_test::client::{Client, DigSettings};
+use dns_test::name_server::NameServer;
+use dns_test::record::Record;
+use dns_test::{FQDN, Network, Resolver, Result};
+
+#[test]
+fn resolves_ptr_records() -> Result<()> {
+    let ipv4_addr: IpAddr = "1.2.3.4".parse()?;
+    let ipv6_addr: IpAddr = "2001:db8::1".parse()?;
+    let ipv4_target = FQDN::TEST_DOMAIN.push_label("v4");
+    let ipv6_target = FQDN::TEST_DOMAIN.push_label("v6");
+
+    let network = Network::new()?;
+
+    let mut root_ns = NameServer::new(&dns_test::PEER, FQDN::ROOT, &network)?;
+    let mut arpa_ns = NameServer::new(&dns_test::PEER, FQDN("arpa.")?, &network)?;
+    arpa_ns
+        .add(Record::ptr(FQDN::reverse(ipv4_addr), ipv4_target.clone()))
+        .add(Record::ptr(FQDN::reverse(ipv6_addr), ipv6_target.clone()));
+    root_ns.referral_nameserver(&arpa_ns);
+
+    let root_hint = root_ns.root_hint();
+    let _root_ns = root_ns.start()?;
+    let _arpa_ns = arpa_ns.start()?;
+
+    let resolver = Resolver::new(&network, root_hint).start()?;
+    let client = Client::new(&network)?;
+    let settings = *DigSettings::default().recurse();
+
+    for (ip_addr, expected_target) in [(ipv4_addr, ipv4_target), (ipv6_addr, ipv6_target)] {
+        let output = client.dig_reverse(settings, resolver.ipv4_addr(), ip_addr)?;
+
+        assert!(output.status.is_noerror(), "{output:#?}");
+        let [answer] = output.answer.try_into().unwrap();
+        let ptr = answer.try_into_ptr().unwrap();
+        assert_eq!(FQDN::reverse(ip_addr), ptr.fqdn);
+        assert_eq!(expected_target, ptr.target);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+//! Test serving stale data (RFC8767): once a cached record has expired, a resolver may keep
+//! answering with it while the authoritative servers are unreachable
+
+use std::net::Ipv4Addr;
+use std::thread;
+use std::time::Duration;
+
+use dns_test::client::{Client, DigSettings};
+use dns_test::name_server::{Graph, NameServer, Running, Sign};
+use dns_test::record::{Record, RecordType};
+use dns_test::{FQDN, Network, Resolver, Result};
+
+const TTL: u32 = 2;
+/// Long enough for the cached record to expire
+const WAIT: Duration = Duration::from_secs(4);
+const MAX_STALE: Duration = Duration::from_secs(60);
+/// RFC8767 section 4 recommends 30 seconds
+const MAX_STALE_ANSWER_TTL: u32 = 30;
+
+#[test]
+#[ignore = "hickory does not support serving stale data"]
+fn serves_stale_record_when_authoritative_is_unreachable() -> Result<()> {
+    let expected_ipv4_addr = Ipv4Addr::new(1, 2, 3, 4);
+    let needle_fqdn = FQDN::EXAMPLE_SUBDOMAIN;
+
+    let Fixture {
+        client,
+        resolver,
+        _nameservers,
+    } = Fixture::new(Some(MAX_STALE), expected_ipv4_addr)?;
+
+    let settings = *DigSettings::default().recurse();
+    let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+
+    assert!(output.status.is_noerror(), "{output:#?}");
+    let [answer] = output.answer.try_into().unwrap();
+    assert!(answer.ttl() <= MAX_STALE_ANSWER_TTL, "{answer:?}");
+    let a = answer.try_into_a().unwrap();
+    assert_eq!(needle_fqdn, a.fqdn);
+    assert_eq!(expected_ipv4_addr, a.ipv4_addr);
+
+    Ok(())
+}
+
+#[test]
+fn servfail_when_authoritative_is_unreachable() -> Result<()> {
+    let needle_fqdn = FQDN::EXAMPLE_SUBDOMAIN;
+
+    let Fixture {
+        client,
+        resolver,
+        _nameservers,
+    } = Fixture::new(None, Ipv4Addr::new(1, 2, 3, 4))?;
+
+    let settings = *DigSettings::default().recurse();
+    let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+
+    assert!(output.status.is_servfail(), "{output:#?}");
+
+    Ok(())
+}
+
+struct Fixture {
+    client: Client,
+    resolver: Resolver,
+    _nameservers: Vec<NameServer<Running>>,
+}
+
+impl Fixture {
+    /// Caches a short-lived record, disconnects the name server that serves it and waits for the
+    /// record to expire
+    fn new(max_stale: Option<Duration>, ipv4_addr: Ipv4Addr) -> Result<Self> {
+        let needle_fqdn = FQDN::EXAMPLE_SUBDOMAIN;
+
+        let network = Network::new()?;
+
+        let mut leaf_ns = NameServer::new(&dns_test::PEER, FQDN::TEST_DOMAIN, &network)?;
+        leaf_ns.add(Record::a(needle_fqdn.clone(), ipv4_addr).with_ttl(TTL));
+
+        let Graph {
+            nameservers, root, ..
+        } = Graph::build(leaf_ns, Sign::No)?;
+        let leaf_ns = &nameservers[0];
+        assert_eq!(&FQDN::TEST_DOMAIN, leaf_ns.zone());
+
+        let mut settings = Resolver::new(&network, root);
+        if let Some(max_stale) = max_stale {
+            settings.serve_stale(max_stale);
+        }
+        let resolver = settings.start()?;
+        let client = Client::new(&network)?;
+        let dig_settings = *DigSettings::default().recurse();
+
+        let output = client.dig(
+            dig_settings,
+            resolver.ipv4_addr(),
+            RecordType::A,
+            &needle_fqdn,
+        )?;
+        assert!(output.status.is_noerror(), "{output:#?}");
+
+        network.disconnect(leaf_ns.container())?;
+        thread::sleep(WAIT);
+
+        Ok(Self {
+            client,
+            resolver,
+            _nameservers: nameservers,
+        })
+    }
+}
@@ -0,0 +1,18 @@
+#!/usr/bin/env python3
+# This server answers every query with SERVFAIL, to simulate a broken
+# authoritative server.
+from dnslib import DNSRecord, RCODE
+from dnslib.server import BaseResolver, DNSHandler, DNSServer
+
+
+class Resolver(BaseResolver):
+    def resolve(self, request: DNSRecord, _handler: DNSHandler) -> DNSRecord:
+        reply = request.reply()
+        reply.header.rcode = RCODE.SERVFAIL
+        return reply
+
+
+if __name__ == "__main__":
+    resolver = Resolver()
+    server = DNSServer(resolver, address="0.0.0.0", port=53)
+    server.start()
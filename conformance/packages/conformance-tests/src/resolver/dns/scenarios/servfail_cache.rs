@@ -0,0 +1,69 @@
+//! Test that resolvers cache resolution failures (RFC9520)
+
+use std::fs;
+use std::thread;
+use std::time::Duration;
+
+use dns_test::client::{Client, DigSettings};
+use dns_test::name_server::NameServer;
+use dns_test::record::RecordType;
+use dns_test::tshark::{Capture, Tshark};
+use dns_test::{FQDN, Implementation, Network, PEER, Resolver, Result};
+
+#[test]
+#[ignore = "hickory-recursor does not cache SERVFAIL responses"]
+fn servfail_is_cached_then_retried() -> Result<()> {
+    // unbound uses a fixed, non-configurable, period of 5 seconds
+    const SERVFAIL_CACHE_TTL: Duration = Duration::from_secs(5);
+
+    let target_fqdn = FQDN("example.testing.")?;
+    let network = Network::new()?;
+
+    let mut root_ns = NameServer::new(&PEER, FQDN::ROOT, &network)?;
+    let leaf_ns = NameServer::new(&Implementation::Dnslib, FQDN::TEST_TLD, &network)?;
+    let script = fs::read_to_string("src/resolver/dns/scenarios/servfail.py")?;
+    leaf_ns.cp("/script.py", &script)?;
+
+    root_ns.referral_nameserver(&leaf_ns);
+
+    let mut resolver = Resolver::new(&network, root_ns.root_hint());
+    if dns_test::SUBJECT.is_bind() {
+        resolver.servfail_cache_ttl(SERVFAIL_CACHE_TTL);
+    }
+    let resolver = resolver.start()?;
+    let client = Client::new(resolver.network())?;
+    let settings = *DigSettings::default().recurse();
+
+    let _root_ns = root_ns.start()?;
+    let _leaf_ns = leaf_ns.start()?;
+
+    let query = |tshark: Tshark| -> Result<usize> {
+        let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &target_fqdn)?;
+        assert!(output.status.is_servfail());
+
+        // give the resolver time to send any further query
+        thread::sleep(Duration::from_secs(1));
+        Ok(Capture::count_outgoing_queries_for(
+            &tshark.terminate()?,
+            &target_fqdn,
+        ))
+    };
+
+    // first resolution: the failure is not cached yet
+    let count = query(resolver.eavesdrop()?)?;
+    assert_ne!(0, count, "resolver did not query the broken name server");
+
+    // the failure is now cached
+    let count = query(resolver.eavesdrop()?)?;
+    assert_eq!(0, count, "resolver did not cache the SERVFAIL");
+
+    // the cached failure has expired
+    thread::sleep(SERVFAIL_CACHE_TTL);
+    let count = query(resolver.eavesdrop()?)?;
+    assert_ne!(
+        0, count,
+        "resolver did not retry after the cached SERVFAIL expired"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,125 @@
+//! the name server of the delegated zone lives in a sibling zone and the parent provides no glue
+//! for it, so the resolver needs to resolve the name server's address before it can follow the
+//! referral
+
+use std::net::Ipv4Addr;
+
+use dns_test::client::{Client, DigOutput, DigSettings};
+use dns_test::name_server::NameServer;
+use dns_test::record::{Record, RecordType};
+use dns_test::tshark::Capture;
+use dns_test::{FQDN, Network, Resolver, Result};
+
+const EXPECTED_IPV4_ADDR: Ipv4Addr = Ipv4Addr::new(1, 2, 3, 4);
+
+#[test]
+fn resolves_sibling_nameserver() -> Result<()> {
+    let Fixture {
+        output,
+        captures,
+        sibling_addr,
+        leaf_addr,
+        nameserver_fqdn,
+        needle_fqdn,
+    } = fixture("resolves-sibling-nameserver", true)?;
+
+    assert!(output.status.is_noerror());
+    let [answer] = output.answer.try_into().unwrap();
+    assert_eq!(EXPECTED_IPV4_ADDR, answer.try_into_a().unwrap().ipv4_addr);
+
+    let position = |predicate: &dyn Fn(&Capture) -> bool| captures.iter().position(predicate);
+    let sibling_lookup = position(&|capture| {
+        capture.is_outgoing_query_for(&nameserver_fqdn)
+            && capture.direction.peer_addr() == sibling_addr
+    });
+    let leaf_lookup = position(&|capture| {
+        capture.is_outgoing_query_for(&needle_fqdn) && capture.direction.peer_addr() == leaf_addr
+    });
+
+    let sibling_lookup = sibling_lookup.expect("resolver did not look up the name server address");
+    let leaf_lookup = leaf_lookup.expect("resolver did not query the delegated zone");
+    assert!(
+        sibling_lookup < leaf_lookup,
+        "name server address must be resolved before the delegated zone is queried"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn unreachable_sibling_zone() -> Result<()> {
+    let Fixture { output, .. } = fixture("unreachable-sibling-zone", false)?;
+
+    assert!(output.status.is_servfail());
+
+    Ok(())
+}
+
+struct Fixture {
+    output: DigOutput,
+    captures: Vec<Capture>,
+    sibling_addr: Ipv4Addr,
+    leaf_addr: Ipv4Addr,
+    nameserver_fqdn: FQDN,
+    needle_fqdn: FQDN,
+}
+
+// `testing.` delegates `{label}.testing.` to a name server in the sibling zone
+// `hickory-dns.testing.`; when `sibling_reachable` is false, the sibling zone's name server is never
+// started
+fn fixture(label: &str, sibling_reachable: bool) -> Result<Fixture> {
+    let network = Network::new()?;
+
+    let leaf_zone = FQDN::TEST_TLD.push_label(label);
+    let needle_fqdn = leaf_zone.push_label("example");
+    let nameserver_fqdn = FQDN::TEST_DOMAIN.push_label(label);
+
+    let mut root_ns = NameServer::new(&dns_test::PEER, FQDN::ROOT, &network)?;
+    let mut tld_ns = NameServer::new(&dns_test::PEER, FQDN::TEST_TLD, &network)?;
+    let mut sibling_ns = NameServer::new(&dns_test::PEER, FQDN::TEST_DOMAIN, &network)?;
+    let mut leaf_ns = NameServer::new(&dns_test::PEER, leaf_zone.clone(), &network)?;
+
+    sibling_ns.add(root_ns.a());
+    sibling_ns.add(tld_ns.a());
+    sibling_ns.add(Record::a(nameserver_fqdn.clone(), leaf_ns.ipv4_addr()));
+
+    leaf_ns.add(Record::ns(leaf_zone.clone(), nameserver_fqdn.clone()));
+    leaf_ns.add(Record::a(needle_fqdn.clone(), EXPECTED_IPV4_ADDR));
+
+    root_ns.referral_nameserver(&tld_ns);
+    tld_ns.referral_nameserver(&sibling_ns);
+    tld_ns.referral_without_glue(leaf_zone, nameserver_fqdn.clone());
+
+    let root_hint = root_ns.root_hint();
+    let sibling_addr = sibling_ns.ipv4_addr();
+    let leaf_addr = leaf_ns.ipv4_addr();
+    let _root_ns = root_ns.start()?;
+    let _tld_ns = tld_ns.start()?;
+    let _leaf_ns = leaf_ns.start()?;
+    // an unreachable sibling name server is kept around, stopped, so that its address does not
+    // get reused
+    let _sibling_ns = if sibling_reachable {
+        (Some(sibling_ns.start()?), None)
+    } else {
+        (None, Some(sibling_ns))
+    };
+
+    let resolver = Resolver::new(&network, root_hint).start()?;
+    let mut tshark = resolver.eavesdrop()?;
+
+    let client = Client::new(&network)?;
+    let settings = *DigSettings::default().recurse().timeout(7);
+    let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+
+    tshark.wait_for_capture()?;
+    let captures = tshark.terminate()?;
+
+    Ok(Fixture {
+        output,
+        captures,
+        sibling_addr,
+        leaf_addr,
+        nameserver_fqdn,
+        needle_fqdn,
+    })
+}
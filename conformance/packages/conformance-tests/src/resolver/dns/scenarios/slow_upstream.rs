@@ -0,0 +1,63 @@
+//! Test how the resolver's upstream timeout interacts with a slow authoritative server
+
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use dns_test::client::{Client, DigOutput, DigSettings};
+use dns_test::name_server::{Graph, NameServer, Sign};
+use dns_test::record::{Record, RecordType};
+use dns_test::{FQDN, Network, Resolver, Result};
+
+/// Added to every packet sent to the leaf zone's name server
+const DELAY: Duration = Duration::from_secs(2);
+
+#[test]
+#[ignore = "hickory does not support configuring its upstream timeout"]
+fn servfail_when_upstream_is_slower_than_timeout() -> Result<()> {
+    let output = fixture(Duration::from_secs(1))?;
+
+    assert!(output.status.is_servfail(), "{output:#?}");
+
+    Ok(())
+}
+
+#[test]
+#[ignore = "hickory does not support configuring its upstream timeout"]
+fn resolves_when_timeout_allows_for_delay() -> Result<()> {
+    let output = fixture(Duration::from_secs(10))?;
+
+    assert!(output.status.is_noerror(), "{output:#?}");
+    let [answer] = output.answer.try_into().unwrap();
+    assert_eq!(
+        Ipv4Addr::new(1, 2, 3, 4),
+        answer.try_into_a().unwrap().ipv4_addr
+    );
+
+    Ok(())
+}
+
+fn fixture(upstream_timeout: Duration) -> Result<DigOutput> {
+    let needle_fqdn = FQDN::EXAMPLE_SUBDOMAIN;
+
+    let network = Network::new()?;
+
+    let mut leaf_ns = NameServer::new(&dns_test::PEER, FQDN::TEST_DOMAIN, &network)?;
+    leaf_ns.add(Record::a(needle_fqdn.clone(), Ipv4Addr::new(1, 2, 3, 4)));
+
+    let Graph {
+        nameservers, root, ..
+    } = Graph::build(leaf_ns, Sign::No)?;
+    let leaf_ns = &nameservers[0];
+    assert_eq!(&FQDN::TEST_DOMAIN, leaf_ns.zone());
+
+    let resolver = Resolver::new(&network, root)
+        .upstream_timeout(upstream_timeout)
+        .start()?;
+    let client = Client::new(&network)?;
+
+    // the resolver must be running for its outgoing packets to be impaired
+    network.set_impairment(&leaf_ns.ipv4_addr(), DELAY, 0.)?;
+
+    let settings = *DigSettings::default().recurse().timeout(30);
+    client.dig(settings, resolver.ipv4_addr(), RecordType::A, &needle_fqdn)
+}
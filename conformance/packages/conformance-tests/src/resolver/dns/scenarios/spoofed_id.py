@@ -0,0 +1,45 @@
+#!/usr/bin/env python3
+# This server answers every A query for the expected name twice: first with a
+# forged response whose transaction ID does not match the query, then with the
+# legitimate response.
+from dnslib import A, DNSLabel, DNSRecord, QTYPE, RCODE, RR
+from dnslib.server import BaseResolver, DNSHandler, DNSServer
+
+
+class Resolver(BaseResolver):
+    def __init__(self):
+        self.expected_name = DNSLabel("example.testing.")
+        self.a = A("192.0.2.1")  # in TEST-NET-1
+        self.spoofed_a = A("198.51.100.1")  # in TEST-NET-2
+
+    def resolve(self, request: DNSRecord, handler: DNSHandler) -> DNSRecord:
+        reply = request.reply()
+        if request.q.qname == self.expected_name:
+            reply.header.rcode = RCODE.NOERROR
+            if request.q.qtype == QTYPE.A:
+                if handler.protocol == "udp":
+                    spoofed = request.reply()
+                    spoofed.header.id = (request.header.id + 1) % 0x10000
+                    spoofed.add_answer(RR(
+                        request.q.qname,
+                        QTYPE.A,
+                        rdata=self.spoofed_a,
+                    ))
+                    _data, connection = handler.request
+                    connection.sendto(spoofed.pack(), handler.client_address)
+                    print("sent spoofed response", flush=True)
+
+                reply.add_answer(RR(
+                    request.q.qname,
+                    QTYPE.A,
+                    rdata=self.a,
+                ))
+        else:
+            reply.header.rcode = RCODE.NXDOMAIN
+        return reply
+
+
+if __name__ == "__main__":
+    resolver = Resolver()
+    server = DNSServer(resolver, address="0.0.0.0", port=53)
+    server.start()
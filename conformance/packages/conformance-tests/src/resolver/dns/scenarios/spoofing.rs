@@ -0,0 +1,74 @@
+//! Test that resolvers discard forged responses
+
+use std::{fs, net::Ipv4Addr};
+
+use dns_test::{
+    FQDN, Implementation, Network, PEER, Resolver, Result,
+    client::{Client, DigOutput, DigSettings},
+    name_server::NameServer,
+    record::RecordType,
+};
+
+// the forged response arrives first but its transaction ID does not match the query's
+#[test]
+fn mismatched_query_id() -> Result<()> {
+    let output = spoofing_fixture("src/resolver/dns/scenarios/spoofed_id.py")?;
+
+    assert!(output.status.is_noerror());
+    let [answer] = output.answer.try_into().unwrap();
+    assert_eq!(
+        Ipv4Addr::new(192, 0, 2, 1),
+        answer.try_into_a().unwrap().ipv4_addr
+    );
+
+    Ok(())
+}
+
+// the forged response arrives first, from the right IP address and with the right transaction ID,
+// but from a source port other than the one the query was sent to
+#[test]
+fn mismatched_source_port() -> Result<()> {
+    let output = spoofing_fixture("src/resolver/dns/scenarios/spoofed_port.py")?;
+
+    assert!(output.status.is_noerror());
+    let [answer] = output.answer.try_into().unwrap();
+    assert_eq!(
+        Ipv4Addr::new(192, 0, 2, 1),
+        answer.try_into_a().unwrap().ipv4_addr
+    );
+
+    Ok(())
+}
+
+/// Resolves `example.testing.` through a `dnslib` server that runs `script_path`
+///
+/// The script must race a forged response against the legitimate one and print
+/// "sent spoofed response" when it does so.
+fn spoofing_fixture(script_path: &str) -> Result<DigOutput> {
+    let target_fqdn = FQDN("example.testing.")?;
+    let network = Network::new()?;
+
+    let mut root_ns = NameServer::new(&PEER, FQDN::ROOT, &network)?;
+    let leaf_ns = NameServer::new(&Implementation::Dnslib, FQDN::TEST_TLD, &network)?;
+    let script = fs::read_to_string(script_path)?;
+    leaf_ns.cp("/script.py", &script)?;
+
+    root_ns.referral_nameserver(&leaf_ns);
+
+    let resolver = Resolver::new(&network, root_ns.root_hint()).start()?;
+    let client = Client::new(resolver.network())?;
+
+    let _root_ns = root_ns.start()?;
+    let leaf_ns = leaf_ns.start()?;
+
+    let settings = *DigSettings::default().recurse();
+    let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &target_fqdn)?;
+
+    dbg!(&output);
+
+    // PRE-CONDITION the forged response was actually sent
+    let logs = leaf_ns.logs()?;
+    assert!(logs.contains("sent spoofed response"), "{logs}");
+
+    Ok(output)
+}
@@ -0,0 +1,18 @@
+#!/usr/bin/env python3
+# This server never answers, to simulate an authoritative server that is down
+# while its host is still reachable.
+from dnslib import DNSError, DNSRecord
+from dnslib.server import BaseResolver, DNSHandler, DNSServer
+
+
+class Resolver(BaseResolver):
+    def resolve(self, request: DNSRecord, _handler: DNSHandler) -> DNSRecord:
+        # This will be caught by the try-except block in DNSHandler.handle(),
+        # which results in no response being sent.
+        raise DNSError(f"Ignoring query for {request.q.qname}")
+
+
+if __name__ == "__main__":
+    resolver = Resolver()
+    server = DNSServer(resolver, address="0.0.0.0", port=53)
+    server.start()
@@ -0,0 +1,73 @@
+//! Extended DNS Errors (RFC8914) set by an authoritative server, rather than by the resolver
+
+use dns_test::client::{Client, DigSettings, ExtendedDnsError};
+use dns_test::name_server::NameServer;
+use dns_test::record::RecordType;
+use dns_test::{FQDN, Implementation, Network, PEER, Resolver, Result};
+
+#[test]
+fn blocked_by_authoritative_server() -> Result<()> {
+    const EXTRA_TEXT: &str = "blocked by zone policy";
+
+    let needle_fqdn = FQDN::TEST_TLD.push_label("blocked");
+    let network = Network::new()?;
+
+    let mut root_ns = NameServer::new(&PEER, FQDN::ROOT, &network)?;
+    let mut leaf_ns = NameServer::new(&Implementation::Dnslib, FQDN::TEST_TLD, &network)?;
+    leaf_ns.set_ede(needle_fqdn.clone(), ExtendedDnsError::Blocked, EXTRA_TEXT);
+
+    root_ns.referral_nameserver(&leaf_ns);
+
+    let mut resolver = Resolver::new(&network, root_ns.root_hint());
+    if dns_test::SUBJECT.supports_ede() {
+        resolver.extended_dns_errors();
+    }
+    let resolver = resolver.start()?;
+
+    let _root_ns = root_ns.start()?;
+    let leaf_ns = leaf_ns.start()?;
+
+    let client = Client::new(&network)?;
+
+    // PRE-CONDITION the authoritative server attaches the EDE to its response
+    let output = client.dig(
+        DigSettings::default(),
+        leaf_ns.ipv4_addr(),
+        RecordType::A,
+        &needle_fqdn,
+    )?;
+    assert!(output.status.is_nxdomain());
+    assert!(output.ede.contains(&ExtendedDnsError::Blocked));
+    assert_eq!(
+        Some(EXTRA_TEXT),
+        output
+            .ede_extra_text
+            .get(&ExtendedDnsError::Blocked)
+            .map(String::as_str)
+    );
+
+    let settings = *DigSettings::default().recurse();
+    let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+
+    dbg!(&output);
+
+    assert!(output.status.is_nxdomain());
+
+    // resolvers may relay the upstream EDE or strip it but they must not alter it
+    if output.ede.contains(&ExtendedDnsError::Blocked) {
+        if let Some(extra_text) = output.ede_extra_text.get(&ExtendedDnsError::Blocked) {
+            assert_eq!(EXTRA_TEXT, extra_text);
+        }
+    } else {
+        assert!(
+            !output
+                .ede_extra_text
+                .values()
+                .any(|text| text == EXTRA_TEXT),
+            "upstream EXTRA-TEXT relayed under a different EDE: {:?}",
+            output.ede_extra_text
+        );
+    }
+
+    Ok(())
+}
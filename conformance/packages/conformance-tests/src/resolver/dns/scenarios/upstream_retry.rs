@@ -0,0 +1,112 @@
+//! Test how resolvers retry a query when one of the authoritative servers of a zone doesn't answer
+
+use std::fs;
+use std::net::Ipv4Addr;
+use std::time::{Duration, SystemTime};
+
+use dns_test::client::{Client, DigSettings};
+use dns_test::name_server::NameServer;
+use dns_test::record::{Record, RecordType};
+use dns_test::tshark::Capture;
+use dns_test::{FQDN, Implementation, Network, PEER, Resolver, Result};
+
+/// How long the resolver may keep waiting on the unresponsive server before it queries the other
+const MAX_FAILOVER: Duration = Duration::from_secs(5);
+/// Retransmissions any closer together than this flood the unresponsive server
+const MIN_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+/// Allowance for scheduling jitter when comparing consecutive retry intervals
+const JITTER: Duration = Duration::from_millis(50);
+/// Resolvers pick among a zone's servers at random so keep asking about new names until the
+/// unresponsive one has been tried
+const MAX_ATTEMPTS: usize = 5;
+
+#[test]
+fn switches_to_responsive_server() -> Result<()> {
+    let expected_ipv4_addr = Ipv4Addr::new(1, 2, 3, 4);
+    let network = Network::new()?;
+
+    let mut root_ns = NameServer::new(&PEER, FQDN::ROOT, &network)?;
+    let dead_ns = NameServer::new(&Implementation::Dnslib, FQDN::TEST_TLD, &network)?;
+    let script = fs::read_to_string("src/resolver/dns/scenarios/unresponsive.py")?;
+    dead_ns.cp("/script.py", &script)?;
+    let mut live_ns = NameServer::new(&PEER, FQDN::TEST_TLD, &network)?;
+
+    let needles = (0..MAX_ATTEMPTS)
+        .map(|index| FQDN::TEST_TLD.push_label(&format!("name{index}")))
+        .collect::<Vec<_>>();
+    for needle_fqdn in &needles {
+        live_ns.add(Record::a(needle_fqdn.clone(), expected_ipv4_addr));
+    }
+
+    root_ns
+        .referral_nameserver(&dead_ns)
+        .referral_nameserver(&live_ns);
+
+    let resolver = Resolver::new(&network, root_ns.root_hint()).start()?;
+    let client = Client::new(&network)?;
+    let settings = *DigSettings::default().recurse().timeout(10);
+
+    let _root_ns = root_ns.start()?;
+    let dead_ns = dead_ns.start()?;
+    let live_ns = live_ns.start()?;
+
+    for needle_fqdn in &needles {
+        let dead_tshark = dead_ns.eavesdrop()?;
+        let mut live_tshark = live_ns.eavesdrop()?;
+
+        let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, needle_fqdn)?;
+        assert!(output.status.is_noerror(), "{output:#?}");
+        let [answer] = output.answer.try_into().unwrap();
+        assert_eq!(expected_ipv4_addr, answer.try_into_a().unwrap().ipv4_addr);
+
+        live_tshark.wait_until(
+            |captures| !query_timestamps(captures, needle_fqdn).is_empty(),
+            Duration::from_secs(5),
+        )?;
+        let switched_at = query_timestamps(&live_tshark.terminate()?, needle_fqdn)[0];
+        let retries = query_timestamps(&dead_tshark.terminate()?, needle_fqdn);
+
+        let Some(&first_try) = retries.first() else {
+            // the resolver went straight to the responsive server
+            continue;
+        };
+
+        println!("queried unresponsive server at {retries:?}; other server at {switched_at:?}");
+
+        let waited = switched_at.duration_since(first_try).unwrap_or_default();
+        assert!(
+            waited <= MAX_FAILOVER,
+            "resolver waited {waited:?} before querying another server"
+        );
+
+        let intervals = retries
+            .windows(2)
+            .map(|pair| pair[1].duration_since(pair[0]).unwrap_or_default())
+            .collect::<Vec<_>>();
+        for interval in &intervals {
+            assert!(
+                *interval >= MIN_RETRY_INTERVAL,
+                "retransmitted after only {interval:?}: {intervals:?}"
+            );
+        }
+        for pair in intervals.windows(2) {
+            assert!(
+                pair[1] + JITTER >= pair[0],
+                "retry interval decreased: {intervals:?}"
+            );
+        }
+
+        return Ok(());
+    }
+
+    panic!("the resolver never queried the unresponsive server");
+}
+
+/// Returns when the eavesdropped server received each of the queries for `fqdn`, in order
+fn query_timestamps(captures: &[Capture], fqdn: &FQDN) -> Vec<SystemTime> {
+    captures
+        .iter()
+        .filter(|capture| capture.is_incoming_query_for(fqdn))
+        .map(|capture| capture.timestamp())
+        .collect()
+}
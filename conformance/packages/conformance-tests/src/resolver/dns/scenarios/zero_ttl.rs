@@ -0,0 +1,90 @@
+//! Test that resolvers don't cache records with a TTL of 0 (RFC1035 section 3.2.1)
+
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use dns_test::client::{Client, DigSettings};
+use dns_test::name_server::{Graph, NameServer, Sign};
+use dns_test::record::{Record, RecordType};
+use dns_test::tshark::Capture;
+use dns_test::zone_file::SignSettings;
+use dns_test::{FQDN, Network, Resolver, Result};
+
+const NUM_QUERIES: usize = 2;
+
+#[test]
+fn zero_ttl_record_is_not_cached() -> Result<()> {
+    fixture(false)
+}
+
+#[test]
+fn zero_ttl_signed_record_is_not_cached() -> Result<()> {
+    fixture(true)
+}
+
+fn fixture(signed: bool) -> Result<()> {
+    let needle_fqdn = FQDN::EXAMPLE_SUBDOMAIN;
+
+    let network = Network::new()?;
+
+    let mut leaf_ns = NameServer::new(&dns_test::PEER, FQDN::TEST_DOMAIN, &network)?;
+    leaf_ns.add(Record::a(needle_fqdn.clone(), Ipv4Addr::new(1, 2, 3, 4)).with_ttl(0));
+
+    let sign = if signed {
+        Sign::Yes {
+            settings: SignSettings::default(),
+        }
+    } else {
+        Sign::No
+    };
+    let Graph {
+        nameservers,
+        root,
+        trust_anchor,
+    } = Graph::build(leaf_ns, sign)?;
+    let leaf_ns = &nameservers[0];
+    assert_eq!(&FQDN::TEST_DOMAIN, leaf_ns.zone());
+
+    let mut resolver = Resolver::new(&network, root);
+    let mut settings = *DigSettings::default().recurse();
+    if let Some(trust_anchor) = &trust_anchor {
+        resolver.trust_anchor(trust_anchor);
+        settings.dnssec().authentic_data();
+    }
+    let resolver = resolver.start()?;
+    let client = Client::new(&network)?;
+
+    let mut tshark = leaf_ns.eavesdrop()?;
+
+    for _ in 0..NUM_QUERIES {
+        let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+        assert!(output.status.is_noerror(), "{output:#?}");
+        assert_eq!(signed, output.flags.authenticated_data, "{output:#?}");
+
+        let mut answer = output.answer.into_iter();
+        let a = answer.next().unwrap();
+        assert_eq!(0, a.ttl());
+
+        if signed {
+            // the signature must cover the RRset as it was published, with a TTL of 0
+            let rrsig = answer.next().unwrap().try_into_rrsig().unwrap();
+            assert_eq!(RecordType::A, rrsig.type_covered);
+            assert_eq!(0, rrsig.original_ttl);
+        }
+        assert!(answer.next().is_none());
+    }
+
+    tshark.wait_until(
+        |captures| Capture::count_incoming_queries_for(captures, &needle_fqdn) >= NUM_QUERIES,
+        Duration::from_secs(5),
+    )?;
+    let captures = tshark.terminate()?;
+
+    // every client query was a cache miss
+    assert_eq!(
+        NUM_QUERIES,
+        Capture::count_incoming_queries_for(&captures, &needle_fqdn)
+    );
+
+    Ok(())
+}
@@ -1,5 +1,11 @@
+mod aggressive_nsec;
 mod bogus;
+mod ds_digest;
 mod ede;
 mod insecure;
+mod key_rollover;
+mod lame_delegation;
+mod mixed_algorithms;
 mod nsec3;
+mod reference_validator;
 mod secure;
@@ -0,0 +1,81 @@
+//! Test the aggressive use of the DNSSEC-validated cache (RFC8198): after an NXDOMAIN answer,
+//! other names covered by the same NSEC records can be denied without asking the name server
+
+use std::net::Ipv4Addr;
+
+use dns_test::client::{Client, DigSettings};
+use dns_test::name_server::{Graph, NameServer, Sign};
+use dns_test::record::{Record, RecordType};
+use dns_test::tshark::Capture;
+use dns_test::zone_file::{Nsec, SignSettings};
+use dns_test::{FQDN, Network, Resolver, Result};
+
+#[test]
+#[ignore = "hickory does not support aggressive use of NSEC records"]
+fn synthesizes_nxdomain_from_cached_nsec() -> Result<()> {
+    let upstream_queries = fixture(true)?;
+
+    assert_eq!(0, upstream_queries);
+
+    Ok(())
+}
+
+#[test]
+fn queries_name_server_when_disabled() -> Result<()> {
+    let upstream_queries = fixture(false)?;
+
+    assert_ne!(0, upstream_queries);
+
+    Ok(())
+}
+
+/// Denies the existence of one name then queries another name covered by the same NSEC records;
+/// returns the number of queries for that second name the resolver sent upstream
+fn fixture(aggressive_nsec: bool) -> Result<usize> {
+    // the NSEC record of `alpha` covers every name that sorts between `alpha` and `omega`
+    let first_fqdn = FQDN::TEST_DOMAIN.push_label("beta");
+    let second_fqdn = FQDN::TEST_DOMAIN.push_label("delta");
+
+    let network = Network::new()?;
+
+    let mut leaf_ns = NameServer::new(&dns_test::PEER, FQDN::TEST_DOMAIN, &network)?;
+    for label in ["alpha", "omega"] {
+        leaf_ns.add(Record::a(
+            FQDN::TEST_DOMAIN.push_label(label),
+            Ipv4Addr::new(1, 2, 3, 4),
+        ));
+    }
+
+    let Graph {
+        nameservers: _nameservers,
+        root,
+        trust_anchor,
+    } = Graph::build(
+        leaf_ns,
+        Sign::Yes {
+            settings: SignSettings::default().nsec(Nsec::_1),
+        },
+    )?;
+
+    let resolver = Resolver::new(&network, root)
+        .trust_anchor(&trust_anchor.unwrap())
+        .aggressive_nsec(aggressive_nsec)
+        .start()?;
+    let client = Client::new(&network)?;
+    let settings = *DigSettings::default().recurse().authentic_data();
+
+    let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &first_fqdn)?;
+    assert!(output.status.is_nxdomain(), "{output:#?}");
+    assert!(output.flags.authenticated_data, "{output:#?}");
+
+    let mut tshark = resolver.eavesdrop()?;
+    let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &second_fqdn)?;
+    assert!(output.status.is_nxdomain(), "{output:#?}");
+    assert!(output.flags.authenticated_data, "{output:#?}");
+
+    tshark.wait_for_capture()?;
+    Ok(Capture::count_outgoing_queries_for(
+        &tshark.terminate()?,
+        &second_fqdn,
+    ))
+}
@@ -1,9 +1,11 @@
 mod no_rrsig_dnskey;
+mod rrsig_stripped_in_transit;
 
+use std::net::Ipv4Addr;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use dns_test::{
-    FQDN, Network, PEER, Resolver, Result, TrustAnchor,
+    FQDN, Implementation, Network, PEER, Resolver, Result, TrustAnchor,
     client::{Client, DigOutput, DigSettings, ExtendedDnsError},
     name_server::{Graph, NameServer, Sign},
     record::{DNSKEY, DNSKEYRData, DS, RRSIG, Record, RecordType},
@@ -45,6 +47,27 @@ fn ds_reserved_key_algo() -> Result<()> {
     Ok(())
 }
 
+// the only DS record of the delegation uses a digest type the resolver does not support so the
+// child zone must be treated as insecure (RFC4035 section 5.2; RFC6840 section 5.2), unlike
+// `ds_bad_key_algo` where the (supported) DS does not match the DNSKEY and the zone is bogus
+#[test]
+fn ds_unassigned_digest_type() -> Result<()> {
+    let output = malformed_ds_fixture(
+        &FQDN::TEST_TLD.push_label("ds-unassigned-digest-type"),
+        |ds| ds.digest_type = 100,
+    )?;
+
+    dbg!(&output);
+
+    assert!(output.status.is_noerror() && !output.flags.authenticated_data);
+
+    if dns_test::SUBJECT.is_unbound() {
+        assert!(output.ede.is_empty());
+    }
+
+    Ok(())
+}
+
 // the key tag in the DS record does not match the key tag in the DNSKEY record
 #[test]
 fn ds_bad_tag() -> Result<()> {
@@ -63,6 +86,36 @@ fn ds_bad_tag() -> Result<()> {
     Ok(())
 }
 
+// with the CD bit set the resolver must skip validation and return the data of the bogus zone
+// (RFC4035 section 3.2.2)
+#[test]
+fn ds_bad_tag_checking_disabled() -> Result<()> {
+    let validate = *DigSettings::default().recurse().authentic_data();
+    let skip_validation = *DigSettings::default()
+        .recurse()
+        .authentic_data()
+        .checking_disabled();
+    let leaf_zone = FQDN::TEST_TLD.push_label("ds-bad-tag-cd");
+
+    let [validated, unvalidated] = malformed_ds_fixture_with_queries(
+        &leaf_zone,
+        |ds| ds.key_tag = !ds.key_tag,
+        [validate, skip_validation],
+    )?;
+
+    dbg!(&validated, &unvalidated);
+
+    assert!(validated.status.is_servfail());
+
+    assert!(unvalidated.status.is_noerror());
+    assert!(unvalidated.flags.checking_disabled);
+    assert!(!unvalidated.flags.authenticated_data);
+    let [soa] = unvalidated.answer.try_into().unwrap();
+    assert_eq!(leaf_zone, soa.try_into_soa().unwrap().zone);
+
+    Ok(())
+}
+
 // the algorithm field in the DS record does not match the algorithm field in the DNSKEY record
 #[test]
 fn ds_bad_key_algo() -> Result<()> {
@@ -174,10 +227,333 @@ fn no_rrsig_ksk() -> Result<()> {
         assert!(output.ede.iter().eq([&ExtendedDnsError::DnssecBogus]));
     }
 
+    // with the CD bit set the resolver must return the bogus data instead (RFC4035 section
+    // 3.2.2)
+    let settings = *DigSettings::default()
+        .recurse()
+        .authentic_data()
+        .checking_disabled();
+    let output = client.dig(settings, resolver.ipv4_addr(), RecordType::NS, &leaf_zone)?;
+
+    dbg!(&output);
+
+    assert!(output.status.is_noerror());
+    assert!(output.flags.checking_disabled);
+    assert!(!output.flags.authenticated_data);
+    assert!(
+        output
+            .answer
+            .iter()
+            .any(|record| matches!(record, Record::NS(ns) if ns.zone == leaf_zone)),
+        "{output:#?}"
+    );
+
+    Ok(())
+}
+
+// the RRSIG that covers the A RRset claims to cover the AAAA RRset instead
+#[test]
+fn rrsig_wrong_type_covered() -> Result<()> {
+    let network = Network::new()?;
+    let leaf_zone = FQDN::TEST_TLD.push_label("rrsig-wrong-type-covered");
+
+    // hickory re-signs the zone on start up, which would undo the tampering below
+    let peer = Implementation::Bind;
+    let mut leaf_ns = NameServer::new(&peer, leaf_zone.clone(), &network)?;
+    leaf_ns.add(Record::a(leaf_zone.clone(), Ipv4Addr::new(1, 2, 3, 4)));
+    let leaf_ns_addr = leaf_ns.ipv4_addr();
+
+    let Graph {
+        nameservers: _nameservers,
+        root,
+        trust_anchor,
+    } = Graph::build(
+        leaf_ns,
+        Sign::AndAmend {
+            settings: SignSettings::default(),
+            mutate: &|zone, records| {
+                if zone == &leaf_zone {
+                    let count = mislabel_rrsig_type_covered(
+                        records,
+                        &leaf_zone,
+                        RecordType::A,
+                        RecordType::AAAA,
+                    );
+                    // sanity check
+                    assert_ne!(0, count);
+                }
+            },
+        },
+    )?;
+
+    let mut resolver = Resolver::new(&network, root);
+
+    let supports_ede = dns_test::SUBJECT.is_unbound();
+    if supports_ede {
+        resolver.extended_dns_errors();
+    }
+
+    let resolver = resolver.trust_anchor(&trust_anchor.unwrap()).start()?;
+
+    let client = Client::new(resolver.network())?;
+
+    // PRE-CONDITION the authoritative server must not return an RRSIG covering the A RRset
+    let settings = *DigSettings::default().dnssec();
+    let output = client.dig(settings, leaf_ns_addr, RecordType::A, &leaf_zone)?;
+    assert!(output.status.is_noerror());
+    assert!(
+        !output.answer.iter().any(|record| matches!(
+            record,
+            Record::RRSIG(rrsig) if rrsig.type_covered == RecordType::A
+        )),
+        "peer name server fails PRE-CONDITION"
+    );
+
+    let settings = *DigSettings::default().recurse().authentic_data();
+    let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &leaf_zone)?;
+
+    dbg!(&output);
+
+    assert!(output.status.is_servfail());
+
+    if supports_ede {
+        assert!(output.ede.iter().eq([&ExtendedDnsError::RrsigsMissing]));
+    }
+
+    Ok(())
+}
+
+// "DNSSEC stripping": all the DNSSEC records are removed from the child zone but the parent's DS
+// record proves that the child zone is signed so the answer must be bogus, not insecure
+#[test]
+fn dnssec_stripped() -> Result<()> {
+    let network = Network::new()?;
+    let leaf_zone = FQDN::TEST_TLD.push_label("dnssec-stripped");
+    let needle_fqdn = leaf_zone.push_label("example");
+
+    // hickory re-signs the zone on start up, which would undo the stripping below
+    let peer = Implementation::Bind;
+    let mut leaf_ns = NameServer::new(&peer, leaf_zone.clone(), &network)?;
+    leaf_ns.add(Record::a(needle_fqdn.clone(), Ipv4Addr::new(1, 2, 3, 4)));
+    let leaf_ns_addr = leaf_ns.ipv4_addr();
+
+    let Graph {
+        nameservers: _nameservers,
+        root,
+        trust_anchor,
+    } = Graph::build(
+        leaf_ns,
+        Sign::AndAmend {
+            settings: SignSettings::default(),
+            mutate: &|zone, records| {
+                if zone == &leaf_zone {
+                    let count = strip_dnssec_records(records);
+                    // sanity check
+                    assert_ne!(0, count);
+                }
+            },
+        },
+    )?;
+
+    let mut resolver = Resolver::new(&network, root);
+
+    let supports_ede = dns_test::SUBJECT.is_unbound();
+    if supports_ede {
+        resolver.extended_dns_errors();
+    }
+
+    let resolver = resolver.trust_anchor(&trust_anchor.unwrap()).start()?;
+
+    let client = Client::new(resolver.network())?;
+
+    // PRE-CONDITION the child zone looks unsigned
+    let settings = *DigSettings::default().dnssec();
+    let output = client.dig(settings, leaf_ns_addr, RecordType::DNSKEY, &leaf_zone)?;
+    assert!(output.status.is_noerror());
+    assert!(
+        output.answer.is_empty(),
+        "peer name server fails PRE-CONDITION"
+    );
+
+    let settings = *DigSettings::default().recurse().authentic_data();
+    let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+
+    dbg!(&output);
+
+    assert!(output.status.is_servfail());
+    assert!(output.answer.is_empty());
+
+    if supports_ede {
+        assert!(
+            output.ede.iter().any(|ede| matches!(
+                ede,
+                ExtendedDnsError::DnskeyMissing
+                    | ExtendedDnsError::RrsigsMissing
+                    | ExtendedDnsError::DnssecBogus
+            )),
+            "{:?}",
+            output.ede
+        );
+    }
+
+    Ok(())
+}
+
+// the RRSIG covering the answer refers to a key tag that no DNSKEY in the (otherwise correctly
+// signed) child zone has; unlike `ds_bad_tag` the chain of trust up to the child's DNSKEY RRset
+// is intact
+#[test]
+fn rrsig_refers_to_missing_dnskey() -> Result<()> {
+    let network = Network::new()?;
+    let leaf_zone = FQDN::TEST_TLD.push_label("rrsig-missing-dnskey");
+    let needle_fqdn = leaf_zone.push_label("example");
+
+    // hickory re-signs the zone on start up, which would undo the tampering below
+    let peer = Implementation::Bind;
+    let mut leaf_ns = NameServer::new(&peer, leaf_zone.clone(), &network)?;
+    leaf_ns.add(Record::a(needle_fqdn.clone(), Ipv4Addr::new(1, 2, 3, 4)));
+
+    let Graph {
+        nameservers: _nameservers,
+        root,
+        trust_anchor,
+    } = Graph::build(
+        leaf_ns,
+        Sign::AndAmend {
+            settings: SignSettings::default(),
+            mutate: &|zone, records| {
+                if zone == &leaf_zone {
+                    let count = retag_rrsigs_to_missing_key(records, &needle_fqdn, RecordType::A);
+                    // sanity check
+                    assert_ne!(0, count);
+                }
+            },
+        },
+    )?;
+
+    let mut resolver = Resolver::new(&network, root);
+
+    let supports_ede = dns_test::SUBJECT.is_unbound();
+    if supports_ede {
+        resolver.extended_dns_errors();
+    }
+
+    let resolver = resolver.trust_anchor(&trust_anchor.unwrap()).start()?;
+
+    let client = Client::new(resolver.network())?;
+
+    // PRE-CONDITION the DNSKEY RRset of the child zone still validates
+    let settings = *DigSettings::default().recurse().authentic_data();
+    let output = client.dig(
+        settings,
+        resolver.ipv4_addr(),
+        RecordType::DNSKEY,
+        &leaf_zone,
+    )?;
+    assert!(output.status.is_noerror(), "{output:#?}");
+    assert!(output.flags.authenticated_data, "{output:#?}");
+
+    let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+
+    dbg!(&output);
+
+    assert!(output.status.is_servfail());
+    assert!(output.answer.is_empty());
+
+    if supports_ede {
+        assert!(
+            output.ede.iter().any(|ede| matches!(
+                ede,
+                ExtendedDnsError::DnssecBogus | ExtendedDnsError::RrsigsMissing
+            )),
+            "{:?}",
+            output.ede
+        );
+    }
+
     Ok(())
 }
 
+/// Changes the `key_tag` field of the RRSIGs at `owner` that cover `record_type` to a value that
+/// matches none of the DNSKEYs in `records`. Returns the number of RRSIGs that were modified.
+fn retag_rrsigs_to_missing_key(
+    records: &mut [Record],
+    owner: &FQDN,
+    record_type: RecordType,
+) -> usize {
+    let key_tags = records
+        .iter()
+        .filter_map(|record| match record {
+            Record::DNSKEY(dnskey) => Some(dnskey.rdata.calculate_key_tag()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let mut count = 0;
+    for record in records.iter_mut() {
+        if let Record::RRSIG(rrsig) = record {
+            if &rrsig.fqdn == owner && rrsig.type_covered == record_type {
+                let mut key_tag = rrsig.key_tag;
+                while key_tags.contains(&key_tag) {
+                    key_tag = key_tag.wrapping_add(1);
+                }
+                rrsig.key_tag = key_tag;
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+/// Changes the `type_covered` field of the RRSIGs at `owner` that cover `actual` to `claimed`,
+/// leaving the signature itself untouched. Returns the number of RRSIGs that were modified.
+fn mislabel_rrsig_type_covered(
+    records: &mut [Record],
+    owner: &FQDN,
+    actual: RecordType,
+    claimed: RecordType,
+) -> usize {
+    let mut count = 0;
+    for record in records.iter_mut() {
+        if let Record::RRSIG(rrsig) = record {
+            if &rrsig.fqdn == owner && rrsig.type_covered == actual {
+                rrsig.type_covered = claimed.clone();
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+/// Removes all the records produced by the signing process. Returns the number of records that
+/// were removed.
+fn strip_dnssec_records(records: &mut Vec<Record>) -> usize {
+    let len = records.len();
+    records.retain(|record| {
+        !matches!(
+            record.record_type(),
+            RecordType::DNSKEY
+                | RecordType::NSEC
+                | RecordType::NSEC3
+                | RecordType::NSEC3PARAM
+                | RecordType::RRSIG
+        )
+    });
+    len - records.len()
+}
+
 fn malformed_ds_fixture(leaf_zone: &FQDN, mutate: impl FnOnce(&mut DS)) -> Result<DigOutput> {
+    let settings = *DigSettings::default().recurse().authentic_data();
+    let [output] = malformed_ds_fixture_with_queries(leaf_zone, mutate, [settings])?;
+    Ok(output)
+}
+
+/// Sends one SOA query for `leaf_zone` per element of `settings`, in order, to the same resolver
+fn malformed_ds_fixture_with_queries<const N: usize>(
+    leaf_zone: &FQDN,
+    mutate: impl FnOnce(&mut DS),
+    settings: [DigSettings; N],
+) -> Result<[DigOutput; N]> {
     let network = Network::new()?;
     let sign_settings = SignSettings::default();
 
@@ -223,12 +599,14 @@ fn malformed_ds_fixture(leaf_zone: &FQDN, mutate: impl FnOnce(&mut DS)) -> Resul
     let resolver = resolver.trust_anchor(&trust_anchor).start()?;
 
     let client = Client::new(&network)?;
-    let settings = *DigSettings::default().recurse().authentic_data();
 
-    let ret = client.dig(settings, resolver.ipv4_addr(), RecordType::SOA, leaf_zone);
+    let ret = settings
+        .into_iter()
+        .map(|settings| client.dig(settings, resolver.ipv4_addr(), RecordType::SOA, leaf_zone))
+        .collect::<Result<Vec<_>>>();
     println!("{}", resolver.logs()?);
 
-    ret
+    Ok(ret?.try_into().unwrap())
 }
 
 #[test]
@@ -242,7 +620,7 @@ fn bogus_zone_plus_trust_anchor_dnskey() -> Result<()> {
     let mut tld_ns = NameServer::new(&PEER, FQDN::TEST_TLD, &network)?;
     let mut nameservers_ns = NameServer::new(&PEER, FQDN::TEST_DOMAIN, &network)?;
     let victim_leaf_ns = NameServer::new(&PEER, leaf_zone.clone(), &network)?;
-    let mut attacker_leaf_ns = NameServer::new(&PEER, leaf_zone.clone(), &network)?;
+    let attacker_leaf_ns = NameServer::new(&PEER, leaf_zone.clone(), &network)?;
 
     root_ns.referral_nameserver(&tld_ns);
     tld_ns.referral_nameserver(&nameservers_ns);
@@ -257,39 +635,39 @@ fn bogus_zone_plus_trust_anchor_dnskey() -> Result<()> {
     let nameservers_ns = nameservers_ns.sign(sign_settings.clone())?;
 
     // We need to phase root zone key generation before root zone signing, so we
-    // can copy the root zone public key into a child zone before generating
-    // keys and signatures for the child zone, which produces records that are
-    // needed before parent zone signing can happen.
+    // can copy the root zone key into a child zone before generating keys and
+    // signatures for the child zone, which produces records that are needed
+    // before parent zone signing can happen.
     let root_signer = Signer::new(root_ns.container(), sign_settings.clone())?;
     let root_keys = root_signer.generate_keys(&FQDN::ROOT)?;
 
     // The victim signs the leaf zone, and the victim's DS record goes into the parent zone.
-    let victim_signer = Signer::new(victim_leaf_ns.container(), sign_settings.clone())?;
-    let victim_keys = victim_signer.generate_keys(&leaf_zone)?;
-    let victim_leaf_zone = victim_signer.sign_zone(victim_leaf_ns.zone_file(), &victim_keys)?;
     // The victim's private keys are not used past this point.
-    drop(victim_keys);
+    let victim_leaf_ns = victim_leaf_ns.sign(sign_settings.clone())?;
+    let victim_ds = victim_leaf_ns.ds().ksk.clone();
     drop(victim_leaf_ns);
 
-    // The attacker adds a DNSKEY record to its zone that contains the root
-    // zone's public key, and signs the zone with its own keys.
-    let mut modified_trust_anchor_key = root_keys.ksk.public.clone().with_ttl(86400);
-    modified_trust_anchor_key.zone = leaf_zone.clone();
-    attacker_leaf_ns.add(modified_trust_anchor_key);
-    println!("before signing:\n{}", attacker_leaf_ns.zone_file());
-    let attacker_leaf_ns = attacker_leaf_ns.sign(sign_settings.clone())?;
+    // The attacker uses the root zone's KSK as the KSK of its zone, so the zone's DNSKEY RRset
+    // contains the trust anchor's public key and is signed with it.
+    let attacker_signer = Signer::new(attacker_leaf_ns.container(), sign_settings.clone())?;
+    let attacker_keys = attacker_signer.import_key(
+        &leaf_zone,
+        &root_keys.ksk.private,
+        root_keys.ksk.public.clone(),
+    )?;
+    let attacker_leaf_ns =
+        attacker_leaf_ns.sign_with_keys(sign_settings.clone(), &attacker_keys)?;
     println!("after signing:\n{}", attacker_leaf_ns.signed_zone_file());
 
     tld_ns.add(nameservers_ns.ds().ksk.clone());
     // Note that the victim's DS record is signed by the TLD zone.
-    tld_ns.add(victim_leaf_zone.ds().ksk.clone());
+    tld_ns.add(victim_ds);
 
     let tld_ns = tld_ns.sign(sign_settings.clone())?;
     root_ns.add(tld_ns.ds().ksk.clone());
 
-    let mut trust_anchor = TrustAnchor::empty();
     let root_ns = root_ns.sign_with_keys(sign_settings, &root_keys)?;
-    trust_anchor.add(root_ns.key_signing_key().clone());
+    let trust_anchor = root_ns.trust_anchor();
 
     let root_hint = root_ns.root_hint();
     let _root_ns = root_ns.start()?;
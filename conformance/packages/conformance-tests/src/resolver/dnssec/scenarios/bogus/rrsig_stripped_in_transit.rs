@@ -0,0 +1,103 @@
+//! the leaf zone is correctly signed but a proxy in front of its name server removes the RRSIG
+//! that covers the A RRset from the responses, i.e. on the wire
+
+use std::net::Ipv4Addr;
+
+use dns_test::{
+    FQDN, Implementation, Network, PEER, Resolver, Result, TrustAnchor,
+    client::{Client, DigSettings, ExtendedDnsError},
+    name_server::{NameServer, ResponseRewrite},
+    record::{Record, RecordType},
+    zone_file::SignSettings,
+};
+
+#[test]
+fn rrsig_stripped_in_transit() -> Result<()> {
+    let network = Network::new()?;
+    let leaf_zone = FQDN::TEST_TLD.push_label("rrsig-stripped-in-transit");
+    let needle_fqdn = leaf_zone.push_label("example");
+    let settings = SignSettings::default();
+
+    let mut leaf_ns = NameServer::new(&PEER, leaf_zone.clone(), &network)?;
+    leaf_ns.add(Record::a(needle_fqdn.clone(), Ipv4Addr::new(1, 2, 3, 4)));
+    let mut proxy_ns = NameServer::new(&Implementation::Dnslib, leaf_zone.clone(), &network)?;
+    proxy_ns.proxy(
+        leaf_ns.ipv4_addr(),
+        &[ResponseRewrite::DropRrsigs(RecordType::A)],
+    );
+
+    let mut nameservers_ns = NameServer::new(&PEER, FQDN::TEST_DOMAIN, &network)?;
+    let mut tld_ns = NameServer::new(&PEER, FQDN::TEST_TLD, &network)?;
+    let mut root_ns = NameServer::new(&PEER, FQDN::ROOT, &network)?;
+
+    nameservers_ns.add(root_ns.a()).add(tld_ns.a());
+
+    root_ns.referral_nameserver(&tld_ns);
+    tld_ns.referral_nameserver(&nameservers_ns);
+    // the parent zone only refers resolvers to the proxy
+    tld_ns.referral_nameserver(&proxy_ns);
+
+    let nameservers_ns = nameservers_ns.sign(settings.clone())?;
+    let leaf_ns = leaf_ns.sign(settings.clone())?;
+
+    tld_ns.add(nameservers_ns.ds().ksk.clone());
+    tld_ns.add(leaf_ns.ds().ksk.clone());
+    let tld_ns = tld_ns.sign(settings.clone())?;
+
+    root_ns.add(tld_ns.ds().ksk.clone());
+    let root_ns = root_ns.sign(settings)?;
+
+    let mut trust_anchor = TrustAnchor::empty();
+    trust_anchor.add(root_ns.key_signing_key().clone());
+    trust_anchor.add(root_ns.zone_signing_key().clone());
+
+    let root_hint = root_ns.root_hint();
+    let _root_ns = root_ns.start()?;
+    let _tld_ns = tld_ns.start()?;
+    let _nameservers_ns = nameservers_ns.start()?;
+    let leaf_ns = leaf_ns.start()?;
+    let proxy_ns = proxy_ns.start()?;
+
+    let mut resolver = Resolver::new(&network, root_hint);
+    if dns_test::SUBJECT.is_unbound() {
+        resolver.extended_dns_errors();
+    }
+    let resolver = resolver.trust_anchor(&trust_anchor).start()?;
+
+    // the leaf zone's NS RRset names the leaf name server itself; make sure the resolver can't
+    // bypass the proxy by querying it
+    network.partition(&resolver.ipv4_addr(), &leaf_ns.ipv4_addr())?;
+
+    let client = Client::new(&network)?;
+
+    // PRE-CONDITION the zone is intact and only the proxied response lacks the RRSIG
+    let covers_a = |record: &Record| matches!(record, Record::RRSIG(rrsig) if rrsig.type_covered == RecordType::A);
+    let settings = *DigSettings::default().dnssec();
+    let output = client.dig(settings, leaf_ns.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+    assert!(output.status.is_noerror());
+    assert!(output.answer.iter().any(covers_a), "{output:#?}");
+
+    let output = client.dig(settings, proxy_ns.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+    assert!(output.status.is_noerror());
+    assert!(
+        output
+            .answer
+            .iter()
+            .any(|record| matches!(record, Record::A(_))),
+        "{output:#?}"
+    );
+    assert!(!output.answer.iter().any(covers_a), "{output:#?}");
+
+    let settings = *DigSettings::default().recurse().authentic_data();
+    let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+
+    dbg!(&output);
+
+    assert!(output.status.is_servfail());
+
+    if dns_test::SUBJECT.is_unbound() {
+        assert!(output.ede.iter().eq([&ExtendedDnsError::RrsigsMissing]));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+//! the DS records of the leaf zone use different digest types
+
+use std::net::Ipv4Addr;
+
+use dns_test::client::{Client, DigOutput, DigSettings};
+use dns_test::name_server::NameServer;
+use dns_test::record::{DS, Record, RecordType};
+use dns_test::zone_file::{DsDigest, SignSettings};
+use dns_test::{FQDN, Network, Resolver, Result, TrustAnchor};
+
+const EXPECTED: Ipv4Addr = Ipv4Addr::new(1, 2, 3, 4);
+
+#[test]
+fn sha384_ds() -> Result<()> {
+    let output = fixture(DsDigest::SHA384, |_| None)?;
+
+    dbg!(&output);
+
+    assert!(output.status.is_noerror());
+    assert!(output.flags.authenticated_data);
+
+    let [record] = output.answer.try_into().unwrap();
+    assert_eq!(EXPECTED, record.try_into_a().unwrap().ipv4_addr);
+
+    Ok(())
+}
+
+// a SHA-1 DS with a digest that does not match the KSK sits next to a valid SHA-256 DS for the
+// same key. validators must use the stronger digest type and ignore the SHA-1 one (RFC4509
+// section 3)
+#[test]
+fn corrupted_sha1_ds_next_to_sha256_ds() -> Result<()> {
+    let output = fixture(DsDigest::SHA256, |ds| {
+        Some(DS {
+            digest_type: DsDigest::SHA1.number(),
+            digest: "00".repeat(DsDigest::SHA1.digest_len()),
+            ..ds.clone()
+        })
+    })?;
+
+    dbg!(&output);
+
+    assert!(output.status.is_noerror());
+    assert!(output.flags.authenticated_data);
+
+    let [record] = output.answer.try_into().unwrap();
+    assert_eq!(EXPECTED, record.try_into_a().unwrap().ipv4_addr);
+
+    Ok(())
+}
+
+/// `hickory-dns.testing.` is signed with `leaf_digest`; its DS record, plus the one
+/// `extra_ds` returns for it, is added to the `testing.` zone
+fn fixture(leaf_digest: DsDigest, extra_ds: impl Fn(&DS) -> Option<DS>) -> Result<DigOutput> {
+    let needle_fqdn = FQDN::EXAMPLE_SUBDOMAIN;
+    let settings = SignSettings::default();
+    let network = Network::new()?;
+
+    let mut leaf_ns = NameServer::new(&dns_test::PEER, FQDN::TEST_DOMAIN, &network)?;
+    let mut tld_ns = NameServer::new(&dns_test::PEER, FQDN::TEST_TLD, &network)?;
+    let mut root_ns = NameServer::new(&dns_test::PEER, FQDN::ROOT, &network)?;
+
+    leaf_ns.add(Record::a(needle_fqdn.clone(), EXPECTED));
+    leaf_ns.add(root_ns.a());
+    leaf_ns.add(tld_ns.a());
+    leaf_ns.add(leaf_ns.a());
+
+    root_ns.referral_nameserver(&tld_ns);
+    tld_ns.referral_nameserver(&leaf_ns);
+
+    let leaf_ns = leaf_ns.sign(settings.clone().ds_digest(leaf_digest))?;
+    let leaf_ds = leaf_ns.ds().ksk.clone();
+    // sanity check
+    assert_eq!(leaf_digest.number(), leaf_ds.digest_type);
+    assert_eq!(leaf_digest.digest_len() * 2, leaf_ds.digest.len());
+
+    if let Some(ds) = extra_ds(&leaf_ds) {
+        tld_ns.add(ds);
+    }
+    tld_ns.add(leaf_ds);
+    let tld_ns = tld_ns.sign(settings.clone())?;
+
+    root_ns.add(tld_ns.ds().ksk.clone());
+    let root_ns = root_ns.sign(settings)?;
+
+    let mut trust_anchor = TrustAnchor::empty();
+    trust_anchor.add(root_ns.key_signing_key().clone());
+    trust_anchor.add(root_ns.zone_signing_key().clone());
+
+    let root_hint = root_ns.root_hint();
+    let _nameservers = [root_ns.start()?, tld_ns.start()?, leaf_ns.start()?];
+
+    let resolver = Resolver::new(&network, root_hint)
+        .trust_anchor(&trust_anchor)
+        .start()?;
+
+    let client = Client::new(&network)?;
+    let settings = *DigSettings::default().recurse().authentic_data();
+    client.dig(settings, resolver.ipv4_addr(), RecordType::A, &needle_fqdn)
+}
@@ -1,4 +1,5 @@
 use std::net::Ipv4Addr;
+use std::time::{Duration, SystemTime};
 
 use dns_test::client::{Client, DigSettings, ExtendedDnsError};
 use dns_test::name_server::{Graph, NameServer, Sign};
@@ -6,6 +7,8 @@ use dns_test::record::{Record, RecordType};
 use dns_test::zone_file::SignSettings;
 use dns_test::{FQDN, Network, Resolver, Result};
 
+const ONE_DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
 #[ignore]
 #[test]
 fn dnskey_missing() -> Result<()> {
@@ -118,6 +121,27 @@ fn dnssec_bogus() -> Result<()> {
     )
 }
 
+#[ignore]
+#[test]
+fn signature_expired() -> Result<()> {
+    // well past `val-sig-skew-max` so that unbound doesn't tolerate it as clock skew
+    let now = SystemTime::now();
+    signature_validity_fixture(
+        ExtendedDnsError::SignatureExpired,
+        SignSettings::default().signature_validity(now - 10 * ONE_DAY, now - 2 * ONE_DAY),
+    )
+}
+
+#[ignore]
+#[test]
+fn signature_not_yet_valid() -> Result<()> {
+    let now = SystemTime::now();
+    signature_validity_fixture(
+        ExtendedDnsError::SignatureNotYetValid,
+        SignSettings::default().signature_validity(now + 2 * ONE_DAY, now + 10 * ONE_DAY),
+    )
+}
+
 // Sets up a minimal, DNSSEC-enabled DNS graph where the leaf zone contains a "needle" A record
 // that we'll search for
 //
@@ -129,11 +153,46 @@ fn fixture(
     expected: ExtendedDnsError,
     amend: fn(needle_fqdn: &FQDN, zone: &FQDN, records: &mut Vec<Record>),
 ) -> Result<()> {
+    let needle_fqdn = FQDN::EXAMPLE_SUBDOMAIN;
+
+    assert_needle_ede(
+        expected,
+        &needle_fqdn,
+        Sign::AndAmend {
+            settings: SignSettings::default(),
+            mutate: &|zone, records| {
+                amend(&needle_fqdn, zone, records);
+            },
+        },
+    )
+}
+
+// like `fixture` but the leaf zone is signed with `leaf_settings`, e.g. to give its signatures a
+// validity period that doesn't include the current time. the other zones are signed correctly
+fn signature_validity_fixture(
+    expected: ExtendedDnsError,
+    leaf_settings: SignSettings,
+) -> Result<()> {
+    assert_needle_ede(
+        expected,
+        &FQDN::EXAMPLE_SUBDOMAIN,
+        Sign::PerZone {
+            settings: &|zone| {
+                if zone == &FQDN::TEST_DOMAIN {
+                    leaf_settings.clone()
+                } else {
+                    SignSettings::default()
+                }
+            },
+        },
+    )
+}
+
+fn assert_needle_ede(expected: ExtendedDnsError, needle_fqdn: &FQDN, sign: Sign) -> Result<()> {
     let subject = &dns_test::SUBJECT;
     let supports_ede = subject.supports_ede();
 
     let expected_ipv4_addr = Ipv4Addr::new(1, 2, 3, 4);
-    let needle_fqdn = FQDN::EXAMPLE_SUBDOMAIN;
 
     let network = Network::new()?;
     let mut leaf_ns = NameServer::new(&dns_test::PEER, FQDN::TEST_DOMAIN, &network)?;
@@ -143,15 +202,7 @@ fn fixture(
         nameservers: _nameservers,
         root,
         trust_anchor,
-    } = Graph::build(
-        leaf_ns,
-        Sign::AndAmend {
-            settings: SignSettings::default(),
-            mutate: &|zone, records| {
-                amend(&needle_fqdn, zone, records);
-            },
-        },
-    )?;
+    } = Graph::build(leaf_ns, sign)?;
 
     let mut resolver = Resolver::new(&network, root);
 
@@ -166,7 +217,7 @@ fn fixture(
     let client = Client::new(&network)?;
 
     let settings = *DigSettings::default().recurse().authentic_data();
-    let output = client.dig(settings, resolver_addr, RecordType::A, &needle_fqdn)?;
+    let output = client.dig(settings, resolver_addr, RecordType::A, needle_fqdn)?;
 
     assert!(output.status.is_servfail());
 
@@ -1,7 +1,7 @@
 use std::net::Ipv4Addr;
 
 use dns_test::client::{Client, DigOutput, DigSettings};
-use dns_test::name_server::NameServer;
+use dns_test::name_server::{Graph, NameServer, Sign};
 use dns_test::record::{A, Record, RecordType};
 use dns_test::zone_file::{Nsec, SignSettings};
 use dns_test::{FQDN, Network, Resolver, Result, TrustAnchor};
@@ -18,6 +18,7 @@ fn unsigned_zone_nsec3() -> Result<()> {
     unsigned_zone_fixture(Nsec::_3 {
         opt_out: false,
         salt: None,
+        iterations: None,
     })
 }
 
@@ -92,6 +93,73 @@ fn unsigned_zone_fixture(nsec: Nsec) -> Result<()> {
     Ok(())
 }
 
+// root (secure) -> `testing.` (insecure: no DS in the root zone) -> `hickory-dns.testing.`
+// (signed, with a DS in the `testing.` zone)
+//
+// the leaf zone is an "island of security": it's signed but can't be reached through a chain of
+// trust from the root so its answers are insecure, not bogus
+#[test]
+fn island_of_security() -> Result<()> {
+    let expected_ipv4_addr = Ipv4Addr::new(1, 2, 3, 4);
+    let needle_fqdn = FQDN::EXAMPLE_SUBDOMAIN;
+
+    let network = Network::new()?;
+
+    let mut leaf_ns = NameServer::new(&dns_test::PEER, FQDN::TEST_DOMAIN, &network)?;
+    leaf_ns.add(Record::a(needle_fqdn.clone(), expected_ipv4_addr));
+
+    let Graph {
+        nameservers,
+        root,
+        trust_anchor,
+    } = Graph::build(
+        leaf_ns,
+        Sign::InsecureDelegations {
+            settings: SignSettings::default(),
+            insecure: &[FQDN::TEST_TLD],
+        },
+    )?;
+
+    let resolver = Resolver::new(&network, root)
+        .trust_anchor(&trust_anchor.unwrap())
+        .start()?;
+
+    let client = Client::new(&network)?;
+
+    // sanity check: the root zone has no DS for `testing.`, but `testing.` has one for the leaf
+    let root_ns = nameservers.last().unwrap();
+    let tld_ns = nameservers
+        .iter()
+        .find(|ns| ns.zone() == &FQDN::TEST_TLD)
+        .unwrap();
+    let settings = DigSettings::default();
+    for (ns, zone, has_ds) in [
+        (root_ns, FQDN::TEST_TLD, false),
+        (tld_ns, FQDN::TEST_DOMAIN, true),
+    ] {
+        let output = client.dig(settings, ns.ipv4_addr(), RecordType::DS, &zone)?;
+        let ds_count = output
+            .answer
+            .iter()
+            .filter(|record| matches!(record, Record::DS(_)))
+            .count();
+        assert_eq!(has_ds, ds_count != 0, "{output:#?}");
+    }
+
+    let settings = *DigSettings::default().recurse().authentic_data();
+    let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+
+    dbg!(&output);
+
+    assert!(output.status.is_noerror());
+    assert!(!output.flags.authenticated_data);
+
+    let [answer] = output.answer.try_into().unwrap();
+    assert_eq!(expected_ipv4_addr, answer.try_into_a().unwrap().ipv4_addr);
+
+    Ok(())
+}
+
 #[test]
 fn no_ds_record_nsec1() -> Result<()> {
     let (output, _logs) =
@@ -111,6 +179,7 @@ fn no_ds_record_nsec3() -> Result<()> {
         SignSettings::default().nsec(Nsec::_3 {
             salt: None,
             opt_out: false,
+            iterations: None,
         }),
         false,
         false,
@@ -130,6 +199,7 @@ fn no_ds_record_nsec3_case_randomization() -> Result<()> {
         SignSettings::default().nsec(Nsec::_3 {
             salt: None,
             opt_out: false,
+            iterations: None,
         }),
         true,
         false,
@@ -59,6 +59,20 @@ fn rsamd5() -> Result<()> {
     Ok(())
 }
 
+// RSA keys this short can be factored so validators must not treat the zone as secure.
+// `ldns-keygen` accepts RSA key sizes down to 512 bits
+#[test]
+fn rsa_512_bit_key() -> Result<()> {
+    let output = fixture("rsa512", SignSettings::rsasha256().rsa_key_bits(512))?;
+
+    dbg!(&output);
+
+    assert!(output.status.is_noerror() || output.status.is_servfail());
+    assert!(!output.flags.authenticated_data);
+
+    Ok(())
+}
+
 fn fixture(label: &str, deprecated_settings: SignSettings) -> Result<DigOutput> {
     let leaf_zone = FQDN::TEST_TLD.push_label(label);
     let needle_fqdn = leaf_zone.push_label("example");
@@ -0,0 +1,94 @@
+//! ZSK rollover using the double-signature method (RFC6781 section 4.1.1.2): the new ZSK is
+//! added to the zone, which is signed with both the old and the new ZSK, before the old ZSK is
+//! removed
+
+use dns_test::client::{Client, DigOutput, DigSettings};
+use dns_test::name_server::NameServer;
+use dns_test::record::{Record, RecordType};
+use dns_test::zone_file::{SignSettings, Signer, SigningKeys};
+use dns_test::{FQDN, Network, Resolver, Result, TrustAnchor};
+
+#[test]
+fn zsk_double_signature_rollover() -> Result<()> {
+    let network = Network::new()?;
+    let settings = SignSettings::default().extra_zsk();
+
+    // the same keys are used in every phase of the rollover
+    let keygen_ns = NameServer::new(&dns_test::PEER, FQDN::ROOT, &network)?;
+    let keys = Signer::new(keygen_ns.container(), settings.clone())?.generate_keys(&FQDN::ROOT)?;
+    drop(keygen_ns);
+
+    let new_zsk = keys.extra_zsks[0].clone();
+    let old_key_tag = keys.zsk.public.calculate_key_tag();
+    let new_key_tag = new_zsk.public.calculate_key_tag();
+
+    let phases = [
+        (
+            "before the rollover",
+            SigningKeys {
+                extra_zsks: vec![],
+                ..keys.clone()
+            },
+            vec![old_key_tag],
+        ),
+        (
+            "double signature",
+            keys.clone(),
+            vec![old_key_tag, new_key_tag],
+        ),
+        (
+            "after the rollover",
+            SigningKeys {
+                zsk: new_zsk,
+                extra_zsks: vec![],
+                ..keys.clone()
+            },
+            vec![new_key_tag],
+        ),
+    ];
+
+    for (phase, keys, mut expected_key_tags) in phases {
+        let output = fixture(&network, settings.clone(), &keys)?;
+
+        dbg!(phase, &output);
+
+        assert!(output.status.is_noerror(), "{phase}");
+        assert!(output.flags.authenticated_data, "{phase}");
+
+        let mut key_tags = output
+            .answer
+            .iter()
+            .filter_map(|record| match record {
+                Record::RRSIG(rrsig) if rrsig.type_covered == RecordType::A => Some(rrsig.key_tag),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        key_tags.sort();
+        expected_key_tags.sort();
+        assert_eq!(expected_key_tags, key_tags, "{phase}");
+    }
+
+    Ok(())
+}
+
+/// Signs the root zone with `keys` and queries a resolver that trusts its KSK for the A record of
+/// the root name server
+fn fixture(network: &Network, settings: SignSettings, keys: &SigningKeys) -> Result<DigOutput> {
+    let mut ns = NameServer::new(&dns_test::PEER, FQDN::ROOT, network)?;
+    ns.add(ns.a());
+    let ns = ns.sign_with_keys(settings, keys)?;
+
+    let mut trust_anchor = TrustAnchor::empty();
+    trust_anchor.add(ns.key_signing_key().clone());
+
+    let root_hint = ns.root_hint();
+    let ns = ns.start()?;
+
+    let resolver = Resolver::new(network, root_hint)
+        .trust_anchor(&trust_anchor)
+        .start()?;
+
+    let client = Client::new(network)?;
+    let settings = *DigSettings::default().recurse().dnssec().authentic_data();
+    client.dig(settings, resolver.ipv4_addr(), RecordType::A, ns.fqdn())
+}
@@ -0,0 +1,120 @@
+use std::net::Ipv4Addr;
+
+use dns_test::client::{Client, DigSettings, ExtendedDnsError};
+use dns_test::name_server::{Graph, NameServer, Sign};
+use dns_test::record::{Record, RecordType};
+use dns_test::zone_file::SignSettings;
+use dns_test::{FQDN, Network, PEER, Resolver, Result};
+
+// the parent zone is signed and contains a valid DS record for the child zone but the glue for
+// the child's name server points to a server that is not authoritative for the child zone. the
+// resolver cannot fetch the child's DNSKEY nor any signed data so resolution must fail, but it must
+// not be reported as a cryptographic validation failure
+#[test]
+fn lame_delegation_from_signed_parent() -> Result<()> {
+    let network = Network::new()?;
+    let leaf_zone = FQDN::TEST_TLD.push_label("lame-delegation");
+    let needle_fqdn = leaf_zone.push_label("example");
+
+    let mut leaf_ns = NameServer::new(&PEER, leaf_zone.clone(), &network)?;
+    leaf_ns.add(Record::a(needle_fqdn.clone(), Ipv4Addr::new(1, 2, 3, 4)));
+    let leaf_ns_fqdn = leaf_ns.fqdn().clone();
+    let leaf_ns_addr = leaf_ns.ipv4_addr();
+
+    // this server is authoritative for an unrelated zone; it does not know about `leaf_zone`
+    let lame_ns = NameServer::new(&PEER, FQDN::TEST_TLD.push_label("unrelated"), &network)?;
+    let lame_ns_addr = lame_ns.ipv4_addr();
+    let lame_ns = lame_ns.start()?;
+
+    let Graph {
+        nameservers: _nameservers,
+        root,
+        trust_anchor,
+    } = Graph::build(
+        leaf_ns,
+        Sign::AndAmend {
+            settings: SignSettings::default(),
+            mutate: &|zone, records| {
+                if zone == &FQDN::TEST_TLD {
+                    let count = redirect_glue(records, &leaf_ns_fqdn, lame_ns_addr);
+                    // sanity check
+                    assert_eq!(1, count);
+                }
+            },
+        },
+    )?;
+
+    let mut resolver = Resolver::new(&network, root);
+
+    let supports_ede = dns_test::SUBJECT.is_unbound();
+    if supports_ede {
+        resolver.extended_dns_errors();
+    }
+
+    let resolver = resolver.trust_anchor(&trust_anchor.unwrap()).start()?;
+
+    let client = Client::new(&network)?;
+
+    // PRE-CONDITIONS the child zone is served by its own name server but not by the lame one
+    let settings = *DigSettings::default().dnssec();
+    let output = client.dig(settings, leaf_ns_addr, RecordType::DNSKEY, &leaf_zone)?;
+    assert!(output.status.is_noerror());
+    assert!(!output.answer.is_empty());
+
+    let output = client.dig(
+        settings,
+        lame_ns.ipv4_addr(),
+        RecordType::DNSKEY,
+        &leaf_zone,
+    )?;
+    assert!(
+        !output.status.is_noerror() || output.answer.is_empty(),
+        "lame name server fails PRE-CONDITION"
+    );
+
+    let settings = *DigSettings::default().recurse().authentic_data();
+    let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+
+    dbg!(&output);
+
+    assert!(output.status.is_servfail());
+    assert!(output.answer.is_empty());
+
+    if supports_ede {
+        assert!(
+            output.ede.iter().any(|ede| matches!(
+                ede,
+                ExtendedDnsError::NoReachableAuthority | ExtendedDnsError::NetworkError
+            )),
+            "expected an EDE that points at the unreachable authority, got {:?}",
+            output.ede
+        );
+        assert!(
+            !output.ede.iter().any(|ede| matches!(
+                ede,
+                ExtendedDnsError::DnssecBogus
+                    | ExtendedDnsError::DnskeyMissing
+                    | ExtendedDnsError::RrsigsMissing
+            )),
+            "unreachable authority reported as a validation failure: {:?}",
+            output.ede
+        );
+    }
+
+    Ok(())
+}
+
+/// Points the glue A records of `nameserver` at `ipv4_addr`. Returns the number of records that
+/// were modified.
+fn redirect_glue(records: &mut [Record], nameserver: &FQDN, ipv4_addr: Ipv4Addr) -> usize {
+    let mut count = 0;
+    for record in records.iter_mut() {
+        if let Record::A(a) = record {
+            if &a.fqdn == nameserver {
+                a.ipv4_addr = ipv4_addr;
+                count += 1;
+            }
+        }
+    }
+    count
+}
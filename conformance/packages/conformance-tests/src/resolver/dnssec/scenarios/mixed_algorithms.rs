@@ -0,0 +1,80 @@
+//! each zone in the chain of trust is signed with a different algorithm
+
+use std::net::Ipv4Addr;
+
+use dns_test::client::{Client, DigOutput, DigSettings};
+use dns_test::name_server::{Graph, NameServer, Sign};
+use dns_test::record::{Record, RecordType};
+use dns_test::zone_file::SignSettings;
+use dns_test::{FQDN, Network, Resolver, Result};
+
+const EXPECTED: Ipv4Addr = Ipv4Addr::new(1, 2, 3, 4);
+
+#[test]
+fn mixed_algorithm_chain_is_secure() -> Result<()> {
+    let output = fixture(SignSettings::ed25519())?;
+
+    dbg!(&output);
+
+    assert!(output.status.is_noerror());
+    assert!(output.flags.authenticated_data);
+
+    let [record] = output.answer.try_into().unwrap();
+    assert_eq!(EXPECTED, record.try_into_a().unwrap().ipv4_addr);
+
+    Ok(())
+}
+
+// the TLD uses an algorithm the resolver does not support so it, and every zone below it, is
+// insecure even though the leaf zone uses a supported algorithm
+#[test]
+fn unsupported_algorithm_in_the_middle() -> Result<()> {
+    let output = fixture(SignSettings::dsa())?;
+
+    dbg!(&output);
+
+    assert!(output.status.is_noerror());
+    assert!(!output.flags.authenticated_data);
+
+    let [record] = output.answer.try_into().unwrap();
+    assert_eq!(EXPECTED, record.try_into_a().unwrap().ipv4_addr);
+
+    Ok(())
+}
+
+/// root zone signed with ECDSA, `testing.` signed with `tld_settings` and `hickory-dns.testing.`
+/// signed with RSA
+fn fixture(tld_settings: SignSettings) -> Result<DigOutput> {
+    let needle_fqdn = FQDN::EXAMPLE_SUBDOMAIN;
+    let network = Network::new()?;
+
+    let mut leaf_ns = NameServer::new(&dns_test::PEER, FQDN::TEST_DOMAIN, &network)?;
+    leaf_ns.add(Record::a(needle_fqdn.clone(), EXPECTED));
+
+    let Graph {
+        nameservers: _nameservers,
+        root,
+        trust_anchor,
+    } = Graph::build(
+        leaf_ns,
+        Sign::PerZone {
+            settings: &|zone| {
+                if zone == &FQDN::ROOT {
+                    SignSettings::ecdsap256sha256()
+                } else if zone == &FQDN::TEST_TLD {
+                    tld_settings.clone()
+                } else {
+                    SignSettings::rsasha256()
+                }
+            },
+        },
+    )?;
+
+    let resolver = Resolver::new(&network, root)
+        .trust_anchor(&trust_anchor.unwrap())
+        .start()?;
+
+    let client = Client::new(&network)?;
+    let settings = *DigSettings::default().recurse().authentic_data();
+    client.dig(settings, resolver.ipv4_addr(), RecordType::A, &needle_fqdn)
+}
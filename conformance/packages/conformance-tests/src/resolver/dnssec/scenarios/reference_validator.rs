@@ -0,0 +1,110 @@
+//! Compare the resolver's verdict against the harness' own reference validator
+//!
+//! The reference validator runs over the answer the authoritative server sends, which the
+//! resolver also receives, so both must reach the same verdict
+
+use std::net::Ipv4Addr;
+
+use base64::prelude::*;
+use dns_test::client::{Client, DigOutput, DigSettings};
+use dns_test::name_server::{NameServer, Running};
+use dns_test::record::{Record, RecordType};
+use dns_test::validator::Verdict;
+use dns_test::zone_file::SignSettings;
+use dns_test::{FQDN, Implementation, Network, Resolver, Result, TrustAnchor};
+
+#[test]
+fn secure_answer() -> Result<()> {
+    let network = Network::new()?;
+    let needle_fqdn = FQDN::TEST_TLD.push_label("example");
+
+    let mut ns = NameServer::new(&dns_test::PEER, FQDN::ROOT, &network)?;
+    ns.add(ns.a())
+        .add(Record::a(needle_fqdn.clone(), Ipv4Addr::new(1, 2, 3, 4)));
+    let ns = ns.sign(SignSettings::default())?;
+    let trust_anchor = ns.trust_anchor();
+    let ns = ns.start()?;
+
+    let (reference, output) = query(&network, &ns, &trust_anchor, &needle_fqdn)?;
+
+    assert_eq!(Verdict::Secure, reference, "{output:#?}");
+    assert_agrees(&reference, &output);
+
+    Ok(())
+}
+
+#[test]
+fn bogus_answer() -> Result<()> {
+    let network = Network::new()?;
+    let needle_fqdn = FQDN::TEST_TLD.push_label("example");
+
+    // hickory re-signs the zone on start up, which would undo the tampering below
+    let mut ns = NameServer::new(&Implementation::Bind, FQDN::ROOT, &network)?;
+    ns.add(ns.a())
+        .add(Record::a(needle_fqdn.clone(), Ipv4Addr::new(1, 2, 3, 4)));
+    let mut ns = ns.sign(SignSettings::default())?;
+
+    let mut modified = 0;
+    for record in &mut ns.signed_zone_file_mut().records {
+        if let Record::RRSIG(rrsig) = record {
+            if rrsig.fqdn == needle_fqdn {
+                let mut signature = BASE64_STANDARD.decode(&rrsig.signature)?;
+                let last = signature.last_mut().expect("empty signature");
+                *last = !*last;
+                rrsig.signature = BASE64_STANDARD.encode(&signature);
+                modified += 1;
+            }
+        }
+    }
+    assert_eq!(1, modified, "sanity check");
+
+    let trust_anchor = ns.trust_anchor();
+    let ns = ns.start()?;
+
+    let (reference, output) = query(&network, &ns, &trust_anchor, &needle_fqdn)?;
+
+    assert!(reference.is_bogus(), "{reference:?}");
+    assert_agrees(&reference, &output);
+
+    Ok(())
+}
+
+/// Returns the reference verdict on the authoritative answer and the resolver's answer
+fn query(
+    network: &Network,
+    ns: &NameServer<Running>,
+    trust_anchor: &TrustAnchor,
+    needle_fqdn: &FQDN,
+) -> Result<(Verdict, DigOutput)> {
+    let client = Client::new(network)?;
+
+    let settings = *DigSettings::default().dnssec();
+    let authoritative = client.dig(settings, ns.ipv4_addr(), RecordType::A, needle_fqdn)?;
+    assert!(authoritative.status.is_noerror(), "{authoritative:#?}");
+    let reference = authoritative.reference_verdict(trust_anchor);
+
+    let resolver = Resolver::new(network, ns.root_hint())
+        .trust_anchor(trust_anchor)
+        .start()?;
+    let settings = *DigSettings::default().recurse().authentic_data();
+    let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, needle_fqdn)?;
+
+    Ok((reference, output))
+}
+
+#[track_caller]
+fn assert_agrees(reference: &Verdict, output: &DigOutput) {
+    match reference {
+        Verdict::Secure => assert!(
+            output.status.is_noerror() && output.flags.authenticated_data,
+            "reference validator says secure but the resolver does not: {output:#?}"
+        ),
+        Verdict::Bogus(reason) => assert!(
+            output.status.is_servfail(),
+            "reference validator says bogus ({reason}) but the resolver does not: {output:#?}"
+        ),
+        Verdict::Indeterminate(reason) => {
+            panic!("reference validator could not reach a verdict: {reason}")
+        }
+    }
+}
@@ -1,10 +1,12 @@
 use std::net::Ipv4Addr;
 
+use base64::prelude::*;
+
 use dns_test::client::{Client, DigSettings};
-use dns_test::name_server::NameServer;
-use dns_test::record::{Record, RecordType};
+use dns_test::name_server::{Graph, NameServer, Sign};
+use dns_test::record::{AplItem, Class, Loc, Record, RecordType, TXT};
 use dns_test::tshark::Capture;
-use dns_test::zone_file::{Nsec, SignSettings};
+use dns_test::zone_file::{Nsec, SignAlgorithm, SignSettings};
 use dns_test::{FQDN, Network, Resolver, Result, TrustAnchor};
 
 use crate::resolver::dnssec::fixtures;
@@ -42,6 +44,35 @@ fn can_validate_without_delegation() -> Result<()> {
     Ok(())
 }
 
+// the trust anchor pins the digest of the root KSK so the resolver must check the root DNSKEY
+// RRset against it
+#[test]
+#[ignore = "hickory does not support DS records in the trust anchor"]
+fn can_validate_with_ds_trust_anchor() -> Result<()> {
+    let network = Network::new()?;
+    let mut ns = NameServer::new(&dns_test::PEER, FQDN::ROOT, &network)?;
+    ns.add(ns.a());
+    let ns = ns.sign(SignSettings::default())?;
+
+    let mut trust_anchor = TrustAnchor::empty();
+    trust_anchor.add_ds(ns.ds().ksk.clone());
+
+    let ns = ns.start()?;
+
+    let resolver = Resolver::new(&network, ns.root_hint())
+        .trust_anchor(&trust_anchor)
+        .start()?;
+
+    let client = Client::new(&network)?;
+    let settings = *DigSettings::default().recurse().authentic_data();
+    let output = client.dig(settings, resolver.ipv4_addr(), RecordType::SOA, &FQDN::ROOT)?;
+
+    assert!(output.status.is_noerror(), "{output:#?}");
+    assert!(output.flags.authenticated_data, "{output:#?}");
+
+    Ok(())
+}
+
 #[test]
 fn can_validate_with_delegation() -> Result<()> {
     let expected_ipv4_addr = Ipv4Addr::new(1, 2, 3, 4);
@@ -72,6 +103,93 @@ fn can_validate_with_delegation() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn can_validate_each_algorithm() -> Result<()> {
+    let expected_ipv4_addr = Ipv4Addr::new(1, 2, 3, 4);
+    let needle_fqdn = FQDN::EXAMPLE_SUBDOMAIN;
+
+    for algorithm in [
+        SignAlgorithm::RSASHA256,
+        SignAlgorithm::ECDSAP256SHA256,
+        SignAlgorithm::ED25519,
+    ] {
+        let (resolver, nameservers, _trust_anchor) = fixtures::minimally_secure(
+            needle_fqdn.clone(),
+            expected_ipv4_addr,
+            SignSettings::default().algorithm(algorithm),
+        )?;
+
+        for ns in &nameservers {
+            let output = Client::new(resolver.network())?.dig(
+                *DigSettings::default().dnssec(),
+                ns.ipv4_addr(),
+                RecordType::DNSKEY,
+                ns.zone(),
+            )?;
+            assert!(output.status.is_noerror(), "{output:#?}");
+            for record in output.answer {
+                match record {
+                    Record::DNSKEY(dnskey) => {
+                        assert_eq!(algorithm.number(), dnskey.rdata.algorithm)
+                    }
+                    Record::RRSIG(rrsig) => assert_eq!(algorithm.number(), rrsig.algorithm),
+                    _ => {}
+                }
+            }
+        }
+
+        let client = Client::new(resolver.network())?;
+        let settings = *DigSettings::default().recurse().authentic_data();
+        let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+
+        assert!(output.status.is_noerror(), "{algorithm:?}: {output:#?}");
+        assert!(output.flags.authenticated_data, "{algorithm:?}");
+
+        let [a] = output.answer.try_into().unwrap();
+        assert_eq!(expected_ipv4_addr, a.try_into_a().unwrap().ipv4_addr);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn can_validate_4096_bit_rsa_keys() -> Result<()> {
+    let expected_ipv4_addr = Ipv4Addr::new(1, 2, 3, 4);
+    let needle_fqdn = FQDN::EXAMPLE_SUBDOMAIN;
+
+    let (resolver, nameservers, _trust_anchor) = fixtures::minimally_secure(
+        needle_fqdn.clone(),
+        expected_ipv4_addr,
+        SignSettings::rsasha256().rsa_key_bits(4_096),
+    )?;
+
+    let client = Client::new(resolver.network())?;
+    for ns in &nameservers {
+        let output = client.dig(
+            DigSettings::default(),
+            ns.ipv4_addr(),
+            RecordType::DNSKEY,
+            ns.zone(),
+        )?;
+        assert!(!output.answer.is_empty(), "{output:#?}");
+        for record in output.answer {
+            // a 4096-bit modulus alone takes up 512 bytes, or 683 base64 characters
+            let Record::DNSKEY(dnskey) = record else {
+                panic!("unexpected record: {record:?}")
+            };
+            assert!(dnskey.rdata.public_key.len() > 683, "{dnskey:?}");
+        }
+    }
+
+    let settings = *DigSettings::default().recurse().authentic_data();
+    let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+
+    assert!(output.status.is_noerror(), "{output:#?}");
+    assert!(output.flags.authenticated_data);
+
+    Ok(())
+}
+
 // the inclusion of RRSIGs records in the answer should not change the outcome of validation
 // if the chain of trust was valid then the RRSIGs, which are part of the chain, must also be secure
 #[test]
@@ -112,6 +230,302 @@ fn also_secure_when_do_is_set() -> Result<()> {
     Ok(())
 }
 
+// the signed answer does not fit in a UDP message so the name server truncates it and the resolver
+// must retry over TCP. validation must use the complete TCP answer, not the partial UDP one
+#[test]
+fn validates_answer_received_over_tcp() -> Result<()> {
+    const NUM_TXT_RECORDS: usize = 20;
+
+    let network = Network::new()?;
+    let needle_fqdn = FQDN::EXAMPLE_SUBDOMAIN;
+
+    let mut leaf_ns = NameServer::new(&dns_test::PEER, FQDN::TEST_DOMAIN, &network)?;
+    // ~200 bytes of RDATA per record; well above the usual EDNS buffer size of 1232 bytes
+    for index in 0..NUM_TXT_RECORDS {
+        leaf_ns.add(TXT {
+            zone: needle_fqdn.clone(),
+            ttl: 86400,
+            class: Class::IN,
+            character_strings: vec![format!("{index:03}{}", "x".repeat(200))],
+        });
+    }
+
+    let Graph {
+        nameservers,
+        root,
+        trust_anchor,
+    } = Graph::build(
+        leaf_ns,
+        Sign::Yes {
+            settings: SignSettings::default(),
+        },
+    )?;
+    let leaf_ns = &nameservers[0];
+    assert_eq!(&FQDN::TEST_DOMAIN, leaf_ns.zone());
+
+    let resolver = Resolver::new(&network, root)
+        .trust_anchor(&trust_anchor.unwrap())
+        .start()?;
+    let mut tshark = resolver.eavesdrop()?;
+
+    let client = Client::new(&network)?;
+    let settings = *DigSettings::default()
+        .recurse()
+        .dnssec()
+        .authentic_data()
+        .tcp();
+    let output = client.dig(
+        settings,
+        resolver.ipv4_addr(),
+        RecordType::TXT,
+        &needle_fqdn,
+    )?;
+
+    tshark.wait_for_capture()?;
+    let captures = tshark.terminate()?;
+
+    // PRE-CONDITION the name server truncated its UDP answer
+    assert!(
+        captures.iter().any(|Capture { message, direction }| {
+            direction.peer_addr() == leaf_ns.ipv4_addr()
+                && message.is_response()
+                && message.is_tc_flag_set()
+        }),
+        "name server did not truncate its answer; captures: {captures:#?}"
+    );
+
+    assert!(output.status.is_noerror());
+    assert!(output.flags.authenticated_data);
+
+    let mut num_txt_records = 0;
+    let mut num_rrsigs = 0;
+    for record in output.answer {
+        match record {
+            Record::TXT(txt) => {
+                assert_eq!(needle_fqdn, txt.zone);
+                num_txt_records += 1;
+            }
+            Record::RRSIG(rrsig) => {
+                assert_eq!(RecordType::TXT, rrsig.type_covered);
+                num_rrsigs += 1;
+            }
+            _ => panic!("unexpected record: {record}"),
+        }
+    }
+    assert_eq!(NUM_TXT_RECORDS, num_txt_records);
+    assert_ne!(0, num_rrsigs);
+
+    Ok(())
+}
+
+#[test]
+fn validates_email_authentication_records() -> Result<()> {
+    let network = Network::new()?;
+    let spf = Record::spf(FQDN::TEST_DOMAIN, "ip4:192.0.2.0/24 -all");
+    // the size of the DER encoding of a 2048-bit RSA public key
+    let public_key = BASE64_STANDARD.encode((0..=u8::MAX).cycle().take(294).collect::<Vec<_>>());
+    let dkim = Record::dkim("mail", &FQDN::TEST_DOMAIN, &public_key);
+
+    let mut leaf_ns = NameServer::new(&dns_test::PEER, FQDN::TEST_DOMAIN, &network)?;
+    leaf_ns.add(spf.clone()).add(dkim.clone());
+
+    let Graph {
+        nameservers: _nameservers,
+        root,
+        trust_anchor,
+    } = Graph::build(
+        leaf_ns,
+        Sign::Yes {
+            settings: SignSettings::default(),
+        },
+    )?;
+
+    let resolver = Resolver::new(&network, root)
+        .trust_anchor(&trust_anchor.unwrap())
+        .start()?;
+
+    let client = Client::new(&network)?;
+    let settings = *DigSettings::default().recurse().authentic_data();
+
+    for expected in [spf, dkim] {
+        let Record::TXT(expected) = expected else {
+            unreachable!()
+        };
+
+        let output = client.dig(
+            settings,
+            resolver.ipv4_addr(),
+            RecordType::TXT,
+            &expected.zone,
+        )?;
+
+        assert!(output.status.is_noerror(), "{output:#?}");
+        assert!(output.flags.authenticated_data, "{output:#?}");
+
+        let [answer] = output.answer.try_into().unwrap();
+        let txt = answer.try_into_txt().unwrap();
+        assert_eq!(expected.character_strings, txt.character_strings);
+        assert_eq!(expected.value(), txt.value());
+    }
+
+    Ok(())
+}
+
+// these types are published in the generic encoding (RFC3597); their RDATA must survive signing,
+// validation and caching unchanged
+#[test]
+fn validates_uncommon_record_types() -> Result<()> {
+    let network = Network::new()?;
+    let records = [
+        Record::loc(
+            FQDN::TEST_DOMAIN.push_label("loc"),
+            &Loc::new(
+                (42 * 3600 + 21 * 60 + 54) * 1000,
+                -(71 * 3600 + 6 * 60 + 18) * 1000,
+                -2_400,
+            ),
+        ),
+        Record::apl(
+            FQDN::TEST_DOMAIN.push_label("apl"),
+            &[AplItem {
+                negation: false,
+                address: Ipv4Addr::new(192, 0, 2, 0).into(),
+                prefix: 24,
+            }],
+        ),
+        Record::rp(
+            FQDN::TEST_DOMAIN.push_label("rp"),
+            &FQDN::TEST_DOMAIN.push_label("admin"),
+            &FQDN::ROOT,
+        ),
+        Record::afsdb(
+            FQDN::TEST_DOMAIN.push_label("afsdb"),
+            1,
+            &FQDN::TEST_DOMAIN.push_label("afs"),
+        ),
+    ];
+
+    let mut leaf_ns = NameServer::new(&dns_test::PEER, FQDN::TEST_DOMAIN, &network)?;
+    for record in &records {
+        leaf_ns.add(record.clone());
+    }
+
+    let Graph {
+        nameservers: _nameservers,
+        root,
+        trust_anchor,
+    } = Graph::build(
+        leaf_ns,
+        Sign::Yes {
+            settings: SignSettings::default(),
+        },
+    )?;
+
+    let resolver = Resolver::new(&network, root)
+        .trust_anchor(&trust_anchor.unwrap())
+        .start()?;
+
+    let client = Client::new(&network)?;
+    let settings = *DigSettings::default()
+        .recurse()
+        .authentic_data()
+        .unknown_format();
+
+    for expected in records {
+        let expected = expected.try_into_unknown().unwrap();
+
+        let output = client.dig(
+            settings,
+            resolver.ipv4_addr(),
+            RecordType::Unknown(expected.r#type),
+            &expected.zone,
+        )?;
+
+        assert!(output.status.is_noerror(), "{output:#?}");
+        assert!(output.flags.authenticated_data, "{output:#?}");
+
+        let [answer] = output.answer.try_into().unwrap();
+        let answer = answer.try_into_unknown().unwrap();
+        assert_eq!(expected.zone, answer.zone);
+        assert_eq!(expected.r#type, answer.r#type);
+        assert_eq!(expected.rdata, answer.rdata);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn validates_tlsa_record() -> Result<()> {
+    let tlsa_fqdn = FQDN::TEST_DOMAIN
+        .push_label("www")
+        .push_label("_tcp")
+        .push_label("_443");
+    // SHA-256 (matching type 1) of a certificate's SubjectPublicKeyInfo (selector 1); usage 3 is
+    // DANE-EE
+    let data = (0..32).collect::<Vec<u8>>();
+
+    validates_record(Record::tlsa(tlsa_fqdn, 3, 1, 1, data))
+}
+
+#[test]
+fn validates_sshfp_record() -> Result<()> {
+    // SHA-256 (type 2) fingerprint of an Ed25519 (algorithm 4) host key
+    let fingerprint = (0..32).rev().collect::<Vec<u8>>();
+
+    validates_record(Record::sshfp(
+        FQDN::TEST_DOMAIN.push_label("host"),
+        4,
+        2,
+        fingerprint,
+    ))
+}
+
+/// Publishes `expected` in a signed zone and checks that it's served, unchanged and authenticated,
+/// by a validating resolver
+fn validates_record(expected: Record) -> Result<()> {
+    let network = Network::new()?;
+
+    let mut leaf_ns = NameServer::new(&dns_test::PEER, FQDN::TEST_DOMAIN, &network)?;
+    leaf_ns.add(expected.clone());
+
+    let Graph {
+        nameservers: _nameservers,
+        root,
+        trust_anchor,
+    } = Graph::build(
+        leaf_ns,
+        Sign::Yes {
+            settings: SignSettings::default(),
+        },
+    )?;
+
+    let resolver = Resolver::new(&network, root)
+        .trust_anchor(&trust_anchor.unwrap())
+        .start()?;
+
+    let client = Client::new(&network)?;
+    let settings = *DigSettings::default().recurse().dnssec().authentic_data();
+    let output = client.dig(
+        settings,
+        resolver.ipv4_addr(),
+        expected.record_type(),
+        expected.owner(),
+    )?;
+
+    assert!(output.status.is_noerror(), "{output:#?}");
+    assert!(output.flags.authenticated_data, "{output:#?}");
+
+    let answer = output
+        .answer
+        .into_iter()
+        .find(|record| record.record_type() == expected.record_type())
+        .expect("did not find the record in the response");
+    // the TTL may have been decremented by the resolver
+    assert_eq!(expected, answer.with_ttl(expected.ttl()));
+
+    Ok(())
+}
+
 #[test]
 fn caches_answer() -> Result<()> {
     let expected_ipv4_addr = Ipv4Addr::new(1, 2, 3, 4);
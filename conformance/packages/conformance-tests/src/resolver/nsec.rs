@@ -3,10 +3,11 @@
 use std::net::Ipv4Addr;
 
 use dns_test::{
-    FQDN, Network, Resolver, Result,
-    client::{Client, DigSettings},
+    FQDN, Implementation, Network, Resolver, Result,
+    client::{Client, DigSettings, ExtendedDnsError},
     name_server::{Graph, NameServer, Sign},
-    record::{Record, RecordType},
+    nsec3::{self, NSEC3Records},
+    record::{NSEC3, Record, RecordType},
     zone_file::{Nsec, SignSettings},
 };
 
@@ -15,6 +16,7 @@ fn zone_exist_domain_does_not_nsec3() -> Result<()> {
     zone_exist_domain_does_not(Nsec::_3 {
         opt_out: false,
         salt: None,
+        iterations: None,
     })
 }
 
@@ -28,6 +30,7 @@ fn zone_does_not_exist_nsec3() -> Result<()> {
     zone_does_not_exist(Nsec::_3 {
         opt_out: false,
         salt: None,
+        iterations: None,
     })
 }
 
@@ -41,6 +44,7 @@ fn domain_exists_record_type_does_not_nsec3() -> Result<()> {
     domain_exists_record_type_does_not(Nsec::_3 {
         opt_out: false,
         salt: None,
+        iterations: None,
     })
 }
 
@@ -49,6 +53,238 @@ fn domain_exists_record_type_does_not_nsec() -> Result<()> {
     domain_exists_record_type_does_not(Nsec::_1)
 }
 
+// the NSEC record at the queried name claims that a TXT RRset exists but the name server answers
+// NODATA. The NSEC record is correctly signed: the TXT RRset existed when the zone was signed and
+// was removed afterwards, along with its RRSIG
+#[test]
+fn nsec_type_bitmap_contradicts_nodata() -> Result<()> {
+    let leaf_zone = FQDN::TEST_TLD.push_label("exists");
+    let needle_fqdn = leaf_zone.push_label("example");
+
+    let network = Network::new()?;
+    // hickory re-signs the zone on start up, which would undo the removal below
+    let peer = Implementation::Bind;
+    let mut leaf_ns = NameServer::new(&peer, leaf_zone.clone(), &network)?;
+    leaf_ns.add(Record::a(needle_fqdn.clone(), Ipv4Addr::new(1, 2, 3, 4)));
+    leaf_ns.add(Record::txt(needle_fqdn.clone(), "removed after signing"));
+
+    let Graph {
+        nameservers: _nameservers,
+        root,
+        trust_anchor,
+    } = Graph::build(
+        leaf_ns,
+        Sign::AndAmend {
+            settings: SignSettings::default().nsec(Nsec::_1),
+            mutate: &|zone, records| {
+                if zone == &leaf_zone {
+                    let count = remove_rrset(records, &needle_fqdn, RecordType::TXT);
+                    // sanity check: the TXT record and its RRSIG
+                    assert_eq!(2, count);
+
+                    // PRE-CONDITION the NSEC record still lists the TXT type
+                    let claims_txt = records.iter().any(|record| match record {
+                        Record::NSEC(nsec) => {
+                            nsec.fqdn == needle_fqdn && nsec.record_types.contains(&RecordType::TXT)
+                        }
+                        _ => false,
+                    });
+                    assert!(claims_txt, "NSEC record does not list the TXT type");
+                }
+            },
+        },
+    )?;
+
+    let resolver = Resolver::new(&network, root)
+        .trust_anchor(&trust_anchor.unwrap())
+        .start()?;
+
+    let client = Client::new(&network)?;
+    let settings = *DigSettings::default().recurse().authentic_data();
+
+    // PRE-CONDITION the rest of the zone validates
+    let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+    assert!(output.status.is_noerror(), "{output:#?}");
+    assert!(output.flags.authenticated_data, "{output:#?}");
+
+    let output = client.dig(
+        settings,
+        resolver.ipv4_addr(),
+        RecordType::TXT,
+        &needle_fqdn,
+    )?;
+
+    dbg!(&output);
+
+    assert!(output.status.is_servfail());
+
+    Ok(())
+}
+
+// the NSEC3 record that covers the queried name was altered after signing so its RRSIG no longer
+// verifies. the resolver must not trust the NXDOMAIN response that it's part of
+#[test]
+fn nxdomain_with_tampered_nsec3_is_bogus() -> Result<()> {
+    let leaf_zone = FQDN::TEST_TLD.push_label("exists");
+    let needle_fqdn = leaf_zone.push_label("unicorn");
+
+    let network = Network::new()?;
+    // hickory re-signs the zone on start up, which would undo the tampering below
+    let peer = Implementation::Bind;
+    let leaf_ns = NameServer::new(&peer, leaf_zone.clone(), &network)?;
+
+    let Graph {
+        nameservers: _nameservers,
+        root,
+        trust_anchor,
+    } = Graph::build(
+        leaf_ns,
+        Sign::AndAmend {
+            settings: SignSettings::default(),
+            mutate: &|zone, records| {
+                if zone == &leaf_zone {
+                    let count = tamper_with_covering_nsec3(records, &needle_fqdn);
+                    assert_eq!(1, count, "sanity check");
+                }
+            },
+        },
+    )?;
+
+    let mut resolver = Resolver::new(&network, root);
+    if dns_test::SUBJECT.is_unbound() {
+        resolver.extended_dns_errors();
+    }
+    let resolver = resolver.trust_anchor(&trust_anchor.unwrap()).start()?;
+
+    let client = Client::new(&network)?;
+    let settings = *DigSettings::default().recurse().authentic_data();
+    let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+
+    dbg!(&output);
+
+    assert!(output.status.is_servfail());
+
+    if dns_test::SUBJECT.is_unbound() {
+        assert!(
+            output.ede.iter().eq([&ExtendedDnsError::DnssecBogus]),
+            "{:?}",
+            output.ede
+        );
+    }
+
+    Ok(())
+}
+
+// RFC9276 section 3.2: validating resolvers may treat responses whose NSEC3 records use more
+// iterations than they are willing to compute as insecure, or as bogus. Either way the NXDOMAIN
+// response must not be reported as authenticated
+#[test]
+#[ignore = "hickory does not cap NSEC3 iterations"]
+fn nxdomain_with_excessive_nsec3_iterations_is_not_authenticated() -> Result<()> {
+    const ITERATIONS: u16 = 200;
+
+    let leaf_zone = FQDN::TEST_TLD.push_label("exists");
+    let needle_fqdn = leaf_zone.push_label("unicorn");
+
+    let network = Network::new()?;
+    // hickory ignores the NSEC3 parameters the zone was signed with
+    let peer = Implementation::Bind;
+    let leaf_ns = NameServer::new(&peer, leaf_zone.clone(), &network)?;
+
+    let settings = SignSettings::default().nsec3_iterations(ITERATIONS);
+    let Graph {
+        nameservers,
+        root,
+        trust_anchor,
+    } = Graph::build(leaf_ns, Sign::Yes { settings })?;
+
+    let leaf_ns = &nameservers[0];
+    assert_eq!(&leaf_zone, leaf_ns.zone());
+
+    let resolver = Resolver::new(&network, root)
+        .trust_anchor(&trust_anchor.unwrap())
+        .start()?;
+
+    let client = Client::new(&network)?;
+
+    // PRE-CONDITION the authoritative server serves NSEC3 records with the configured iterations
+    let output = client.dig(
+        *DigSettings::default().dnssec(),
+        leaf_ns.ipv4_addr(),
+        RecordType::A,
+        &needle_fqdn,
+    )?;
+    assert!(output.status.is_nxdomain(), "{output:#?}");
+    let nsec3_rrs = output
+        .authority
+        .into_iter()
+        .filter_map(|record| record.try_into_nsec3().ok())
+        .collect::<Vec<_>>();
+    assert!(!nsec3_rrs.is_empty());
+    assert!(
+        nsec3_rrs.iter().all(|nsec3| nsec3.iterations == ITERATIONS),
+        "{nsec3_rrs:#?}"
+    );
+
+    let settings = *DigSettings::default().recurse().authentic_data();
+    let output = client.dig(settings, resolver.ipv4_addr(), RecordType::A, &needle_fqdn)?;
+
+    dbg!(&output);
+
+    assert!(
+        output.status.is_nxdomain() || output.status.is_servfail(),
+        "{output:#?}"
+    );
+    assert!(!output.flags.authenticated_data);
+
+    Ok(())
+}
+
+/// Adds a type to the bitmap of the NSEC3 record that covers `name`, which invalidates its RRSIG
+/// but not the proof of nonexistence. Returns the number of records that were modified.
+fn tamper_with_covering_nsec3(records: &mut [Record], name: &FQDN) -> usize {
+    let nsec3s = records
+        .iter()
+        .filter_map(|record| record.clone().try_into_nsec3().ok())
+        .collect::<Vec<_>>();
+    let Some(NSEC3 {
+        salt, iterations, ..
+    }) = nsec3s.first()
+    else {
+        return 0;
+    };
+    let name_hash = nsec3::hash(name, salt, *iterations).unwrap();
+    let Some(cover) = NSEC3Records::from_records(nsec3s.clone())
+        .find_cover(&name_hash)
+        .map(|nsec3| nsec3.fqdn.clone())
+    else {
+        return 0;
+    };
+
+    let mut count = 0;
+    for record in records {
+        if let Record::NSEC3(nsec3) = record {
+            if nsec3.fqdn == cover {
+                assert!(!nsec3.record_types.contains(&RecordType::CAA));
+                nsec3.record_types.push(RecordType::CAA);
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+/// Removes the `record_type` RRset at `owner` and the RRSIGs that cover it, leaving the NSEC
+/// record that lists the type in place. Returns the number of records that were removed.
+fn remove_rrset(records: &mut Vec<Record>, owner: &FQDN, record_type: RecordType) -> usize {
+    let len = records.len();
+    records.retain(|record| match record {
+        Record::RRSIG(rrsig) => !(&rrsig.fqdn == owner && rrsig.type_covered == record_type),
+        _ => !(record.owner() == owner && record.record_type() == record_type),
+    });
+    len - records.len()
+}
+
 fn zone_exist_domain_does_not(nsec: Nsec) -> Result<()> {
     let leaf_zone = FQDN::TEST_TLD.push_label("exists");
     let needle_fqdn = leaf_zone.push_label("unicorn");
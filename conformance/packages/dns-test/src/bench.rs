@@ -0,0 +1,297 @@
+//! Load generation against name servers, for performance regression testing
+
+use core::str::FromStr;
+use std::fmt::Write as _;
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use crate::container::{Container, Image, Network};
+use crate::record::RecordType;
+use crate::{Error, FQDN, Result};
+
+/// A `dnsperf` load generator
+pub struct Dnsperf {
+    inner: Container,
+}
+
+impl Dnsperf {
+    pub fn new(network: &Network) -> Result<Self> {
+        Ok(Self {
+            inner: Container::run(&Image::Dnsperf, network)?,
+        })
+    }
+
+    pub fn container_id(&self) -> &str {
+        self.inner.id()
+    }
+
+    pub fn ipv4_addr(&self) -> Ipv4Addr {
+        self.inner.ipv4_addr()
+    }
+
+    /// Sends the given `queries` to `server`, in a loop, for the duration specified in `settings`
+    pub fn bench(
+        &self,
+        settings: BenchSettings,
+        server: Ipv4Addr,
+        queries: &[(RecordType, FQDN)],
+    ) -> Result<BenchReport> {
+        const QUERIES_PATH: &str = "/queries.txt";
+
+        if queries.is_empty() {
+            return Err("at least one query must be provided".into());
+        }
+
+        let mut datafile = String::new();
+        for (record_type, fqdn) in queries {
+            writeln!(datafile, "{fqdn} {record_type}")?;
+        }
+        self.inner.cp(QUERIES_PATH, &datafile)?;
+
+        let server = server.to_string();
+        let duration = settings.duration.as_secs().max(1).to_string();
+        let clients = settings.clients.to_string();
+        let timeout = settings.timeout.as_secs().max(1).to_string();
+        let max_qps = settings.max_qps.map(|qps| qps.to_string());
+        let max_runs = settings.max_runs.map(|runs| runs.to_string());
+
+        let mut command_and_args = vec![
+            "dnsperf",
+            "-v",
+            "-s",
+            &server,
+            "-d",
+            QUERIES_PATH,
+            "-l",
+            &duration,
+            "-c",
+            &clients,
+            "-t",
+            &timeout,
+        ];
+        if let Some(max_qps) = &max_qps {
+            command_and_args.extend(["-Q", max_qps]);
+        }
+        if let Some(max_runs) = &max_runs {
+            command_and_args.extend(["-n", max_runs]);
+        }
+
+        self.inner.stdout(&command_and_args)?.parse()
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct BenchSettings {
+    duration: Duration,
+    clients: u32,
+    max_qps: Option<u32>,
+    max_runs: Option<u32>,
+    timeout: Duration,
+}
+
+impl Default for BenchSettings {
+    fn default() -> Self {
+        Self {
+            duration: Duration::from_secs(5),
+            clients: 1,
+            max_qps: None,
+            max_runs: None,
+            timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+impl BenchSettings {
+    /// How long to generate load for; rounded down to whole seconds
+    pub fn duration(&mut self, duration: Duration) -> &mut Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Number of simulated clients
+    pub fn clients(&mut self, clients: u32) -> &mut Self {
+        self.clients = clients;
+        self
+    }
+
+    /// Caps the number of queries sent per second; by default there's no cap
+    pub fn max_qps(&mut self, max_qps: u32) -> &mut Self {
+        self.max_qps = Some(max_qps);
+        self
+    }
+
+    /// Stops after sending the list of queries this many times, even if `duration` has not elapsed
+    /// yet; by default the list is sent over and over until `duration` elapses
+    pub fn max_runs(&mut self, max_runs: u32) -> &mut Self {
+        self.max_runs = Some(max_runs);
+        self
+    }
+
+    /// How long to wait for a response before considering the query lost; rounded down to whole
+    /// seconds
+    pub fn timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct BenchReport {
+    pub queries_sent: u64,
+    pub queries_completed: u64,
+    pub queries_lost: u64,
+    /// Achieved queries per second
+    pub qps: f64,
+    /// The latency of each completed query, in ascending order
+    pub latencies: Vec<Duration>,
+}
+
+impl BenchReport {
+    /// Returns the latency below which `percentile` percent of the completed queries fall
+    ///
+    /// Returns `None` if no query completed
+    pub fn latency_percentile(&self, percentile: f64) -> Option<Duration> {
+        assert!(
+            (0. ..=100.).contains(&percentile),
+            "percentile must be in the range 0..=100"
+        );
+
+        if self.latencies.is_empty() {
+            return None;
+        }
+
+        // nearest-rank method
+        let rank = (percentile / 100. * self.latencies.len() as f64).ceil() as usize;
+        Some(self.latencies[rank.saturating_sub(1)])
+    }
+}
+
+impl FromStr for BenchReport {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        const QUERIES_SENT_PREFIX: &str = "Queries sent:";
+        const QUERIES_COMPLETED_PREFIX: &str = "Queries completed:";
+        const QUERIES_LOST_PREFIX: &str = "Queries lost:";
+        const QPS_PREFIX: &str = "Queries per second:";
+        const RESPONSE_PREFIX: &str = "> ";
+
+        fn first_column(input: &str) -> Result<&str> {
+            Ok(input
+                .split_whitespace()
+                .next()
+                .ok_or("missing value in dnsperf statistics")?)
+        }
+
+        let mut queries_sent = None;
+        let mut queries_completed = None;
+        let mut queries_lost = None;
+        let mut qps = None;
+        let mut latencies = vec![];
+
+        for line in input.lines() {
+            if let Some(unprefixed) = line.strip_prefix(RESPONSE_PREFIX) {
+                // e.g. `> NOERROR example.com A 0.000126`
+                let latency = unprefixed
+                    .split_whitespace()
+                    .last()
+                    .ok_or("missing latency in dnsperf response line")?;
+                latencies.push(parse_latency(latency)?);
+                continue;
+            }
+
+            let line = line.trim();
+            if let Some(unprefixed) = line.strip_prefix(QUERIES_SENT_PREFIX) {
+                queries_sent = Some(first_column(unprefixed)?.parse()?);
+            } else if let Some(unprefixed) = line.strip_prefix(QUERIES_COMPLETED_PREFIX) {
+                queries_completed = Some(first_column(unprefixed)?.parse()?);
+            } else if let Some(unprefixed) = line.strip_prefix(QUERIES_LOST_PREFIX) {
+                queries_lost = Some(first_column(unprefixed)?.parse()?);
+            } else if let Some(unprefixed) = line.strip_prefix(QPS_PREFIX) {
+                qps = Some(first_column(unprefixed)?.parse()?);
+            }
+        }
+
+        latencies.sort();
+
+        Ok(Self {
+            queries_sent: queries_sent.ok_or("missing `Queries sent` statistic")?,
+            queries_completed: queries_completed.ok_or("missing `Queries completed` statistic")?,
+            queries_lost: queries_lost.ok_or("missing `Queries lost` statistic")?,
+            qps: qps.ok_or("missing `Queries per second` statistic")?,
+            latencies,
+        })
+    }
+}
+
+/// Parses a `dnsperf` latency, in seconds, without going through a floating point representation
+fn parse_latency(input: &str) -> Result<Duration> {
+    let (secs, fraction) = input.split_once('.').unwrap_or((input, ""));
+    if fraction.len() > 9 || !fraction.bytes().all(|byte| byte.is_ascii_digit()) {
+        return Err(format!("invalid latency: {input}").into());
+    }
+
+    let nanos = format!("{fraction:0<9}").parse()?;
+    Ok(Duration::new(secs.parse()?, nanos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report() -> Result<()> {
+        // `dnsperf -v` output, with most of the per-query lines removed
+        let input = "DNS Performance Testing Tool
+Version 2.11.2
+
+[Status] Command line: dnsperf -v -s 172.21.0.2 -d /queries.txt -l 5 -c 1 -t 5
+[Status] Sending queries (to 172.21.0.2:53)
+[Status] Started at: Wed Oct 16 11:00:00 2026
+[Status] Stopping after 5.000000 seconds
+> NOERROR . SOA 0.000310
+> NOERROR example.testing. A 0.000126
+> NOERROR . SOA 0.000201
+> NOERROR example.testing. A 0.000090
+[Status] Testing complete (time limit)
+
+Statistics:
+
+  Queries sent:         4
+  Queries completed:    4 (100.00%)
+  Queries lost:         0 (0.00%)
+
+  Response codes:       NOERROR 4 (100.00%)
+  Average packet size:  request 33, response 92
+  Run time (s):         5.000114
+  Queries per second:   0.799982
+
+  Average Latency (s):  0.000182 (min 0.000090, max 0.000310)
+  Latency StdDev (s):   0.000097
+
+";
+
+        let report: BenchReport = input.parse()?;
+
+        assert_eq!(4, report.queries_sent);
+        assert_eq!(4, report.queries_completed);
+        assert_eq!(0, report.queries_lost);
+        assert!((report.qps - 0.799982).abs() < f64::EPSILON);
+        assert_eq!(4, report.latencies.len());
+
+        assert_eq!(
+            Some(Duration::from_micros(90)),
+            report.latency_percentile(0.)
+        );
+        assert_eq!(
+            Some(Duration::from_micros(126)),
+            report.latency_percentile(50.)
+        );
+        assert_eq!(
+            Some(Duration::from_micros(310)),
+            report.latency_percentile(99.)
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,426 @@
+//! Network-wide packet capture and a minimal pcap parser
+
+use std::net::Ipv4Addr;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{self, AtomicUsize};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::{env, fs, thread};
+
+use crate::container::{Image, Network};
+use crate::record::RecordType;
+use crate::{FQDN, Result, wire};
+
+const PCAP_PATH: &str = "/tmp/capture.pcap";
+const START_TIMEOUT: Duration = Duration::from_secs(10);
+
+const LINKTYPE_ETHERNET: u32 = 1;
+const ETHERTYPE_IPV4: u16 = 0x0800;
+const IPPROTO_UDP: u8 = 17;
+
+/// A `tcpdump` process capturing the DNS traffic of a whole [`Network`]
+///
+/// Unlike [`Tshark`](crate::tshark::Tshark), which only sees the traffic of one container, this
+/// sees every packet that crosses the network's bridge, e.g. the queries a resolver sends to each
+/// of the name servers. `tcpdump` runs in a sidecar container that shares the host's network
+/// namespace.
+///
+/// The capture stops when the handle is dropped. If that happens while the thread is panicking,
+/// e.g. because an assertion failed, the pcap file is saved to the temporary directory first.
+pub struct CaptureHandle {
+    /// Name of the sidecar container
+    name: String,
+}
+
+impl CaptureHandle {
+    pub(crate) fn new(network: &Network) -> Result<Self> {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let image_tag = Image::Client.build();
+        let count = COUNT.fetch_add(1, atomic::Ordering::Relaxed);
+        let name = format!("{}-capture-{count}", network.name());
+        let interface = network.bridge_interface();
+
+        let mut command = Command::new("docker");
+        command
+            .args([
+                "run",
+                "--rm",
+                "--detach",
+                "--cap-add=NET_RAW",
+                "--cap-add=NET_ADMIN",
+                "--network=host",
+                "--name",
+                &name,
+            ])
+            .arg(image_tag)
+            // `-U` flushes each packet to the file as soon as it's captured
+            .args([
+                "tcpdump", "-i", &interface, "-U", "-Z", "root", "-w", PCAP_PATH,
+            ])
+            .arg("udp port 53");
+
+        let output = command.output()?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("{command:?} failed\n{stderr}").into());
+        }
+
+        let handle = Self { name };
+
+        // packets sent before `tcpdump` attaches to the interface would be missed
+        let deadline = Instant::now() + START_TIMEOUT;
+        loop {
+            let output = Command::new("docker")
+                .args(["logs", &handle.name])
+                .output()?;
+            let stderr = String::from_utf8_lossy(&output.stderr);
+
+            if stderr.contains("listening on") {
+                break;
+            }
+
+            if !output.status.success() || Instant::now() > deadline {
+                return Err(format!("`tcpdump` did not start\n{stderr}").into());
+            }
+
+            thread::sleep(Duration::from_millis(100));
+        }
+
+        Ok(handle)
+    }
+
+    /// Returns the DNS messages captured so far
+    pub fn packets(&self) -> Result<Vec<CapturedQuery>> {
+        parse_pcap(&self.pcap()?)
+    }
+
+    /// Writes the packets captured so far to `path`, in pcap format, e.g. to inspect them with
+    /// wireshark
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        fs::write(path, self.pcap()?)?;
+        Ok(())
+    }
+
+    fn pcap(&self) -> Result<Vec<u8>> {
+        let mut command = Command::new("docker");
+        command.args(["exec", &self.name, "cat", PCAP_PATH]);
+
+        let output = command.output()?;
+        if !output.status.success() {
+            return Err(format!("{command:?} failed").into());
+        }
+
+        Ok(output.stdout)
+    }
+}
+
+impl Drop for CaptureHandle {
+    fn drop(&mut self) {
+        if thread::panicking() {
+            let path = env::temp_dir().join(format!("{}.pcap", self.name));
+            match self.save(&path) {
+                Ok(()) => eprintln!("packet capture saved to {}", path.display()),
+                Err(e) => eprintln!("failed to save the packet capture: {e}"),
+            }
+        }
+
+        let _ = Command::new("docker")
+            .args(["rm", "--force", &self.name])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+    }
+}
+
+/// A DNS message, query or response, sent over UDP; only its header and question are parsed
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapturedQuery {
+    /// When `tcpdump` captured the packet
+    pub timestamp: SystemTime,
+    pub source: Ipv4Addr,
+    pub destination: Ipv4Addr,
+    pub id: u16,
+    /// Whether the QR flag is set
+    pub is_response: bool,
+    pub qname: FQDN,
+    pub qtype: RecordType,
+}
+
+impl CapturedQuery {
+    /// Returns `true` if this is a query whose question is about `fqdn`, in any case
+    pub fn is_query_for(&self, fqdn: &FQDN) -> bool {
+        !self.is_response && fqdn.eq_ignore_case(self.qname.as_str())
+    }
+}
+
+/// Parses the DNS messages sent over UDP and IPv4 out of a pcap file with Ethernet framing
+///
+/// Other packets are skipped, as is a truncated last record, which `tcpdump` may still be
+/// writing
+pub fn parse_pcap(pcap: &[u8]) -> Result<Vec<CapturedQuery>> {
+    let header = pcap
+        .get(..24)
+        .ok_or("pcap file is missing its global header")?;
+    let (big_endian, nanosecond_resolution) = match header[..4] {
+        [0xd4, 0xc3, 0xb2, 0xa1] => (false, false),
+        [0xa1, 0xb2, 0xc3, 0xd4] => (true, false),
+        [0x4d, 0x3c, 0xb2, 0xa1] => (false, true),
+        [0xa1, 0xb2, 0x3c, 0x4d] => (true, true),
+        _ => return Err(format!("not a pcap file; magic number: {:02x?}", &header[..4]).into()),
+    };
+    let read_u32 = |bytes: &[u8], offset: usize| {
+        let bytes = [
+            bytes[offset],
+            bytes[offset + 1],
+            bytes[offset + 2],
+            bytes[offset + 3],
+        ];
+        if big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        }
+    };
+
+    let link_type = read_u32(header, 20);
+    if link_type != LINKTYPE_ETHERNET {
+        return Err(format!("unsupported link type: {link_type}").into());
+    }
+
+    let mut messages = vec![];
+    let mut records = &pcap[24..];
+    while records.len() >= 16 {
+        let seconds = read_u32(records, 0);
+        let fraction = read_u32(records, 4);
+        let captured_len = read_u32(records, 8) as usize;
+        let Some(frame) = records.get(16..16 + captured_len) else {
+            break;
+        };
+        records = &records[16 + captured_len..];
+
+        let fraction = if nanosecond_resolution {
+            Duration::from_nanos(fraction.into())
+        } else {
+            Duration::from_micros(fraction.into())
+        };
+        let timestamp = UNIX_EPOCH + Duration::from_secs(seconds.into()) + fraction;
+
+        if let Some(message) = parse_frame(frame, timestamp) {
+            messages.push(message);
+        }
+    }
+
+    Ok(messages)
+}
+
+/// Returns `None` if `frame` does not contain a DNS message sent over UDP and IPv4
+fn parse_frame(frame: &[u8], timestamp: SystemTime) -> Option<CapturedQuery> {
+    if wire::read_u16(frame, 12).ok()? != ETHERTYPE_IPV4 {
+        return None;
+    }
+
+    let ip = frame.get(14..)?;
+    let header_len = usize::from(ip.first()? & 0x0f) * 4;
+    let fragment_offset = wire::read_u16(ip, 6).ok()? & 0x1fff;
+    if *ip.get(9)? != IPPROTO_UDP || fragment_offset != 0 {
+        return None;
+    }
+    let source = Ipv4Addr::from(<[u8; 4]>::try_from(ip.get(12..16)?).ok()?);
+    let destination = Ipv4Addr::from(<[u8; 4]>::try_from(ip.get(16..20)?).ok()?);
+
+    // skip the UDP header
+    let message = ip.get(header_len + 8..)?;
+    let id = wire::message_id(message).ok()?;
+    let flags = wire::read_u16(message, 2).ok()?;
+    let (qname, qtype) = wire::first_question(message).ok()??;
+
+    Some(CapturedQuery {
+        timestamp,
+        source,
+        destination,
+        id,
+        is_response: flags & 0x8000 != 0,
+        qname,
+        qtype,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Implementation;
+    use crate::client::{Client, DigSettings};
+    use crate::name_server::NameServer;
+
+    use super::*;
+
+    const CLIENT_ADDR: Ipv4Addr = Ipv4Addr::new(172, 21, 0, 2);
+    const SERVER_ADDR: Ipv4Addr = Ipv4Addr::new(172, 21, 0, 3);
+
+    fn pcap(frames: &[Vec<u8>]) -> Vec<u8> {
+        let mut pcap = vec![0xd4, 0xc3, 0xb2, 0xa1];
+        pcap.extend(2u16.to_le_bytes()); // major version
+        pcap.extend(4u16.to_le_bytes()); // minor version
+        pcap.extend([0; 8]); // time zone and accuracy
+        pcap.extend(65535u32.to_le_bytes()); // snapshot length
+        pcap.extend(LINKTYPE_ETHERNET.to_le_bytes());
+
+        for (seconds, frame) in frames.iter().enumerate() {
+            pcap.extend((seconds as u32).to_le_bytes());
+            pcap.extend(500u32.to_le_bytes());
+            pcap.extend((frame.len() as u32).to_le_bytes());
+            pcap.extend((frame.len() as u32).to_le_bytes());
+            pcap.extend(frame);
+        }
+        pcap
+    }
+
+    fn dns_frame(
+        source: Ipv4Addr,
+        destination: Ipv4Addr,
+        id: u16,
+        is_response: bool,
+        qname: &FQDN,
+        qtype: RecordType,
+    ) -> Vec<u8> {
+        let mut message = id.to_be_bytes().to_vec();
+        message.extend(if is_response { [0x84, 0] } else { [0, 0] });
+        message.extend([0, 1, 0, 0, 0, 0, 0, 0]);
+        message.extend(qname.wire_format().unwrap());
+        message.extend(qtype.code().to_be_bytes());
+        message.extend(1u16.to_be_bytes()); // class IN
+
+        let mut frame = vec![0; 12]; // MAC addresses
+        frame.extend(ETHERTYPE_IPV4.to_be_bytes());
+
+        frame.extend([0x45, 0]); // version 4; 20-byte header
+        frame.extend((20 + 8 + message.len() as u16).to_be_bytes());
+        frame.extend([0; 4]); // identification, flags and fragment offset
+        frame.extend([64, IPPROTO_UDP, 0, 0]); // TTL, protocol and checksum
+        frame.extend(source.octets());
+        frame.extend(destination.octets());
+
+        frame.extend(12345u16.to_be_bytes());
+        frame.extend(53u16.to_be_bytes());
+        frame.extend((8 + message.len() as u16).to_be_bytes());
+        frame.extend([0, 0]); // checksum
+
+        frame.extend(message);
+        frame
+    }
+
+    #[test]
+    fn parses_question() -> Result<()> {
+        let qname = FQDN::EXAMPLE_SUBDOMAIN;
+        let pcap = pcap(&[
+            dns_frame(CLIENT_ADDR, SERVER_ADDR, 42, false, &qname, RecordType::A),
+            dns_frame(SERVER_ADDR, CLIENT_ADDR, 42, true, &qname, RecordType::A),
+        ]);
+
+        let [query, response] = parse_pcap(&pcap)?.try_into().unwrap();
+
+        assert_eq!(
+            CapturedQuery {
+                timestamp: UNIX_EPOCH + Duration::from_micros(500),
+                source: CLIENT_ADDR,
+                destination: SERVER_ADDR,
+                id: 42,
+                is_response: false,
+                qname: qname.clone(),
+                qtype: RecordType::A,
+            },
+            query
+        );
+        assert!(query.is_query_for(&qname));
+
+        assert!(response.is_response);
+        assert_eq!(SERVER_ADDR, response.source);
+        assert!(!response.is_query_for(&qname));
+
+        Ok(())
+    }
+
+    #[test]
+    fn parses_escaped_question() -> Result<()> {
+        let qname = FQDN(r"a\.b\032c.Example.hickory-dns.testing.")?;
+        let pcap = pcap(&[dns_frame(
+            CLIENT_ADDR,
+            SERVER_ADDR,
+            42,
+            false,
+            &qname,
+            RecordType::A,
+        )]);
+
+        let [query] = parse_pcap(&pcap)?.try_into().unwrap();
+
+        assert_eq!(qname, query.qname);
+        assert!(query.is_query_for(&FQDN(r"a\.b\032c.example.hickory-dns.testing.")?));
+        assert!(!query.is_query_for(&FQDN::EXAMPLE_SUBDOMAIN));
+
+        Ok(())
+    }
+
+    #[test]
+    fn skips_other_packets_and_truncated_record() -> Result<()> {
+        let mut arp = vec![0; 12];
+        arp.extend(0x0806u16.to_be_bytes());
+        arp.extend([0; 28]);
+
+        let root_query = dns_frame(
+            CLIENT_ADDR,
+            SERVER_ADDR,
+            1,
+            false,
+            &FQDN::ROOT,
+            RecordType::NS,
+        );
+        let mut pcap = pcap(&[arp, root_query.clone(), root_query]);
+        pcap.truncate(pcap.len() - 10);
+
+        let [query] = parse_pcap(&pcap)?.try_into().unwrap();
+
+        assert_eq!(FQDN::ROOT, query.qname);
+        assert_eq!(RecordType::NS, query.qtype);
+
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_other_formats() {
+        assert!(parse_pcap(&[]).is_err());
+        assert!(parse_pcap(&[0; 24]).is_err());
+
+        let mut pcap = pcap(&[]);
+        // Linux "cooked" capture
+        pcap[20] = 113;
+        assert!(parse_pcap(&pcap).is_err());
+    }
+
+    #[test]
+    fn captures_traffic_between_containers() -> Result<()> {
+        let network = Network::new()?;
+        let ns = NameServer::new(&Implementation::test_peer(), FQDN::ROOT, &network)?.start()?;
+        let client = Client::new(&network)?;
+
+        let capture = network.capture()?;
+        let output = client.dig(
+            DigSettings::default(),
+            ns.ipv4_addr(),
+            RecordType::SOA,
+            &FQDN::ROOT,
+        )?;
+        assert!(output.status.is_noerror());
+
+        let packets = capture.packets()?;
+        assert!(
+            packets.iter().any(|packet| packet.is_query_for(&FQDN::ROOT)
+                && packet.source == client.ipv4_addr()
+                && packet.destination == ns.ipv4_addr()
+                && packet.qtype == RecordType::SOA),
+            "{packets:#?}"
+        );
+
+        Ok(())
+    }
+}
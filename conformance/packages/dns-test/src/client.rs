@@ -1,11 +1,18 @@
+use core::fmt;
 use core::str::FromStr;
-use std::collections::BTreeSet;
-use std::net::Ipv4Addr;
+use std::collections::{BTreeMap, BTreeSet};
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::{Duration, SystemTime};
+
+use base64::prelude::*;
+use ipnet::{IpNet, Ipv4Net};
 
 use crate::container::{Container, Image, Network};
-use crate::record::{Record, RecordType};
+use crate::name_server::NameServer;
+use crate::record::{CNAME, Record, RecordType, SOA};
 use crate::trust_anchor::TrustAnchor;
-use crate::{Error, FQDN, Result};
+use crate::validator::{self, Verdict};
+use crate::{Error, FQDN, Result, wire};
 
 pub struct Client {
     inner: Container,
@@ -56,16 +63,173 @@ impl Client {
         ])
     }
 
-    pub fn dig(
+    /// Issues a reverse lookup, i.e. a PTR query, for `ip_addr`
+    pub fn dig_reverse(
         &self,
         settings: DigSettings,
         server: Ipv4Addr,
+        ip_addr: IpAddr,
+    ) -> Result<DigOutput> {
+        self.dig(settings, server, RecordType::PTR, &FQDN::reverse(ip_addr))
+    }
+
+    /// Transfers the `zone` from the name server at `server` (AXFR)
+    ///
+    /// The returned records are in the order they were received so the zone's SOA record is both
+    /// the first and the last record. Returns an error if the server did not allow the transfer
+    pub fn axfr(&self, server: Ipv4Addr, zone: &FQDN) -> Result<Vec<Record>> {
+        let output = self.inner.stdout(&[
+            "dig",
+            "+comments",
+            &format!("@{server}"),
+            "AXFR",
+            zone.as_str(),
+        ])?;
+
+        parse_axfr(&output)
+    }
+
+    /// Requests the changes made to the `zone` since `from_serial` from the name server at
+    /// `server` (IXFR, RFC1995)
+    ///
+    /// Returns an error if the server did not allow the transfer
+    pub fn ixfr(&self, server: Ipv4Addr, zone: &FQDN, from_serial: u32) -> Result<IxfrResponse> {
+        let output = self.inner.stdout(&[
+            "dig",
+            "+comments",
+            &format!("@{server}"),
+            &format!("IXFR={from_serial}"),
+            zone.as_str(),
+        ])?;
+
+        parse_axfr(&output)?.try_into()
+    }
+
+    /// Sends `payload`, as is, in a single UDP datagram to port 53 of `server` and returns the
+    /// payload of the response
+    ///
+    /// Use this to send messages that `dig` won't produce, e.g. malformed ones. Returns an empty
+    /// `Vec` if no response arrives within a couple of seconds
+    pub fn send_raw(&self, server: impl Into<IpAddr>, payload: &[u8]) -> Result<Vec<u8>> {
+        let server = server.into();
+        // `docker exec` output is text so the payloads are base64 encoded on the way in and out
+        let script = format!(
+            "echo {} | base64 -d | nc -u -w 2 -q 2 {server} 53 | base64 -w 0",
+            BASE64_STANDARD.encode(payload)
+        );
+
+        let output = self.inner.stdout(&["sh", "-c", &script])?;
+        Ok(BASE64_STANDARD.decode(output.trim())?)
+    }
+
+    /// Sends a query for the `record_type` RRset at `fqdn` in a single UDP datagram to `server`
+    /// and returns the decoded response, including its wire format in [`DigOutput::raw`]
+    ///
+    /// Use this to make assertions about the exact encoding of a response, e.g. name compression.
+    /// The query does not request recursion
+    pub fn dig_wire(
+        &self,
+        server: impl Into<IpAddr>,
         record_type: RecordType,
         fqdn: &FQDN,
     ) -> Result<DigOutput> {
+        const ID: u16 = 0x2a2a;
+
+        let query = wire::encode_query(ID, record_type, fqdn, false)?;
+        let response = self.send_raw(server, &query)?;
+        if response.is_empty() {
+            return Err(format!("no response to the query for {fqdn}").into());
+        }
+
+        let id = wire::message_id(&response)?;
+        if id != ID {
+            return Err(format!("response with unexpected ID {id}").into());
+        }
+
+        wire::decode_response(&response)
+    }
+
+    /// Sends all the `queries` over a single TCP connection to `server`, without waiting for
+    /// responses in between, and returns the responses in the order of `queries`
+    ///
+    /// Responses are matched to queries by message ID so the server may answer in any order.
+    /// The queries do not request recursion. Returns an error if any query goes unanswered
+    pub fn dig_pipeline(
+        &self,
+        server: impl Into<IpAddr>,
+        queries: &[(RecordType, FQDN)],
+    ) -> Result<Vec<DigOutput>> {
+        let server = server.into();
+
+        // TCP messages are prefixed with their length (RFC1035 section 4.2.2)
+        let mut stream = vec![];
+        for (index, (record_type, fqdn)) in queries.iter().enumerate() {
+            let id = u16::try_from(index)?;
+            let query = wire::encode_query(id, record_type.clone(), fqdn, false)?;
+            stream.extend(u16::try_from(query.len())?.to_be_bytes());
+            stream.extend(query);
+        }
+
+        let script = format!(
+            "echo {} | base64 -d | nc -w 2 -q 2 {server} 53 | base64 -w 0",
+            BASE64_STANDARD.encode(&stream)
+        );
+        let output = self.inner.stdout(&["sh", "-c", &script])?;
+        let mut stream = &BASE64_STANDARD.decode(output.trim())?[..];
+
+        let mut responses = queries.iter().map(|_| None).collect::<Vec<_>>();
+        while !stream.is_empty() {
+            let len = match stream {
+                [high, low, ..] => usize::from(u16::from_be_bytes([*high, *low])),
+                _ => return Err("truncated TCP length prefix".into()),
+            };
+            let message = stream.get(2..2 + len).ok_or("truncated TCP message")?;
+            stream = &stream[2 + len..];
+
+            let id = wire::message_id(message)?;
+            let response = responses
+                .get_mut(usize::from(id))
+                .ok_or_else(|| format!("response with unexpected ID {id}"))?;
+            if response.is_some() {
+                return Err(format!("more than one response with ID {id}").into());
+            }
+            *response = Some(wire::decode_response(message)?);
+        }
+
+        responses
+            .into_iter()
+            .zip(queries)
+            .map(|(response, (record_type, fqdn))| {
+                response.ok_or_else(|| {
+                    format!("no response to the {record_type} query for {fqdn}").into()
+                })
+            })
+            .collect()
+    }
+
+    pub fn dig(
+        &self,
+        settings: DigSettings,
+        server: impl Into<IpAddr>,
+        record_type: RecordType,
+        fqdn: &FQDN,
+    ) -> Result<DigOutput> {
+        self.dig_raw(settings, server.into(), record_type, fqdn)?
+            .parse()
+    }
+
+    /// Returns the unparsed output of `dig`
+    pub(crate) fn dig_raw(
+        &self,
+        settings: DigSettings,
+        server: IpAddr,
+        record_type: RecordType,
+        fqdn: &FQDN,
+    ) -> Result<String> {
         let timeoutflag = &settings.timeoutflag();
         let ednsflag = settings.ednsflag();
         let opcodeflag = settings.opcodeflag();
+        let cookieflag = settings.cookieflag();
 
         let mut command_and_args = vec![
             "dig",
@@ -79,11 +243,12 @@ impl Client {
             opcodeflag.as_str(),
             settings.header_only_flag(),
             settings.tcpflag(),
-            settings.cookieflag(),
+            cookieflag.as_str(),
             settings.ednsnegflag(),
             settings.ignoreflag(),
             settings.nsidflag(),
             settings.expireflag(),
+            settings.unknownformatflag(),
         ];
 
         let edns_option_flag = settings.ednsoptionflag();
@@ -98,7 +263,14 @@ impl Client {
         if let Some(bufsize_flag) = bufsize_flag.as_ref() {
             command_and_args.push(bufsize_flag);
         }
-        if let Some(subnetflag) = settings.subnetflag() {
+        if let Some(tlsflag) = settings.tlsflag() {
+            command_and_args.push(tlsflag);
+        }
+        if let Some(httpsflag) = settings.httpsflag() {
+            command_and_args.push(httpsflag);
+        }
+        let subnetflag = settings.subnetflag();
+        if let Some(subnetflag) = subnetflag.as_ref() {
             command_and_args.push(subnetflag);
         }
 
@@ -106,14 +278,114 @@ impl Client {
         let record_type_name = record_type.as_name();
         command_and_args.extend_from_slice(&[
             server_arg.as_str(),
+            settings.classflag(),
             record_type_name.as_ref(),
             fqdn.as_str(),
         ]);
 
-        let output = self.inner.stdout(&command_and_args)?;
+        self.inner.stdout(&command_and_args)
+    }
+}
 
-        output.parse()
+/// Response to an IXFR query
+#[derive(Debug)]
+pub enum IxfrResponse {
+    /// The client's copy of the zone is up to date; holds the SOA record of the zone
+    UpToDate(SOA),
+    /// The changes made to the zone, oldest first
+    Incremental(Vec<IxfrDelta>),
+    /// The server sent the entire zone instead, like it would in response to an AXFR query. The
+    /// zone's SOA record is both the first and the last record
+    Full(Vec<Record>),
+}
+
+/// Changes made to a zone between two serials
+#[derive(Debug)]
+pub struct IxfrDelta {
+    pub from_serial: u32,
+    pub to_serial: u32,
+    pub removed: Vec<Record>,
+    pub added: Vec<Record>,
+}
+
+impl TryFrom<Vec<Record>> for IxfrResponse {
+    type Error = Error;
+
+    fn try_from(records: Vec<Record>) -> Result<Self> {
+        let Some(Record::SOA(current)) = records.first() else {
+            return Err(
+                format!("IXFR response does not start with an SOA record: {records:?}").into(),
+            );
+        };
+
+        let is_incremental = records.len() > 2 && records[1].is_soa();
+        if records.len() == 1 {
+            return Ok(Self::UpToDate(current.clone()));
+        } else if !is_incremental {
+            return Ok(Self::Full(records));
+        }
+
+        // current SOA, then for each change: old SOA, removed records, new SOA, added records;
+        // and finally the current SOA again
+        let mut deltas = vec![];
+        let mut records = records[1..records.len() - 1].iter().peekable();
+        while let Some(record) = records.next() {
+            let Record::SOA(from) = record else {
+                return Err(format!("expected an SOA record; found {record:?}").into());
+            };
+
+            let mut removed = vec![];
+            while let Some(record) = records.next_if(|record| !record.is_soa()) {
+                removed.push(record.clone());
+            }
+
+            let Some(Record::SOA(to)) = records.next() else {
+                return Err("IXFR change is missing its new SOA record".into());
+            };
+
+            let mut added = vec![];
+            while let Some(record) = records.next_if(|record| !record.is_soa()) {
+                added.push(record.clone());
+            }
+
+            deltas.push(IxfrDelta {
+                from_serial: from.settings.serial,
+                to_serial: to.settings.serial,
+                removed,
+                added,
+            });
+        }
+
+        Ok(Self::Incremental(deltas))
+    }
+}
+
+fn parse_axfr(output: &str) -> Result<Vec<Record>> {
+    const STATUS_PREFIX: &str = "status: ";
+
+    let mut records = vec![];
+    for line in output.lines() {
+        if let Some(comment) = line.strip_prefix(';') {
+            // every message of the transfer has its own header
+            if let Some((_, unprefixed)) = comment.split_once(STATUS_PREFIX) {
+                let (status_text, _rest) = unprefixed.split_once(',').unwrap_or((unprefixed, ""));
+                let status: DigStatus = status_text.parse()?;
+                if !status.is_noerror() {
+                    return Err(format!("zone transfer failed with status {status:?}").into());
+                }
+            } else if comment.contains("Transfer failed") {
+                return Err(format!("zone transfer failed: {output}").into());
+            }
+        } else if !line.is_empty() {
+            records.push(line.parse()?);
+        }
+    }
+
+    if records.is_empty() {
+        return Err(format!("zone transfer returned no records: {output}").into());
     }
+
+    Ok(records)
 }
 
 #[derive(Clone, Copy)]
@@ -132,7 +404,10 @@ pub struct DigSettings {
     opcode: u8,
     header_only: bool,
     tcp: bool,
+    tls: bool,
+    https: bool,
     cookie: bool,
+    client_cookie: Option<[u8; 8]>,
     ednsneg: bool,
     extra_edns_option: Option<u16>,
     extra_edns_flags: Option<u16>,
@@ -140,7 +415,9 @@ pub struct DigSettings {
     bufsize: Option<u16>,
     nsid: bool,
     expire: bool,
-    subnet_zero: bool,
+    subnet: Option<IpNet>,
+    unknown_format: bool,
+    chaos: bool,
 }
 
 impl Default for DigSettings {
@@ -156,7 +433,10 @@ impl Default for DigSettings {
             opcode: 0,
             header_only: false,
             tcp: false,
+            tls: false,
+            https: false,
             cookie: true,
+            client_cookie: None,
             ednsneg: true,
             extra_edns_option: None,
             extra_edns_flags: None,
@@ -164,7 +444,9 @@ impl Default for DigSettings {
             bufsize: None,
             nsid: false,
             expire: false,
-            subnet_zero: false,
+            subnet: None,
+            unknown_format: false,
+            chaos: false,
         }
     }
 }
@@ -298,19 +580,59 @@ impl DigSettings {
         }
     }
 
+    /// Use DNS-over-TLS (RFC7858) on port 853 instead of UDP.
+    ///
+    /// The server's certificate is not verified.
+    pub fn tls(&mut self) -> &mut Self {
+        self.tls = true;
+        self
+    }
+
+    fn tlsflag(&self) -> Option<&'static str> {
+        match self.tls {
+            true => Some("+tls"),
+            false => None,
+        }
+    }
+
+    /// Use DNS-over-HTTPS (RFC8484) on port 443, at the `/dns-query` endpoint, instead of UDP.
+    ///
+    /// The server's certificate is not verified.
+    pub fn https(&mut self) -> &mut Self {
+        self.https = true;
+        self
+    }
+
+    fn httpsflag(&self) -> Option<&'static str> {
+        match self.https {
+            true => Some("+https"),
+            false => None,
+        }
+    }
+
     /// Do not send a COOKIE EDNS option.
     pub fn nocookie(&mut self) -> &mut Self {
         self.cookie = false;
         self
     }
 
-    fn cookieflag(&self) -> &'static str {
+    /// Send a COOKIE EDNS option with the given client cookie instead of a random one.
+    pub fn cookie(&mut self, client_cookie: [u8; 8]) -> &mut Self {
+        self.cookie = true;
+        self.client_cookie = Some(client_cookie);
+        self
+    }
+
+    fn cookieflag(&self) -> String {
         // Only use "+cookie" when EDNS is enabled (the default). Otherwise, "+cookie" overrides
         // "+noedns".
-        if self.edns.is_some() && self.cookie {
-            "+cookie"
-        } else {
-            "+nocookie"
+        if self.edns.is_none() || !self.cookie {
+            return "+nocookie".to_string();
+        }
+
+        match self.client_cookie {
+            Some(client_cookie) => format!("+cookie={}", hex::encode(client_cookie)),
+            None => "+cookie".to_string(),
         }
     }
 
@@ -399,16 +721,51 @@ impl DigSettings {
         }
     }
 
+    /// Send the EDNS client subnet option, with the subnet 0.0.0.0/0.
     pub fn subnet_zero(&mut self) -> &mut Self {
-        self.subnet_zero = true;
+        self.subnet(Ipv4Net::default().into())
+    }
+
+    /// Send the EDNS client subnet option (RFC7871), with the given subnet.
+    pub fn subnet(&mut self, net: IpNet) -> &mut Self {
+        self.subnet = Some(net);
         self
     }
 
-    /// Send the EDNS client subnet option, with the subnet 0.0.0.0/0.
-    fn subnetflag(&self) -> Option<&'static str> {
-        match self.subnet_zero {
-            true => Some("+subnet=0"),
-            false => None,
+    fn subnetflag(&self) -> Option<String> {
+        let net = self.subnet?;
+        if net.prefix_len() == 0 && net.addr().is_unspecified() {
+            Some("+subnet=0".to_string())
+        } else {
+            Some(format!("+subnet={net}"))
+        }
+    }
+
+    /// Print the RDATA of all records in the generic encoding (RFC3597), which parses into
+    /// [`Record::Unknown`], instead of their presentation format
+    pub fn unknown_format(&mut self) -> &mut Self {
+        self.unknown_format = true;
+        self
+    }
+
+    fn unknownformatflag(&self) -> &'static str {
+        match self.unknown_format {
+            true => "+unknownformat",
+            false => "+nounknownformat",
+        }
+    }
+
+    /// Sends the query in the CHAOS class instead of the Internet class, e.g. to ask for
+    /// `version.bind`
+    pub fn class_chaos(&mut self) -> &mut Self {
+        self.chaos = true;
+        self
+    }
+
+    fn classflag(&self) -> &'static str {
+        match self.chaos {
+            true => "CH",
+            false => "IN",
         }
     }
 }
@@ -416,6 +773,8 @@ impl DigSettings {
 #[derive(Debug)]
 pub struct DigOutput {
     pub ede: BTreeSet<ExtendedDnsError>,
+    /// The EXTRA-TEXT of the EDEs in `ede` that carry one
+    pub ede_extra_text: BTreeMap<ExtendedDnsError, String>,
     pub flags: DigFlags,
     pub status: DigStatus,
     pub answer: Vec<Record>,
@@ -424,10 +783,305 @@ pub struct DigOutput {
     pub opt: bool,
     pub options: Vec<(u16, String)>,
     pub must_be_zero: bool,
-    pub edns_must_be_zero: bool,
     pub opcode: String,
-    pub edns_version: Option<u8>,
-    pub dnssec_ok_flag: bool,
+    /// Name server identifier (RFC5001); only present if requested with [`DigSettings::nsid`]
+    pub nsid: Option<String>,
+    /// COOKIE option (RFC7873) returned by the server: the 8-byte client cookie followed by the
+    /// server cookie, if any
+    pub cookie: Option<Vec<u8>>,
+    /// SCOPE PREFIX-LENGTH of the CLIENT-SUBNET option (RFC7871) returned by the server
+    pub ecs_scope: Option<u8>,
+    /// Round-trip time of the query, as measured by `dig`; `None` if the output has no stats
+    pub query_time: Option<Duration>,
+    /// Size, in bytes, of the response; `None` if the output has no stats
+    pub msg_size: Option<usize>,
+    /// The OPT pseudo-record of the response; `None` if it has none
+    pub edns: Option<EdnsInfo>,
+    /// The response in the wire format; only present if it was received with
+    /// [`Client::dig_wire`] or [`Client::dig_pipeline`]
+    pub raw: Option<Vec<u8>>,
+}
+
+/// The fixed fields of an OPT pseudo-record (RFC6891 section 6.1.3)
+///
+/// The extended RCODE bits are not included; `dig` folds them into [`DigOutput::status`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EdnsInfo {
+    pub version: u8,
+    /// DNSSEC OK bit (RFC3225)
+    pub dnssec_ok: bool,
+    /// Requestor's UDP payload size
+    pub udp_payload_size: u16,
+    /// The reserved flag bits, which must be zero
+    pub must_be_zero: u16,
+}
+
+impl FromStr for EdnsInfo {
+    type Err = Error;
+
+    /// Parses dig's `; EDNS: version: ` line without that prefix, e.g.
+    /// `0, flags: do; MBZ: 0x0040, udp: 1232`
+    fn from_str(input: &str) -> Result<Self> {
+        let (version, rest) = input
+            .split_once(", flags:")
+            .ok_or_else(|| format!("invalid EDNS line: {input}"))?;
+        let (flags, rest) = rest
+            .split_once(';')
+            .ok_or_else(|| format!("invalid EDNS line: {input}"))?;
+
+        let mut must_be_zero = 0;
+        let mut udp_payload_size = None;
+        for field in rest.split(',') {
+            let Some((name, value)) = field.trim().split_once(": ") else {
+                continue;
+            };
+
+            match name {
+                "MBZ" => {
+                    let hex = value.trim_start_matches("0x");
+                    must_be_zero = u16::from_str_radix(hex, 16)?;
+                }
+                "udp" => udp_payload_size = Some(value.parse()?),
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            version: version.parse()?,
+            dnssec_ok: flags.split_whitespace().any(|flag| flag == "do"),
+            udp_payload_size: udp_payload_size
+                .ok_or_else(|| format!("EDNS line has no UDP payload size: {input}"))?,
+            must_be_zero,
+        })
+    }
+}
+
+impl DigOutput {
+    /// The version of the OPT pseudo-record; `None` if the response has none
+    pub fn edns_version(&self) -> Option<u8> {
+        self.edns.map(|edns| edns.version)
+    }
+
+    /// Returns `true` if the OPT pseudo-record has the DNSSEC OK bit set
+    pub fn dnssec_ok_flag(&self) -> bool {
+        self.edns.is_some_and(|edns| edns.dnssec_ok)
+    }
+
+    /// Returns `true` if any of the reserved flag bits of the OPT pseudo-record is set
+    pub fn edns_must_be_zero(&self) -> bool {
+        self.edns.is_some_and(|edns| edns.must_be_zero != 0)
+    }
+
+    /// Returns `true` if a record of the given type appears in the answer, authority or additional
+    /// section
+    pub fn contains_record_type(&self, record_type: RecordType) -> bool {
+        self.answer
+            .iter()
+            .chain(&self.authority)
+            .chain(&self.additional)
+            .any(|record| record.record_type() == record_type)
+    }
+
+    /// Follows the CNAME records in the answer section, starting at `qname`, and returns them in
+    /// chain order
+    ///
+    /// DNAME-synthesized CNAMEs are part of the chain; the DNAME records themselves are not
+    pub fn cname_chain(&self, qname: &FQDN) -> Vec<&CNAME> {
+        let mut chain: Vec<&CNAME> = vec![];
+        let mut owner = qname;
+        // bounded by the size of the answer section in case the chain loops
+        while chain.len() < self.answer.len() {
+            let Some(cname) = self.answer.iter().find_map(|record| match record {
+                Record::CNAME(cname) if cname.fqdn == *owner => Some(cname),
+                _ => None,
+            }) else {
+                break;
+            };
+            chain.push(cname);
+            owner = &cname.target;
+        }
+        chain
+    }
+
+    /// Compares this output against the `expected` one
+    ///
+    /// Only the parts of `expected` that are set take part in the comparison. Records are
+    /// compared regardless of their order within a section.
+    pub fn diff(&self, expected: &ExpectedOutput) -> DigOutputDiff {
+        let status = expected
+            .status
+            .filter(|status| *status != self.status)
+            .map(|status| (status, self.status));
+
+        let flags = expected
+            .flags
+            .as_ref()
+            .map(|flags| flags.diff(&self.flags))
+            .unwrap_or_default();
+
+        let section = |expected: &Option<Vec<Record>>, actual: &[Record]| {
+            expected
+                .as_ref()
+                .map(|expected| SectionDiff::new(expected, actual))
+                .unwrap_or_default()
+        };
+
+        DigOutputDiff {
+            status,
+            flags,
+            answer: section(&expected.answer, &self.answer),
+            authority: section(&expected.authority, &self.authority),
+            additional: section(&expected.additional, &self.additional),
+        }
+    }
+
+    /// Validates the answer section with the harness' own, minimal, DNSSEC validator
+    ///
+    /// This provides a verdict that is independent of the implementations under test. See the
+    /// [`validator`](crate::validator) module for its limitations
+    pub fn reference_verdict(&self, trust_anchor: &TrustAnchor) -> Verdict {
+        validator::validate(&self.answer, trust_anchor, SystemTime::now())
+    }
+
+    /// Returns the algorithm numbers of the RRSIGs, in any section, that cover the `record_type`
+    /// RRset at `owner`
+    pub fn rrsig_algorithms(&self, owner: &FQDN, record_type: RecordType) -> Vec<u8> {
+        self.answer
+            .iter()
+            .chain(&self.authority)
+            .chain(&self.additional)
+            .filter_map(|record| match record {
+                Record::RRSIG(rrsig)
+                    if &rrsig.fqdn == owner && rrsig.type_covered == record_type =>
+                {
+                    Some(rrsig.algorithm)
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Panics with a structured diff if this output does not match the `expected` one
+    #[track_caller]
+    pub fn assert_matches(&self, expected: &ExpectedOutput) {
+        let diff = self.diff(expected);
+        assert!(diff.is_empty(), "unexpected dig output\n{diff}");
+    }
+
+    /// Panics if the response was not sent by `nameserver`, as identified by its NSID
+    ///
+    /// NSID is a hop-by-hop option so this identifies the server that `dig` queried, not the
+    /// authoritative servers a resolver may have used. The query must have been sent with
+    /// [`DigSettings::nsid`].
+    #[track_caller]
+    pub fn assert_answered_by<S>(&self, nameserver: &NameServer<S>) {
+        let Some(nsid) = &self.nsid else {
+            panic!("response has no NSID; was it requested with `DigSettings::nsid`?");
+        };
+
+        assert_eq!(
+            nameserver.nsid(),
+            nsid,
+            "response was not sent by {} ({})",
+            nameserver.fqdn(),
+            nameserver.ipv4_addr()
+        );
+    }
+}
+
+/// The parts of a `DigOutput` that a test cares about; see [`DigOutput::diff`]
+#[derive(Debug, Default)]
+pub struct ExpectedOutput {
+    pub status: Option<DigStatus>,
+    pub flags: Option<DigFlags>,
+    pub answer: Option<Vec<Record>>,
+    pub authority: Option<Vec<Record>>,
+    pub additional: Option<Vec<Record>>,
+}
+
+/// The output of [`DigOutput::diff`]
+#[derive(Debug, Default)]
+pub struct DigOutputDiff {
+    /// (expected, actual)
+    pub status: Option<(DigStatus, DigStatus)>,
+    /// (flag name, expected, actual)
+    pub flags: Vec<(&'static str, bool, bool)>,
+    pub answer: SectionDiff,
+    pub authority: SectionDiff,
+    pub additional: SectionDiff,
+}
+
+impl DigOutputDiff {
+    pub fn is_empty(&self) -> bool {
+        self.status.is_none()
+            && self.flags.is_empty()
+            && self.answer.is_empty()
+            && self.authority.is_empty()
+            && self.additional.is_empty()
+    }
+}
+
+impl fmt::Display for DigOutputDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some((expected, actual)) = self.status {
+            writeln!(f, "status: expected {expected:?}, got {actual:?}")?;
+        }
+
+        if !self.flags.is_empty() {
+            writeln!(f, "flags:")?;
+            for (name, expected, actual) in &self.flags {
+                writeln!(f, "  {name}: expected {expected}, got {actual}")?;
+            }
+        }
+
+        for (name, section) in [
+            ("answer", &self.answer),
+            ("authority", &self.authority),
+            ("additional", &self.additional),
+        ] {
+            if section.is_empty() {
+                continue;
+            }
+
+            writeln!(f, "{name} section:")?;
+            for record in &section.missing {
+                writeln!(f, "- {record}")?;
+            }
+            for record in &section.extra {
+                writeln!(f, "+ {record}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Records that were expected but are `missing`, and records that were not expected but are
+/// present (`extra`)
+#[derive(Debug, Default)]
+pub struct SectionDiff {
+    pub missing: Vec<Record>,
+    pub extra: Vec<Record>,
+}
+
+impl SectionDiff {
+    fn new(expected: &[Record], actual: &[Record]) -> Self {
+        let mut extra = actual.to_vec();
+        let mut missing = vec![];
+        for record in expected {
+            if let Some(index) = extra.iter().position(|other| other == record) {
+                extra.remove(index);
+            } else {
+                missing.push(record.clone());
+            }
+        }
+
+        Self { missing, extra }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.extra.is_empty()
+    }
 }
 
 impl FromStr for DigOutput {
@@ -441,9 +1095,14 @@ impl FromStr for DigOutput {
         const OPT_PREFIX: &str = "; OPT=";
         const OPT_HEADER: &str = ";; OPT PSEUDOSECTION:";
         const EDNS_PREFIX: &str = "; EDNS: version: ";
+        const NSID_PREFIX: &str = "; NSID: ";
+        const COOKIE_PREFIX: &str = "; COOKIE: ";
+        const CLIENT_SUBNET_PREFIX: &str = "; CLIENT-SUBNET: ";
         const ANSWER_HEADER: &str = ";; ANSWER SECTION:";
         const AUTHORITY_HEADER: &str = ";; AUTHORITY SECTION:";
         const ADDITIONAL_HEADER: &str = ";; ADDITIONAL SECTION:";
+        const QUERY_TIME_PREFIX: &str = ";; Query time: ";
+        const MSG_SIZE_PREFIX: &str = ";; MSG SIZE  rcvd: ";
 
         fn not_found(prefix: &str) -> String {
             format!("`{prefix}` line was not found")
@@ -463,13 +1122,17 @@ impl FromStr for DigOutput {
         let mut authority = None;
         let mut additional = None;
         let mut ede = BTreeSet::new();
+        let mut ede_extra_text = BTreeMap::new();
         let mut options = Vec::new();
         let mut opt = false;
         let mut must_be_zero = false;
         let mut opcode = None;
-        let mut edns_version = None;
-        let mut edns_must_be_zero = false;
-        let mut dnssec_ok_flag = false;
+        let mut nsid = None;
+        let mut cookie = None;
+        let mut ecs_scope = None;
+        let mut query_time = None;
+        let mut msg_size = None;
+        let mut edns = None;
 
         let mut lines = input.lines();
         while let Some(line) = lines.next() {
@@ -512,34 +1175,76 @@ impl FromStr for DigOutput {
 
                 status = Some(status_text.parse()?);
             } else if let Some(unprefixed) = line.strip_prefix(EDE_PREFIX) {
-                let code = unprefixed
-                    .split_once(' ')
-                    .map(|(code, _rest)| code)
-                    .unwrap_or(unprefixed);
+                let (code, rest) = unprefixed.split_once(' ').unwrap_or((unprefixed, ""));
 
                 let code = code.parse()?;
                 let inserted = ede.insert(code);
                 assert!(inserted, "unexpected: duplicate EDE {code:?}");
+
+                // e.g. `9 (DNSKEY Missing): (no SEP matching the DS found)`
+                if let Some((_name, extra_text)) = rest.split_once("): (") {
+                    let extra_text = extra_text.strip_suffix(')').unwrap_or(extra_text);
+                    ede_extra_text.insert(code, extra_text.to_string());
+                }
             } else if line.starts_with(OPT_HEADER) {
                 opt = true;
             } else if let Some(unprefixed) = line.strip_prefix(EDNS_PREFIX) {
-                let (version_text, _rest) = unprefixed
-                    .split_once(',')
-                    .ok_or_else(|| missing(EDNS_PREFIX, "comma (,)"))?;
-
-                if edns_version.is_some() {
+                if edns.is_some() {
                     return Err(more_than_once(EDNS_PREFIX).into());
                 }
 
-                edns_version = Some(version_text.parse()?);
+                edns = Some(unprefixed.parse()?);
+            } else if let Some(unprefixed) = line.strip_prefix(NSID_PREFIX) {
+                // e.g. `; NSID: 6e 73 31 ("ns1")`; the quoted part is the printable form
+                let (_hex, quoted) = unprefixed
+                    .split_once('"')
+                    .ok_or_else(|| missing(NSID_PREFIX, "quote (\")"))?;
+                let (printable, _rest) = quoted
+                    .split_once('"')
+                    .ok_or_else(|| missing(NSID_PREFIX, "quote (\")"))?;
+
+                if nsid.is_some() {
+                    return Err(more_than_once(NSID_PREFIX).into());
+                }
 
-                if line.contains("MBZ:") {
-                    edns_must_be_zero = true;
+                nsid = Some(printable.to_string());
+            } else if let Some(unprefixed) = line.strip_prefix(COOKIE_PREFIX) {
+                // e.g. `; COOKIE: 0102030405060708b1a6c5f8ce6c3e55 (good)`
+                let (hex, _rest) = unprefixed.split_once(' ').unwrap_or((unprefixed, ""));
+
+                if cookie.is_some() {
+                    return Err(more_than_once(COOKIE_PREFIX).into());
                 }
 
-                if line.contains("flags: do") {
-                    dnssec_ok_flag = true;
+                cookie = Some(hex::decode(hex)?);
+            } else if let Some(unprefixed) = line.strip_prefix(CLIENT_SUBNET_PREFIX) {
+                // e.g. `; CLIENT-SUBNET: 192.0.2.0/24/16`; the last component is the scope
+                let (_subnet, scope) = unprefixed
+                    .rsplit_once('/')
+                    .ok_or_else(|| missing(CLIENT_SUBNET_PREFIX, "slash (/)"))?;
+
+                if ecs_scope.is_some() {
+                    return Err(more_than_once(CLIENT_SUBNET_PREFIX).into());
                 }
+
+                ecs_scope = Some(scope.trim().parse()?);
+            } else if let Some(unprefixed) = line.strip_prefix(QUERY_TIME_PREFIX) {
+                // e.g. `;; Query time: 43 msec`
+                let millis = unprefixed
+                    .strip_suffix(" msec")
+                    .ok_or_else(|| missing(QUERY_TIME_PREFIX, "unit (msec)"))?;
+
+                if query_time.is_some() {
+                    return Err(more_than_once(QUERY_TIME_PREFIX).into());
+                }
+
+                query_time = Some(Duration::from_millis(millis.parse()?));
+            } else if let Some(unprefixed) = line.strip_prefix(MSG_SIZE_PREFIX) {
+                if msg_size.is_some() {
+                    return Err(more_than_once(MSG_SIZE_PREFIX).into());
+                }
+
+                msg_size = Some(unprefixed.trim().parse()?);
             } else if let Some(unprefixed) = line.strip_prefix(OPT_PREFIX) {
                 let Some((option_str, value)) = unprefixed.split_once(": ") else {
                     return Err("could not parse option".into());
@@ -600,15 +1305,20 @@ impl FromStr for DigOutput {
             authority: authority.unwrap_or_default(),
             additional: additional.unwrap_or_default(),
             ede,
+            ede_extra_text,
             flags: flags.ok_or_else(|| not_found(FLAGS_PREFIX))?,
             status: status.ok_or_else(|| not_found(STATUS_PREFIX))?,
             options,
             opt,
             must_be_zero,
-            edns_must_be_zero,
             opcode: opcode.ok_or_else(|| not_found(OPCODE_PREFIX))?,
-            edns_version,
-            dnssec_ok_flag,
+            nsid,
+            cookie,
+            ecs_scope,
+            query_time,
+            msg_size,
+            edns,
+            raw: None,
         })
     }
 }
@@ -617,10 +1327,15 @@ impl FromStr for DigOutput {
 pub enum ExtendedDnsError {
     UnsupportedDnskeyAlgorithm = 1,
     DnssecBogus = 6,
+    SignatureExpired = 7,
+    SignatureNotYetValid = 8,
     DnskeyMissing = 9,
     RrsigsMissing = 10,
+    Blocked = 15,
+    Censored = 16,
     Prohibited = 18,
     NoReachableAuthority = 22,
+    NetworkError = 23,
 }
 
 impl FromStr for ExtendedDnsError {
@@ -632,10 +1347,15 @@ impl FromStr for ExtendedDnsError {
         let code = match code {
             1 => Self::UnsupportedDnskeyAlgorithm,
             6 => Self::DnssecBogus,
+            7 => Self::SignatureExpired,
+            8 => Self::SignatureNotYetValid,
             9 => Self::DnskeyMissing,
             10 => Self::RrsigsMissing,
+            15 => Self::Blocked,
+            16 => Self::Censored,
             18 => Self::Prohibited,
             22 => Self::NoReachableAuthority,
+            23 => Self::NetworkError,
             _ => todo!("EDE {code} has not yet been implemented"),
         };
 
@@ -643,7 +1363,7 @@ impl FromStr for ExtendedDnsError {
     }
 }
 
-#[derive(Debug, Default, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct DigFlags {
     pub authenticated_data: bool,
     pub authoritative_answer: bool,
@@ -654,6 +1374,34 @@ pub struct DigFlags {
     pub truncation: bool,
 }
 
+impl DigFlags {
+    /// Returns the flags that differ, as (flag name, our value, `actual` value) triples
+    fn diff(&self, actual: &DigFlags) -> Vec<(&'static str, bool, bool)> {
+        let Self {
+            authenticated_data,
+            authoritative_answer,
+            checking_disabled,
+            qr,
+            recursion_available,
+            recursion_desired,
+            truncation,
+        } = *self;
+
+        [
+            ("ad", authenticated_data, actual.authenticated_data),
+            ("aa", authoritative_answer, actual.authoritative_answer),
+            ("cd", checking_disabled, actual.checking_disabled),
+            ("qr", qr, actual.qr),
+            ("ra", recursion_available, actual.recursion_available),
+            ("rd", recursion_desired, actual.recursion_desired),
+            ("tc", truncation, actual.truncation),
+        ]
+        .into_iter()
+        .filter(|(_, expected, actual)| expected != actual)
+        .collect()
+    }
+}
+
 impl FromStr for DigFlags {
     type Err = Error;
 
@@ -691,15 +1439,32 @@ impl FromStr for DigFlags {
     }
 }
 
+/// The response code, including the extended RCODE bits of the OPT pseudo-record (RFC6891
+/// section 6.1.3); `dig` combines both into the 12-bit code it reports as `status`
 #[allow(clippy::upper_case_acronyms)]
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum DigStatus {
-    BADVERS,
     NOERROR,
-    NOTIMP,
+    FORMERR,
+    SERVFAIL,
     NXDOMAIN,
+    NOTIMP,
     REFUSED,
-    SERVFAIL,
+    YXDOMAIN,
+    YXRRSET,
+    NXRRSET,
+    NOTAUTH,
+    NOTZONE,
+    BADVERS,
+    BADKEY,
+    BADTIME,
+    BADMODE,
+    BADNAME,
+    BADALG,
+    BADTRUNC,
+    BADCOOKIE,
+    /// A code without a mnemonic, e.g. one of the reserved codes 11 to 15
+    Other(u16),
 }
 
 impl DigStatus {
@@ -708,6 +1473,11 @@ impl DigStatus {
         matches!(self, Self::NOERROR)
     }
 
+    #[must_use]
+    pub fn is_formerr(&self) -> bool {
+        matches!(self, Self::FORMERR)
+    }
+
     #[must_use]
     pub fn is_nxdomain(&self) -> bool {
         matches!(self, Self::NXDOMAIN)
@@ -717,6 +1487,83 @@ impl DigStatus {
     pub fn is_servfail(&self) -> bool {
         matches!(self, Self::SERVFAIL)
     }
+
+    #[must_use]
+    pub fn is_notimp(&self) -> bool {
+        matches!(self, Self::NOTIMP)
+    }
+
+    #[must_use]
+    pub fn is_refused(&self) -> bool {
+        matches!(self, Self::REFUSED)
+    }
+
+    #[must_use]
+    pub fn is_badvers(&self) -> bool {
+        matches!(self, Self::BADVERS)
+    }
+
+    /// The numeric response code (RFC6895 section 2.3)
+    pub fn code(&self) -> u16 {
+        match self {
+            Self::NOERROR => 0,
+            Self::FORMERR => 1,
+            Self::SERVFAIL => 2,
+            Self::NXDOMAIN => 3,
+            Self::NOTIMP => 4,
+            Self::REFUSED => 5,
+            Self::YXDOMAIN => 6,
+            Self::YXRRSET => 7,
+            Self::NXRRSET => 8,
+            Self::NOTAUTH => 9,
+            Self::NOTZONE => 10,
+            Self::BADVERS => 16,
+            Self::BADKEY => 17,
+            Self::BADTIME => 18,
+            Self::BADMODE => 19,
+            Self::BADNAME => 20,
+            Self::BADALG => 21,
+            Self::BADTRUNC => 22,
+            Self::BADCOOKIE => 23,
+            Self::Other(code) => *code,
+        }
+    }
+
+    /// The inverse of [`DigStatus::code`]
+    pub fn from_code(code: u16) -> Self {
+        match code {
+            0 => Self::NOERROR,
+            1 => Self::FORMERR,
+            2 => Self::SERVFAIL,
+            3 => Self::NXDOMAIN,
+            4 => Self::NOTIMP,
+            5 => Self::REFUSED,
+            6 => Self::YXDOMAIN,
+            7 => Self::YXRRSET,
+            8 => Self::NXRRSET,
+            9 => Self::NOTAUTH,
+            10 => Self::NOTZONE,
+            16 => Self::BADVERS,
+            17 => Self::BADKEY,
+            18 => Self::BADTIME,
+            19 => Self::BADMODE,
+            20 => Self::BADNAME,
+            21 => Self::BADALG,
+            22 => Self::BADTRUNC,
+            23 => Self::BADCOOKIE,
+            _ => Self::Other(code),
+        }
+    }
+
+    /// The upper 8 bits of the code, which travel in the OPT pseudo-record
+    pub fn extended_rcode(&self) -> u8 {
+        (self.code() >> 4) as u8
+    }
+
+    /// The lower 4 bits of the code, which travel in the message header
+    pub fn header_rcode(&self) -> u8 {
+        (self.code() & 0xf) as u8
+    }
 }
 
 impl FromStr for DigStatus {
@@ -724,13 +1571,33 @@ impl FromStr for DigStatus {
 
     fn from_str(input: &str) -> Result<Self> {
         let status = match input {
-            "BADVERS" => Self::BADVERS,
             "NOERROR" => Self::NOERROR,
-            "NOTIMP" => Self::NOTIMP,
+            "FORMERR" => Self::FORMERR,
+            "SERVFAIL" => Self::SERVFAIL,
             "NXDOMAIN" => Self::NXDOMAIN,
+            "NOTIMP" => Self::NOTIMP,
             "REFUSED" => Self::REFUSED,
-            "SERVFAIL" => Self::SERVFAIL,
-            _ => return Err(format!("unknown status: {input}").into()),
+            "YXDOMAIN" => Self::YXDOMAIN,
+            "YXRRSET" => Self::YXRRSET,
+            "NXRRSET" => Self::NXRRSET,
+            "NOTAUTH" => Self::NOTAUTH,
+            "NOTZONE" => Self::NOTZONE,
+            "BADVERS" => Self::BADVERS,
+            "BADKEY" => Self::BADKEY,
+            "BADTIME" => Self::BADTIME,
+            "BADMODE" => Self::BADMODE,
+            "BADNAME" => Self::BADNAME,
+            "BADALG" => Self::BADALG,
+            "BADTRUNC" => Self::BADTRUNC,
+            "BADCOOKIE" => Self::BADCOOKIE,
+            // `dig` prints reserved codes as e.g. `RESERVED11` and unassigned ones as numbers
+            _ => {
+                let code = input.strip_prefix("RESERVED").unwrap_or(input);
+                let code = code
+                    .parse()
+                    .map_err(|_| format!("unknown status: {input}"))?;
+                Self::from_code(code)
+            }
         };
 
         Ok(status)
@@ -741,6 +1608,36 @@ impl FromStr for DigStatus {
 mod tests {
     use super::*;
 
+    #[test]
+    fn status_codes() -> Result<()> {
+        for (input, code) in [
+            ("NOERROR", 0),
+            ("FORMERR", 1),
+            ("NOTAUTH", 9),
+            ("RESERVED11", 11),
+            ("BADVERS", 16),
+            ("BADCOOKIE", 23),
+            ("3841", 3841),
+        ] {
+            let status: DigStatus = input.parse()?;
+            assert_eq!(code, status.code(), "{input}");
+        }
+
+        for code in 0..=4095 {
+            assert_eq!(code, DigStatus::from_code(code).code());
+        }
+
+        let badvers = DigStatus::BADVERS;
+        assert_eq!(1, badvers.extended_rcode());
+        assert_eq!(0, badvers.header_rcode());
+        assert!(badvers.is_badvers());
+
+        assert_eq!(DigStatus::NXDOMAIN, "3".parse()?);
+        assert!("garbage".parse::<DigStatus>().is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn dig_nxdomain() -> Result<()> {
         // $ dig nonexistent.domain.
@@ -776,6 +1673,104 @@ mod tests {
         );
         assert!(output.answer.is_empty());
         assert!(output.opt);
+        assert_eq!(Some(Duration::from_millis(3)), output.query_time);
+        assert_eq!(Some(47), output.msg_size);
+
+        Ok(())
+    }
+
+    #[test]
+    fn stats_are_optional() -> Result<()> {
+        // $ dig +nostats nonexistent.domain.
+        let input = "
+; <<>> DiG 9.18.18-0ubuntu0.22.04.1-Ubuntu <<>> +nostats nonexistent.domain.
+;; global options: +cmd
+;; Got answer:
+;; ->>HEADER<<- opcode: QUERY, status: NXDOMAIN, id: 45583
+;; flags: qr rd ra; QUERY: 1, ANSWER: 0, AUTHORITY: 0, ADDITIONAL: 1
+
+;; OPT PSEUDOSECTION:
+; EDNS: version: 0, flags:; udp: 1232
+;; QUESTION SECTION:
+;nonexistent.domain.		IN	A
+
+";
+
+        let output: DigOutput = input.parse()?;
+
+        assert_eq!(None, output.query_time);
+        assert_eq!(None, output.msg_size);
+
+        Ok(())
+    }
+
+    #[test]
+    fn diff() -> Result<()> {
+        // $ dig A .
+        let input = "
+; <<>> DiG 9.18.24 <<>> A .
+;; global options: +cmd
+;; Got answer:
+;; ->>HEADER<<- opcode: QUERY, status: NOERROR, id: 39670
+;; flags: qr rd ra; QUERY: 1, ANSWER: 0, AUTHORITY: 1, ADDITIONAL: 1
+
+;; OPT PSEUDOSECTION:
+; EDNS: version: 0, flags:; udp: 1232
+;; QUESTION SECTION:
+;.				IN	A
+
+;; AUTHORITY SECTION:
+.			2910	IN	SOA	a.root-servers.net. nstld.verisign-grs.com. 2024022600 1800 900 604800 86400
+
+;; Query time: 43 msec
+;; SERVER: 192.168.1.1#53(192.168.1.1) (UDP)
+;; WHEN: Mon Feb 26 11:55:50 CET 2024
+;; MSG SIZE  rcvd: 103
+";
+
+        let output: DigOutput = input.parse()?;
+        let soa: Record = ".	2910	IN	SOA	a.root-servers.net. nstld.verisign-grs.com. 2024022600 1800 900 604800 86400".parse()?;
+        let a = Record::a(FQDN::ROOT, Ipv4Addr::new(1, 2, 3, 4));
+
+        let expected = ExpectedOutput {
+            status: Some(DigStatus::NOERROR),
+            authority: Some(vec![soa.clone()]),
+            ..ExpectedOutput::default()
+        };
+        assert!(output.diff(&expected).is_empty());
+        output.assert_matches(&expected);
+
+        let expected = ExpectedOutput {
+            status: Some(DigStatus::NXDOMAIN),
+            flags: Some(DigFlags {
+                qr: true,
+                recursion_desired: true,
+                ..DigFlags::default()
+            }),
+            answer: Some(vec![a.clone()]),
+            authority: Some(vec![]),
+            ..ExpectedOutput::default()
+        };
+        let diff = output.diff(&expected);
+        assert_eq!(Some((DigStatus::NXDOMAIN, DigStatus::NOERROR)), diff.status);
+        assert_eq!(vec![("ra", false, true)], diff.flags);
+        assert_eq!(vec![a.clone()], diff.answer.missing);
+        assert!(diff.answer.extra.is_empty());
+        assert!(diff.authority.missing.is_empty());
+        assert_eq!(vec![soa.clone()], diff.authority.extra);
+        assert!(diff.additional.is_empty());
+
+        let expected = format!(
+            "status: expected NXDOMAIN, got NOERROR
+flags:
+  ra: expected false, got true
+answer section:
+- {a}
+authority section:
++ {soa}
+"
+        );
+        assert_eq!(expected, diff.to_string());
 
         Ok(())
     }
@@ -851,6 +1846,53 @@ l.root-servers.net. 518400  IN  A   199.7.83.42
         Ok(())
     }
 
+    #[test]
+    fn cname_chain() -> Result<()> {
+        // $ dig @1.1.1.1 A www.github.com.
+        // with a second alias spliced in
+        let input = "; <<>> DiG 9.18.28-1~deb12u2-Debian <<>> @1.1.1.1 A www.github.com.
+; (1 server found)
+;; global options: +cmd
+;; Got answer:
+;; ->>HEADER<<- opcode: QUERY, status: NOERROR, id: 20116
+;; flags: qr rd ra; QUERY: 1, ANSWER: 3, AUTHORITY: 0, ADDITIONAL: 1
+
+;; OPT PSEUDOSECTION:
+; EDNS: version: 0, flags:; udp: 1232
+;; QUESTION SECTION:
+;www.github.com.			IN	A
+
+;; ANSWER SECTION:
+www.github.com.		3600	IN	CNAME	github.com.
+github.com.		3600	IN	CNAME	lb.github.com.
+lb.github.com.		60	IN	A	140.82.121.4
+
+;; Query time: 20 msec
+;; SERVER: 1.1.1.1#53(1.1.1.1) (UDP)
+;; WHEN: Mon Jan 13 10:02:11 UTC 2025
+;; MSG SIZE  rcvd: 93
+";
+
+        let output: DigOutput = input.parse()?;
+
+        let chain = output
+            .cname_chain(&FQDN("www.github.com.")?)
+            .into_iter()
+            .map(|cname| (cname.fqdn.as_str(), cname.target.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            [
+                ("www.github.com.", "github.com."),
+                ("github.com.", "lb.github.com."),
+            ],
+            chain.as_slice()
+        );
+
+        assert!(output.cname_chain(&FQDN("lb.github.com.")?).is_empty());
+
+        Ok(())
+    }
+
     #[test]
     fn ede() -> Result<()> {
         let input = "; <<>> DiG 9.18.24-1-Debian <<>> +recurse +nodnssec +adflag +nocdflag @192.168.176.5 A example.nameservers.com.
@@ -874,12 +1916,225 @@ l.root-servers.net. 518400  IN  A   199.7.83.42
 
         let output: DigOutput = input.parse()?;
 
-        assert!(output.ede.into_iter().eq([ExtendedDnsError::DnskeyMissing]));
-        assert_eq!(output.edns_version, Some(0));
+        assert!(
+            output
+                .ede
+                .iter()
+                .copied()
+                .eq([ExtendedDnsError::DnskeyMissing])
+        );
+        assert_eq!(output.edns_version(), Some(0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn nsid() -> Result<()> {
+        let input = "; <<>> DiG 9.18.28-1~deb12u2-Debian <<>> +nsid @172.21.0.2 SOA hickory-dns.testing.
+; (1 server found)
+;; global options: +cmd
+;; Got answer:
+;; ->>HEADER<<- opcode: QUERY, status: NOERROR, id: 20127
+;; flags: qr aa rd; QUERY: 1, ANSWER: 1, AUTHORITY: 0, ADDITIONAL: 1
+;; WARNING: recursion requested but not available
+
+;; OPT PSEUDOSECTION:
+; EDNS: version: 0, flags:; udp: 1232
+; NSID: 70 72 69 6d 61 72 79 31 2e 68 69 63 6b 6f 72 79 2d 64 6e 73 2e 74 65 73 74 69 6e 67 2e (\"primary1.hickory-dns.testing.\")
+;; QUESTION SECTION:
+;hickory-dns.testing.		IN	SOA
+
+;; ANSWER SECTION:
+hickory-dns.testing.	86400	IN	SOA	primary1.hickory-dns.testing. admin1.hickory-dns.testing. 2024010101 1800 900 604800 86400
+
+;; Query time: 0 msec
+;; SERVER: 172.21.0.2#53(172.21.0.2) (UDP)
+;; WHEN: Fri Oct 16 11:00:00 UTC 2026
+;; MSG SIZE  rcvd: 150
+";
+
+        let output: DigOutput = input.parse()?;
+
+        assert_eq!(
+            Some("primary1.hickory-dns.testing."),
+            output.nsid.as_deref()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn cookie() -> Result<()> {
+        let input = "; <<>> DiG 9.18.28-1~deb12u2-Debian <<>> +cookie=0102030405060708 @172.21.0.2 SOA hickory-dns.testing.
+; (1 server found)
+;; global options: +cmd
+;; Got answer:
+;; ->>HEADER<<- opcode: QUERY, status: NOERROR, id: 20127
+;; flags: qr aa rd; QUERY: 1, ANSWER: 1, AUTHORITY: 0, ADDITIONAL: 1
+;; WARNING: recursion requested but not available
+
+;; OPT PSEUDOSECTION:
+; EDNS: version: 0, flags:; udp: 1232
+; COOKIE: 0102030405060708010000006711a8b0c7e2d9f41b3a5c6e (good)
+;; QUESTION SECTION:
+;hickory-dns.testing.		IN	SOA
+
+;; ANSWER SECTION:
+hickory-dns.testing.	86400	IN	SOA	primary1.hickory-dns.testing. admin1.hickory-dns.testing. 2024010101 1800 900 604800 86400
+
+;; Query time: 0 msec
+;; SERVER: 172.21.0.2#53(172.21.0.2) (UDP)
+;; WHEN: Fri Oct 16 11:00:00 UTC 2026
+;; MSG SIZE  rcvd: 150
+";
+
+        let output: DigOutput = input.parse()?;
+
+        let cookie = output.cookie.unwrap();
+        assert_eq!([1, 2, 3, 4, 5, 6, 7, 8], cookie[..8]);
+        assert_eq!(16, cookie[8..].len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn client_subnet() -> Result<()> {
+        let input = "; <<>> DiG 9.18.28-1~deb12u2-Debian <<>> +subnet=2001:db8:1::/48 @172.21.0.2 A example.hickory-dns.testing.
+; (1 server found)
+;; global options: +cmd
+;; Got answer:
+;; ->>HEADER<<- opcode: QUERY, status: NOERROR, id: 20127
+;; flags: qr aa rd; QUERY: 1, ANSWER: 1, AUTHORITY: 0, ADDITIONAL: 1
+;; WARNING: recursion requested but not available
+
+;; OPT PSEUDOSECTION:
+; EDNS: version: 0, flags:; udp: 1232
+; CLIENT-SUBNET: 2001:db8:1::/48/32
+;; QUESTION SECTION:
+;example.hickory-dns.testing.	IN	A
+
+;; ANSWER SECTION:
+example.hickory-dns.testing. 300	IN	A	10.0.0.2
+
+;; Query time: 0 msec
+;; SERVER: 172.21.0.2#53(172.21.0.2) (UDP)
+;; WHEN: Fri Oct 16 11:00:00 UTC 2026
+;; MSG SIZE  rcvd: 150
+";
+
+        let output: DigOutput = input.parse()?;
+        assert_eq!(Some(32), output.ecs_scope);
+
+        let output: DigOutput = input
+            .replace("; CLIENT-SUBNET: 2001:db8:1::/48/32\n", "")
+            .parse()?;
+        assert_eq!(None, output.ecs_scope);
+
+        Ok(())
+    }
+
+    #[test]
+    fn axfr() -> Result<()> {
+        let input = "
+; <<>> DiG 9.18.28-1~deb12u2-Debian <<>> +comments @172.21.0.2 AXFR hickory-dns.testing.
+; (1 server found)
+;; global options: +cmd
+;; Got answer:
+;; ->>HEADER<<- opcode: QUERY, status: NOERROR, id: 17064
+;; flags: qr aa; QUERY: 1, ANSWER: 4, AUTHORITY: 0, ADDITIONAL: 0
+
+;; QUESTION SECTION:
+;hickory-dns.testing.		IN	AXFR
+
+;; ANSWER SECTION:
+hickory-dns.testing.	86400	IN	SOA	primary1.hickory-dns.testing. admin1.hickory-dns.testing. 2024010101 1800 900 604800 86400
+hickory-dns.testing.	86400	IN	NS	primary1.hickory-dns.testing.
+primary1.hickory-dns.testing. 86400 IN	A	172.21.0.2
+hickory-dns.testing.	86400	IN	SOA	primary1.hickory-dns.testing. admin1.hickory-dns.testing. 2024010101 1800 900 604800 86400
+
+;; Query time: 0 msec
+;; SERVER: 172.21.0.2#53(172.21.0.2) (TCP)
+;; WHEN: Fri Oct 16 11:00:00 UTC 2026
+;; XFR size: 4 records (messages 1, bytes 203)
+
+";
+
+        let records = parse_axfr(input)?;
+
+        assert_eq!(4, records.len());
+        assert!(records[0].is_soa());
+        assert!(records[3].is_soa());
 
         Ok(())
     }
 
+    #[test]
+    fn ixfr() -> Result<()> {
+        let soa = |serial| {
+            format!(
+                "hickory-dns.testing.	86400	IN	SOA	primary1.hickory-dns.testing. admin1.hickory-dns.testing. {serial} 1800 900 604800 86400"
+            )
+        };
+        let a = |ipv4_addr| format!("example.hickory-dns.testing. 86400 IN	A	{ipv4_addr}");
+
+        let records = [
+            soa(3),
+            soa(1),
+            a("1.1.1.1"),
+            soa(2),
+            a("2.2.2.2"),
+            soa(2),
+            soa(3),
+            soa(3),
+        ]
+        .iter()
+        .map(|line| line.parse())
+        .collect::<Result<Vec<Record>>>()?;
+
+        let IxfrResponse::Incremental(deltas) = records.try_into()? else {
+            panic!("expected an incremental response");
+        };
+        let [first, second] = deltas.try_into().unwrap();
+        assert_eq!((1, 2), (first.from_serial, first.to_serial));
+        assert_eq!(vec![a("1.1.1.1").parse::<Record>()?], first.removed);
+        assert_eq!(vec![a("2.2.2.2").parse::<Record>()?], first.added);
+        assert_eq!((2, 3), (second.from_serial, second.to_serial));
+        assert!(second.removed.is_empty());
+        assert!(second.added.is_empty());
+
+        let records = [soa(3), a("2.2.2.2"), soa(3)]
+            .iter()
+            .map(|line| line.parse())
+            .collect::<Result<Vec<Record>>>()?;
+        assert!(matches!(records.try_into()?, IxfrResponse::Full(_)));
+
+        let records = vec![soa(3).parse()?];
+        assert!(matches!(records.try_into()?, IxfrResponse::UpToDate(_)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn axfr_refused() {
+        let input = "
+; <<>> DiG 9.18.28-1~deb12u2-Debian <<>> +comments @172.21.0.2 AXFR hickory-dns.testing.
+; (1 server found)
+;; global options: +cmd
+;; Got answer:
+;; ->>HEADER<<- opcode: QUERY, status: REFUSED, id: 17064
+;; flags: qr; QUERY: 1, ANSWER: 0, AUTHORITY: 0, ADDITIONAL: 0
+
+;; QUESTION SECTION:
+;hickory-dns.testing.		IN	AXFR
+
+; Transfer failed.
+";
+
+        let error = parse_axfr(input).unwrap_err();
+
+        assert!(error.to_string().contains("REFUSED"), "{error}");
+    }
+
     #[test]
     fn multiple_ede() -> Result<()> {
         let input = "; <<>> DiG 9.18.28-1~deb12u2-Debian <<>> @1.1.1.1 allow-query-none.extended-dns-errors.com.
@@ -909,6 +2164,18 @@ l.root-servers.net. 518400  IN  A   199.7.83.42
             ExtendedDnsError::Prohibited,
             ExtendedDnsError::NoReachableAuthority,
         ]));
+        assert_eq!(
+            Some("no SEP matching the DS found for allow-query-none.extended-dns-errors.com."),
+            output
+                .ede_extra_text
+                .get(&ExtendedDnsError::DnskeyMissing)
+                .map(String::as_str)
+        );
+        assert!(
+            !output
+                .ede_extra_text
+                .contains_key(&ExtendedDnsError::Prohibited)
+        );
 
         Ok(())
     }
@@ -1026,7 +2293,16 @@ example.testing.	0	IN	A	1.2.3.4
         let output: DigOutput = input.parse()?;
 
         assert!(!output.must_be_zero);
-        assert!(output.edns_must_be_zero);
+        assert!(output.edns_must_be_zero());
+        assert_eq!(
+            Some(EdnsInfo {
+                version: 0,
+                dnssec_ok: false,
+                udp_payload_size: 1,
+                must_be_zero: 0x40,
+            }),
+            output.edns
+        );
 
         Ok(())
     }
@@ -1064,7 +2340,12 @@ primary0.hickory-dns.testing. 86400 IN	RRSIG	A 8 3 86400 20250107210902 20241210
 
         let output: DigOutput = input.parse()?;
 
-        assert!(output.dnssec_ok_flag);
+        assert!(output.dnssec_ok_flag());
+        let edns = output.edns.expect("OPT pseudo-record");
+        assert!(edns.dnssec_ok);
+        assert_eq!(0, edns.version);
+        assert_eq!(1232, edns.udp_payload_size);
+        assert_eq!(0, edns.must_be_zero);
 
         Ok(())
     }
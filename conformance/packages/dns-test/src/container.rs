@@ -2,7 +2,7 @@ mod network;
 
 use core::{fmt, str};
 use std::ffi::OsStr;
-use std::net::Ipv4Addr;
+use std::net::{Ipv4Addr, Ipv6Addr};
 use std::process::{self, ChildStderr, ChildStdout, ExitStatus};
 use std::process::{Command, Stdio};
 use std::sync::atomic::AtomicUsize;
@@ -11,7 +11,7 @@ use std::{env, fs};
 
 use tempfile::{NamedTempFile, TempDir};
 
-pub use crate::container::network::Network;
+pub use crate::container::network::{Network, ResourceInfo};
 use crate::{Error, HickoryDnssecFeature, Implementation, Repository, Result};
 
 #[derive(Clone)]
@@ -26,6 +26,7 @@ pub enum Image {
     Bind,
     Dnslib,
     Client,
+    Dnsperf,
     Hickory {
         repo: Repository<'static>,
         dnssec_feature: HickoryDnssecFeature,
@@ -47,6 +48,7 @@ impl Image {
             Self::Bind => include_str!("docker/bind.Dockerfile"),
             Self::Dnslib => include_str!("docker/dnslib.Dockerfile"),
             Self::Client => include_str!("docker/client.Dockerfile"),
+            Self::Dnsperf => include_str!("docker/dnsperf.Dockerfile"),
             Self::Hickory { .. } => include_str!("docker/hickory.Dockerfile"),
             Self::Unbound => include_str!("docker/unbound.Dockerfile"),
             Self::EdeDotCom => include_str!("docker/ede-dot-com/Dockerfile"),
@@ -70,6 +72,11 @@ impl Image {
                 &CLIENT_ONCE
             }
 
+            Self::Dnsperf => {
+                static DNSPERF_ONCE: Once = Once::new();
+                &DNSPERF_ONCE
+            }
+
             Self::Hickory { .. } => {
                 static HICKORY_ONCE: Once = Once::new();
                 &HICKORY_ONCE
@@ -110,6 +117,7 @@ impl fmt::Display for Image {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Client => f.write_str("client"),
+            Self::Dnsperf => f.write_str("dnsperf"),
             Self::Bind => f.write_str("bind"),
             Self::Dnslib => f.write_str("dnslib"),
             Self::Hickory { dnssec_feature, .. } => write!(f, "hickory-{dnssec_feature}"),
@@ -119,14 +127,15 @@ impl fmt::Display for Image {
     }
 }
 
-impl Container {
-    /// Starts the container in a "parked" state
-    pub fn run(image: &Image, network: &Network) -> Result<Self> {
-        let image_tag = format!("{PACKAGE_NAME}-{image}");
+impl Image {
+    /// Builds the image, once per process, unless `$DNS_TEST_SKIP_DOCKER_BUILD` is set; returns
+    /// the image's tag
+    pub(crate) fn build(&self) -> String {
+        let image_tag = format!("{PACKAGE_NAME}-{self}");
 
         if !skip_docker_build() {
-            image.once().call_once(|| {
-                let dockerfile = image.dockerfile();
+            self.once().call_once(|| {
+                let dockerfile = self.dockerfile();
                 let docker_build_dir =
                     TempDir::new().expect("failed to create temporary directory");
                 let docker_build_dir = docker_build_dir.path();
@@ -144,15 +153,16 @@ impl Container {
                 // local Docker image.
                 command.env("DOCKER_BUILDKIT", "1");
 
-                if let Image::Hickory { dnssec_feature, .. } = image {
+                if let Image::Hickory { dnssec_feature, .. } = self {
                     command.arg(format!("--build-arg=DNSSEC_FEATURE={dnssec_feature}"));
                 };
 
                 if docker_build_gha_cache() {
-                    let scope = match image {
+                    let scope = match self {
                         Image::Bind => "bind",
                         Image::Dnslib => "dnslib",
                         Image::Client => "client",
+                        Image::Dnsperf => "dnsperf",
                         Image::Hickory {
                             dnssec_feature: HickoryDnssecFeature::AwsLcRs,
                             ..
@@ -166,7 +176,7 @@ impl Container {
                     };
 
                     command.arg(format!("--cache-from=type=gha,scope=${scope}"));
-                    if let Image::Hickory { .. } = image {
+                    if let Image::Hickory { .. } = self {
                         command.arg(format!(
                             "--cache-to=type=gha,scope=${scope},mode=max,ignore-error=true"
                         ));
@@ -177,7 +187,7 @@ impl Container {
                     }
                 }
 
-                if let Image::Hickory { repo, .. } = image {
+                if let Image::Hickory { repo, .. } = self {
                     let mut cp_r = Command::new("git");
                     cp_r.args([
                         "clone",
@@ -190,7 +200,7 @@ impl Container {
                     exec_or_panic(&mut cp_r, false);
                 }
 
-                if let Image::EdeDotCom = image {
+                if let Image::EdeDotCom = self {
                     fs::write(
                         docker_build_dir.join("configure_child.sh"),
                         include_str!("docker/ede-dot-com/configure_child.sh"),
@@ -210,6 +220,15 @@ impl Container {
             });
         }
 
+        image_tag
+    }
+}
+
+impl Container {
+    /// Starts the container in a "parked" state
+    pub fn run(image: &Image, network: &Network) -> Result<Self> {
+        let image_tag = image.build();
+
         let mut command = Command::new("docker");
         let pid = process::id();
         let count = container_count();
@@ -233,11 +252,13 @@ impl Container {
         let id = output.stdout;
 
         let ipv4_addr = get_ipv4_addr(&id)?;
+        let ipv6_addr = get_ipv6_addr(&id)?;
 
         let inner = Inner {
             id,
             name,
             ipv4_addr,
+            ipv6_addr,
             network: network.clone(),
         };
         Ok(Self {
@@ -331,6 +352,11 @@ impl Container {
         self.inner.ipv4_addr
     }
 
+    /// Returns `None` unless the container's network was created with [`Network::with_ipv6`]
+    pub fn ipv6_addr(&self) -> Option<Ipv6Addr> {
+        self.inner.ipv6_addr
+    }
+
     pub fn id(&self) -> &str {
         &self.inner.id
     }
@@ -380,8 +406,8 @@ fn container_count() -> usize {
 struct Inner {
     name: String,
     id: String,
-    // TODO probably also want the IPv6 address
     ipv4_addr: Ipv4Addr,
+    ipv6_addr: Option<Ipv6Addr>,
     network: Network,
 }
 
@@ -499,6 +525,29 @@ fn get_ipv4_addr(container_id: &str) -> Result<Ipv4Addr> {
     Ok(ipv4_addr.parse()?)
 }
 
+fn get_ipv6_addr(container_id: &str) -> Result<Option<Ipv6Addr>> {
+    let mut command = Command::new("docker");
+    command
+        .args([
+            "inspect",
+            "-f",
+            "{{range.NetworkSettings.Networks}}{{.GlobalIPv6Address}}{{end}}",
+        ])
+        .arg(container_id);
+
+    let output = command.output()?;
+    if !output.status.success() {
+        return Err(format!("`{command:?}` failed").into());
+    }
+
+    let ipv6_addr = str::from_utf8(&output.stdout)?.trim();
+    if ipv6_addr.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(ipv6_addr.parse()?))
+}
+
 // this ensures the container gets deleted and does not linger after the test runner process ends
 impl Drop for Inner {
     fn drop(&mut self) {
@@ -1,12 +1,20 @@
 use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write as _,
+    net::Ipv4Addr,
     process::{self, Command, Stdio},
+    str::FromStr,
     sync::{
         Arc, Mutex,
         atomic::{self, AtomicUsize},
     },
+    time::Duration,
 };
 
-use crate::Result;
+use crate::capture::CaptureHandle;
+use crate::container::Container;
+use crate::tshark::Capture;
+use crate::{Error, Result};
 
 /// Represents a network in which to put containers into.
 #[derive(Clone)]
@@ -22,30 +30,476 @@ impl Network {
     pub fn netmask(&self) -> &str {
         &self.0.config.subnet
     }
+
+    /// Returns the IPv6 subnet mask; `None` unless the network was created with
+    /// [`Network::with_ipv6`]
+    pub fn ipv6_netmask(&self) -> Option<&str> {
+        self.0.config.ipv6_subnet.as_deref()
+    }
+
+    /// Returns `true` if `addr` belongs to this network's subnet
+    pub fn contains(&self, addr: Ipv4Addr) -> bool {
+        subnet_contains(self.netmask(), addr)
+    }
+
+    /// Panics if any of the `captures` was sent to, or received from, an address outside this
+    /// network
+    ///
+    /// Use this to check that a test only exercised the provisioned name servers, e.g. that a
+    /// resolver did not fall back to the real root servers. Only meaningful when the network has
+    /// internet access; see [`Network::with_internet_access`].
+    pub fn assert_no_leaks(&self, captures: &[Capture]) {
+        let leaked = captures
+            .iter()
+            .filter(|capture| !self.contains(capture.direction.peer_addr()))
+            .collect::<Vec<_>>();
+
+        assert!(
+            leaked.is_empty(),
+            "DNS traffic left the test network ({}):\n{leaked:#?}",
+            self.netmask()
+        );
+    }
+
+    /// Returns the name of the host's bridge interface that carries this network's traffic
+    pub(crate) fn bridge_interface(&self) -> String {
+        // Docker names bridges after the first 12 characters of the network ID
+        let id = &self.0.config.id;
+        format!("br-{}", &id[..id.len().min(12)])
+    }
+
+    /// Starts capturing the DNS traffic of every container attached to this network
+    ///
+    /// See [`CaptureHandle`] for details
+    pub fn capture(&self) -> Result<CaptureHandle> {
+        CaptureHandle::new(self)
+    }
+
+    /// Delays by `delay`, and drops `loss_pct` percent of, the packets that the other containers
+    /// on this network send to `node`
+    ///
+    /// Traffic between other pairs of containers, and the packets `node` sends, are unaffected.
+    /// Calling this again for the same `node` replaces its impairment. Only the containers that
+    /// are attached to the network at the time of the call are configured.
+    pub fn set_impairment(&self, node: &Ipv4Addr, delay: Duration, loss_pct: f32) -> Result<()> {
+        let impairments = {
+            let mut impairments = self
+                .0
+                .impairments
+                .lock()
+                .map_err(|_| "impairments lock poisoned")?;
+            impairments.insert(*node, Impairment { delay, loss_pct });
+            impairments.clone()
+        };
+
+        for resource in self.resources()? {
+            let script = tc_script(&impairments, resource.ipv4_addr)?;
+            docker_exec_script(&resource.name, &script)?;
+        }
+
+        Ok(())
+    }
+
+    /// Drops every packet exchanged between the containers at `a` and `b`
+    ///
+    /// Traffic between either of them and the other containers on this network is unaffected.
+    /// Undo with [`Network::heal`]; partitions that are still in place when the network is
+    /// dropped are healed automatically.
+    pub fn partition(&self, a: &Ipv4Addr, b: &Ipv4Addr) -> Result<()> {
+        let link = Link::new(*a, *b)?;
+
+        let inserted = self
+            .0
+            .partitions
+            .lock()
+            .map_err(|_| "partitions lock poisoned")?
+            .insert(link);
+        if !inserted {
+            return Ok(());
+        }
+
+        self.apply_partition(link, IptablesOp::Append)
+    }
+
+    /// Undoes a previous [`Network::partition`] call; does nothing if `a` and `b` are not
+    /// partitioned
+    pub fn heal(&self, a: &Ipv4Addr, b: &Ipv4Addr) -> Result<()> {
+        let link = Link::new(*a, *b)?;
+
+        let removed = self
+            .0
+            .partitions
+            .lock()
+            .map_err(|_| "partitions lock poisoned")?
+            .remove(&link);
+        if !removed {
+            return Ok(());
+        }
+
+        self.apply_partition(link, IptablesOp::Delete)
+    }
+
+    /// Returns how many packets the container at `source` tried to send to `destination` and
+    /// were dropped because the two are partitioned; see [`Network::partition`]
+    ///
+    /// Use this to check that a container did try to reach a peer it was partitioned from
+    pub fn dropped_packets(&self, source: &Ipv4Addr, destination: &Ipv4Addr) -> Result<u64> {
+        let resource = self
+            .resources()?
+            .into_iter()
+            .find(|resource| resource.ipv4_addr == *source)
+            .ok_or_else(|| format!("{source} is not attached to network {}", self.name()))?;
+
+        let listing = docker_exec_script(&resource.name, "iptables -L OUTPUT -n -v -x")?;
+        dropped_packet_count(&listing, *destination)
+    }
+
+    fn apply_partition(&self, link: Link, op: IptablesOp) -> Result<()> {
+        let mut applied = false;
+        for resource in self.resources()? {
+            if let Some(peer_addr) = link.peer_of(resource.ipv4_addr) {
+                docker_exec_script(&resource.name, &iptables_script(op, peer_addr))?;
+                applied = true;
+            }
+        }
+
+        if !applied {
+            let Link(a, b) = link;
+            return Err(
+                format!("neither {a} nor {b} is attached to network {}", self.name()).into(),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Lists the containers currently attached to this network
+    pub fn resources(&self) -> Result<Vec<ResourceInfo>> {
+        list_resources(self.name())
+    }
+
+    /// Detaches `container` from this network, making it unreachable from every other container
+    ///
+    /// Use this to simulate an authoritative name server going down entirely; see
+    /// [`Network::partition`] to only cut it off from some of the other containers
+    pub fn disconnect(&self, container: &Container) -> Result<()> {
+        let mut command = Command::new("docker");
+        command
+            .args(["network", "disconnect", "--force", self.name()])
+            .arg(container.id());
+
+        let output = command.output()?;
+        if !output.status.success() {
+            return Err(format!("{command:?} failed").into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Runs `script` inside the container named `container_name` and returns its standard output
+fn docker_exec_script(container_name: &str, script: &str) -> Result<String> {
+    let mut command = Command::new("docker");
+    command.args(["exec", container_name, "sh", "-c", script]);
+
+    let output = command.output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("{command:?} failed\n{stderr}").into());
+    }
+
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+fn list_resources(network_name: &str) -> Result<Vec<ResourceInfo>> {
+    let mut command = Command::new("docker");
+    command
+        .args([
+            "network",
+            "inspect",
+            "-f",
+            "{{range .Containers}}{{.Name}} {{.IPv4Address}}\n{{end}}",
+        ])
+        .arg(network_name);
+
+    let output = command.output()?;
+    if !output.status.success() {
+        return Err(format!("{command:?} failed").into());
+    }
+
+    std::str::from_utf8(&output.stdout)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.parse())
+        .collect()
+}
+
+/// A container attached to a [`Network`]
+#[derive(Debug, PartialEq)]
+pub struct ResourceInfo {
+    pub name: String,
+    pub ipv4_addr: Ipv4Addr,
+    /// The image the container runs, e.g. "unbound" or "client"; `None` if the container was not
+    /// created by this harness
+    pub image: Option<String>,
+    /// Whether the container was created by this process
+    pub owned: bool,
+}
+
+impl FromStr for ResourceInfo {
+    type Err = Error;
+
+    /// Parses a `{name} {ipv4_addr}/{prefix_len}` line
+    fn from_str(input: &str) -> Result<Self> {
+        let (name, cidr) = input
+            .trim()
+            .split_once(' ')
+            .ok_or_else(|| format!("invalid resource: {input}"))?;
+        let ipv4_addr = cidr
+            .split_once('/')
+            .map_or(cidr, |(addr, _)| addr)
+            .parse()?;
+
+        // containers are named `{package}-{image}-{pid}-{count}`; see `Container::run`
+        let parsed_name = name
+            .strip_prefix(env!("CARGO_PKG_NAME"))
+            .and_then(|rest| rest.strip_prefix('-'))
+            .and_then(|rest| {
+                let mut columns = rest.rsplitn(3, '-');
+                let _count = columns.next()?.parse::<usize>().ok()?;
+                let pid = columns.next()?.parse::<u32>().ok()?;
+                let image = columns.next()?;
+                Some((image, pid))
+            });
+
+        Ok(Self {
+            name: name.to_string(),
+            ipv4_addr,
+            image: parsed_name.map(|(image, _pid)| image.to_string()),
+            owned: parsed_name.is_some_and(|(_image, pid)| pid == process::id()),
+        })
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Impairment {
+    delay: Duration,
+    loss_pct: f32,
+}
+
+/// Returns the `tc` commands that apply `impairments` to the packets that the container at
+/// `own_addr` sends
+///
+/// Each impaired destination gets its own band of a `prio` qdisc, with a `netem` qdisc attached,
+/// and a filter that steers the packets sent to that destination into it. The first 3 bands keep
+/// handling the rest of the traffic, as they do by default.
+fn tc_script(impairments: &BTreeMap<Ipv4Addr, Impairment>, own_addr: Ipv4Addr) -> Result<String> {
+    // `prio` supports up to 16 bands
+    const MAX_IMPAIRMENTS: usize = 16 - 3;
+
+    let impairments = impairments
+        .iter()
+        .filter(|(destination, _)| **destination != own_addr)
+        .collect::<Vec<_>>();
+    if impairments.len() > MAX_IMPAIRMENTS {
+        return Err(format!("at most {MAX_IMPAIRMENTS} nodes can be impaired").into());
+    }
+
+    // start from a clean slate; this fails if no qdisc was set up yet
+    let mut script = String::from("tc qdisc del dev eth0 root 2>/dev/null; set -e\n");
+    if impairments.is_empty() {
+        return Ok(script);
+    }
+
+    writeln!(
+        script,
+        "tc qdisc add dev eth0 root handle 1: prio bands {}",
+        3 + impairments.len()
+    )?;
+    for (index, (destination, Impairment { delay, loss_pct })) in impairments.iter().enumerate() {
+        // class minor numbers are hexadecimal
+        let class = format!("1:{:x}", 4 + index);
+        writeln!(
+            script,
+            "tc qdisc add dev eth0 parent {class} netem delay {}ms loss {loss_pct}%",
+            delay.as_millis()
+        )?;
+        writeln!(
+            script,
+            "tc filter add dev eth0 parent 1: protocol ip prio 1 u32 match ip dst {destination}/32 flowid {class}"
+        )?;
+    }
+
+    Ok(script)
+}
+
+/// A pair of partitioned containers; the lower address comes first so that each pair has a
+/// single representation
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Link(Ipv4Addr, Ipv4Addr);
+
+impl Link {
+    fn new(a: Ipv4Addr, b: Ipv4Addr) -> Result<Self> {
+        if a == b {
+            return Err(format!("can't partition {a} from itself").into());
+        }
+
+        Ok(Self(a.min(b), a.max(b)))
+    }
+
+    /// Returns the other end of the link if `addr` is one of its ends
+    fn peer_of(&self, addr: Ipv4Addr) -> Option<Ipv4Addr> {
+        let Self(a, b) = *self;
+        if addr == a {
+            Some(b)
+        } else if addr == b {
+            Some(a)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum IptablesOp {
+    Append,
+    Delete,
+}
+
+/// Returns the `iptables` commands that add, or remove, the rules that drop the packets a
+/// container exchanges with `peer_addr`
+fn iptables_script(op: IptablesOp, peer_addr: Ipv4Addr) -> String {
+    let flag = match op {
+        IptablesOp::Append => "-A",
+        IptablesOp::Delete => "-D",
+    };
+
+    format!(
+        "iptables {flag} INPUT -s {peer_addr} -j DROP && iptables {flag} OUTPUT -d {peer_addr} -j DROP"
+    )
+}
+
+/// Sums the packet counters of the `DROP` rules for `peer_addr` in `listing`, the output of
+/// `iptables -L OUTPUT -n -v -x`
+fn dropped_packet_count(listing: &str, peer_addr: Ipv4Addr) -> Result<u64> {
+    let peer_addr = peer_addr.to_string();
+    let mut count = 0;
+    // the first 2 lines are the chain's policy and the column headers
+    for line in listing.lines().skip(2) {
+        // pkts bytes target prot opt in out source destination
+        let columns = line.split_whitespace().collect::<Vec<_>>();
+        if let [pkts, _bytes, "DROP", .., destination] = columns.as_slice() {
+            if *destination == peer_addr {
+                count += pkts.parse::<u64>()?;
+            }
+        }
+    }
+
+    Ok(count)
+}
+
+/// `subnet` is in CIDR notation, e.g. "172.21.0.0/16"
+fn subnet_contains(subnet: &str, addr: Ipv4Addr) -> bool {
+    let Some((network_addr, prefix_len)) = subnet.split_once('/') else {
+        return false;
+    };
+    let (Ok(network_addr), Ok(prefix_len)) =
+        (network_addr.parse::<Ipv4Addr>(), prefix_len.parse::<u32>())
+    else {
+        return false;
+    };
+
+    let mask = u32::MAX.checked_shl(32 - prefix_len.min(32)).unwrap_or(0);
+    u32::from(network_addr) & mask == u32::from(addr) & mask
 }
 
 struct NetworkInner {
     name: String,
     config: NetworkConfig,
+    /// Set with [`Network::set_impairment`]; keyed by destination
+    impairments: Mutex<BTreeMap<Ipv4Addr, Impairment>>,
+    /// Set with [`Network::partition`]
+    partitions: Mutex<BTreeSet<Link>>,
 }
 
 impl Network {
     pub fn new() -> Result<Self> {
         let pid = process::id();
         let network_name = env!("CARGO_PKG_NAME");
-        Ok(Self(Arc::new(NetworkInner::new(pid, network_name, true)?)))
+        Ok(Self(Arc::new(NetworkInner::new(
+            pid,
+            network_name,
+            true,
+            false,
+        )?)))
     }
 
     pub fn with_internet_access() -> Result<Self> {
         let pid = process::id();
         let network_name = env!("CARGO_PKG_NAME");
-        Ok(Self(Arc::new(NetworkInner::new(pid, network_name, false)?)))
+        Ok(Self(Arc::new(NetworkInner::new(
+            pid,
+            network_name,
+            false,
+            false,
+        )?)))
+    }
+
+    /// Creates a dual-stack network: containers get an IPv6 address in addition to their IPv4
+    /// address
+    pub fn with_ipv6() -> Result<Self> {
+        let pid = process::id();
+        let network_name = env!("CARGO_PKG_NAME");
+        Ok(Self(Arc::new(NetworkInner::new(
+            pid,
+            network_name,
+            true,
+            true,
+        )?)))
     }
 }
 
 /// This ensure the Docker network is deleted after the test runner process ends.
 impl Drop for NetworkInner {
     fn drop(&mut self) {
+        // containers usually go away before their network does, taking their `tc` and
+        // `iptables` configuration with them, but clear the impairments and heal the partitions
+        // of any container that is still attached
+        let impaired = self
+            .impairments
+            .get_mut()
+            .is_ok_and(|impairments| !impairments.is_empty());
+        let partitions = self
+            .partitions
+            .get_mut()
+            .map(std::mem::take)
+            .unwrap_or_default();
+        if impaired || !partitions.is_empty() {
+            if let Ok(resources) = list_resources(&self.name) {
+                for resource in resources {
+                    let mut script = String::new();
+                    if impaired {
+                        script.push_str("tc qdisc del dev eth0 root;");
+                    }
+                    for link in &partitions {
+                        if let Some(peer_addr) = link.peer_of(resource.ipv4_addr) {
+                            script.push_str(&iptables_script(IptablesOp::Delete, peer_addr));
+                            script.push(';');
+                        }
+                    }
+                    if script.is_empty() {
+                        continue;
+                    }
+
+                    let _ = Command::new("docker")
+                        .args(["exec", &resource.name, "sh", "-c", &script])
+                        .stdout(Stdio::null())
+                        .stderr(Stdio::null())
+                        .status();
+                }
+            }
+        }
+
         let _ = Command::new("docker")
             .args(["network", "rm", "--force", self.name.as_str()])
             .stdout(Stdio::null())
@@ -55,7 +509,7 @@ impl Drop for NetworkInner {
 }
 
 impl NetworkInner {
-    pub fn new(pid: u32, network_name: &str, internal: bool) -> Result<Self> {
+    pub fn new(pid: u32, network_name: &str, internal: bool, ipv6: bool) -> Result<Self> {
         static CRITICAL_SECTION: Mutex<()> = Mutex::new(());
 
         let count = network_count();
@@ -66,6 +520,12 @@ impl NetworkInner {
         if internal {
             command.arg("--internal");
         }
+        if ipv6 {
+            // a unique local address (RFC4193) prefix that doesn't collide with the networks of
+            // other test processes or threads
+            let subnet = format!("fd00:{:x}:{:x}:{count:x}::/64", pid >> 16, pid & 0xffff);
+            command.args(["--ipv6", "--subnet", &subnet]);
+        }
         command.arg("--attachable").arg(&network_name);
 
         // create network
@@ -89,14 +549,20 @@ impl NetworkInner {
         Ok(Self {
             name: network_name,
             config,
+            impairments: Mutex::new(BTreeMap::new()),
+            partitions: Mutex::new(BTreeSet::new()),
         })
     }
 }
 
 /// Collects all important configs.
 pub struct NetworkConfig {
+    /// The network's ID, as assigned by Docker
+    id: String,
     /// The CIDR subnet mask, e.g. "172.21.0.0/16"
     subnet: String,
+    /// The CIDR IPv6 subnet mask, e.g. "fd00:0:1234:1::/64"; `None` if IPv6 is not enabled
+    ipv6_subnet: Option<String>,
 }
 
 /// Return network config
@@ -107,7 +573,7 @@ fn get_network_config(network_name: &str) -> Result<NetworkConfig> {
             "network",
             "inspect",
             "-f",
-            "{{range .IPAM.Config}}{{.Subnet}}{{end}}",
+            "{{.Id}}{{range .IPAM.Config}} {{.Subnet}}{{end}}",
         ])
         .arg(network_name);
 
@@ -116,8 +582,20 @@ fn get_network_config(network_name: &str) -> Result<NetworkConfig> {
         return Err(format!("{command:?} failed").into());
     }
 
-    let subnet = std::str::from_utf8(&output.stdout)?.trim().to_string();
-    Ok(NetworkConfig { subnet })
+    let stdout = std::str::from_utf8(&output.stdout)?;
+    let mut columns = stdout.split_whitespace();
+    let id = columns.next();
+    let (ipv6_subnets, ipv4_subnets): (Vec<_>, Vec<_>) =
+        columns.partition(|subnet| subnet.contains(':'));
+    let (Some(id), [subnet]) = (id, ipv4_subnets.as_slice()) else {
+        return Err(format!("unexpected `docker network inspect` output: {stdout}").into());
+    };
+
+    Ok(NetworkConfig {
+        id: id.to_string(),
+        subnet: subnet.to_string(),
+        ipv6_subnet: ipv6_subnets.first().map(|subnet| subnet.to_string()),
+    })
 }
 
 fn network_count() -> usize {
@@ -142,6 +620,163 @@ mod tests {
         stdout.trim().lines().any(|line| line == network_name)
     }
 
+    #[test]
+    fn dropped_packet_count_works() -> Result<()> {
+        let listing = "\
+Chain OUTPUT (policy ACCEPT 12 packets, 914 bytes)
+    pkts      bytes target     prot opt in     out     source               destination
+       3      213 DROP       all  --  *      *       0.0.0.0/0            172.21.0.4
+       0        0 DROP       all  --  *      *       0.0.0.0/0            172.21.0.5
+";
+
+        assert_eq!(
+            3,
+            dropped_packet_count(listing, Ipv4Addr::new(172, 21, 0, 4))?
+        );
+        assert_eq!(
+            0,
+            dropped_packet_count(listing, Ipv4Addr::new(172, 21, 0, 5))?
+        );
+        assert_eq!(
+            0,
+            dropped_packet_count(listing, Ipv4Addr::new(172, 21, 0, 6))?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn subnet_contains_works() {
+        let subnet = "172.21.0.0/16";
+        assert!(subnet_contains(subnet, Ipv4Addr::new(172, 21, 0, 2)));
+        assert!(subnet_contains(subnet, Ipv4Addr::new(172, 21, 255, 255)));
+        assert!(!subnet_contains(subnet, Ipv4Addr::new(172, 22, 0, 2)));
+        assert!(!subnet_contains(subnet, Ipv4Addr::new(198, 41, 0, 4)));
+
+        assert!(subnet_contains("0.0.0.0/0", Ipv4Addr::new(198, 41, 0, 4)));
+        assert!(!subnet_contains("garbage", Ipv4Addr::new(172, 21, 0, 2)));
+    }
+
+    #[test]
+    fn tc_script_only_impairs_traffic_to_node() -> Result<()> {
+        let resolver = Ipv4Addr::new(172, 21, 0, 2);
+        let slow_ns = Ipv4Addr::new(172, 21, 0, 3);
+        let lossy_ns = Ipv4Addr::new(172, 21, 0, 4);
+        let impairments = BTreeMap::from([
+            (
+                slow_ns,
+                Impairment {
+                    delay: Duration::from_secs(2),
+                    loss_pct: 0.,
+                },
+            ),
+            (
+                lossy_ns,
+                Impairment {
+                    delay: Duration::ZERO,
+                    loss_pct: 12.5,
+                },
+            ),
+        ]);
+
+        let script = tc_script(&impairments, resolver)?;
+        assert!(script.contains("prio bands 5"), "{script}");
+        assert!(
+            script.contains("parent 1:4 netem delay 2000ms loss 0%"),
+            "{script}"
+        );
+        assert!(script.contains("dst 172.21.0.3/32 flowid 1:4"), "{script}");
+        assert!(
+            script.contains("parent 1:5 netem delay 0ms loss 12.5%"),
+            "{script}"
+        );
+        assert!(script.contains("dst 172.21.0.4/32 flowid 1:5"), "{script}");
+
+        // a node's own packets are not impaired
+        let script = tc_script(&impairments, slow_ns)?;
+        assert!(script.contains("prio bands 4"), "{script}");
+        assert!(!script.contains("172.21.0.3"), "{script}");
+
+        let script = tc_script(&BTreeMap::new(), resolver)?;
+        assert!(!script.contains("tc qdisc add"), "{script}");
+
+        Ok(())
+    }
+
+    #[test]
+    fn partition_links_are_unordered() -> Result<()> {
+        let resolver = Ipv4Addr::new(172, 21, 0, 2);
+        let name_server = Ipv4Addr::new(172, 21, 0, 3);
+        let client = Ipv4Addr::new(172, 21, 0, 4);
+
+        let link = Link::new(name_server, resolver)?;
+        assert!(link == Link::new(resolver, name_server)?);
+        assert_eq!(Some(name_server), link.peer_of(resolver));
+        assert_eq!(Some(resolver), link.peer_of(name_server));
+        assert_eq!(None, link.peer_of(client));
+
+        assert!(Link::new(resolver, resolver).is_err());
+
+        let script = iptables_script(IptablesOp::Append, name_server);
+        assert!(
+            script.contains("-A INPUT -s 172.21.0.3 -j DROP"),
+            "{script}"
+        );
+        assert!(
+            script.contains("-A OUTPUT -d 172.21.0.3 -j DROP"),
+            "{script}"
+        );
+        let script = iptables_script(IptablesOp::Delete, name_server);
+        assert!(
+            script.contains("-D INPUT -s 172.21.0.3 -j DROP"),
+            "{script}"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_resource_info() -> Result<()> {
+        let pid = process::id();
+        let resource: ResourceInfo =
+            format!("dns-test-hickory-dnssec-ring-{pid}-3 172.21.0.2/16").parse()?;
+        assert_eq!(
+            ResourceInfo {
+                name: format!("dns-test-hickory-dnssec-ring-{pid}-3"),
+                ipv4_addr: Ipv4Addr::new(172, 21, 0, 2),
+                image: Some("hickory-dnssec-ring".to_string()),
+                owned: true,
+            },
+            resource
+        );
+
+        let resource: ResourceInfo =
+            format!("dns-test-unbound-{}-1 172.21.0.3/16", pid + 1).parse()?;
+        assert_eq!(Some("unbound"), resource.image.as_deref());
+        assert!(!resource.owned);
+
+        let resource: ResourceInfo = "some-other-container 172.21.0.4/16".parse()?;
+        assert_eq!(None, resource.image);
+        assert!(!resource.owned);
+
+        Ok(())
+    }
+
+    #[test]
+    fn resources_works() -> Result<()> {
+        let network = Network::new()?;
+        let container = Container::run(&Image::Client, &network)?;
+
+        let resources = network.resources()?;
+        let [resource] = resources.try_into().expect("exactly one resource");
+        assert_eq!(container.name(), resource.name);
+        assert_eq!(container.ipv4_addr(), resource.ipv4_addr);
+        assert_eq!(Some("client"), resource.image.as_deref());
+        assert!(resource.owned);
+
+        Ok(())
+    }
+
     #[test]
     fn create_works() -> Result<()> {
         let network = Network::new();
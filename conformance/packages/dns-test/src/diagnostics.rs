@@ -0,0 +1,182 @@
+//! Collects the logs, `dig` outputs and packet captures of a test scenario and prints them when
+//! the scenario fails
+
+use core::cell::RefCell;
+use core::fmt::Write as _;
+use std::io::{self, Write as _};
+use std::net::IpAddr;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::client::{Client, DigOutput, DigSettings};
+use crate::container::Container;
+use crate::implementation::Role;
+use crate::name_server::{NameServer, Running};
+use crate::record::RecordType;
+use crate::tshark::Tshark;
+use crate::{FQDN, Forwarder, Implementation, Resolver, Result};
+
+/// Runs the scenario `f` and, if it returns an error or panics, prints everything that was
+/// registered with the [`Diagnostics`] it was given
+///
+/// The error or panic is then propagated as if `f` had been called directly
+pub fn run_with_diagnostics<T>(f: impl FnOnce(&Diagnostics) -> Result<T>) -> Result<T> {
+    let diagnostics = Diagnostics::default();
+
+    match panic::catch_unwind(AssertUnwindSafe(|| f(&diagnostics))) {
+        Ok(Ok(value)) => Ok(value),
+
+        Ok(Err(e)) => {
+            diagnostics.dump(&format!("scenario returned an error: {e}"));
+            Err(e)
+        }
+
+        Err(payload) => {
+            diagnostics.dump("scenario panicked");
+            panic::resume_unwind(payload)
+        }
+    }
+}
+
+/// Artifacts of a test scenario
+///
+/// Registered servers are kept alive, i.e. their containers are not removed, until the
+/// diagnostics have been printed
+#[derive(Default)]
+pub struct Diagnostics {
+    servers: RefCell<Vec<Server>>,
+    digs: RefCell<Vec<Dig>>,
+    captures: RefCell<Vec<(String, Tshark)>>,
+}
+
+impl Diagnostics {
+    pub fn name_server(&self, name_server: &NameServer<Running>) -> &Self {
+        self.add_server(
+            format!("name server for {}", name_server.zone()),
+            name_server.container().clone(),
+            name_server.implementation().clone(),
+            Role::NameServer,
+        )
+    }
+
+    pub fn resolver(&self, resolver: &Resolver) -> &Self {
+        self.add_server(
+            "resolver".to_string(),
+            resolver.container().clone(),
+            resolver.implementation().clone(),
+            Role::Resolver,
+        )
+    }
+
+    pub fn forwarder(&self, forwarder: &Forwarder) -> &Self {
+        self.add_server(
+            "forwarder".to_string(),
+            forwarder.container().clone(),
+            forwarder.implementation().clone(),
+            Role::Forwarder,
+        )
+    }
+
+    /// Starts capturing the DNS traffic of the `name_server`
+    pub fn eavesdrop(&self, name_server: &NameServer<Running>) -> Result<&Self> {
+        let label = format!("name server for {}", name_server.zone());
+        self.captures
+            .borrow_mut()
+            .push((label, name_server.eavesdrop()?));
+        Ok(self)
+    }
+
+    /// Like [`Client::dig`] but also records the unparsed output of `dig`
+    pub fn dig(
+        &self,
+        client: &Client,
+        settings: DigSettings,
+        server: impl Into<IpAddr>,
+        record_type: RecordType,
+        fqdn: &FQDN,
+    ) -> Result<DigOutput> {
+        let server = server.into();
+        let res = client.dig_raw(settings, server, record_type.clone(), fqdn);
+
+        let output = match &res {
+            Ok(output) => output.clone(),
+            Err(e) => format!("error: {e}"),
+        };
+        self.digs.borrow_mut().push(Dig {
+            command: format!("dig @{server} {} {fqdn}", record_type.as_name()),
+            output,
+        });
+
+        res?.parse()
+    }
+
+    fn add_server(
+        &self,
+        label: String,
+        container: Container,
+        implementation: Implementation,
+        role: Role,
+    ) -> &Self {
+        self.servers.borrow_mut().push(Server {
+            label,
+            container,
+            implementation,
+            role,
+        });
+        self
+    }
+
+    fn dump(&self, reason: &str) {
+        let mut output = format!("\n\nDIAGNOSTICS ({reason})");
+
+        for server in self.servers.borrow().iter() {
+            write!(
+                output,
+                "\n\n--- {} {} {} ---",
+                server.label,
+                server.container.ipv4_addr(),
+                server.container.id(),
+            )
+            .unwrap();
+
+            for (stream, logfile) in [
+                ("STDOUT", server.implementation.stdout_logfile(server.role)),
+                ("STDERR", server.implementation.stderr_logfile(server.role)),
+            ] {
+                let logs = server
+                    .container
+                    .stdout(&["cat", &logfile])
+                    .unwrap_or_else(|e| format!("could not read {logfile}: {e}"));
+                write!(output, "\n{stream}:\n{logs}").unwrap();
+            }
+        }
+
+        for dig in self.digs.borrow().iter() {
+            write!(output, "\n\n--- {} ---\n{}", dig.command, dig.output).unwrap();
+        }
+
+        for (label, tshark) in self.captures.take() {
+            write!(output, "\n\n--- packets captured by {label} ---").unwrap();
+            match tshark.terminate() {
+                Ok(captures) => write!(output, "\n{captures:#?}").unwrap(),
+                Err(e) => write!(output, "\ncould not capture packets: {e}").unwrap(),
+            }
+        }
+
+        output.push('\n');
+
+        // write everything at once so the output does not interleave with that of other tests
+        let _ = io::stderr().write_all(output.as_bytes());
+    }
+}
+
+struct Server {
+    label: String,
+    container: Container,
+    implementation: Implementation,
+    role: Role,
+}
+
+struct Dig {
+    command: String,
+    output: String,
+}
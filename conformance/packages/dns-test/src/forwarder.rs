@@ -5,6 +5,8 @@ use crate::{
     container::{Child, Container},
     implementation::{Config, Role},
     record::DNSKEY,
+    tshark::Tshark,
+    zone_file::Root,
 };
 
 pub struct Forwarder {
@@ -15,14 +17,19 @@ pub struct Forwarder {
 
 impl Forwarder {
     #[allow(clippy::new_ret_no_self)]
-    pub fn new<'a>(network: &Network, resolver: &'a Resolver) -> ForwarderSettings<'a> {
+    pub fn new(network: &Network, resolver: &Resolver) -> ForwarderSettings {
         ForwarderSettings {
             network: network.clone(),
-            resolver,
+            upstream: resolver.ipv4_addr(),
             trust_anchor: TrustAnchor::empty(),
+            policy: ForwardPolicy::Only,
         }
     }
 
+    pub fn eavesdrop(&self) -> Result<Tshark> {
+        Tshark::new(&self.container)
+    }
+
     pub fn network(&self) -> &Network {
         self.container.network()
     }
@@ -39,6 +46,14 @@ impl Forwarder {
         self.container.ipv4_addr()
     }
 
+    pub(crate) fn container(&self) -> &Container {
+        &self.container
+    }
+
+    pub(crate) fn implementation(&self) -> &Implementation {
+        &self.implementation
+    }
+
     /// Returns the logs collected so far
     pub fn logs(&self) -> Result<String> {
         if self.implementation.is_hickory() {
@@ -59,13 +74,25 @@ impl Forwarder {
     }
 }
 
-pub struct ForwarderSettings<'a> {
+/// What a forwarder does when the resolver it forwards to fails to answer
+#[derive(Clone)]
+pub enum ForwardPolicy {
+    /// Fail the query; this is the default
+    Only,
+    /// Fall back to resolving the query itself, starting at `root`
+    ///
+    /// Not supported by hickory.
+    First { root: Root },
+}
+
+pub struct ForwarderSettings {
     network: Network,
-    resolver: &'a Resolver,
+    upstream: Ipv4Addr,
     trust_anchor: TrustAnchor,
+    policy: ForwardPolicy,
 }
 
-impl ForwarderSettings<'_> {
+impl ForwarderSettings {
     /// Starts a DNS server in the forwarder role.
     ///
     /// The server uses the implementation chosen by the `$DNS_TEST_SUBJECT` environment variable.
@@ -75,13 +102,57 @@ impl ForwarderSettings<'_> {
 
     /// Starts a DNS server in the forwarder role.
     pub fn start_with_subject(&self, implementation: &Implementation) -> Result<Forwarder> {
+        self.start_forwarding_to(self.upstream, implementation)
+    }
+
+    /// Starts `hops` DNS servers in the forwarder role, each one forwarding to the previous one
+    /// and the first one forwarding to the resolver.
+    ///
+    /// The returned forwarders are in chain order so the last one is the entry point of the chain.
+    /// All the forwarders use the same settings and the implementation chosen by the
+    /// `$DNS_TEST_SUBJECT` environment variable.
+    pub fn start_chain(&self, hops: usize) -> Result<Vec<Forwarder>> {
+        self.start_chain_with_subject(hops, &crate::SUBJECT)
+    }
+
+    /// Starts `hops` DNS servers in the forwarder role, each one forwarding to the previous one
+    /// and the first one forwarding to the resolver.
+    pub fn start_chain_with_subject(
+        &self,
+        hops: usize,
+        implementation: &Implementation,
+    ) -> Result<Vec<Forwarder>> {
+        assert_ne!(0, hops, "a forwarder chain needs at least one hop");
+
+        let mut forwarders = Vec::<Forwarder>::with_capacity(hops);
+        for _ in 0..hops {
+            let upstream = forwarders
+                .last()
+                .map(|forwarder| forwarder.ipv4_addr())
+                .unwrap_or(self.upstream);
+            forwarders.push(self.start_forwarding_to(upstream, implementation)?);
+        }
+
+        Ok(forwarders)
+    }
+
+    fn start_forwarding_to(
+        &self,
+        upstream: Ipv4Addr,
+        implementation: &Implementation,
+    ) -> Result<Forwarder> {
         let image = implementation.clone().into();
         let container = Container::run(&image, &self.network)?;
 
+        if let ForwardPolicy::First { root } = &self.policy {
+            container.cp("/etc/root.hints", &format!("{root}\n"))?;
+        }
+
         let use_dnssec = !self.trust_anchor.is_empty();
         let config = Config::Forwarder {
             use_dnssec,
-            resolver_ip: self.resolver.ipv4_addr(),
+            resolver_ip: upstream,
+            forward_first: matches!(self.policy, ForwardPolicy::First { .. }),
         };
         let config_contents = implementation.format_config(config);
         if let Some(conf_file_path) = implementation.conf_file_path(Role::Forwarder) {
@@ -89,6 +160,11 @@ impl ForwarderSettings<'_> {
         }
 
         if use_dnssec {
+            assert!(
+                !implementation.is_hickory() || self.trust_anchor.ds().is_empty(),
+                "hickory does not support DS records in the trust anchor"
+            );
+
             let path = if implementation.is_bind() {
                 "/etc/bind/bind.keys"
             } else {
@@ -119,11 +195,15 @@ impl ForwarderSettings<'_> {
         self
     }
 
-    /// Adds all the keys in the `other` trust anchor to ours
+    /// Adds all the keys, and DS records, in the `other` trust anchor to ours
     pub fn trust_anchor(&mut self, other: &TrustAnchor) -> &mut Self {
-        for key in other.keys() {
-            self.trust_anchor.add(key.clone());
-        }
+        self.trust_anchor.extend(other);
+        self
+    }
+
+    /// Sets what the forwarder does when the resolver fails to answer
+    pub fn policy(&mut self, policy: ForwardPolicy) -> &mut Self {
+        self.policy = policy;
         self
     }
 }
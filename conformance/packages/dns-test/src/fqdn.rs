@@ -1,9 +1,16 @@
 use core::fmt;
 use core::str::FromStr;
 use std::borrow::Cow;
+use std::fmt::Write as _;
+use std::net::IpAddr;
 
 use crate::{Error, Result};
 
+/// The maximum length of a label, in bytes (RFC1035 section 2.3.4)
+const MAX_LABEL_LEN: usize = 63;
+/// The maximum length of a name in wire format, in bytes (RFC1035 section 2.3.4)
+const MAX_NAME_LEN: usize = 255;
+
 #[derive(Clone, Eq, Hash, PartialEq)]
 pub struct FQDN {
     inner: Cow<'static, str>,
@@ -50,6 +57,33 @@ impl FQDN {
         inner: Cow::Borrowed("example.hickory-dns.testing."),
     };
 
+    /// Returns the name used for reverse lookups of `ip_addr`, i.e. the owner of its PTR record
+    ///
+    /// IPv4 addresses map into `in-addr.arpa.` (RFC1035) and IPv6 addresses, one nibble per
+    /// label, map into `ip6.arpa.` (RFC3596)
+    pub fn reverse(ip_addr: IpAddr) -> FQDN {
+        let mut inner = String::new();
+        match ip_addr {
+            IpAddr::V4(ipv4_addr) => {
+                for octet in ipv4_addr.octets().iter().rev() {
+                    write!(inner, "{octet}.").unwrap();
+                }
+                inner.push_str("in-addr.arpa.");
+            }
+
+            IpAddr::V6(ipv6_addr) => {
+                for octet in ipv6_addr.octets().iter().rev() {
+                    write!(inner, "{:x}.{:x}.", octet & 0xf, octet >> 4).unwrap();
+                }
+                inner.push_str("ip6.arpa.");
+            }
+        }
+
+        FQDN {
+            inner: Cow::Owned(inner),
+        }
+    }
+
     pub fn is_root(&self) -> bool {
         self.inner == "."
     }
@@ -103,6 +137,123 @@ impl FQDN {
     pub fn last_label(&self) -> &str {
         self.inner.split_once('.').map(|(label, _)| label).unwrap()
     }
+
+    /// Returns the name made of the wire format `labels`, most specific first, in presentation
+    /// format: `.` and `\` are escaped with a `\` and bytes that are not printable ASCII become
+    /// `\DDD` escapes (RFC1035 section 5.1)
+    pub(crate) fn from_wire_labels<'a>(labels: impl IntoIterator<Item = &'a [u8]>) -> FQDN {
+        let mut inner = String::new();
+        for label in labels {
+            for &byte in label {
+                match byte {
+                    b'.' | b'\\' => {
+                        inner.push('\\');
+                        inner.push(char::from(byte));
+                    }
+                    b'!'..=b'~' => inner.push(char::from(byte)),
+                    _ => write!(inner, "\\{byte:03}").unwrap(),
+                }
+            }
+            inner.push('.');
+        }
+        if inner.is_empty() {
+            inner.push('.');
+        }
+
+        FQDN {
+            inner: Cow::Owned(inner),
+        }
+    }
+
+    /// Returns `true` if `name`, with or without its trailing dot, is this name in any case
+    ///
+    /// Use this to match the question of captured queries, as resolvers may randomize the case
+    /// of the names they send upstream
+    pub(crate) fn eq_ignore_case(&self, name: &str) -> bool {
+        self.inner
+            .trim_end_matches('.')
+            .eq_ignore_ascii_case(name.trim_end_matches('.'))
+    }
+
+    /// Returns the labels of the name, most specific first and without the root label, with the
+    /// escapes of the presentation format (RFC1035 section 5.1), e.g. `\.` and `\DDD`, undone
+    ///
+    /// Fails if an escape is malformed, a label is longer than 63 bytes or the name is longer
+    /// than 255 bytes in wire format
+    pub(crate) fn wire_labels(&self) -> Result<Vec<Vec<u8>>> {
+        let invalid_escape = || format!("invalid escape sequence in {self}");
+
+        let mut labels = vec![];
+        let mut label = vec![];
+        let mut bytes = self.inner.bytes();
+        while let Some(byte) = bytes.next() {
+            match byte {
+                b'.' => labels.push(std::mem::take(&mut label)),
+                b'\\' => {
+                    let escaped = bytes.next().ok_or_else(invalid_escape)?;
+                    if escaped.is_ascii_digit() {
+                        // `\DDD` where DDD is a three digit decimal number no greater than 255
+                        let mut value = u16::from(escaped - b'0');
+                        for _ in 0..2 {
+                            let digit = bytes
+                                .next()
+                                .filter(u8::is_ascii_digit)
+                                .ok_or_else(invalid_escape)?;
+                            value = value * 10 + u16::from(digit - b'0');
+                        }
+                        label.push(u8::try_from(value).map_err(|_| invalid_escape())?);
+                    } else {
+                        label.push(escaped);
+                    }
+                }
+                _ => label.push(byte),
+            }
+        }
+        labels.push(label);
+        labels.retain(|label| !label.is_empty());
+
+        if let Some(label) = labels.iter().find(|label| label.len() > MAX_LABEL_LEN) {
+            return Err(format!(
+                "label of {} bytes in {self}; the maximum is {MAX_LABEL_LEN}",
+                label.len()
+            )
+            .into());
+        }
+
+        // a length byte per label plus the root label
+        let name_len = labels.iter().map(|label| 1 + label.len()).sum::<usize>() + 1;
+        if name_len > MAX_NAME_LEN {
+            return Err(format!(
+                "{self} is {name_len} bytes long in wire format; the maximum is {MAX_NAME_LEN}"
+            )
+            .into());
+        }
+
+        Ok(labels)
+    }
+
+    /// Returns the uncompressed wire format of the name, with its case preserved
+    ///
+    /// See [`FQDN::wire_labels`] for the cases in which this fails
+    pub fn wire_format(&self) -> Result<Vec<u8>> {
+        let mut wire = vec![];
+        for label in self.wire_labels()? {
+            // `wire_labels` checked that the length fits
+            wire.push(label.len() as u8);
+            wire.extend(label);
+        }
+        wire.push(0);
+        Ok(wire)
+    }
+
+    /// Returns the canonical wire format of the name (RFC4034 section 6.2): uncompressed and
+    /// lowercased
+    pub fn canonical_wire_format(&self) -> Result<Vec<u8>> {
+        // label lengths are at most 63 so they are not affected by the case conversion
+        let mut wire = self.wire_format()?;
+        wire.make_ascii_lowercase();
+        Ok(wire)
+    }
 }
 
 impl FromStr for FQDN {
@@ -154,4 +305,77 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn reverse() -> Result<()> {
+        assert_eq!(
+            FQDN("4.3.2.1.in-addr.arpa.")?,
+            FQDN::reverse("1.2.3.4".parse()?)
+        );
+
+        // example from section 2.5 of RFC3596
+        assert_eq!(
+            FQDN("b.a.9.8.7.6.5.0.4.0.0.0.3.0.0.0.2.0.0.0.1.0.0.0.0.0.0.0.1.2.3.4.ip6.arpa.")?,
+            FQDN::reverse("4321:0:1:2:3:4:567:89ab".parse()?)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn wire_format() -> Result<()> {
+        assert_eq!(vec![0], FQDN::ROOT.wire_format()?);
+        assert_eq!(
+            b"\x07Example\x03com\x00".to_vec(),
+            FQDN("Example.com.")?.wire_format()?
+        );
+        assert_eq!(
+            b"\x07example\x03com\x00".to_vec(),
+            FQDN("Example.com.")?.canonical_wire_format()?
+        );
+
+        // an escaped `.` is part of the label, not a separator
+        assert_eq!(
+            b"\x03a.b\x03com\x00".to_vec(),
+            FQDN(r"a\.b.com.")?.wire_format()?
+        );
+        assert_eq!(
+            b"\x03a\x00b\x03com\x00".to_vec(),
+            FQDN(r"a\000b.com.")?.wire_format()?
+        );
+
+        assert!(FQDN(r"a\25.com.")?.wire_format().is_err());
+        assert!(FQDN(r"a\256.com.")?.wire_format().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn from_wire_labels() -> Result<()> {
+        assert_eq!(FQDN::ROOT, FQDN::from_wire_labels([]));
+
+        let fqdn = FQDN(r"a\.b\\c\032d\000.Example.com.")?;
+        let labels = fqdn.wire_labels()?;
+        assert_eq!(b"a.b\\c d\0", labels[0].as_slice());
+        assert_eq!(
+            fqdn,
+            FQDN::from_wire_labels(labels.iter().map(Vec::as_slice))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn wire_format_length_limits() -> Result<()> {
+        let label = "a".repeat(63);
+        assert!(FQDN(format!("{label}.com."))?.wire_format().is_ok());
+        assert!(FQDN(format!("a{label}.com."))?.wire_format().is_err());
+
+        // 4 * 64 bytes for the labels and their length bytes, plus the root label
+        let name = format!("{label}.").repeat(4);
+        assert!(FQDN(name.clone())?.wire_format().is_err());
+        assert!(FQDN(name[2..].to_string())?.wire_format().is_ok());
+
+        Ok(())
+    }
 }
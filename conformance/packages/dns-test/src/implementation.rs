@@ -16,6 +16,21 @@ pub enum Config<'a> {
         origin: &'a FQDN,
         use_dnssec: bool,
         additional_zones: HashMap<FQDN, ZoneFile>,
+        /// Name server identifier (RFC5001)
+        nsid: &'a str,
+        /// Clients allowed to transfer the zones (AXFR)
+        allow_transfer: &'a [Ipv4Addr],
+        /// Response Rate Limiting, in responses per second; `None` disables it
+        response_rate_limit: Option<u32>,
+        /// Leave out unrequested additional section records; `None` leaves the implementation's
+        /// default in place
+        minimal_responses: Option<bool>,
+        /// Recurse on behalf of clients that set the RD flag, starting from the root hints in
+        /// `/etc/root.hints`
+        allow_recursion: bool,
+        /// Reported in response to `version.bind` queries; `None` leaves the implementation's
+        /// default in place
+        version: Option<&'a str>,
     },
     Resolver {
         use_dnssec: bool,
@@ -23,10 +38,38 @@ pub enum Config<'a> {
         /// Extended DNS error (RFC8914)
         ede: bool,
         case_randomization: bool,
+        /// `None` leaves the implementation's default in place
+        prefetch: Option<bool>,
+        /// In seconds; `None` leaves the implementation's default in place
+        servfail_cache_ttl: Option<u64>,
+        /// EDNS UDP payload size; `None` leaves the implementation's default in place
+        edns_buffer_size: Option<u16>,
+        /// Also listen for DNS-over-TLS (RFC7858) queries on port 853
+        tls: bool,
+        /// Also listen for DNS-over-HTTPS (RFC8484) queries on port 443
+        https: bool,
+        /// IPv6 prefix used to synthesize AAAA records (DNS64, RFC6147); `None` disables DNS64
+        dns64_prefix: Option<String>,
+        /// `None` leaves the implementation's default in place
+        qname_minimization: Option<bool>,
+        /// Aggressive use of the DNSSEC-validated cache (RFC8198); `None` leaves the
+        /// implementation's default in place
+        aggressive_nsec: Option<bool>,
+        /// How long, in seconds, expired records may be served for (RFC8767); `None` disables
+        /// serve-stale
+        serve_stale_ttl: Option<u64>,
+        /// In milliseconds; `None` leaves the implementation's default in place
+        upstream_timeout_ms: Option<u64>,
+        /// `None` if the network does not support IPv6
+        ipv6_netmask: Option<&'a str>,
+        /// Neither serve clients nor query name servers over IPv4
+        ipv6_only: bool,
     },
     Forwarder {
         resolver_ip: Ipv4Addr,
         use_dnssec: bool,
+        /// Fall back to recursion, using the root hints, when the resolver fails to answer
+        forward_first: bool,
     },
 }
 
@@ -112,6 +155,18 @@ impl Implementation {
                 netmask,
                 ede,
                 case_randomization,
+                prefetch,
+                servfail_cache_ttl,
+                edns_buffer_size,
+                tls,
+                https,
+                dns64_prefix,
+                qname_minimization,
+                aggressive_nsec,
+                serve_stale_ttl,
+                upstream_timeout_ms,
+                ipv6_netmask,
+                ipv6_only,
             } => match self {
                 Self::Bind => {
                     assert!(!ede, "the BIND resolver does not support EDE (RFC8914)");
@@ -120,6 +175,18 @@ impl Implementation {
                         include_str!("templates/named.resolver.conf.jinja"),
                         use_dnssec => use_dnssec,
                         netmask => netmask,
+                        prefetch => prefetch,
+                        servfail_cache_ttl => servfail_cache_ttl,
+                        edns_buffer_size => edns_buffer_size,
+                        tls => tls,
+                        https => https,
+                        dns64_prefix => dns64_prefix,
+                        qname_minimization => qname_minimization,
+                        aggressive_nsec => aggressive_nsec,
+                        serve_stale_ttl => serve_stale_ttl,
+                        upstream_timeout_ms => upstream_timeout_ms,
+                        ipv6_netmask => ipv6_netmask,
+                        ipv6_only => ipv6_only,
                     )
                 }
 
@@ -129,6 +196,54 @@ impl Implementation {
                 }
 
                 Self::Hickory { .. } => {
+                    assert_ne!(
+                        Some(true),
+                        prefetch,
+                        "the hickory resolver does not support prefetching"
+                    );
+                    assert!(
+                        servfail_cache_ttl.is_none(),
+                        "the hickory resolver does not support configuring SERVFAIL caching"
+                    );
+                    assert!(
+                        edns_buffer_size.is_none(),
+                        "the hickory resolver does not support configuring its EDNS buffer size"
+                    );
+                    assert!(
+                        !tls,
+                        "the hickory resolver image is not built with DNS-over-TLS support"
+                    );
+                    assert!(
+                        !https,
+                        "the hickory resolver image is not built with DNS-over-HTTPS support"
+                    );
+                    assert!(
+                        dns64_prefix.is_none(),
+                        "the hickory resolver does not support DNS64"
+                    );
+                    assert_ne!(
+                        Some(false),
+                        qname_minimization,
+                        "the hickory resolver always minimizes query names"
+                    );
+                    assert_ne!(
+                        Some(true),
+                        aggressive_nsec,
+                        "the hickory resolver does not support aggressive use of NSEC records"
+                    );
+                    assert!(
+                        serve_stale_ttl.is_none(),
+                        "the hickory resolver does not support serving stale data"
+                    );
+                    assert!(
+                        upstream_timeout_ms.is_none(),
+                        "the hickory resolver does not support configuring its upstream timeout"
+                    );
+                    assert!(
+                        !ipv6_only,
+                        "the hickory resolver can't be restricted to IPv6"
+                    );
+
                     // TODO enable EDE in Hickory when supported
                     minijinja::render!(
                         include_str!("templates/hickory.resolver.toml.jinja"),
@@ -138,12 +253,28 @@ impl Implementation {
                 }
 
                 Self::Unbound => {
+                    assert!(
+                        servfail_cache_ttl.is_none(),
+                        "unbound caches SERVFAIL responses for a fixed period of 5 seconds"
+                    );
+
                     minijinja::render!(
                         include_str!("templates/unbound.conf.jinja"),
                         use_dnssec => use_dnssec,
                         netmask => netmask,
                         ede => ede,
                         case_randomization => case_randomization,
+                        prefetch => prefetch,
+                        edns_buffer_size => edns_buffer_size,
+                        tls => tls,
+                        https => https,
+                        dns64_prefix => dns64_prefix,
+                        qname_minimization => qname_minimization,
+                        aggressive_nsec => aggressive_nsec,
+                        serve_stale_ttl => serve_stale_ttl,
+                        upstream_timeout_ms => upstream_timeout_ms,
+                        ipv6_netmask => ipv6_netmask,
+                        ipv6_only => ipv6_only,
                     )
                 }
 
@@ -157,29 +288,82 @@ impl Implementation {
                 origin,
                 use_dnssec,
                 additional_zones,
+                nsid,
+                allow_transfer,
+                response_rate_limit,
+                minimal_responses,
+                allow_recursion,
+                version,
             } => match self {
                 Self::Bind => {
                     minijinja::render!(
                         include_str!("templates/named.name-server.conf.jinja"),
                         fqdn => origin.as_str(),
+                        nsid => nsid,
+                        allow_transfer => allow_transfer.iter().map(|x| x.to_string()).collect::<Vec<_>>(),
                         additional_zones => additional_zones.keys().map(|x| x.as_str()).collect::<Vec<&str>>(),
+                        response_rate_limit => response_rate_limit,
+                        minimal_responses => minimal_responses,
+                        allow_recursion => allow_recursion,
+                        version => version,
                     )
                 }
 
                 Self::Dnslib => {
+                    assert!(
+                        response_rate_limit.is_none(),
+                        "dnslib name servers do not support response rate limiting"
+                    );
+                    assert!(
+                        minimal_responses.is_none(),
+                        "dnslib name servers can't be configured to send minimal responses"
+                    );
+                    assert!(!allow_recursion, "dnslib name servers can't recurse");
+                    assert!(
+                        version.is_none(),
+                        "dnslib name servers do not answer CHAOS class queries"
+                    );
+
                     // Dnslib name servers don't have a config
                     "".into()
                 }
 
                 Self::Unbound => {
+                    assert!(!allow_recursion, "NSD is an authoritative-only name server");
+
                     minijinja::render!(
                         include_str!("templates/nsd.conf.jinja"),
                         fqdn => origin.as_str(),
+                        nsid => nsid,
+                        allow_transfer => allow_transfer.iter().map(|x| x.to_string()).collect::<Vec<_>>(),
                         additional_zones => additional_zones.keys().map(|x| x.as_str()).collect::<Vec<&str>>(),
+                        response_rate_limit => response_rate_limit,
+                        minimal_responses => minimal_responses,
+                        version => version,
                     )
                 }
 
                 Self::Hickory { dnssec_feature, .. } => {
+                    assert!(
+                        response_rate_limit.is_none(),
+                        "the hickory name server does not support response rate limiting"
+                    );
+                    assert_ne!(
+                        Some(true),
+                        minimal_responses,
+                        "the hickory name server always includes additional section records"
+                    );
+                    assert!(
+                        !allow_recursion,
+                        "the hickory name server can't recurse for names outside its zones"
+                    );
+                    assert!(
+                        version.is_none(),
+                        "the hickory name server does not answer CHAOS class queries"
+                    );
+
+                    // TODO set the NSID when hickory supports it
+                    // hickory can't restrict zone transfers to specific clients
                     let use_pkcs8 = matches!(dnssec_feature, HickoryDnssecFeature::Ring);
                     minijinja::render!(
                         include_str!("templates/hickory.name-server.toml.jinja"),
@@ -187,6 +371,7 @@ impl Implementation {
                         use_dnssec => use_dnssec,
                         additional_zones => additional_zones.keys().map(|x| x.as_str()).collect::<Vec<&str>>(),
                         use_pkcs8 => use_pkcs8,
+                        allow_axfr => !allow_transfer.is_empty(),
                     )
                 }
 
@@ -196,11 +381,13 @@ impl Implementation {
             Config::Forwarder {
                 resolver_ip,
                 use_dnssec,
+                forward_first,
             } => match self {
                 Self::Bind => minijinja::render!(
                     include_str!("templates/named.forwarder.conf.jinja"),
                     resolver_ip => resolver_ip,
                     use_dnssec => use_dnssec,
+                    forward_first => forward_first,
                 ),
 
                 Self::Dnslib => {
@@ -208,16 +395,24 @@ impl Implementation {
                     "".into()
                 }
 
-                Self::Hickory { .. } => minijinja::render!(
-                    include_str!("templates/hickory.forwarder.toml.jinja"),
-                    resolver_ip => resolver_ip,
-                    use_dnssec => use_dnssec,
-                ),
+                Self::Hickory { .. } => {
+                    assert!(
+                        !forward_first,
+                        "the hickory forwarder cannot fall back to recursion"
+                    );
+
+                    minijinja::render!(
+                        include_str!("templates/hickory.forwarder.toml.jinja"),
+                        resolver_ip => resolver_ip,
+                        use_dnssec => use_dnssec,
+                    )
+                }
 
                 Self::Unbound => minijinja::render!(
                     include_str!("templates/unbound.forwarder.conf.jinja"),
                     resolver_ip => resolver_ip,
                     use_dnssec => use_dnssec,
+                    forward_first => forward_first,
                 ),
 
                 Self::EdeDotCom => {
@@ -366,6 +561,7 @@ pub fn Repository(input: impl Into<Cow<'static, str>>) -> Repository<'static> {
     Repository { inner: input }
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for Implementation {
     fn default() -> Self {
         Self::Unbound
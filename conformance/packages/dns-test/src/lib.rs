@@ -8,23 +8,29 @@ use lazy_static::lazy_static;
 use name_server::{NameServer, Running};
 
 pub use crate::container::Network;
-pub use crate::forwarder::Forwarder;
+pub use crate::forwarder::{ForwardPolicy, Forwarder};
 pub use crate::fqdn::FQDN;
 pub use crate::implementation::{HickoryDnssecFeature, Implementation, Repository};
 pub use crate::resolver::Resolver;
 pub use crate::trust_anchor::TrustAnchor;
 
+pub mod bench;
+pub mod capture;
 pub mod client;
 pub mod container;
+pub mod diagnostics;
 mod forwarder;
 mod fqdn;
 mod implementation;
 pub mod name_server;
+pub mod nsec;
 pub mod nsec3;
 pub mod record;
 mod resolver;
 mod trust_anchor;
 pub mod tshark;
+pub mod validator;
+pub mod wire;
 pub mod zone_file;
 
 pub type Error = Box<dyn std::error::Error>;
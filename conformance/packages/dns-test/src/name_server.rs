@@ -1,9 +1,13 @@
 use core::sync::atomic::{self, AtomicUsize};
-use std::{collections::HashMap, net::Ipv4Addr, thread, time::Duration};
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::time::{Duration, Instant};
+use std::{collections::HashMap, thread};
 
+use crate::client::ExtendedDnsError;
 use crate::container::{Child, Container, Network};
 use crate::implementation::{Config, Role};
-use crate::record::{self, DS, Record, SOA, SoaSettings};
+use crate::record::{self, DS, Record, RecordType, SOA, SoaSettings};
+use crate::tshark::Tshark;
 use crate::zone_file::{self, Root, SigningKeys, ZoneFile};
 use crate::zone_file::{SignSettings, Signer};
 use crate::{DEFAULT_TTL, FQDN, Implementation, Result, TrustAnchor};
@@ -25,6 +29,19 @@ pub enum Sign<'a> {
         settings: SignSettings,
         mutate: &'a dyn Fn(&FQDN, &mut Vec<Record>),
     },
+    /// Signs each zone file with the settings that `settings` returns for that zone, e.g. to use
+    /// a different algorithm in each zone
+    PerZone {
+        settings: &'a dyn Fn(&FQDN) -> SignSettings,
+    },
+    /// Signs every zone file but leaves the DS records of the `insecure` zones out of their
+    /// parents' zone files, which makes those delegations, and every zone below them, insecure
+    ///
+    /// A signed zone below an insecure delegation is an "island of security"
+    InsecureDelegations {
+        settings: SignSettings,
+        insecure: &'a [FQDN],
+    },
 }
 
 impl Graph {
@@ -35,10 +52,10 @@ impl Graph {
     ///
     /// The returned name servers are sorted from leaf zone to root zone.
     ///
-    /// both `Sign::Yes` and `Sign::AndAmend` will add a DS record with the hash of the child's
-    /// key to the parent's zone file
+    /// all the `Sign` variants but `Sign::No` will add a DS record with the hash of the child's
+    /// key to the parent's zone file, except for the zones `Sign::InsecureDelegations` excludes
     ///
-    /// a non-empty `TrustAnchor` is returned only when `Sign::Yes` or `Sign::AndAmend` is used
+    /// a non-empty `TrustAnchor` is returned only when `Sign::No` is not used
     pub fn build(leaf: NameServer<Stopped>, sign: Sign) -> Result<Self> {
         assert_eq!(2, leaf.zone().num_labels(), "not yet implemented");
         assert_eq!(
@@ -65,6 +82,9 @@ impl Graph {
             let nameserver = NameServer::new(&implementation, parent.clone(), &network)?;
 
             nameservers_ns.add(nameserver.a());
+            if let Some(aaaa) = nameserver.aaaa() {
+                nameservers_ns.add(aaaa);
+            }
             nameservers.push(nameserver);
 
             zone = parent;
@@ -105,10 +125,20 @@ impl Graph {
 
             _ => {
                 let mut trust_anchor = TrustAnchor::empty();
-                let (settings, maybe_mutate) = match sign {
+                let settings_for = |zone: &FQDN| match &sign {
                     Sign::No => unreachable!(),
-                    Sign::Yes { settings } => (settings, None),
-                    Sign::AndAmend { settings, mutate } => (settings, Some(mutate)),
+                    Sign::Yes { settings }
+                    | Sign::AndAmend { settings, .. }
+                    | Sign::InsecureDelegations { settings, .. } => settings.clone(),
+                    Sign::PerZone { settings } => settings(zone),
+                };
+                let maybe_mutate = match &sign {
+                    Sign::AndAmend { mutate, .. } => Some(mutate),
+                    _ => None,
+                };
+                let insecure: &[FQDN] = match &sign {
+                    Sign::InsecureDelegations { insecure, .. } => insecure,
+                    _ => &[],
                 };
 
                 let mut running = vec![];
@@ -125,8 +155,11 @@ impl Graph {
                         }
                     }
 
-                    let mut nameserver = nameserver.sign(settings.clone())?;
-                    children_ds.push(nameserver.ds().ksk.clone());
+                    let settings = settings_for(nameserver.zone());
+                    let mut nameserver = nameserver.sign(settings)?;
+                    if !insecure.contains(nameserver.zone()) {
+                        children_ds.push(nameserver.ds().ksk.clone());
+                    }
                     children_num_labels = nameserver.zone().num_labels();
                     if let Some(mutate) = maybe_mutate {
                         let zone = nameserver.zone().clone();
@@ -197,13 +230,22 @@ impl NameServerBuilder {
         zone_file.add(Record::ns(zone, nameserver.clone()));
         // BIND requires that `nameserver` has an A record
         zone_file.add(Record::a(nameserver.clone(), container.ipv4_addr()));
+        if let Some(ipv6_addr) = container.ipv6_addr() {
+            zone_file.add(Record::aaaa(nameserver.clone(), ipv6_addr));
+        }
 
         Ok(NameServer {
             container,
             implementation,
-            state: Stopped,
+            state: Stopped::default(),
             zone_file,
             additional_zones: HashMap::new(),
+            nsid: None,
+            allow_transfer: Vec::new(),
+            response_rate_limit: None,
+            minimal_responses: None,
+            recursion_root: None,
+            version: None,
         })
     }
 
@@ -220,6 +262,12 @@ pub struct NameServer<State> {
     state: State,
     zone_file: ZoneFile,
     additional_zones: HashMap<FQDN, ZoneFile>,
+    nsid: Option<String>,
+    allow_transfer: Vec<Ipv4Addr>,
+    response_rate_limit: Option<u32>,
+    minimal_responses: Option<bool>,
+    recursion_root: Option<Root>,
+    version: Option<String>,
 }
 
 impl NameServer<Stopped> {
@@ -260,12 +308,27 @@ impl NameServer<Stopped> {
     }
 
     /// Adds a NS + A record pair to the zone file from another NameServer
+    ///
+    /// An AAAA glue record is added as well if `nameserver` has an IPv6 address
     pub fn referral_nameserver<T>(&mut self, nameserver: &NameServer<T>) -> &mut Self {
         self.referral(
             nameserver.zone().clone(),
             nameserver.fqdn().clone(),
             nameserver.ipv4_addr(),
-        )
+        );
+        if let Some(aaaa) = nameserver.aaaa() {
+            self.add(aaaa);
+        }
+        self
+    }
+
+    /// Adds a NS record, but no glue, to the zone file
+    ///
+    /// Resolvers need to resolve `nameserver` on their own before they can follow the referral;
+    /// use this when `nameserver` lives outside the zone being delegated, e.g. in a sibling zone
+    pub fn referral_without_glue(&mut self, zone: FQDN, nameserver: FQDN) -> &mut Self {
+        self.zone_file.add(Record::ns(zone, nameserver));
+        self
     }
 
     /// Adds a record to the name server's zone file
@@ -285,6 +348,122 @@ impl NameServer<Stopped> {
         self.additional_zones.insert(name, zone);
     }
 
+    /// Sets the serial of the zone's SOA record
+    pub fn set_serial(&mut self, serial: u32) -> &mut Self {
+        self.set_soa_settings(SoaOverrides {
+            serial: Some(serial),
+            ..SoaOverrides::default()
+        })
+    }
+
+    /// Overrides the fields of the zone's SOA record that are set in `overrides`, e.g. its
+    /// `minimum` field, which caps the TTL of negative answers (RFC2308); the other fields keep
+    /// their current value
+    pub fn set_soa_settings(&mut self, overrides: SoaOverrides) -> &mut Self {
+        let SoaOverrides {
+            serial,
+            refresh,
+            retry,
+            expire,
+            minimum,
+        } = overrides;
+
+        let settings = &mut self.zone_file.soa.settings;
+        settings.serial = serial.unwrap_or(settings.serial);
+        settings.refresh = refresh.unwrap_or(settings.refresh);
+        settings.retry = retry.unwrap_or(settings.retry);
+        settings.expire = expire.unwrap_or(settings.expire);
+        settings.minimum = minimum.unwrap_or(settings.minimum);
+        self
+    }
+
+    /// Overrides the name server identifier (NSID, RFC5001) this server reports
+    ///
+    /// The hickory name server does not support NSID and ignores this setting
+    pub fn set_nsid(&mut self, id: &str) -> &mut Self {
+        self.nsid = Some(id.to_string());
+        self
+    }
+
+    /// Allows the client at `to` to transfer the zone (AXFR)
+    ///
+    /// The hickory name server can't restrict transfers to specific clients; it allows every
+    /// client to transfer the zone once this is called.
+    pub fn allow_transfer(&mut self, to: Ipv4Addr) -> &mut Self {
+        self.allow_transfer.push(to);
+        self
+    }
+
+    /// Limits identical responses sent to a single client network to `responses_per_second`
+    /// (Response Rate Limiting); responses over the limit are either dropped or sent truncated
+    /// (TC=1) so legitimate clients can retry over TCP
+    ///
+    /// Not supported by hickory.
+    pub fn response_rate_limit(&mut self, responses_per_second: u32) -> &mut Self {
+        self.response_rate_limit = Some(responses_per_second);
+        self
+    }
+
+    /// Enables or disables minimal responses: leaving out additional section records, like the
+    /// addresses of an MX record's mail exchange, that the client did not ask for
+    ///
+    /// If this is never called, the implementation's default behavior is used. Hickory always
+    /// includes those records so it can't be enabled.
+    pub fn minimal_responses(&mut self, enabled: bool) -> &mut Self {
+        self.minimal_responses = Some(enabled);
+        self
+    }
+
+    /// Enables recursion for clients that ask for it (RD=1), starting from the `root` name server
+    /// of the test network; by default the name server only answers from its zones and clears
+    /// the RA flag
+    ///
+    /// Only supported by BIND
+    pub fn allow_recursion(&mut self, root: Root) -> &mut Self {
+        self.recursion_root = Some(root);
+        self
+    }
+
+    /// Sets the version string the name server reports in response to `version.bind` TXT
+    /// queries in the CHAOS class
+    ///
+    /// Not supported by hickory.
+    pub fn set_version(&mut self, version: &str) -> &mut Self {
+        self.version = Some(version.to_string());
+        self
+    }
+
+    /// Makes the name server answer queries for `qname` with NXDOMAIN and the Extended DNS Error
+    /// `code`, with `text` as its EXTRA-TEXT (RFC8914)
+    ///
+    /// Queries for other names are answered from the zone file. Only the `Dnslib` implementation
+    /// supports this; the server's `/script.py` is generated when it's started.
+    pub fn set_ede(&mut self, qname: FQDN, code: ExtendedDnsError, text: &str) -> &mut Self {
+        assert!(
+            self.implementation.is_dnslib(),
+            "only dnslib name servers can be configured to return EDEs"
+        );
+
+        self.state.ede.push((qname, code, text.to_string()));
+        self
+    }
+
+    /// Makes the name server a proxy that relays every query to the name server at `upstream`
+    /// and applies `rewrites`, in order, to the answer section of its responses, e.g. to tamper
+    /// with responses on the wire while leaving the upstream zone intact
+    ///
+    /// The name server's own zone file is not served. Only the `Dnslib` implementation supports
+    /// this; the server's `/script.py` is generated when it's started.
+    pub fn proxy(&mut self, upstream: Ipv4Addr, rewrites: &[ResponseRewrite]) -> &mut Self {
+        assert!(
+            self.implementation.is_dnslib(),
+            "only dnslib name servers can act as a proxy"
+        );
+
+        self.state.proxy = Some((upstream, rewrites.to_vec()));
+        self
+    }
+
     /// Freezes and signs the name server's zone file
     pub fn sign(self, settings: SignSettings) -> Result<NameServer<Signed>> {
         let Self {
@@ -292,9 +471,17 @@ impl NameServer<Stopped> {
             zone_file,
             implementation,
             additional_zones,
-            state: _,
+            nsid,
+            allow_transfer,
+            response_rate_limit,
+            minimal_responses,
+            recursion_root,
+            version,
+            state,
         } = self;
 
+        state.assert_signable();
+
         let signer = Signer::new(&container, settings)?;
         let keys = signer.generate_keys(zone_file.origin())?;
         let state = signer.sign_zone(&zone_file, &keys)?;
@@ -305,6 +492,63 @@ impl NameServer<Stopped> {
             zone_file,
             state,
             additional_zones,
+            nsid,
+            allow_transfer,
+            response_rate_limit,
+            minimal_responses,
+            recursion_root,
+            version,
+        })
+    }
+
+    /// Like [`NameServer::sign`] but the zone file is also signed with keys generated from each
+    /// of the `additional` settings, e.g. to serve RRSIGs of more than one algorithm
+    ///
+    /// The KSK, ZSK and DS records of the returned name server correspond to `settings`
+    pub fn sign_with_additional_algorithms(
+        self,
+        settings: SignSettings,
+        additional: &[SignSettings],
+    ) -> Result<NameServer<Signed>> {
+        let Self {
+            container,
+            zone_file,
+            implementation,
+            additional_zones,
+            nsid,
+            allow_transfer,
+            response_rate_limit,
+            minimal_responses,
+            recursion_root,
+            version,
+            state,
+        } = self;
+
+        state.assert_signable();
+
+        let additional_keys = additional
+            .iter()
+            .map(|settings| {
+                Signer::new(&container, settings.clone())?.generate_keys(zone_file.origin())
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let signer = Signer::new(&container, settings)?;
+        let keys = signer.generate_keys(zone_file.origin())?;
+        let state = signer.sign_zone_with_additional_keys(&zone_file, &keys, &additional_keys)?;
+
+        Ok(NameServer {
+            container,
+            implementation,
+            zone_file,
+            state,
+            additional_zones,
+            nsid,
+            allow_transfer,
+            response_rate_limit,
+            minimal_responses,
+            recursion_root,
+            version,
         })
     }
 
@@ -319,9 +563,17 @@ impl NameServer<Stopped> {
             zone_file,
             implementation,
             additional_zones,
-            state: _,
+            nsid,
+            allow_transfer,
+            response_rate_limit,
+            minimal_responses,
+            recursion_root,
+            version,
+            state,
         } = self;
 
+        state.assert_signable();
+
         let signer = Signer::new(&container, settings)?;
         let state = signer.sign_zone(&zone_file, keys)?;
 
@@ -331,6 +583,12 @@ impl NameServer<Stopped> {
             zone_file,
             state,
             additional_zones,
+            nsid,
+            allow_transfer,
+            response_rate_limit,
+            minimal_responses,
+            recursion_root,
+            version,
         })
     }
 
@@ -341,13 +599,25 @@ impl NameServer<Stopped> {
             zone_file,
             implementation,
             additional_zones,
-            state: _,
+            nsid,
+            allow_transfer,
+            response_rate_limit,
+            minimal_responses,
+            recursion_root,
+            version,
+            state,
         } = self;
 
         let config = Config::NameServer {
             origin: zone_file.origin(),
             use_dnssec: false,
             additional_zones: additional_zones.clone(),
+            nsid: nsid.as_deref().unwrap_or(zone_file.soa.nameserver.as_str()),
+            allow_transfer: &allow_transfer,
+            response_rate_limit,
+            minimal_responses,
+            allow_recursion: recursion_root.is_some(),
+            version: version.as_deref(),
         };
 
         if let Some(conf_file_path) = implementation.conf_file_path(config.role()) {
@@ -357,6 +627,10 @@ impl NameServer<Stopped> {
             )?;
         }
 
+        if let Some(root) = &recursion_root {
+            container.cp(ROOT_HINTS_PATH, &format!("{root}\n"))?;
+        }
+
         container.status_ok(&["mkdir", "-p", ZONES_DIR])?;
         container.cp(&zone_file_path(), &zone_file.to_string())?;
 
@@ -364,6 +638,55 @@ impl NameServer<Stopped> {
             container.cp(&format!("{ZONES_DIR}/{key}zone"), &zone_file.to_string())?;
         }
 
+        if let Some((upstream, rewrites)) = &state.proxy {
+            assert!(
+                state.ede.is_empty(),
+                "a proxy relays the upstream name server's responses; it can't return EDEs"
+            );
+
+            let rewrites = rewrites
+                .iter()
+                .map(|rewrite| match rewrite {
+                    ResponseRewrite::DropRrsigs(record_type) => ("drop_rrsigs", record_type.code()),
+                    ResponseRewrite::DropRecords(record_type) => {
+                        ("drop_records", record_type.code())
+                    }
+                    ResponseRewrite::Reverse => ("reverse", 0),
+                })
+                .collect::<Vec<_>>();
+
+            container.cp(
+                "/script.py",
+                &minijinja::render!(
+                    include_str!("templates/dnslib.proxy.py.jinja"),
+                    upstream => upstream.to_string(),
+                    rewrites => rewrites,
+                ),
+            )?;
+        } else if !state.ede.is_empty() {
+            let ede = state
+                .ede
+                .iter()
+                .map(|(qname, code, text)| {
+                    // `Debug` formatting produces string literals that python understands
+                    (
+                        format!("{:?}", qname.as_str()),
+                        *code as u16,
+                        format!("{text:?}"),
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            container.cp(
+                "/script.py",
+                &minijinja::render!(
+                    include_str!("templates/dnslib.ede.py.jinja"),
+                    ede => ede,
+                    zone_file_path => zone_file_path(),
+                ),
+            )?;
+        }
+
         let mut child = container.spawn(&implementation.cmd_args(config.role()))?;
 
         // For Dnslib, make sure the python interpreter is still running after two seconds
@@ -389,6 +712,12 @@ impl NameServer<Stopped> {
             implementation,
             zone_file,
             additional_zones,
+            nsid,
+            allow_transfer,
+            response_rate_limit,
+            minimal_responses,
+            recursion_root,
+            version,
             state: Running {
                 _child: child,
                 trust_anchor: None,
@@ -398,6 +727,8 @@ impl NameServer<Stopped> {
 }
 
 const ZONES_DIR: &str = "/etc/zones";
+/// The root hints of a name server that recurses; see [`NameServer::allow_recursion`]
+const ROOT_HINTS_PATH: &str = "/etc/root.hints";
 const ZONE_FILENAME: &str = "main.zone";
 const ZSK_PRIVATE_FILENAME: &str = "zsk.key";
 const ZSK_PKCS8_FILENAME: &str = "zsk.pk8";
@@ -428,6 +759,12 @@ impl NameServer<Signed> {
             zone_file,
             implementation,
             additional_zones,
+            nsid,
+            allow_transfer,
+            response_rate_limit,
+            minimal_responses,
+            recursion_root,
+            version,
             state,
         } = self;
 
@@ -435,6 +772,12 @@ impl NameServer<Signed> {
             origin: zone_file.origin(),
             use_dnssec: state.use_dnssec,
             additional_zones: additional_zones.clone(),
+            nsid: nsid.as_deref().unwrap_or(zone_file.soa.nameserver.as_str()),
+            allow_transfer: &allow_transfer,
+            response_rate_limit,
+            minimal_responses,
+            allow_recursion: recursion_root.is_some(),
+            version: version.as_deref(),
         };
 
         if let Some(conf_file_path) = implementation.conf_file_path(config.role()) {
@@ -444,6 +787,10 @@ impl NameServer<Signed> {
             )?;
         }
 
+        if let Some(root) = &recursion_root {
+            container.cp(ROOT_HINTS_PATH, &format!("{root}\n"))?;
+        }
+
         if implementation.is_hickory() && state.use_dnssec {
             // FIXME: Hickory does not support pre-signed zonefiles. We copy the unsigned
             // zonefile so hickory can sign the zonefile itself.
@@ -478,6 +825,12 @@ impl NameServer<Signed> {
             implementation,
             zone_file,
             additional_zones,
+            nsid,
+            allow_transfer,
+            response_rate_limit,
+            minimal_responses,
+            recursion_root,
+            version,
             state: Running {
                 _child: child,
                 trust_anchor: Some(state.trust_anchor()),
@@ -515,6 +868,90 @@ impl NameServer<Running> {
         self.state.trust_anchor.as_ref()
     }
 
+    /// Starts capturing the DNS traffic that flows in and out of this name server
+    ///
+    /// NOTE the `dnslib` image does not ship `tshark`
+    pub fn eavesdrop(&self) -> Result<Tshark> {
+        Tshark::new(&self.container)
+    }
+
+    /// Returns the serial of the SOA record the name server currently serves for its zone
+    pub fn serial(&self) -> Result<u32> {
+        let zone = self.zone().as_str();
+        let output = self
+            .container
+            .stdout(&["drill", zone, "@127.0.0.1", "SOA"])?;
+
+        output
+            .lines()
+            .filter(|line| !line.starts_with(';'))
+            .find_map(|line| line.parse::<SOA>().ok())
+            .map(|soa| soa.settings.serial)
+            .ok_or_else(|| format!("no SOA record for {zone} in drill output:\n{output}").into())
+    }
+
+    /// Polls the name server until the serial of its zone reaches `target`, or `timeout` elapses
+    ///
+    /// Serials are compared using serial number arithmetic (RFC1982) so a serial that has wrapped
+    /// around past `target` also counts as having reached it. Use this, rather than a fixed sleep,
+    /// to wait for a zone transfer or reload to take effect.
+    pub fn wait_for_serial(&self, target: u32, timeout: Duration) -> Result<()> {
+        const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            // the server may not be ready to answer queries yet
+            let serial = self.serial();
+            if serial
+                .as_ref()
+                .is_ok_and(|serial| serial_reached(*serial, target))
+            {
+                return Ok(());
+            }
+
+            if Instant::now() >= deadline {
+                return Err(format!(
+                    "serial of {} did not reach {target} within {timeout:?}; last seen: {serial:?}",
+                    self.zone()
+                )
+                .into());
+            }
+
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    /// Applies `change` to the zone, increments the serial of its SOA record and makes the name
+    /// server reload the zone file. Returns the new serial
+    ///
+    /// BIND keeps the differences between the old and new zone so it can serve them over IXFR.
+    /// Only unsigned zones can be updated and the hickory name server does not support reloading
+    /// zones. Use [`NameServer::wait_for_serial`] to wait for the reload to take effect
+    pub fn update_zone(&mut self, change: impl FnOnce(&mut ZoneFile)) -> Result<u32> {
+        if self.state.trust_anchor.is_some() {
+            return Err("signed zones cannot be updated".into());
+        }
+
+        let pid_file = match &self.implementation {
+            Implementation::Bind => "/tmp/named.pid",
+            Implementation::Unbound => "/tmp/nsd.pid",
+            implementation => {
+                return Err(format!("{implementation:?} does not support reloading zones").into());
+            }
+        };
+
+        change(&mut self.zone_file);
+        let soa = &mut self.zone_file.soa.settings;
+        soa.serial = soa.serial.wrapping_add(1);
+
+        self.container
+            .cp(&zone_file_path(), &self.zone_file.to_string())?;
+        self.container
+            .status_ok(&["sh", "-c", &format!("kill -HUP $(cat {pid_file})")])?;
+
+        Ok(self.zone_file.soa.settings.serial)
+    }
+
     /// Returns the logs collected so far
     pub fn logs(&self) -> Result<String> {
         if self.implementation.is_hickory() || self.implementation.is_dnslib() {
@@ -552,10 +989,19 @@ impl<S> NameServer<S> {
         &self.container
     }
 
+    pub(crate) fn implementation(&self) -> &Implementation {
+        &self.implementation
+    }
+
     pub fn ipv4_addr(&self) -> Ipv4Addr {
         self.container.ipv4_addr()
     }
 
+    /// Returns `None` unless the server's network was created with [`Network::with_ipv6`]
+    pub fn ipv6_addr(&self) -> Option<Ipv6Addr> {
+        self.container.ipv6_addr()
+    }
+
     /// Zone file BEFORE signing
     pub fn zone_file(&self) -> &ZoneFile {
         &self.zone_file
@@ -569,18 +1015,75 @@ impl<S> NameServer<S> {
         &self.zone_file.soa.nameserver
     }
 
+    /// The name server identifier (NSID, RFC5001) this server reports, which is its FQDN unless
+    /// overridden with [`NameServer::set_nsid`]
+    ///
+    /// Not supported by the hickory name server
+    pub fn nsid(&self) -> &str {
+        self.nsid.as_deref().unwrap_or(self.fqdn().as_str())
+    }
+
     /// Returns the [`Record::A`] record for this server.
     pub fn a(&self) -> Record {
         Record::a(self.fqdn().clone(), self.ipv4_addr())
     }
 
+    /// Returns the AAAA record for this server; `None` if it has no IPv6 address
+    pub fn aaaa(&self) -> Option<Record> {
+        Some(Record::aaaa(self.fqdn().clone(), self.ipv6_addr()?))
+    }
+
     /// Returns the [`Root`] hint for this server.
     pub fn root_hint(&self) -> Root {
-        Root::new(self.fqdn().clone(), self.ipv4_addr())
+        let mut root = Root::new(self.fqdn().clone(), self.ipv4_addr());
+        root.ipv6_addr = self.ipv6_addr();
+        root
+    }
+}
+
+/// The fields of a zone's SOA record to change with [`NameServer::set_soa_settings`]; see
+/// [`SoaSettings`] for their meaning
+#[derive(Clone, Debug, Default)]
+pub struct SoaOverrides {
+    pub serial: Option<u32>,
+    pub refresh: Option<u32>,
+    pub retry: Option<u32>,
+    pub expire: Option<u32>,
+    pub minimum: Option<u32>,
+}
+
+#[derive(Default)]
+pub struct Stopped {
+    ede: Vec<(FQDN, ExtendedDnsError, String)>,
+    /// Upstream name server and the rewrites to apply to its responses
+    proxy: Option<(Ipv4Addr, Vec<ResponseRewrite>)>,
+}
+
+impl Stopped {
+    /// Panics if the name server was configured in a way that only works with an unsigned zone
+    fn assert_signable(&self) {
+        assert!(
+            self.ede.is_empty(),
+            "the EDEs set with `NameServer::set_ede` can't be served from a signed zone"
+        );
+        assert!(
+            self.proxy.is_none(),
+            "a proxy serves the upstream name server's zone; it has no zone of its own to sign"
+        );
     }
 }
 
-pub struct Stopped;
+/// A change that a proxying name server, see [`NameServer::proxy`], makes to the answer section
+/// of the responses it relays
+#[derive(Debug, Clone)]
+pub enum ResponseRewrite {
+    /// Removes the RRSIG records that cover the given record type
+    DropRrsigs(RecordType),
+    /// Removes the records of the given type
+    DropRecords(RecordType),
+    /// Reverses the order of the records
+    Reverse,
+}
 
 /// DS records for both the KSK and the ZSK
 #[derive(Debug)]
@@ -641,6 +1144,11 @@ pub struct Running {
     trust_anchor: Option<TrustAnchor>,
 }
 
+/// `serial >= target`, in serial number arithmetic (RFC1982)
+fn serial_reached(serial: u32, target: u32) -> bool {
+    serial.wrapping_sub(target) < 1 << 31
+}
+
 fn primary_ns(ns_count: usize, zone: &FQDN) -> FQDN {
     FQDN(format!("primary{ns_count}.{}", expand_zone(zone))).unwrap()
 }
@@ -655,10 +1163,9 @@ fn expand_zone(zone: &FQDN) -> String {
     } else if zone.num_labels() == 1 {
         if *zone == FQDN::TEST_TLD {
             FQDN::TEST_DOMAIN.as_str().to_string()
-        } else if *zone == FQDN::COM_TLD {
-            "nameservers.com.".to_string()
         } else {
-            unimplemented!()
+            // e.g. `nameservers.com.` or `nameservers.arpa.`
+            format!("nameservers.{zone}")
         }
     } else {
         zone.to_string()
@@ -734,6 +1241,18 @@ mod tests {
         eprintln!("ZSK:\n{}", ns.zone_signing_key());
         eprintln!("root.zone.signed:\n{}", ns.signed_zone_file());
 
+        let diff = ns.zone_file().diff(ns.signed_zone_file());
+        assert!(
+            diff.only_added(&[
+                RecordType::DNSKEY,
+                RecordType::NSEC,
+                RecordType::NSEC3,
+                RecordType::NSEC3PARAM,
+                RecordType::RRSIG,
+            ]),
+            "{diff:#?}"
+        );
+
         let tld_ns = ns.start()?;
 
         let ns_addr = tld_ns.ipv4_addr();
@@ -756,6 +1275,65 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn signed_rrsig_original_ttls() -> Result<()> {
+        let network = Network::new()?;
+        let mut ns = NameServer::new(&Implementation::Unbound, FQDN::TEST_DOMAIN, &network)?;
+        let needle_fqdn = FQDN::EXAMPLE_SUBDOMAIN;
+        ns.add(A {
+            fqdn: needle_fqdn.clone(),
+            ttl: 300,
+            ipv4_addr: Ipv4Addr::new(1, 2, 3, 4),
+        });
+        let mut ns = ns.sign(SignSettings::default())?;
+
+        let signed = ns.signed_zone_file_mut();
+        if let Err(mismatches) = signed.check_rrsig_original_ttls() {
+            panic!("{mismatches:#?}");
+        }
+
+        let rrsig = signed
+            .records
+            .iter_mut()
+            .filter_map(Record::as_rrsig_mut)
+            .find(|rrsig| rrsig.fqdn == needle_fqdn && rrsig.type_covered == RecordType::A)
+            .expect("RRSIG covering the A record");
+        assert_eq!(300, rrsig.original_ttl);
+        rrsig.original_ttl = DEFAULT_TTL;
+
+        let mismatches = signed.check_rrsig_original_ttls().unwrap_err();
+        let [mismatch] = mismatches.try_into().expect("exactly one mismatch");
+        assert_eq!(needle_fqdn, mismatch.rrsig.fqdn);
+        assert_eq!(300, mismatch.rrset_ttl);
+
+        Ok(())
+    }
+
+    #[test]
+    fn serial_arithmetic() {
+        assert!(serial_reached(5, 5));
+        assert!(serial_reached(6, 5));
+        assert!(!serial_reached(4, 5));
+        // wrap around
+        assert!(serial_reached(0, u32::MAX));
+        assert!(!serial_reached(u32::MAX, 0));
+    }
+
+    #[test]
+    fn wait_for_serial() -> Result<()> {
+        let network = Network::new()?;
+        let ns = NameServer::new(&Implementation::Unbound, FQDN::ROOT, &network)?.start()?;
+        let serial = ns.zone_file().soa.settings.serial;
+
+        ns.wait_for_serial(serial, Duration::from_secs(10))?;
+        assert_eq!(serial, ns.serial()?);
+
+        let res = ns.wait_for_serial(serial + 1, Duration::from_secs(1));
+        assert!(res.is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn nsd_logs_works() -> Result<()> {
         let network = Network::new()?;
@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+
+use crate::{Error, FQDN, Result, record::NSEC, zone_file::ZoneFile};
+
+/// A domain name in the canonical DNS name order (RFC4034 section 6.1): labels are compared from
+/// the most significant one (rightmost) to the least significant one, as case-insensitive octet
+/// strings
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct CanonicalName(Vec<Vec<u8>>);
+
+impl TryFrom<&FQDN> for CanonicalName {
+    type Error = Error;
+
+    fn try_from(fqdn: &FQDN) -> Result<Self> {
+        let mut labels = fqdn.wire_labels()?;
+        for label in &mut labels {
+            label.make_ascii_lowercase();
+        }
+        labels.reverse();
+        Ok(Self(labels))
+    }
+}
+
+pub struct NSECRecords {
+    records: BTreeMap<CanonicalName, NSEC>,
+}
+
+impl NSECRecords {
+    /// Extract the NSEC RRs from the signed zonefile and sort them in canonical order by owner
+    /// name
+    pub fn new(signed_zf: &ZoneFile) -> Result<Self> {
+        Self::from_records(
+            signed_zf
+                .records
+                .iter()
+                .cloned()
+                .filter_map(|rr| rr.try_into_nsec().ok()),
+        )
+    }
+
+    /// Like [`NSECRecords::new`] but over arbitrary NSEC RRs, e.g. those in a response
+    pub fn from_records(records: impl IntoIterator<Item = NSEC>) -> Result<Self> {
+        Ok(Self {
+            records: records
+                .into_iter()
+                .map(|nsec| Ok((CanonicalName::try_from(&nsec.fqdn)?, nsec)))
+                .collect::<Result<_>>()?,
+        })
+    }
+
+    /// An NSEC RR "matches" a name if its owner name is that name
+    pub fn find_match<'a>(&'a self, name: &FQDN) -> Result<Option<&'a NSEC>> {
+        Ok(self.records.get(&CanonicalName::try_from(name)?))
+    }
+
+    /// An NSEC RR "covers" a name if the name falls strictly between the owner name and the next
+    /// domain name of the NSEC RR, in canonical order. The last NSEC RR of a zone's chain, whose
+    /// next domain name is the zone apex, covers every name that sorts after its owner name
+    pub fn find_cover<'a>(&'a self, name: &FQDN) -> Result<Option<&'a NSEC>> {
+        let name = CanonicalName::try_from(name)?;
+        let Some((owner, candidate)) = self
+            .records
+            .range(..=name.clone())
+            .last()
+            // names that sort before the first owner name, i.e. before the zone apex, are not
+            // part of the zone but are covered by the last RR of the chain, like in NSEC3
+            .or_else(|| self.records.last_key_value())
+        else {
+            return Ok(None);
+        };
+
+        let next = CanonicalName::try_from(&candidate.next_domain)?;
+        let covers = if *owner < next {
+            name < next
+        } else {
+            // the last RR of the chain
+            *owner < name || name < next
+        };
+        Ok((*owner != name && covers).then_some(candidate))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Result;
+    use crate::record::RecordType;
+
+    use super::*;
+
+    fn nsec(owner: &'static str, next: &'static str) -> Result<NSEC> {
+        Ok(NSEC {
+            fqdn: FQDN(owner)?,
+            ttl: 86400,
+            next_domain: FQDN(next)?,
+            record_types: vec![RecordType::NSEC, RecordType::RRSIG],
+        })
+    }
+
+    #[test]
+    fn canonical_order() -> Result<()> {
+        // RFC4034 section 6.1
+        let ordered = [
+            "example.",
+            "a.example.",
+            "yljkjljk.a.example.",
+            "Z.a.example.",
+            "zABC.a.EXAMPLE.",
+            "z.example.",
+            "\\001.z.example.",
+            "*.z.example.",
+            "\\200.z.example.",
+        ];
+
+        let names = ordered
+            .iter()
+            .map(|name| CanonicalName::try_from(&FQDN(*name)?))
+            .collect::<Result<Vec<_>>>()?;
+        for pair in names.windows(2) {
+            assert!(pair[0] < pair[1], "{pair:?}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn find_match_and_cover() -> Result<()> {
+        let records = NSECRecords::from_records([
+            nsec("example.", "b.example.")?,
+            nsec("b.example.", "d.example.")?,
+            nsec("d.example.", "example.")?,
+        ])?;
+
+        assert_eq!(
+            FQDN("b.example.")?,
+            records.find_match(&FQDN("B.example.")?)?.unwrap().fqdn
+        );
+        assert!(records.find_match(&FQDN("c.example.")?)?.is_none());
+
+        assert_eq!(
+            FQDN("b.example.")?,
+            records.find_cover(&FQDN("c.example.")?)?.unwrap().fqdn
+        );
+        // descendants sort right after their ancestors
+        assert_eq!(
+            FQDN("b.example.")?,
+            records.find_cover(&FQDN("a.b.example.")?)?.unwrap().fqdn
+        );
+        assert_eq!(
+            FQDN("example.")?,
+            records.find_cover(&FQDN("a.example.")?)?.unwrap().fqdn
+        );
+        // after the owner name of the last RR of the chain
+        assert_eq!(
+            FQDN("d.example.")?,
+            records.find_cover(&FQDN("e.example.")?)?.unwrap().fqdn
+        );
+        assert!(records.find_cover(&FQDN("d.example.")?)?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_escapes() -> Result<()> {
+        for name in [
+            "\\256.example.",
+            "\\1.example.",
+            "\\1a1.example.",
+            "\\25.example.",
+        ] {
+            assert!(CanonicalName::try_from(&FQDN(name)?).is_err(), "{name}");
+        }
+
+        Ok(())
+    }
+}
@@ -1,6 +1,54 @@
 use std::collections::BTreeMap;
 
-use crate::{record::NSEC3, zone_file::ZoneFile};
+use ring::digest;
+
+use crate::{FQDN, Result, record::NSEC3, zone_file::ZoneFile};
+
+/// Computes the NSEC3 hash (RFC5155 section 5) of `name` using SHA-1 and returns it in the same
+/// format as the first label of an NSEC3 owner name: base32hex encoded, uppercase and unpadded
+///
+/// `salt` uses the text representation of the NSEC3 record: hex encoded or `-` for no salt
+pub fn hash(name: &FQDN, salt: &str, iterations: u16) -> Result<String> {
+    let salt = if salt == "-" {
+        vec![]
+    } else {
+        hex::decode(salt)?
+    };
+
+    let mut digest = digest_with_salt(&name.canonical_wire_format()?, &salt);
+    for _ in 0..iterations {
+        digest = digest_with_salt(&digest, &salt);
+    }
+
+    Ok(base32hex(&digest))
+}
+
+fn digest_with_salt(input: &[u8], salt: &[u8]) -> Vec<u8> {
+    let mut context = digest::Context::new(&digest::SHA1_FOR_LEGACY_USE_ONLY);
+    context.update(input);
+    context.update(salt);
+    context.finish().as_ref().to_vec()
+}
+
+fn base32hex(input: &[u8]) -> String {
+    const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHIJKLMNOPQRSTUV";
+
+    let mut output = String::new();
+    let mut buffer = 0u16;
+    let mut bits = 0;
+    for byte in input {
+        buffer = (buffer << 8) | u16::from(*byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            output.push(ALPHABET[usize::from((buffer >> bits) & 0x1f)].into());
+        }
+    }
+    if bits > 0 {
+        output.push(ALPHABET[usize::from((buffer << (5 - bits)) & 0x1f)].into());
+    }
+    output
+}
 
 pub struct NSEC3Records {
     records: BTreeMap<String, NSEC3>,
@@ -10,21 +58,68 @@ impl NSEC3Records {
     /// Extract the NSEC3 RRs from the signed zonefile and sort them by the hash embedded in the
     /// last label of each record's owner.
     pub fn new(signed_zf: &ZoneFile) -> Self {
-        Self {
-            records: signed_zf
+        Self::from_records(
+            signed_zf
                 .records
                 .iter()
                 .cloned()
-                .filter_map(|rr| {
-                    let mut nsec3_rr = rr.try_into_nsec3().ok()?;
+                .filter_map(|rr| rr.try_into_nsec3().ok()),
+        )
+    }
+
+    /// Like [`NSEC3Records::new`] but over arbitrary NSEC3 RRs, e.g. those in a response
+    pub fn from_records(records: impl IntoIterator<Item = NSEC3>) -> Self {
+        Self {
+            records: records
+                .into_iter()
+                .map(|mut nsec3_rr| {
                     nsec3_rr.next_hashed_owner_name =
                         nsec3_rr.next_hashed_owner_name.to_uppercase();
-                    Some((nsec3_rr.fqdn.last_label().to_uppercase(), nsec3_rr))
+                    (nsec3_rr.fqdn.last_label().to_uppercase(), nsec3_rr)
                 })
                 .collect(),
         }
     }
 
+    /// Iterates over the NSEC3 RRs in hash order; the next hashed owner name of each RR is the
+    /// owner hash of the following one, and that of the last RR wraps around to the first, if the
+    /// chain is complete
+    pub fn iter(&self) -> impl Iterator<Item = &NSEC3> {
+        self.records.values()
+    }
+
+    /// The number of NSEC3 RRs
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// The salt of the NSEC3 RRs, in their text representation: hex encoded or `-` for no salt
+    pub fn salt(&self) -> Option<&str> {
+        self.records
+            .values()
+            .next()
+            .map(|nsec3| nsec3.salt.as_str())
+    }
+
+    /// The number of additional hash iterations of the NSEC3 RRs
+    pub fn iterations(&self) -> Option<u16> {
+        self.records.values().next().map(|nsec3| nsec3.iterations)
+    }
+
+    /// Hashes `name` using the salt and iterations of the NSEC3 RRs
+    pub fn hash(&self, name: &FQDN) -> Result<String> {
+        let nsec3 = self
+            .records
+            .values()
+            .next()
+            .ok_or("no NSEC3 RRs to take the hash parameters from")?;
+        hash(name, &nsec3.salt, nsec3.iterations)
+    }
+
     ///  An NSEC3 RR is said to "match" a name if the owner name of the NSEC3 RR is the same as the
     ///  hashed owner name of that name.
     pub fn find_match<'a>(&'a self, name_hash: &str) -> Option<&'a NSEC3> {
@@ -46,8 +141,16 @@ impl NSEC3Records {
             .or_else(|| self.records.last_key_value())?;
 
         // If the found hash is exactly the name's hash, return None as it wouldn't be proving its
-        // nonexistence. Otherwise return the RR with that hash.
-        (hash != name_hash).then_some(candidate)
+        // nonexistence. The candidate always covers the name when the records form a complete
+        // chain but that may not be the case when they are a subset, e.g. those in a response.
+        let next_hash = candidate.next_hashed_owner_name.as_str();
+        let covers = if hash.as_str() < next_hash {
+            name_hash < next_hash
+        } else {
+            // the last RR of the chain
+            hash.as_str() < name_hash || name_hash < next_hash
+        };
+        (hash != name_hash && covers).then_some(candidate)
     }
 
     /// This proof consists of (up to) two different NSEC3 RRs:
@@ -64,3 +167,104 @@ impl NSEC3Records {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_rfc5155_example() -> Result<()> {
+        // appendix A of RFC5155
+        assert_eq!(
+            "0P9MHAVEQVM6T7VBL5LOP2U3T2RP3TOM",
+            hash(&FQDN("example.")?, "aabbccdd", 12)?
+        );
+        assert_eq!(
+            "35MTHGPGCU1QG68FAB165KLNSNK3DPVL",
+            hash(&FQDN("a.example.")?, "aabbccdd", 12)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn hash_with_records_parameters() -> Result<()> {
+        let records = NSEC3Records::from_records([
+            "0p9mhaveqvm6t7vbl5lop2u3t2rp3tom.example. 3600 IN NSEC3 1 1 12 aabbccdd 2t7b4g4vsa5smi47k61mv5bv1a22bojr NS SOA MX RRSIG DNSKEY NSEC3PARAM".parse()?,
+        ]);
+
+        assert_eq!(Some("aabbccdd"), records.salt());
+        assert_eq!(Some(12), records.iterations());
+        let name_hash = records.hash(&FQDN("example.")?)?;
+        assert_eq!("0P9MHAVEQVM6T7VBL5LOP2U3T2RP3TOM", name_hash);
+        assert!(records.find_match(&name_hash).is_some());
+
+        assert!(NSEC3Records::from_records([]).hash(&FQDN::ROOT).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn iter_in_hash_order() -> Result<()> {
+        let records = NSEC3Records::from_records([
+            "2t7b4g4vsa5smi47k61mv5bv1a22bojr.example. 3600 IN NSEC3 1 1 12 aabbccdd 2vptu5timamqttgl4luu9kg21e0aor3s A RRSIG".parse()?,
+            "2vptu5timamqttgl4luu9kg21e0aor3s.example. 3600 IN NSEC3 1 1 12 aabbccdd 0p9mhaveqvm6t7vbl5lop2u3t2rp3tom MX RRSIG".parse()?,
+            "0p9mhaveqvm6t7vbl5lop2u3t2rp3tom.example. 3600 IN NSEC3 1 1 12 aabbccdd 2t7b4g4vsa5smi47k61mv5bv1a22bojr NS SOA MX RRSIG DNSKEY NSEC3PARAM".parse()?,
+        ]);
+
+        assert_eq!(3, records.len());
+        assert!(!records.is_empty());
+        let owner_hashes = records
+            .iter()
+            .map(|nsec3| nsec3.fqdn.last_label().to_uppercase())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            [
+                "0P9MHAVEQVM6T7VBL5LOP2U3T2RP3TOM",
+                "2T7B4G4VSA5SMI47K61MV5BV1A22BOJR",
+                "2VPTU5TIMAMQTTGL4LUU9KG21E0AOR3S",
+            ],
+            owner_hashes.as_slice()
+        );
+
+        assert!(NSEC3Records::from_records([]).is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn find_cover_wraps_around() -> Result<()> {
+        let records = NSEC3Records::from_records([
+            "0p9mhaveqvm6t7vbl5lop2u3t2rp3tom.example. 3600 IN NSEC3 1 1 12 aabbccdd 2t7b4g4vsa5smi47k61mv5bv1a22bojr NS SOA MX RRSIG DNSKEY NSEC3PARAM".parse()?,
+            "2t7b4g4vsa5smi47k61mv5bv1a22bojr.example. 3600 IN NSEC3 1 1 12 aabbccdd 2vptu5timamqttgl4luu9kg21e0aor3s A RRSIG".parse()?,
+            "2vptu5timamqttgl4luu9kg21e0aor3s.example. 3600 IN NSEC3 1 1 12 aabbccdd 0p9mhaveqvm6t7vbl5lop2u3t2rp3tom MX RRSIG".parse()?,
+        ]);
+        let last_owner = "2VPTU5TIMAMQTTGL4LUU9KG21E0AOR3S";
+
+        // sorts after every owner name
+        let cover = records
+            .find_cover("VVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVV")
+            .expect("the wrap-around RR covers the hash");
+        assert_eq!(last_owner, cover.fqdn.last_label().to_uppercase());
+
+        // sorts before every owner name
+        let cover = records
+            .find_cover("00000000000000000000000000000000")
+            .expect("the wrap-around RR covers the hash");
+        assert_eq!(last_owner, cover.fqdn.last_label().to_uppercase());
+
+        // an ordinary cover in the middle of the chain
+        let cover = records
+            .find_cover("2T7B4G4VSA5SMI47K61MV5BV1A22BOJS")
+            .expect("the second RR covers the hash");
+        assert_eq!(
+            "2T7B4G4VSA5SMI47K61MV5BV1A22BOJR",
+            cover.fqdn.last_label().to_uppercase()
+        );
+
+        // a matching hash is not covered
+        assert!(records.find_cover(last_owner).is_none());
+
+        Ok(())
+    }
+}
@@ -5,7 +5,7 @@ use core::str::FromStr;
 use core::{array, fmt};
 use std::borrow::Cow;
 use std::fmt::Write;
-use std::net::Ipv4Addr;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::{any, mem};
 
 use crate::{DEFAULT_TTL, Error, FQDN, Result};
@@ -28,6 +28,14 @@ macro_rules! record_types {
                     Self::Unknown(code) => Cow::Owned(format!("type{code}")),
                 }
             }
+
+            /// Returns the record type whose IANA code is `code`
+            pub fn from_code(code: u16) -> Self {
+                [$(Self::$variant),*]
+                    .into_iter()
+                    .find(|record_type| record_type.code() == code)
+                    .unwrap_or(Self::Unknown(code))
+            }
         }
 
         impl FromStr for RecordType {
@@ -58,23 +66,75 @@ macro_rules! record_types {
 }
 
 record_types!(
-    A, AAAA, CAA, CNAME, DNSKEY, DS, MX, NS, NSEC, NSEC3, NSEC3PARAM, RRSIG, SOA, TXT
+    A, AAAA, CAA, CDNSKEY, CDS, CNAME, DNAME, DNSKEY, DS, HTTPS, MX, NAPTR, NS, NSEC, NSEC3,
+    NSEC3PARAM, PTR, RRSIG, SOA, SRV, SSHFP, SVCB, TLSA, TXT
 );
 
-#[derive(Debug, Clone)]
+impl RecordType {
+    /// The numeric value of the record type, as registered with IANA
+    pub fn code(&self) -> u16 {
+        match self {
+            Self::A => 1,
+            Self::NS => 2,
+            Self::CNAME => 5,
+            Self::SOA => 6,
+            Self::PTR => 12,
+            Self::MX => 15,
+            Self::TXT => 16,
+            Self::AAAA => 28,
+            Self::SRV => 33,
+            Self::NAPTR => 35,
+            Self::DNAME => 39,
+            Self::DS => 43,
+            Self::SSHFP => 44,
+            Self::RRSIG => 46,
+            Self::NSEC => 47,
+            Self::DNSKEY => 48,
+            Self::NSEC3 => 50,
+            Self::NSEC3PARAM => 51,
+            Self::TLSA => 52,
+            Self::SVCB => 64,
+            Self::CDS => 59,
+            Self::CDNSKEY => 60,
+            Self::HTTPS => 65,
+            Self::CAA => 257,
+            Self::Unknown(code) => *code,
+        }
+    }
+}
+
+/// Record types that have no native representation in this crate but that can be authored with
+/// constructors like [`Record::loc`] and are parsed as [`UnknownRdata`]
+const GENERIC_RECORD_TYPES: [(&str, u16); 4] =
+    [("RP", 17), ("AFSDB", 18), ("LOC", 29), ("APL", 42)];
+
+#[derive(Debug, Clone, PartialEq)]
 #[allow(clippy::upper_case_acronyms)]
 pub enum Record {
     A(A),
+    AAAA(AAAA),
     CAA(CAA),
     CNAME(CNAME),
+    DNAME(DNAME),
+    CDNSKEY(CDNSKEY),
+    CDS(CDS),
     DNSKEY(DNSKEY),
     DS(DS),
+    HTTPS(HTTPS),
+    MX(MX),
+    /// Boxed because it's much larger than the other variants
+    NAPTR(Box<NAPTR>),
     NS(NS),
     NSEC(NSEC),
     NSEC3(NSEC3),
     NSEC3PARAM(NSEC3PARAM),
+    PTR(PTR),
     RRSIG(RRSIG),
     SOA(SOA),
+    SRV(SRV),
+    SSHFP(SSHFP),
+    SVCB(SVCB),
+    TLSA(TLSA),
     TXT(TXT),
     Unknown(UnknownRdata),
 }
@@ -85,6 +145,18 @@ impl From<NSEC3> for Record {
     }
 }
 
+impl From<CDNSKEY> for Record {
+    fn from(v: CDNSKEY) -> Self {
+        Self::CDNSKEY(v)
+    }
+}
+
+impl From<CDS> for Record {
+    fn from(v: CDS) -> Self {
+        Self::CDS(v)
+    }
+}
+
 impl From<DNSKEY> for Record {
     fn from(v: DNSKEY) -> Self {
         Self::DNSKEY(v)
@@ -103,18 +175,48 @@ impl From<A> for Record {
     }
 }
 
+impl From<AAAA> for Record {
+    fn from(v: AAAA) -> Self {
+        Self::AAAA(v)
+    }
+}
+
 impl From<CNAME> for Record {
     fn from(v: CNAME) -> Self {
         Self::CNAME(v)
     }
 }
 
+impl From<DNAME> for Record {
+    fn from(v: DNAME) -> Self {
+        Self::DNAME(v)
+    }
+}
+
+impl From<MX> for Record {
+    fn from(v: MX) -> Self {
+        Self::MX(v)
+    }
+}
+
+impl From<NAPTR> for Record {
+    fn from(v: NAPTR) -> Self {
+        Self::NAPTR(Box::new(v))
+    }
+}
+
 impl From<NS> for Record {
     fn from(v: NS) -> Self {
         Self::NS(v)
     }
 }
 
+impl From<PTR> for Record {
+    fn from(v: PTR) -> Self {
+        Self::PTR(v)
+    }
+}
+
 impl From<RRSIG> for Record {
     fn from(v: RRSIG) -> Self {
         Self::RRSIG(v)
@@ -127,6 +229,54 @@ impl From<SOA> for Record {
     }
 }
 
+impl From<CAA> for Record {
+    fn from(v: CAA) -> Self {
+        Self::CAA(v)
+    }
+}
+
+impl From<SRV> for Record {
+    fn from(v: SRV) -> Self {
+        Self::SRV(v)
+    }
+}
+
+impl From<SSHFP> for Record {
+    fn from(v: SSHFP) -> Self {
+        Self::SSHFP(v)
+    }
+}
+
+impl From<SVCB> for Record {
+    fn from(v: SVCB) -> Self {
+        Self::SVCB(v)
+    }
+}
+
+impl From<HTTPS> for Record {
+    fn from(v: HTTPS) -> Self {
+        Self::HTTPS(v)
+    }
+}
+
+impl From<TLSA> for Record {
+    fn from(v: TLSA) -> Self {
+        Self::TLSA(v)
+    }
+}
+
+impl From<TXT> for Record {
+    fn from(v: TXT) -> Self {
+        Self::TXT(v)
+    }
+}
+
+impl From<UnknownRdata> for Record {
+    fn from(v: UnknownRdata) -> Self {
+        Self::Unknown(v)
+    }
+}
+
 impl Record {
     pub fn as_rrsig_mut(&mut self) -> Option<&mut RRSIG> {
         if let Self::RRSIG(rrsig) = self {
@@ -144,6 +294,14 @@ impl Record {
         }
     }
 
+    pub fn try_into_aaaa(self) -> CoreResult<AAAA, Self> {
+        if let Self::AAAA(v) = self {
+            Ok(v)
+        } else {
+            Err(self)
+        }
+    }
+
     pub fn try_into_cname(self) -> CoreResult<CNAME, Self> {
         if let Self::CNAME(v) = self {
             Ok(v)
@@ -152,6 +310,22 @@ impl Record {
         }
     }
 
+    pub fn try_into_dname(self) -> CoreResult<DNAME, Self> {
+        if let Self::DNAME(v) = self {
+            Ok(v)
+        } else {
+            Err(self)
+        }
+    }
+
+    pub fn try_into_mx(self) -> CoreResult<MX, Self> {
+        if let Self::MX(mx) = self {
+            Ok(mx)
+        } else {
+            Err(self)
+        }
+    }
+
     pub fn try_into_rrsig(self) -> CoreResult<RRSIG, Self> {
         if let Self::RRSIG(v) = self {
             Ok(v)
@@ -168,6 +342,46 @@ impl Record {
         }
     }
 
+    pub fn try_into_srv(self) -> CoreResult<SRV, Self> {
+        if let Self::SRV(srv) = self {
+            Ok(srv)
+        } else {
+            Err(self)
+        }
+    }
+
+    pub fn try_into_sshfp(self) -> CoreResult<SSHFP, Self> {
+        if let Self::SSHFP(sshfp) = self {
+            Ok(sshfp)
+        } else {
+            Err(self)
+        }
+    }
+
+    pub fn try_into_svcb(self) -> CoreResult<SVCB, Self> {
+        if let Self::SVCB(svcb) = self {
+            Ok(svcb)
+        } else {
+            Err(self)
+        }
+    }
+
+    pub fn try_into_https(self) -> CoreResult<HTTPS, Self> {
+        if let Self::HTTPS(https) = self {
+            Ok(https)
+        } else {
+            Err(self)
+        }
+    }
+
+    pub fn try_into_tlsa(self) -> CoreResult<TLSA, Self> {
+        if let Self::TLSA(tlsa) = self {
+            Ok(tlsa)
+        } else {
+            Err(self)
+        }
+    }
+
     pub fn try_into_txt(self) -> CoreResult<TXT, Self> {
         if let Self::TXT(txt) = self {
             Ok(txt)
@@ -180,6 +394,128 @@ impl Record {
         matches!(self, Self::SOA(..))
     }
 
+    pub fn record_type(&self) -> RecordType {
+        match self {
+            Record::A(_) => RecordType::A,
+            Record::AAAA(_) => RecordType::AAAA,
+            Record::CAA(_) => RecordType::CAA,
+            Record::CNAME(_) => RecordType::CNAME,
+            Record::DNAME(_) => RecordType::DNAME,
+            Record::CDNSKEY(_) => RecordType::CDNSKEY,
+            Record::CDS(_) => RecordType::CDS,
+            Record::DNSKEY(_) => RecordType::DNSKEY,
+            Record::DS(_) => RecordType::DS,
+            Record::HTTPS(_) => RecordType::HTTPS,
+            Record::MX(_) => RecordType::MX,
+            Record::NAPTR(_) => RecordType::NAPTR,
+            Record::NS(_) => RecordType::NS,
+            Record::NSEC(_) => RecordType::NSEC,
+            Record::NSEC3(_) => RecordType::NSEC3,
+            Record::NSEC3PARAM(_) => RecordType::NSEC3PARAM,
+            Record::PTR(_) => RecordType::PTR,
+            Record::RRSIG(_) => RecordType::RRSIG,
+            Record::SOA(_) => RecordType::SOA,
+            Record::SRV(_) => RecordType::SRV,
+            Record::SSHFP(_) => RecordType::SSHFP,
+            Record::SVCB(_) => RecordType::SVCB,
+            Record::TLSA(_) => RecordType::TLSA,
+            Record::TXT(_) => RecordType::TXT,
+            Record::Unknown(unknown) => RecordType::Unknown(unknown.r#type),
+        }
+    }
+
+    /// Returns the owner name of this record
+    pub fn owner(&self) -> &FQDN {
+        match self {
+            Record::A(A { fqdn, .. })
+            | Record::AAAA(AAAA { fqdn, .. })
+            | Record::CNAME(CNAME { fqdn, .. })
+            | Record::DNAME(DNAME { fqdn, .. })
+            | Record::MX(MX { fqdn, .. })
+            | Record::NSEC(NSEC { fqdn, .. })
+            | Record::NSEC3(NSEC3 { fqdn, .. })
+            | Record::PTR(PTR { fqdn, .. })
+            | Record::SRV(SRV { fqdn, .. })
+            | Record::SSHFP(SSHFP { fqdn, .. })
+            | Record::TLSA(TLSA { fqdn, .. })
+            | Record::SVCB(SVCB { fqdn, .. })
+            | Record::HTTPS(HTTPS { fqdn, .. })
+            | Record::RRSIG(RRSIG { fqdn, .. }) => fqdn,
+            Record::CAA(CAA { zone, .. })
+            | Record::CDNSKEY(CDNSKEY { zone, .. })
+            | Record::CDS(CDS { zone, .. })
+            | Record::DNSKEY(DNSKEY { zone, .. })
+            | Record::DS(DS { zone, .. })
+            | Record::NS(NS { zone, .. })
+            | Record::NSEC3PARAM(NSEC3PARAM { zone, .. })
+            | Record::SOA(SOA { zone, .. })
+            | Record::TXT(TXT { zone, .. })
+            | Record::Unknown(UnknownRdata { zone, .. }) => zone,
+            Record::NAPTR(naptr) => &naptr.fqdn,
+        }
+    }
+
+    pub fn ttl(&self) -> u32 {
+        match self {
+            Record::A(A { ttl, .. })
+            | Record::AAAA(AAAA { ttl, .. })
+            | Record::CAA(CAA { ttl, .. })
+            | Record::CNAME(CNAME { ttl, .. })
+            | Record::DNAME(DNAME { ttl, .. })
+            | Record::CDNSKEY(CDNSKEY { ttl, .. })
+            | Record::CDS(CDS { ttl, .. })
+            | Record::DNSKEY(DNSKEY { ttl, .. })
+            | Record::DS(DS { ttl, .. })
+            | Record::MX(MX { ttl, .. })
+            | Record::NS(NS { ttl, .. })
+            | Record::NSEC(NSEC { ttl, .. })
+            | Record::NSEC3(NSEC3 { ttl, .. })
+            | Record::NSEC3PARAM(NSEC3PARAM { ttl, .. })
+            | Record::PTR(PTR { ttl, .. })
+            | Record::RRSIG(RRSIG { ttl, .. })
+            | Record::SOA(SOA { ttl, .. })
+            | Record::SRV(SRV { ttl, .. })
+            | Record::SSHFP(SSHFP { ttl, .. })
+            | Record::TLSA(TLSA { ttl, .. })
+            | Record::SVCB(SVCB { ttl, .. })
+            | Record::HTTPS(HTTPS { ttl, .. })
+            | Record::TXT(TXT { ttl, .. })
+            | Record::Unknown(UnknownRdata { ttl, .. }) => *ttl,
+            Record::NAPTR(naptr) => naptr.ttl,
+        }
+    }
+
+    pub fn with_ttl(mut self, ttl: u32) -> Self {
+        match &mut self {
+            Record::A(A { ttl: old, .. })
+            | Record::AAAA(AAAA { ttl: old, .. })
+            | Record::CAA(CAA { ttl: old, .. })
+            | Record::CNAME(CNAME { ttl: old, .. })
+            | Record::DNAME(DNAME { ttl: old, .. })
+            | Record::CDNSKEY(CDNSKEY { ttl: old, .. })
+            | Record::CDS(CDS { ttl: old, .. })
+            | Record::DNSKEY(DNSKEY { ttl: old, .. })
+            | Record::DS(DS { ttl: old, .. })
+            | Record::MX(MX { ttl: old, .. })
+            | Record::NS(NS { ttl: old, .. })
+            | Record::NSEC(NSEC { ttl: old, .. })
+            | Record::NSEC3(NSEC3 { ttl: old, .. })
+            | Record::NSEC3PARAM(NSEC3PARAM { ttl: old, .. })
+            | Record::PTR(PTR { ttl: old, .. })
+            | Record::RRSIG(RRSIG { ttl: old, .. })
+            | Record::SOA(SOA { ttl: old, .. })
+            | Record::SRV(SRV { ttl: old, .. })
+            | Record::SSHFP(SSHFP { ttl: old, .. })
+            | Record::TLSA(TLSA { ttl: old, .. })
+            | Record::SVCB(SVCB { ttl: old, .. })
+            | Record::HTTPS(HTTPS { ttl: old, .. })
+            | Record::TXT(TXT { ttl: old, .. })
+            | Record::Unknown(UnknownRdata { ttl: old, .. }) => *old = ttl,
+            Record::NAPTR(naptr) => naptr.ttl = ttl,
+        }
+        self
+    }
+
     pub fn a(fqdn: FQDN, ipv4_addr: Ipv4Addr) -> Self {
         A {
             fqdn,
@@ -189,6 +525,15 @@ impl Record {
         .into()
     }
 
+    pub fn aaaa(fqdn: FQDN, ipv6_addr: Ipv6Addr) -> Self {
+        AAAA {
+            fqdn,
+            ttl: DEFAULT_TTL,
+            ipv6_addr,
+        }
+        .into()
+    }
+
     pub fn cname(fqdn: FQDN, target: FQDN) -> Self {
         CNAME {
             fqdn,
@@ -198,6 +543,26 @@ impl Record {
         .into()
     }
 
+    /// A DNAME (RFC6672) record that redirects the subtree below `fqdn` to `target`
+    pub fn dname(fqdn: FQDN, target: FQDN) -> Self {
+        DNAME {
+            fqdn,
+            target,
+            ttl: DEFAULT_TTL,
+        }
+        .into()
+    }
+
+    /// A PTR record; use [`FQDN::reverse`] to get the owner name for an IP address
+    pub fn ptr(fqdn: FQDN, target: FQDN) -> Self {
+        PTR {
+            fqdn,
+            ttl: DEFAULT_TTL,
+            target,
+        }
+        .into()
+    }
+
     pub fn ns(zone: FQDN, nameserver: FQDN) -> Self {
         NS {
             zone,
@@ -207,6 +572,219 @@ impl Record {
         .into()
     }
 
+    /// A Certification Authority Authorization (RFC8659) record, e.g. `0 issue "ca.example.net"`
+    pub fn caa(zone: FQDN, flags: u8, tag: &str, value: &str) -> Self {
+        CAA {
+            zone,
+            ttl: DEFAULT_TTL,
+            flags,
+            tag: tag.to_string(),
+            value: value.to_string(),
+        }
+        .into()
+    }
+
+    /// A DANE (RFC6698) record; `fqdn` is usually of the form `_443._tcp.www.example.com.`
+    pub fn tlsa(fqdn: FQDN, usage: u8, selector: u8, matching_type: u8, data: Vec<u8>) -> Self {
+        TLSA {
+            fqdn,
+            ttl: DEFAULT_TTL,
+            usage,
+            selector,
+            matching_type,
+            data,
+        }
+        .into()
+    }
+
+    /// A Naming Authority Pointer (RFC3403) record; the `regexp` is given unescaped
+    pub fn naptr(
+        fqdn: FQDN,
+        order: u16,
+        preference: u16,
+        flags: &str,
+        services: &str,
+        regexp: &str,
+        replacement: FQDN,
+    ) -> Self {
+        NAPTR {
+            fqdn,
+            ttl: DEFAULT_TTL,
+            order,
+            preference,
+            flags: flags.to_string(),
+            services: services.to_string(),
+            regexp: regexp.to_string(),
+            replacement,
+        }
+        .into()
+    }
+
+    /// A mail exchange record; lower `preference` values are preferred
+    pub fn mx(fqdn: FQDN, preference: u16, exchange: FQDN) -> Self {
+        MX {
+            fqdn,
+            ttl: DEFAULT_TTL,
+            preference,
+            exchange,
+        }
+        .into()
+    }
+
+    /// A service location (RFC2782) record; `fqdn` is of the form `_sip._tcp.example.com.`
+    pub fn srv(fqdn: FQDN, priority: u16, weight: u16, port: u16, target: FQDN) -> Self {
+        SRV {
+            fqdn,
+            ttl: DEFAULT_TTL,
+            priority,
+            weight,
+            port,
+            target,
+        }
+        .into()
+    }
+
+    /// An SSH host key fingerprint (RFC4255) record
+    pub fn sshfp(fqdn: FQDN, algorithm: u8, fp_type: u8, fingerprint: Vec<u8>) -> Self {
+        SSHFP {
+            fqdn,
+            ttl: DEFAULT_TTL,
+            algorithm,
+            fp_type,
+            fingerprint,
+        }
+        .into()
+    }
+
+    /// A service binding (RFC9460) record; a `priority` of 0 makes it an alias to `target`
+    pub fn svcb(fqdn: FQDN, priority: u16, target: FQDN, params: Vec<SvcParam>) -> Self {
+        SVCB {
+            fqdn,
+            ttl: DEFAULT_TTL,
+            rdata: SvcbRData {
+                priority,
+                target,
+                params,
+            },
+        }
+        .into()
+    }
+
+    /// Like [`Record::svcb`] but for HTTPS origins
+    pub fn https(fqdn: FQDN, priority: u16, target: FQDN, params: Vec<SvcParam>) -> Self {
+        HTTPS {
+            fqdn,
+            ttl: DEFAULT_TTL,
+            rdata: SvcbRData {
+                priority,
+                target,
+                params,
+            },
+        }
+        .into()
+    }
+
+    /// A TXT record whose `value` is split into as many character strings as needed
+    pub fn txt(fqdn: FQDN, value: &str) -> Self {
+        TXT {
+            zone: fqdn,
+            ttl: DEFAULT_TTL,
+            class: Class::IN,
+            character_strings: TXT::split(value),
+        }
+        .into()
+    }
+
+    /// A TXT record made of exactly the given character strings, e.g. to test how a split at a
+    /// specific position or an empty string is handled
+    pub fn txt_character_strings(fqdn: FQDN, character_strings: &[&str]) -> Self {
+        for string in character_strings {
+            assert!(
+                string.is_ascii(),
+                "non-ASCII characters in TXT records are not supported"
+            );
+            assert!(
+                string.len() <= TXT::MAX_CHARACTER_STRING_LEN,
+                "character string is longer than {} bytes",
+                TXT::MAX_CHARACTER_STRING_LEN
+            );
+        }
+
+        TXT {
+            zone: fqdn,
+            ttl: DEFAULT_TTL,
+            class: Class::IN,
+            character_strings: character_strings
+                .iter()
+                .map(|string| string.to_string())
+                .collect(),
+        }
+        .into()
+    }
+
+    /// A Sender Policy Framework (RFC7208) record; `policy` is appended to the `v=spf1` version
+    /// tag, e.g. `ip4:192.0.2.0/24 -all`
+    pub fn spf(fqdn: FQDN, policy: &str) -> Self {
+        Self::txt(fqdn, &format!("v=spf1 {policy}"))
+    }
+
+    /// A DomainKeys Identified Mail (RFC6376) public key record for `selector` in `domain`
+    ///
+    /// `public_key` is the base64 encoded RSA public key; a 2048-bit key does not fit in a single
+    /// character string
+    pub fn dkim(selector: &str, domain: &FQDN, public_key: &str) -> Self {
+        let fqdn = domain.push_label("_domainkey").push_label(selector);
+        Self::txt(fqdn, &format!("v=DKIM1; k=rsa; p={public_key}"))
+    }
+
+    /// A Responsible Person (RFC1183) record; `mbox` is the mailbox, with its `@` replaced by a
+    /// `.`, and `txt` the owner of TXT records with more information
+    pub fn rp(fqdn: FQDN, mbox: &FQDN, txt: &FQDN) -> Self {
+        let mut rdata = mbox
+            .wire_format()
+            .expect("`mbox` is not a valid domain name");
+        rdata.extend(txt.wire_format().expect("`txt` is not a valid domain name"));
+        UnknownRdata::generic(fqdn, "RP", rdata).into()
+    }
+
+    /// An AFS Data Base location (RFC1183) record
+    pub fn afsdb(fqdn: FQDN, subtype: u16, hostname: &FQDN) -> Self {
+        let mut rdata = subtype.to_be_bytes().to_vec();
+        rdata.extend(
+            hostname
+                .wire_format()
+                .expect("`hostname` is not a valid domain name"),
+        );
+        UnknownRdata::generic(fqdn, "AFSDB", rdata).into()
+    }
+
+    /// A Location Information (RFC1876) record
+    pub fn loc(fqdn: FQDN, loc: &Loc) -> Self {
+        UnknownRdata::generic(fqdn, "LOC", loc.rdata()).into()
+    }
+
+    /// An Address Prefix List (RFC3123) record
+    pub fn apl(fqdn: FQDN, items: &[AplItem]) -> Self {
+        let rdata = items.iter().flat_map(AplItem::rdata).collect();
+        UnknownRdata::generic(fqdn, "APL", rdata).into()
+    }
+
+    pub fn try_into_cdnskey(self) -> CoreResult<CDNSKEY, Self> {
+        if let Self::CDNSKEY(v) = self {
+            Ok(v)
+        } else {
+            Err(self)
+        }
+    }
+
+    pub fn try_into_cds(self) -> CoreResult<CDS, Self> {
+        if let Self::CDS(v) = self {
+            Ok(v)
+        } else {
+            Err(self)
+        }
+    }
+
     pub fn try_into_ds(self) -> CoreResult<DS, Self> {
         if let Self::DS(v) = self {
             Ok(v)
@@ -215,6 +793,22 @@ impl Record {
         }
     }
 
+    pub fn try_into_naptr(self) -> CoreResult<NAPTR, Self> {
+        if let Self::NAPTR(v) = self {
+            Ok(*v)
+        } else {
+            Err(self)
+        }
+    }
+
+    pub fn try_into_nsec(self) -> CoreResult<NSEC, Self> {
+        if let Self::NSEC(v) = self {
+            Ok(v)
+        } else {
+            Err(self)
+        }
+    }
+
     pub fn try_into_nsec3(self) -> CoreResult<NSEC3, Self> {
         if let Self::NSEC3(v) = self {
             Ok(v)
@@ -223,38 +817,72 @@ impl Record {
         }
     }
 
+    pub fn try_into_ptr(self) -> CoreResult<PTR, Self> {
+        if let Self::PTR(v) = self {
+            Ok(v)
+        } else {
+            Err(self)
+        }
+    }
+
     pub fn try_into_caa(self) -> CoreResult<CAA, Self> {
         match self {
             Self::CAA(v) => Ok(v),
             _ => Err(self),
         }
     }
+
+    pub fn try_into_unknown(self) -> CoreResult<UnknownRdata, Self> {
+        match self {
+            Self::Unknown(v) => Ok(v),
+            _ => Err(self),
+        }
+    }
 }
 
 impl FromStr for Record {
     type Err = Error;
 
     fn from_str(input: &str) -> Result<Self> {
-        let record_type = input
-            .split_whitespace()
-            .nth(3)
-            .ok_or("record is missing the type column")?;
+        let mut columns = input.split_whitespace().skip(3);
+        let record_type = columns.next().ok_or("record is missing the type column")?;
+
+        // `dig +unknownformat` uses the generic encoding (RFC3597) even for known record types
+        if columns.next() == Some("\\#") {
+            return Ok(Record::Unknown(input.parse()?));
+        }
 
         let record = match record_type {
             "A" => Record::A(input.parse()?),
+            "AAAA" => Record::AAAA(input.parse()?),
             "CAA" => Record::CAA(input.parse()?),
             "CNAME" => Record::CNAME(input.parse()?),
+            "DNAME" => Record::DNAME(input.parse()?),
+            "CDNSKEY" => Record::CDNSKEY(input.parse()?),
+            "CDS" => Record::CDS(input.parse()?),
             "DNSKEY" => Record::DNSKEY(input.parse()?),
             "DS" => Record::DS(input.parse()?),
+            "HTTPS" => Record::HTTPS(input.parse()?),
+            "MX" => Record::MX(input.parse()?),
+            "NAPTR" => Record::NAPTR(Box::new(input.parse()?)),
             "NS" => Record::NS(input.parse()?),
             "NSEC" => Record::NSEC(input.parse()?),
             "NSEC3" => Record::NSEC3(input.parse()?),
             "NSEC3PARAM" => Record::NSEC3PARAM(input.parse()?),
+            "PTR" => Record::PTR(input.parse()?),
             "RRSIG" => Record::RRSIG(input.parse()?),
             "SOA" => Record::SOA(input.parse()?),
+            "SRV" => Record::SRV(input.parse()?),
+            "SSHFP" => Record::SSHFP(input.parse()?),
+            "SVCB" => Record::SVCB(input.parse()?),
+            "TLSA" => Record::TLSA(input.parse()?),
             "TXT" => Record::TXT(input.parse()?),
             _ => {
-                if record_type.starts_with("TYPE") {
+                if record_type.starts_with("TYPE")
+                    || GENERIC_RECORD_TYPES
+                        .iter()
+                        .any(|(name, _)| *name == record_type)
+                {
                     Record::Unknown(input.parse()?)
                 } else {
                     return Err(format!("unknown record type: {record_type}").into());
@@ -270,23 +898,35 @@ impl fmt::Display for Record {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Record::A(a) => write!(f, "{a}"),
+            Record::AAAA(aaaa) => write!(f, "{aaaa}"),
             Record::CAA(caa) => write!(f, "{caa}"),
             Record::CNAME(cname) => write!(f, "{cname}"),
+            Record::DNAME(dname) => write!(f, "{dname}"),
+            Record::CDNSKEY(cdnskey) => write!(f, "{cdnskey}"),
+            Record::CDS(cds) => write!(f, "{cds}"),
             Record::DS(ds) => write!(f, "{ds}"),
             Record::DNSKEY(dnskey) => write!(f, "{dnskey}"),
+            Record::HTTPS(https) => write!(f, "{https}"),
+            Record::MX(mx) => write!(f, "{mx}"),
+            Record::NAPTR(naptr) => write!(f, "{naptr}"),
             Record::NS(ns) => write!(f, "{ns}"),
             Record::NSEC(nsec) => write!(f, "{nsec}"),
             Record::NSEC3(nsec3) => write!(f, "{nsec3}"),
             Record::NSEC3PARAM(nsec3param) => write!(f, "{nsec3param}"),
+            Record::PTR(ptr) => write!(f, "{ptr}"),
             Record::RRSIG(rrsig) => write!(f, "{rrsig}"),
             Record::SOA(soa) => write!(f, "{soa}"),
+            Record::SRV(srv) => write!(f, "{srv}"),
+            Record::SSHFP(sshfp) => write!(f, "{sshfp}"),
+            Record::SVCB(svcb) => write!(f, "{svcb}"),
+            Record::TLSA(tlsa) => write!(f, "{tlsa}"),
             Record::TXT(txt) => write!(f, "{txt}"),
             Record::Unknown(other) => write!(f, "{other}"),
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct A {
     pub fqdn: FQDN,
     pub ttl: u32,
@@ -335,14 +975,14 @@ impl fmt::Display for A {
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct CNAME {
+#[derive(Debug, Clone, PartialEq)]
+pub struct AAAA {
     pub fqdn: FQDN,
     pub ttl: u32,
-    pub target: FQDN,
+    pub ipv6_addr: Ipv6Addr,
 }
 
-impl FromStr for CNAME {
+impl FromStr for AAAA {
     type Err = Error;
 
     fn from_str(input: &str) -> Result<Self> {
@@ -353,7 +993,7 @@ impl FromStr for CNAME {
             Some(ttl),
             Some(class),
             Some(record_type),
-            Some(target),
+            Some(ipv6_addr),
             None,
         ] = array::from_fn(|_| columns.next())
         else {
@@ -366,12 +1006,106 @@ impl FromStr for CNAME {
         Ok(Self {
             fqdn: fqdn.parse()?,
             ttl: ttl.parse()?,
-            target: target.parse()?,
+            ipv6_addr: ipv6_addr.parse()?,
         })
     }
 }
 
-impl fmt::Display for CNAME {
+impl fmt::Display for AAAA {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            fqdn,
+            ttl,
+            ipv6_addr,
+        } = self;
+
+        let record_type = unqualified_type_name::<Self>();
+        write!(f, "{fqdn}\t{ttl}\t{CLASS}\t{record_type}\t{ipv6_addr}")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CNAME {
+    pub fqdn: FQDN,
+    pub ttl: u32,
+    pub target: FQDN,
+}
+
+impl FromStr for CNAME {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        let mut columns = input.split_whitespace();
+
+        let [
+            Some(fqdn),
+            Some(ttl),
+            Some(class),
+            Some(record_type),
+            Some(target),
+            None,
+        ] = array::from_fn(|_| columns.next())
+        else {
+            return Err("expected 5 columns".into());
+        };
+
+        check_record_type::<Self>(record_type)?;
+        check_class(class)?;
+
+        Ok(Self {
+            fqdn: fqdn.parse()?,
+            ttl: ttl.parse()?,
+            target: target.parse()?,
+        })
+    }
+}
+
+impl fmt::Display for CNAME {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { fqdn, ttl, target } = self;
+
+        let record_type = unqualified_type_name::<Self>();
+        write!(f, "{fqdn}\t{ttl}\t{CLASS}\t{record_type}\t{target}")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DNAME {
+    pub fqdn: FQDN,
+    pub ttl: u32,
+    pub target: FQDN,
+}
+
+impl FromStr for DNAME {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        let mut columns = input.split_whitespace();
+
+        let [
+            Some(fqdn),
+            Some(ttl),
+            Some(class),
+            Some(record_type),
+            Some(target),
+            None,
+        ] = array::from_fn(|_| columns.next())
+        else {
+            return Err("expected 5 columns".into());
+        };
+
+        check_record_type::<Self>(record_type)?;
+        check_class(class)?;
+
+        Ok(Self {
+            fqdn: fqdn.parse()?,
+            ttl: ttl.parse()?,
+            target: target.parse()?,
+        })
+    }
+}
+
+impl fmt::Display for DNAME {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let Self { fqdn, ttl, target } = self;
 
@@ -381,7 +1115,7 @@ impl fmt::Display for CNAME {
 }
 
 // integer types chosen based on bit sizes in section 2.1 of RFC4034
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct DNSKEY {
     pub zone: FQDN,
     pub ttl: u32,
@@ -389,7 +1123,7 @@ pub struct DNSKEY {
 }
 
 impl DNSKEY {
-    const KSK_BIT: u16 = 1;
+    pub(crate) const KSK_BIT: u16 = 1;
 
     /// formats the `DNSKEY` in the format `delv` expects
     pub(super) fn delv(&self) -> String {
@@ -490,7 +1224,7 @@ impl fmt::Display for DNSKEY {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct DNSKEYRData {
     pub flags: u16,
     pub protocol: u8,
@@ -534,7 +1268,7 @@ impl DNSKEYRData {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct DS {
     pub zone: FQDN,
     pub ttl: u32,
@@ -544,6 +1278,22 @@ pub struct DS {
     pub digest: String,
 }
 
+impl DS {
+    /// formats the `DS` in the format `delv` expects
+    pub(super) fn delv(&self) -> String {
+        let Self {
+            zone,
+            key_tag,
+            algorithm,
+            digest_type,
+            digest,
+            ..
+        } = self;
+
+        format!("{zone} static-ds {key_tag} {algorithm} {digest_type} \"{digest}\";\n")
+    }
+}
+
 impl FromStr for DS {
     type Err = Error;
 
@@ -603,27 +1353,67 @@ impl fmt::Display for DS {
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct NS {
+/// Child copy of a DS record (RFC7344), published to signal the parent which DS it should hold
+#[derive(Debug, Clone, PartialEq)]
+pub struct CDS {
     pub zone: FQDN,
     pub ttl: u32,
-    pub nameserver: FQDN,
+    pub key_tag: u16,
+    pub algorithm: u8,
+    pub digest_type: u8,
+    pub digest: String,
 }
 
-impl fmt::Display for NS {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let Self {
+impl CDS {
+    /// The CDS that asks the parent to remove all the DS records of `zone` (RFC8078 section 4)
+    pub fn delete(zone: FQDN) -> Self {
+        Self {
+            zone,
+            ttl: DEFAULT_TTL,
+            key_tag: 0,
+            algorithm: 0,
+            digest_type: 0,
+            digest: "00".to_string(),
+        }
+    }
+
+    pub fn is_delete(&self) -> bool {
+        self.algorithm == 0
+    }
+
+    /// Whether this CDS asks for exactly the given DS record
+    pub fn matches(&self, ds: &DS) -> bool {
+        self.zone == ds.zone
+            && self.key_tag == ds.key_tag
+            && self.algorithm == ds.algorithm
+            && self.digest_type == ds.digest_type
+            && self.digest.eq_ignore_ascii_case(&ds.digest)
+    }
+}
+
+impl From<DS> for CDS {
+    fn from(ds: DS) -> Self {
+        let DS {
             zone,
             ttl,
-            nameserver,
-        } = self;
+            key_tag,
+            algorithm,
+            digest_type,
+            digest,
+        } = ds;
 
-        let record_type = unqualified_type_name::<Self>();
-        write!(f, "{zone}\t{ttl}\t{CLASS}\t{record_type}\t{nameserver}")
+        Self {
+            zone,
+            ttl,
+            key_tag,
+            algorithm,
+            digest_type,
+            digest,
+        }
     }
 }
 
-impl FromStr for NS {
+impl FromStr for CDS {
     type Err = Error;
 
     fn from_str(input: &str) -> Result<Self> {
@@ -634,11 +1424,12 @@ impl FromStr for NS {
             Some(ttl),
             Some(class),
             Some(record_type),
-            Some(nameserver),
-            None,
+            Some(key_tag),
+            Some(algorithm),
+            Some(digest_type),
         ] = array::from_fn(|_| columns.next())
         else {
-            return Err("expected 5 columns".into());
+            return Err("expected at least 7 columns".into());
         };
 
         check_record_type::<Self>(record_type)?;
@@ -647,93 +1438,269 @@ impl FromStr for NS {
         Ok(Self {
             zone: zone.parse()?,
             ttl: ttl.parse()?,
-            nameserver: nameserver.parse()?,
+            key_tag: key_tag.parse()?,
+            algorithm: algorithm.parse()?,
+            digest_type: digest_type.parse()?,
+            digest: columns.collect(),
         })
     }
 }
 
+impl fmt::Display for CDS {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            zone,
+            ttl,
+            key_tag,
+            algorithm,
+            digest_type,
+            digest,
+        } = self;
+
+        let record_type = unqualified_type_name::<Self>();
+        write!(
+            f,
+            "{zone}\t{ttl}\t{CLASS}\t{record_type}\t{key_tag} {algorithm} {digest_type}"
+        )?;
+
+        write_split_long_string(f, digest)
+    }
+}
+
+/// Child copy of a DNSKEY record (RFC7344), published to signal the parent which DS it should hold
 #[derive(Debug, Clone, PartialEq)]
-pub struct NSEC {
-    pub fqdn: FQDN,
+pub struct CDNSKEY {
+    pub zone: FQDN,
     pub ttl: u32,
-    pub next_domain: FQDN,
-    pub record_types: Vec<RecordType>,
+    pub rdata: DNSKEYRData,
 }
 
-impl FromStr for NSEC {
+impl CDNSKEY {
+    /// The CDNSKEY that asks the parent to remove all the DS records of `zone` (RFC8078 section 4)
+    pub fn delete(zone: FQDN) -> Self {
+        Self {
+            zone,
+            ttl: DEFAULT_TTL,
+            rdata: DNSKEYRData {
+                flags: 0,
+                protocol: 3,
+                algorithm: 0,
+                public_key: "AA==".to_string(),
+            },
+        }
+    }
+
+    pub fn is_delete(&self) -> bool {
+        self.rdata.algorithm == 0
+    }
+}
+
+impl From<DNSKEY> for CDNSKEY {
+    fn from(dnskey: DNSKEY) -> Self {
+        let DNSKEY { zone, ttl, rdata } = dnskey;
+        Self { zone, ttl, rdata }
+    }
+}
+
+impl FromStr for CDNSKEY {
     type Err = Error;
 
     fn from_str(input: &str) -> Result<Self> {
         let mut columns = input.split_whitespace();
 
         let [
-            Some(fqdn),
+            Some(zone),
             Some(ttl),
             Some(class),
             Some(record_type),
-            Some(next_domain),
+            Some(flags),
+            Some(protocol),
+            Some(algorithm),
         ] = array::from_fn(|_| columns.next())
         else {
-            return Err("expected at least 5 columns".into());
+            return Err("expected at least 7 columns".into());
         };
 
         check_record_type::<Self>(record_type)?;
         check_class(class)?;
 
-        let mut record_types = vec![];
-        for column in columns {
-            record_types.push(column.parse()?);
-        }
-
         Ok(Self {
-            fqdn: fqdn.parse()?,
+            zone: zone.parse()?,
             ttl: ttl.parse()?,
-            next_domain: next_domain.parse()?,
-            record_types,
+            rdata: DNSKEYRData {
+                flags: flags.parse()?,
+                protocol: protocol.parse()?,
+                algorithm: algorithm.parse()?,
+                public_key: columns.collect(),
+            },
         })
     }
 }
 
-impl fmt::Display for NSEC {
+impl fmt::Display for CDNSKEY {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let Self {
-            fqdn,
+            zone,
             ttl,
-            next_domain,
-            record_types,
+            rdata:
+                DNSKEYRData {
+                    flags,
+                    protocol,
+                    algorithm,
+                    public_key,
+                },
         } = self;
 
         let record_type = unqualified_type_name::<Self>();
-        write!(f, "{fqdn}\t{ttl}\t{CLASS}\t{record_type}\t{next_domain}")?;
-
-        for record_type in record_types {
-            write!(f, " {record_type}")?;
-        }
+        write!(
+            f,
+            "{zone}\t{ttl}\t{CLASS}\t{record_type}\t{flags} {protocol} {algorithm}"
+        )?;
 
-        Ok(())
+        write_split_long_string(f, public_key)
     }
 }
 
-// integer types chosen based on bit sizes in section 3.2 of RFC5155
 #[derive(Debug, Clone, PartialEq)]
-pub struct NSEC3 {
-    pub fqdn: FQDN,
+pub struct NS {
+    pub zone: FQDN,
     pub ttl: u32,
-    pub hash_alg: u8,
-    pub flags: u8,
-    pub iterations: u16,
-    pub salt: String,
-    pub next_hashed_owner_name: String,
-    pub record_types: Vec<RecordType>,
+    pub nameserver: FQDN,
 }
 
-impl FromStr for NSEC3 {
-    type Err = Error;
-
-    fn from_str(input: &str) -> Result<Self> {
-        let mut columns = input.split_whitespace();
-
-        let [
+impl fmt::Display for NS {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            zone,
+            ttl,
+            nameserver,
+        } = self;
+
+        let record_type = unqualified_type_name::<Self>();
+        write!(f, "{zone}\t{ttl}\t{CLASS}\t{record_type}\t{nameserver}")
+    }
+}
+
+impl FromStr for NS {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        let mut columns = input.split_whitespace();
+
+        let [
+            Some(zone),
+            Some(ttl),
+            Some(class),
+            Some(record_type),
+            Some(nameserver),
+            None,
+        ] = array::from_fn(|_| columns.next())
+        else {
+            return Err("expected 5 columns".into());
+        };
+
+        check_record_type::<Self>(record_type)?;
+        check_class(class)?;
+
+        Ok(Self {
+            zone: zone.parse()?,
+            ttl: ttl.parse()?,
+            nameserver: nameserver.parse()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NSEC {
+    pub fqdn: FQDN,
+    pub ttl: u32,
+    pub next_domain: FQDN,
+    pub record_types: Vec<RecordType>,
+}
+
+impl FromStr for NSEC {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        let mut columns = input.split_whitespace();
+
+        let [
+            Some(fqdn),
+            Some(ttl),
+            Some(class),
+            Some(record_type),
+            Some(next_domain),
+        ] = array::from_fn(|_| columns.next())
+        else {
+            return Err("expected at least 5 columns".into());
+        };
+
+        check_record_type::<Self>(record_type)?;
+        check_class(class)?;
+
+        let mut record_types = vec![];
+        for column in columns {
+            record_types.push(column.parse()?);
+        }
+
+        Ok(Self {
+            fqdn: fqdn.parse()?,
+            ttl: ttl.parse()?,
+            next_domain: next_domain.parse()?,
+            record_types,
+        })
+    }
+}
+
+impl fmt::Display for NSEC {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            fqdn,
+            ttl,
+            next_domain,
+            record_types,
+        } = self;
+
+        let record_type = unqualified_type_name::<Self>();
+        write!(f, "{fqdn}\t{ttl}\t{CLASS}\t{record_type}\t{next_domain}")?;
+
+        for record_type in record_types {
+            write!(f, " {record_type}")?;
+        }
+
+        Ok(())
+    }
+}
+
+// integer types chosen based on bit sizes in section 3.2 of RFC5155
+#[derive(Debug, Clone, PartialEq)]
+pub struct NSEC3 {
+    pub fqdn: FQDN,
+    pub ttl: u32,
+    pub hash_alg: u8,
+    pub flags: u8,
+    pub iterations: u16,
+    pub salt: String,
+    pub next_hashed_owner_name: String,
+    pub record_types: Vec<RecordType>,
+}
+
+impl NSEC3 {
+    const OPT_OUT_BIT: u8 = 1;
+
+    /// Whether the NSEC3 RR may cover unsigned delegations (RFC5155 section 3.1.2.1)
+    pub fn is_opt_out(&self) -> bool {
+        self.flags & Self::OPT_OUT_BIT != 0
+    }
+}
+
+impl FromStr for NSEC3 {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        let mut columns = input.split_whitespace();
+
+        let [
             Some(fqdn),
             Some(ttl),
             Some(class),
@@ -797,7 +1764,7 @@ impl fmt::Display for NSEC3 {
 }
 
 // integer types chosen based on bit sizes in section 4.2 of RFC5155
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct NSEC3PARAM {
     pub zone: FQDN,
     pub ttl: u32,
@@ -862,9 +1829,54 @@ impl fmt::Display for NSEC3PARAM {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct PTR {
+    pub fqdn: FQDN,
+    pub ttl: u32,
+    pub target: FQDN,
+}
+
+impl FromStr for PTR {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        let mut columns = input.split_whitespace();
+
+        let [
+            Some(fqdn),
+            Some(ttl),
+            Some(class),
+            Some(record_type),
+            Some(target),
+            None,
+        ] = array::from_fn(|_| columns.next())
+        else {
+            return Err("expected 5 columns".into());
+        };
+
+        check_record_type::<Self>(record_type)?;
+        check_class(class)?;
+
+        Ok(Self {
+            fqdn: fqdn.parse()?,
+            ttl: ttl.parse()?,
+            target: target.parse()?,
+        })
+    }
+}
+
+impl fmt::Display for PTR {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { fqdn, ttl, target } = self;
+
+        let record_type = unqualified_type_name::<Self>();
+        write!(f, "{fqdn}\t{ttl}\t{CLASS}\t{record_type}\t{target}")
+    }
+}
+
 // integer types chosen based on bit sizes in section 3.1 of RFC4034
 #[allow(clippy::upper_case_acronyms)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct RRSIG {
     pub fqdn: FQDN,
     pub ttl: u32,
@@ -958,7 +1970,7 @@ impl fmt::Display for RRSIG {
 }
 
 #[allow(clippy::upper_case_acronyms)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct SOA {
     pub zone: FQDN,
     pub ttl: u32,
@@ -1028,7 +2040,7 @@ impl fmt::Display for SOA {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct SoaSettings {
     pub serial: u32,
     pub refresh: u32,
@@ -1064,13 +2076,42 @@ impl fmt::Display for SoaSettings {
 }
 
 #[allow(clippy::upper_case_acronyms)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct TXT {
     pub zone: FQDN,
     pub ttl: u32,
+    pub class: Class,
     pub character_strings: Vec<String>,
 }
 
+impl TXT {
+    /// Maximum length of a character string (RFC1035 section 3.3)
+    const MAX_CHARACTER_STRING_LEN: usize = 255;
+
+    /// Returns the character strings concatenated, which is how e.g. SPF and DKIM consumers
+    /// interpret them
+    pub fn value(&self) -> String {
+        self.character_strings.concat()
+    }
+
+    fn split(value: &str) -> Vec<String> {
+        assert!(
+            value.is_ascii(),
+            "non-ASCII characters in TXT records are not supported"
+        );
+
+        if value.is_empty() {
+            return vec![String::new()];
+        }
+
+        value
+            .as_bytes()
+            .chunks(Self::MAX_CHARACTER_STRING_LEN)
+            .map(|chunk| String::from_utf8(chunk.to_vec()).unwrap())
+            .collect()
+    }
+}
+
 impl FromStr for TXT {
     type Err = Error;
 
@@ -1094,7 +2135,7 @@ impl FromStr for TXT {
         };
 
         check_record_type::<Self>(record_type)?;
-        check_class(class)?;
+        let class = class.parse()?;
 
         let mut character_strings = Vec::new();
         let mut current_string = String::new();
@@ -1162,6 +2203,7 @@ impl FromStr for TXT {
         Ok(Self {
             zone: zone.parse()?,
             ttl: ttl.parse()?,
+            class,
             character_strings,
         })
     }
@@ -1172,11 +2214,12 @@ impl fmt::Display for TXT {
         let Self {
             zone,
             ttl,
+            class,
             character_strings,
         } = self;
 
         let record_type = unqualified_type_name::<Self>();
-        write!(f, "{zone}\t{ttl}\t{CLASS}\t{record_type}")?;
+        write!(f, "{zone}\t{ttl}\t{class}\t{record_type}")?;
         let mut is_first = true;
         for string in character_strings.iter() {
             if is_first {
@@ -1190,8 +2233,37 @@ impl fmt::Display for TXT {
     }
 }
 
+/// The class of a TXT record
+///
+/// Records are in the Internet class except for the TXT records that name servers use to
+/// describe themselves, e.g. the answer to a `version.bind` query
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Class {
+    IN,
+    /// CHAOS
+    CH,
+}
+
+impl FromStr for Class {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        match input {
+            "IN" => Ok(Self::IN),
+            "CH" => Ok(Self::CH),
+            _ => Err(format!("unknown class: {input}").into()),
+        }
+    }
+}
+
+impl fmt::Display for Class {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
 #[allow(clippy::upper_case_acronyms)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct CAA {
     pub zone: FQDN,
     pub ttl: u32,
@@ -1204,65 +2276,678 @@ impl FromStr for CAA {
     type Err = Error;
 
     fn from_str(input: &str) -> CoreResult<Self, Self::Err> {
+        // the value is the rest of the line; it may be quoted and contain whitespace
+        let mut rest = input.trim();
+        let [zone, ttl, class, record_type, flags, tag] = array::from_fn(|_| {
+            let (column, tail) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            rest = tail.trim_start();
+            column
+        });
+
+        if tag.is_empty() || rest.is_empty() {
+            return Err("expected 7 columns".into());
+        }
+
+        check_record_type::<Self>(record_type)?;
+        check_class(class)?;
+
+        let value = match rest.strip_prefix('"') {
+            Some(quoted) => quoted
+                .strip_suffix('"')
+                .ok_or("unterminated quoted CAA value")?,
+            None if rest.contains(char::is_whitespace) => return Err("expected 7 columns".into()),
+            None => rest,
+        };
+
+        Ok(Self {
+            zone: zone.parse()?,
+            ttl: ttl.parse()?,
+            flags: flags.parse()?,
+            tag: tag.to_string(),
+            value: value.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for CAA {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self {
+            zone,
+            ttl,
+            flags,
+            tag,
+            value,
+        } = self;
+
+        let record_type = unqualified_type_name::<Self>();
+        write!(f, "{zone}\t{ttl}\t{CLASS}\t{record_type}\t{flags} {tag} ")?;
+        if value.is_empty() || value.contains(char::is_whitespace) {
+            write!(f, "\"{value}\"")?;
+        } else {
+            f.write_str(value)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TLSA {
+    pub fqdn: FQDN,
+    pub ttl: u32,
+    pub usage: u8,
+    pub selector: u8,
+    pub matching_type: u8,
+    /// The certificate association data, e.g. the SHA-256 hash of a certificate
+    pub data: Vec<u8>,
+}
+
+impl FromStr for TLSA {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self> {
         let mut columns = input.split_whitespace();
 
         let [
-            Some(zone),
+            Some(fqdn),
             Some(ttl),
             Some(class),
             Some(record_type),
-            Some(flags),
-            Some(tag),
-            Some(value),
-            None,
+            Some(usage),
+            Some(selector),
+            Some(matching_type),
         ] = array::from_fn(|_| columns.next())
         else {
-            return Err("expected 7 columns".into());
+            return Err("expected at least 7 columns".into());
         };
 
         check_record_type::<Self>(record_type)?;
         check_class(class)?;
 
-        let value = if value == "\"\"" {
-            "".to_string()
-        } else {
-            value.to_string()
-        };
+        // long hex strings are split into several columns
+        let data = hex::decode(columns.collect::<String>())?;
+        if data.is_empty() {
+            return Err("TLSA record has no certificate association data".into());
+        }
 
         Ok(Self {
-            zone: zone.parse()?,
+            fqdn: fqdn.parse()?,
             ttl: ttl.parse()?,
-            flags: flags.parse()?,
-            tag: tag.to_string(),
-            value,
+            usage: usage.parse()?,
+            selector: selector.parse()?,
+            matching_type: matching_type.parse()?,
+            data,
+        })
+    }
+}
+
+impl fmt::Display for TLSA {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            fqdn,
+            ttl,
+            usage,
+            selector,
+            matching_type,
+            data,
+        } = self;
+
+        let record_type = unqualified_type_name::<Self>();
+        write!(
+            f,
+            "{fqdn}\t{ttl}\t{CLASS}\t{record_type}\t{usage} {selector} {matching_type}"
+        )?;
+
+        write_split_long_string(f, &hex::encode_upper(data))
+    }
+}
+
+/// The character strings of the RDATA are stored unescaped, e.g. a `\\1` in the text
+/// representation of `regexp` is a single backslash followed by `1`
+#[derive(Debug, Clone, PartialEq)]
+pub struct NAPTR {
+    pub fqdn: FQDN,
+    pub ttl: u32,
+    pub order: u16,
+    pub preference: u16,
+    pub flags: String,
+    pub services: String,
+    pub regexp: String,
+    /// The root name when `regexp` is used instead
+    pub replacement: FQDN,
+}
+
+impl FromStr for NAPTR {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        let mut rest = input.trim();
+        let [fqdn, ttl, class, record_type, order, preference] = array::from_fn(|_| {
+            let (column, tail) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            rest = tail.trim_start();
+            column
+        });
+
+        check_record_type::<Self>(record_type)?;
+        check_class(class)?;
+
+        let Ok([flags, services, regexp, replacement]) =
+            <[String; 4]>::try_from(parse_character_strings(rest)?)
+        else {
+            return Err("expected 10 columns".into());
+        };
+
+        Ok(Self {
+            fqdn: fqdn.parse()?,
+            ttl: ttl.parse()?,
+            order: order.parse()?,
+            preference: preference.parse()?,
+            flags,
+            services,
+            regexp,
+            replacement: replacement.parse()?,
+        })
+    }
+}
+
+impl fmt::Display for NAPTR {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            fqdn,
+            ttl,
+            order,
+            preference,
+            flags,
+            services,
+            regexp,
+            replacement,
+        } = self;
+
+        let record_type = unqualified_type_name::<Self>();
+        write!(
+            f,
+            "{fqdn}\t{ttl}\t{CLASS}\t{record_type}\t{order} {preference} {} {} {} {replacement}",
+            quote_character_string(flags),
+            quote_character_string(services),
+            quote_character_string(regexp),
+        )
+    }
+}
+
+/// Splits `input` into whitespace-separated, possibly quoted, character strings and resolves the
+/// `\X` and `\DDD` escapes of the master file format (RFC1035 section 5.1)
+fn parse_character_strings(input: &str) -> Result<Vec<String>> {
+    let mut strings = vec![];
+    let mut chars = input.chars().peekable();
+    loop {
+        while chars.next_if(char::is_ascii_whitespace).is_some() {}
+        let Some(first) = chars.peek().copied() else {
+            break;
+        };
+
+        let quoted = first == '"';
+        if quoted {
+            chars.next();
+        }
+
+        let mut string = String::new();
+        loop {
+            match chars.next() {
+                None if quoted => return Err("quoted string was not closed".into()),
+                None => break,
+                Some('"') if quoted => break,
+                Some(c) if !quoted && c.is_ascii_whitespace() => break,
+                Some('\\') => {
+                    let escaped = chars.next().ok_or("incomplete escape sequence")?;
+                    if escaped.is_ascii_digit() {
+                        let digits = [Some(escaped), chars.next(), chars.next()]
+                            .into_iter()
+                            .collect::<Option<String>>()
+                            .ok_or("incomplete escape sequence")?;
+                        let byte: u8 = digits.parse()?;
+                        if !byte.is_ascii() {
+                            return Err("non-ASCII characters are not supported".into());
+                        }
+                        string.push(char::from(byte));
+                    } else {
+                        string.push(escaped);
+                    }
+                }
+                Some(c) => string.push(c),
+            }
+        }
+        strings.push(string);
+    }
+
+    Ok(strings)
+}
+
+/// The inverse of [`parse_character_strings`], for a single string
+fn quote_character_string(string: &str) -> String {
+    let mut quoted = String::from('"');
+    for c in string.chars() {
+        match c {
+            '"' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            ' '..='~' => quoted.push(c),
+            _ => {
+                for byte in c.to_string().bytes() {
+                    quoted.push_str(&format!("\\{byte:03}"));
+                }
+            }
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MX {
+    pub fqdn: FQDN,
+    pub ttl: u32,
+    pub preference: u16,
+    pub exchange: FQDN,
+}
+
+impl FromStr for MX {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        let mut columns = input.split_whitespace();
+
+        let [
+            Some(fqdn),
+            Some(ttl),
+            Some(class),
+            Some(record_type),
+            Some(preference),
+            Some(exchange),
+            None,
+        ] = array::from_fn(|_| columns.next())
+        else {
+            return Err("expected 6 columns".into());
+        };
+
+        check_record_type::<Self>(record_type)?;
+        check_class(class)?;
+
+        Ok(Self {
+            fqdn: fqdn.parse()?,
+            ttl: ttl.parse()?,
+            preference: preference.parse()?,
+            exchange: exchange.parse()?,
+        })
+    }
+}
+
+impl fmt::Display for MX {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            fqdn,
+            ttl,
+            preference,
+            exchange,
+        } = self;
+
+        let record_type = unqualified_type_name::<Self>();
+        write!(
+            f,
+            "{fqdn}\t{ttl}\t{CLASS}\t{record_type}\t{preference} {exchange}"
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SRV {
+    pub fqdn: FQDN,
+    pub ttl: u32,
+    pub priority: u16,
+    /// Relative weight among the records that share the same `priority`
+    pub weight: u16,
+    pub port: u16,
+    pub target: FQDN,
+}
+
+impl FromStr for SRV {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        let mut columns = input.split_whitespace();
+
+        let [
+            Some(fqdn),
+            Some(ttl),
+            Some(class),
+            Some(record_type),
+            Some(priority),
+            Some(weight),
+            Some(port),
+            Some(target),
+            None,
+        ] = array::from_fn(|_| columns.next())
+        else {
+            return Err("expected 8 columns".into());
+        };
+
+        check_record_type::<Self>(record_type)?;
+        check_class(class)?;
+
+        Ok(Self {
+            fqdn: fqdn.parse()?,
+            ttl: ttl.parse()?,
+            priority: priority.parse()?,
+            weight: weight.parse()?,
+            port: port.parse()?,
+            target: target.parse()?,
+        })
+    }
+}
+
+impl fmt::Display for SRV {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            fqdn,
+            ttl,
+            priority,
+            weight,
+            port,
+            target,
+        } = self;
+
+        let record_type = unqualified_type_name::<Self>();
+        write!(
+            f,
+            "{fqdn}\t{ttl}\t{CLASS}\t{record_type}\t{priority} {weight} {port} {target}"
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SSHFP {
+    pub fqdn: FQDN,
+    pub ttl: u32,
+    /// The algorithm of the host key, e.g. 4 for Ed25519
+    pub algorithm: u8,
+    /// The hash function used for the fingerprint, e.g. 2 for SHA-256
+    pub fp_type: u8,
+    pub fingerprint: Vec<u8>,
+}
+
+impl FromStr for SSHFP {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        let mut columns = input.split_whitespace();
+
+        let [
+            Some(fqdn),
+            Some(ttl),
+            Some(class),
+            Some(record_type),
+            Some(algorithm),
+            Some(fp_type),
+        ] = array::from_fn(|_| columns.next())
+        else {
+            return Err("expected at least 7 columns".into());
+        };
+
+        check_record_type::<Self>(record_type)?;
+        check_class(class)?;
+
+        // long hex strings are split into several columns
+        let fingerprint = hex::decode(columns.collect::<String>())?;
+        if fingerprint.is_empty() {
+            return Err("SSHFP record has no fingerprint".into());
+        }
+
+        Ok(Self {
+            fqdn: fqdn.parse()?,
+            ttl: ttl.parse()?,
+            algorithm: algorithm.parse()?,
+            fp_type: fp_type.parse()?,
+            fingerprint,
+        })
+    }
+}
+
+impl fmt::Display for SSHFP {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            fqdn,
+            ttl,
+            algorithm,
+            fp_type,
+            fingerprint,
+        } = self;
+
+        let record_type = unqualified_type_name::<Self>();
+        write!(
+            f,
+            "{fqdn}\t{ttl}\t{CLASS}\t{record_type}\t{algorithm} {fp_type}"
+        )?;
+
+        write_split_long_string(f, &hex::encode_upper(fingerprint))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SVCB {
+    pub fqdn: FQDN,
+    pub ttl: u32,
+    pub rdata: SvcbRData,
+}
+
+/// An SVCB record that's specific to the HTTPS protocol (RFC9460 section 9)
+#[derive(Debug, Clone, PartialEq)]
+pub struct HTTPS {
+    pub fqdn: FQDN,
+    pub ttl: u32,
+    pub rdata: SvcbRData,
+}
+
+/// The RDATA shared by the SVCB and HTTPS record types
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvcbRData {
+    /// 0 means AliasMode; any other value ServiceMode
+    pub priority: u16,
+    pub target: FQDN,
+    /// In the order they appear in the record
+    pub params: Vec<SvcParam>,
+}
+
+impl SvcbRData {
+    pub fn is_alias(&self) -> bool {
+        self.priority == 0
+    }
+
+    /// Parses the columns that follow the record type
+    fn parse<'a>(mut columns: impl Iterator<Item = &'a str>) -> Result<Self> {
+        let [Some(priority), Some(target)] = array::from_fn(|_| columns.next()) else {
+            return Err("expected a priority and a target name".into());
+        };
+
+        Ok(Self {
+            priority: priority.parse()?,
+            target: target.parse()?,
+            params: columns.map(str::parse).collect::<Result<_>>()?,
         })
     }
 }
 
-impl fmt::Display for CAA {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let Self {
-            zone,
-            ttl,
-            flags,
-            tag,
-            value,
-        } = self;
-
-        let record_type = unqualified_type_name::<Self>();
-        write!(f, "{zone}\t{ttl}\t{CLASS}\t{record_type}\t{flags} {tag} ")?;
-        if value.is_empty() {
-            write!(f, "\"\"")?;
-        } else {
-            f.write_str(value)?;
+impl fmt::Display for SvcbRData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            priority,
+            target,
+            params,
+        } = self;
+
+        write!(f, "{priority} {target}")?;
+        for param in params {
+            write!(f, " {param}")?;
+        }
+
+        Ok(())
+    }
+}
+
+macro_rules! svcb_like {
+    ($($record:ident),*) => {
+        $(
+            impl FromStr for $record {
+                type Err = Error;
+
+                fn from_str(input: &str) -> Result<Self> {
+                    let mut columns = input.split_whitespace();
+
+                    let [Some(fqdn), Some(ttl), Some(class), Some(record_type)] =
+                        array::from_fn(|_| columns.next())
+                    else {
+                        return Err("expected at least 6 columns".into());
+                    };
+
+                    check_record_type::<Self>(record_type)?;
+                    check_class(class)?;
+
+                    Ok(Self {
+                        fqdn: fqdn.parse()?,
+                        ttl: ttl.parse()?,
+                        rdata: SvcbRData::parse(columns)?,
+                    })
+                }
+            }
+
+            impl fmt::Display for $record {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    let Self { fqdn, ttl, rdata } = self;
+
+                    let record_type = unqualified_type_name::<Self>();
+                    write!(f, "{fqdn}\t{ttl}\t{CLASS}\t{record_type}\t{rdata}")
+                }
+            }
+        )*
+    };
+}
+
+svcb_like!(SVCB, HTTPS);
+
+/// A key/value pair of an SVCB or HTTPS record (RFC9460 section 14.3.2)
+#[derive(Debug, Clone, PartialEq)]
+pub enum SvcParam {
+    /// The keys that clients must understand to use the record
+    Mandatory(Vec<String>),
+    /// Application-Layer Protocol Negotiation identifiers, e.g. `h2`
+    Alpn(Vec<String>),
+    Port(u16),
+    Ipv4Hint(Vec<Ipv4Addr>),
+    Ipv6Hint(Vec<Ipv6Addr>),
+    /// Any other key, e.g. `no-default-alpn`, with its value in text representation
+    Other {
+        key: String,
+        value: Option<String>,
+    },
+}
+
+impl SvcParam {
+    /// The numeric value of the key, as registered with IANA
+    pub fn key_code(key: &str) -> Option<u16> {
+        let code = match key {
+            "mandatory" => 0,
+            "alpn" => 1,
+            "no-default-alpn" => 2,
+            "port" => 3,
+            "ipv4hint" => 4,
+            "ech" => 5,
+            "ipv6hint" => 6,
+            _ => return key.strip_prefix("key")?.parse().ok(),
+        };
+        Some(code)
+    }
+
+    pub fn key(&self) -> &str {
+        match self {
+            Self::Mandatory(_) => "mandatory",
+            Self::Alpn(_) => "alpn",
+            Self::Port(_) => "port",
+            Self::Ipv4Hint(_) => "ipv4hint",
+            Self::Ipv6Hint(_) => "ipv6hint",
+            Self::Other { key, .. } => key,
+        }
+    }
+}
+
+impl FromStr for SvcParam {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        let Some((key, value)) = input.split_once('=') else {
+            return Ok(Self::Other {
+                key: input.to_string(),
+                value: None,
+            });
+        };
+
+        let value = value
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+            .unwrap_or(value);
+        let list = || value.split(',').map(str::to_string).collect();
+
+        let param = match key {
+            "mandatory" => Self::Mandatory(list()),
+            "alpn" => Self::Alpn(list()),
+            "port" => Self::Port(value.parse()?),
+            "ipv4hint" => Self::Ipv4Hint(
+                value
+                    .split(',')
+                    .map(str::parse)
+                    .collect::<CoreResult<_, _>>()?,
+            ),
+            "ipv6hint" => Self::Ipv6Hint(
+                value
+                    .split(',')
+                    .map(str::parse)
+                    .collect::<CoreResult<_, _>>()?,
+            ),
+            _ => Self::Other {
+                key: key.to_string(),
+                value: Some(value.to_string()),
+            },
+        };
+
+        Ok(param)
+    }
+}
+
+impl fmt::Display for SvcParam {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let key = self.key();
+        match self {
+            Self::Mandatory(keys) => write!(f, "{key}={}", keys.join(",")),
+            Self::Alpn(ids) => write!(f, "{key}=\"{}\"", ids.join(",")),
+            Self::Port(port) => write!(f, "{key}={port}"),
+            Self::Ipv4Hint(addrs) => write!(f, "{key}={}", join_addrs(addrs)),
+            Self::Ipv6Hint(addrs) => write!(f, "{key}={}", join_addrs(addrs)),
+            Self::Other { value: None, .. } => f.write_str(key),
+            Self::Other {
+                value: Some(value), ..
+            } => write!(f, "{key}=\"{value}\""),
         }
-
-        Ok(())
     }
 }
 
+fn join_addrs(addrs: &[impl fmt::Display]) -> String {
+    addrs
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
 /// A record of unknown type.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct UnknownRdata {
     pub zone: FQDN,
     pub ttl: u32,
@@ -1289,12 +2974,16 @@ impl FromStr for UnknownRdata {
         };
 
         check_class(class)?;
-        let Some(type_number) = record_type.strip_prefix("TYPE") else {
-            return Err(
-                "tried to parse `{record_type}` record as a generic unknown type record".into(),
-            );
+        let r#type = if let Some(type_number) = record_type.strip_prefix("TYPE") {
+            type_number.parse()?
+        } else if let Some((_, code)) = GENERIC_RECORD_TYPES
+            .iter()
+            .find(|(name, _)| *name == record_type)
+        {
+            *code
+        } else {
+            record_type.parse::<RecordType>()?.code()
         };
-        let r#type = type_number.parse()?;
 
         if generic_encoding_token != "\\#" {
             return Err("tried to parse a record of unknown type but \\# was not present".into());
@@ -1336,8 +3025,117 @@ impl fmt::Display for UnknownRdata {
     }
 }
 
+impl UnknownRdata {
+    /// `mnemonic` must be one of `GENERIC_RECORD_TYPES`
+    fn generic(zone: FQDN, mnemonic: &str, rdata: Vec<u8>) -> Self {
+        let (_, r#type) = GENERIC_RECORD_TYPES
+            .iter()
+            .find(|(name, _)| *name == mnemonic)
+            .unwrap();
+
+        Self {
+            zone,
+            ttl: DEFAULT_TTL,
+            r#type: *r#type,
+            rdata,
+        }
+    }
+}
+
+/// The RDATA of a LOC record (RFC1876)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Loc {
+    /// In thousandths of an arc second; positive values are north of the equator
+    pub latitude: i32,
+    /// In thousandths of an arc second; positive values are east of the prime meridian
+    pub longitude: i32,
+    /// In centimeters, relative to the WGS 84 reference spheroid
+    pub altitude: i32,
+    /// Diameter, in centimeters, of a sphere enclosing the described entity
+    pub size: u64,
+    /// In centimeters
+    pub horizontal_precision: u64,
+    /// In centimeters
+    pub vertical_precision: u64,
+}
+
+impl Loc {
+    /// A location with the default size (1m) and precisions (10km horizontal, 10m vertical) of
+    /// the LOC presentation format
+    pub fn new(latitude: i32, longitude: i32, altitude: i32) -> Self {
+        Self {
+            latitude,
+            longitude,
+            altitude,
+            size: 100,
+            horizontal_precision: 1_000_000,
+            vertical_precision: 1_000,
+        }
+    }
+
+    /// Sizes and precisions are rounded up to the nearest value that LOC can represent
+    fn rdata(&self) -> Vec<u8> {
+        const VERSION: u8 = 0;
+        const EQUATOR: u32 = 1 << 31;
+        // the altitude is encoded relative to a base 100,000m below the reference spheroid
+        const ALTITUDE_BASE: i64 = 10_000_000;
+
+        let mut rdata = vec![
+            VERSION,
+            encode_loc_centimeters(self.size),
+            encode_loc_centimeters(self.horizontal_precision),
+            encode_loc_centimeters(self.vertical_precision),
+        ];
+        rdata.extend(EQUATOR.wrapping_add_signed(self.latitude).to_be_bytes());
+        rdata.extend(EQUATOR.wrapping_add_signed(self.longitude).to_be_bytes());
+        rdata.extend(((ALTITUDE_BASE + i64::from(self.altitude)) as u32).to_be_bytes());
+        rdata
+    }
+}
+
+/// Encodes `centimeters` as a mantissa in the high nibble and a power of ten in the low nibble
+fn encode_loc_centimeters(centimeters: u64) -> u8 {
+    let mut mantissa = centimeters;
+    let mut exponent = 0;
+    while mantissa > 9 && exponent < 9 {
+        mantissa = mantissa.div_ceil(10);
+        exponent += 1;
+    }
+    (mantissa.min(9) as u8) << 4 | exponent
+}
+
+/// An item of an APL record (RFC3123)
+#[derive(Debug, Clone, PartialEq)]
+pub struct AplItem {
+    /// Whether the prefix is excluded from the list
+    pub negation: bool,
+    pub address: IpAddr,
+    pub prefix: u8,
+}
+
+impl AplItem {
+    fn rdata(&self) -> Vec<u8> {
+        let (family, octets): (u16, Vec<u8>) = match self.address {
+            IpAddr::V4(address) => (1, address.octets().to_vec()),
+            IpAddr::V6(address) => (2, address.octets().to_vec()),
+        };
+
+        // trailing zero octets are not transmitted
+        let len = octets
+            .iter()
+            .rposition(|octet| *octet != 0)
+            .map_or(0, |index| index + 1);
+
+        let mut rdata = family.to_be_bytes().to_vec();
+        rdata.push(self.prefix);
+        rdata.push(u8::from(self.negation) << 7 | len as u8);
+        rdata.extend_from_slice(&octets[..len]);
+        rdata
+    }
+}
+
 fn check_class(class: &str) -> Result<()> {
-    if class != "IN" {
+    if class != CLASS {
         return Err(format!("unknown class: {class}").into());
     }
 
@@ -1399,6 +3197,30 @@ mod tests {
         Ok(())
     }
 
+    // dig AAAA a.root-servers.net
+    const AAAA_INPUT: &str = "a.root-servers.net.	86400	IN	AAAA	2001:503:ba3e::2:30";
+
+    #[test]
+    fn aaaa() -> Result<()> {
+        let aaaa @ AAAA {
+            fqdn,
+            ttl,
+            ipv6_addr,
+        } = &AAAA_INPUT.parse()?;
+
+        assert_eq!("a.root-servers.net.", fqdn.as_str());
+        assert_eq!(86400, *ttl);
+        assert_eq!(
+            Ipv6Addr::new(0x2001, 0x503, 0xba3e, 0, 0, 0, 2, 0x30),
+            *ipv6_addr
+        );
+
+        let output = aaaa.to_string();
+        assert_eq!(AAAA_INPUT, output);
+
+        Ok(())
+    }
+
     // dig CNAME www.isc.org
     const CNAME_INPUT: &str = "www.isc.org.	277	IN	CNAME	isc.map.fastlydns.net.";
 
@@ -1416,6 +3238,22 @@ mod tests {
         Ok(())
     }
 
+    const DNAME_INPUT: &str = "example.testing.\t3600\tIN\tDNAME\texample.com.";
+
+    #[test]
+    fn dname() -> Result<()> {
+        let dname @ DNAME { fqdn, ttl, target } = &DNAME_INPUT.parse()?;
+
+        assert_eq!("example.testing.", fqdn.as_str());
+        assert_eq!(3600, *ttl);
+        assert_eq!("example.com.", target.as_str());
+
+        let output = dname.to_string();
+        assert_eq!(DNAME_INPUT, output);
+
+        Ok(())
+    }
+
     // dig DNSKEY .
     const DNSKEY_INPUT: &str = ".	1116	IN	DNSKEY	257 3 8 AwEAAaz/tAm8yTn4Mfeh5eyI96WSVexTBAvkMgJzkKTOiW1vkIbzxeF3 +/4RgWOq7HrxRixHlFlExOLAJr5emLvN7SWXgnLh4+B5xQlNVz8Og8kv ArMtNROxVQuCaSnIDdD5LKyWbRd2n9WGe2R8PzgCmr3EgVLrjyBxWezF 0jLHwVN8efS3rCj/EWgvIWgb9tarpVUDK/b58Da+sqqls3eNbuv7pr+e oZG+SrDK6nWeL3c6H5Apxz7LjVc1uTIdsIXxuOLYA4/ilBmSVIzuDWfd RUfhHdY6+cn8HFRm+2hM8AnXGXws9555KrUB5qihylGa8subX2Nn6UwN R1AkUTV74bU=";
 
@@ -1534,6 +3372,44 @@ mod tests {
         let output = ds.to_string();
         assert_eq!(DS_INPUT, output);
 
+        assert_eq!(
+            format!("com. static-ds 19718 13 2 \"{expected}\";\n"),
+            ds.delv()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn cds() -> Result<()> {
+        let ds: DS = DS_INPUT.parse()?;
+        let cds = CDS::from(ds.clone());
+        assert!(cds.matches(&ds));
+        assert!(!cds.is_delete());
+
+        let output = cds.to_string();
+        assert_eq!(DS_INPUT.replace("\tDS\t", "\tCDS\t"), output);
+        assert_eq!(cds, output.parse()?);
+
+        let delete = CDS::delete(FQDN::TEST_DOMAIN);
+        assert!(delete.is_delete());
+        let output = delete.to_string();
+        assert!(output.ends_with("\tCDS\t0 0 0 00"), "{output}");
+        assert_eq!(delete, output.parse()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn cdnskey() -> Result<()> {
+        let cdnskey = CDNSKEY::delete(FQDN::TEST_DOMAIN);
+        assert!(cdnskey.is_delete());
+
+        let output = cdnskey.to_string();
+        assert!(output.ends_with("\tCDNSKEY\t0 3 0 AA=="), "{output}");
+        assert_eq!(cdnskey, output.parse()?);
+        assert!(matches!(output.parse()?, Record::CDNSKEY(..)));
+
         Ok(())
     }
 
@@ -1613,6 +3489,7 @@ mod tests {
         assert_eq!(3571, *ttl);
         assert_eq!(1, *hash_alg);
         assert_eq!(0, *flags);
+        assert!(!nsec3.is_opt_out());
         assert_eq!(5, *iterations);
         assert_eq!("53BCBC5805D2B761", salt);
         assert_eq!("GVPMD82B8ER38VUEGP72I721LIH19RGR", next_hashed_owner_name);
@@ -1637,6 +3514,16 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn nsec3_opt_out() -> Result<()> {
+        let nsec3: NSEC3 = "ck0pojmg874ljref7efn8430qvit8bsm.com. 86400 IN NSEC3 1 1 0 - CK0Q2D6NI4I7EQH8NA30NS61O48UL8G5 NS SOA RRSIG DNSKEY NSEC3PARAM".parse()?;
+
+        assert_eq!(1, nsec3.flags);
+        assert!(nsec3.is_opt_out());
+
+        Ok(())
+    }
+
     // dig NSEC3PARAM com.
     const NSEC3PARAM_INPUT: &str = "com.	86238	IN	NSEC3PARAM	1 0 0 -";
 
@@ -1662,6 +3549,23 @@ mod tests {
         Ok(())
     }
 
+    // dig -x 1.1.1.1
+    const PTR_INPUT: &str = "1.1.1.1.in-addr.arpa.	1800	IN	PTR	one.one.one.one.";
+
+    #[test]
+    fn ptr() -> Result<()> {
+        let ptr @ PTR { fqdn, ttl, target } = &PTR_INPUT.parse()?;
+
+        assert_eq!("1.1.1.1.in-addr.arpa.", fqdn.as_str());
+        assert_eq!(1800, *ttl);
+        assert_eq!("one.one.one.one.", target.as_str());
+
+        let output = ptr.to_string();
+        assert_eq!(PTR_INPUT, output);
+
+        Ok(())
+    }
+
     // dig +dnssec SOA .
     const RRSIG_INPUT: &str = ".	1800	IN	RRSIG	SOA 7 0 1800 20240306132701 20240207132701 11264 . wXpRU4elJPGYm2kgVVsIwGf1IkYJcQ3UE4mwmItWdxj0XWSWY07MO4Ll DMJgsE0u64Q/345Ck7+aQ904uLebwCvpFnsmkyCxk82XIAfHN9FiwzSy qoR/zZEvBONaej3vrvsqPwh8q/pvypLft9647HcFdwY0juzZsbrAaDAX 8WY=";
 
@@ -1744,6 +3648,76 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn txt_character_strings() -> Result<()> {
+        let long = "x".repeat(255);
+        let record = Record::txt_character_strings(FQDN::TEST_DOMAIN, &[&long, ""]);
+        let Record::TXT(txt) = &record else {
+            panic!("expected a TXT record")
+        };
+
+        assert_eq!(vec![long.clone(), String::new()], txt.character_strings);
+
+        let output = record.to_string();
+        assert!(output.ends_with(&format!("\"{long}\" \"\"")), "{output}");
+        assert_eq!(record, output.parse()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn chaos_class_txt() -> Result<()> {
+        let input = "version.bind.\t0\tCH\tTXT\t\"9.18.28\"";
+        let record: Record = input.parse()?;
+
+        let txt = record.clone().try_into_txt().unwrap();
+        assert_eq!("version.bind.", txt.zone.as_str());
+        assert_eq!(Class::CH, txt.class);
+        assert_eq!(vec!["9.18.28".to_owned()], txt.character_strings);
+
+        assert_eq!(input, record.to_string());
+
+        // only TXT records can be in the CHAOS class
+        assert!(
+            "version.bind.\t0\tCH\tA\t1.2.3.4"
+                .parse::<Record>()
+                .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic = "longer than 255 bytes"]
+    fn txt_character_string_too_long() {
+        Record::txt_character_strings(FQDN::TEST_DOMAIN, &[&"x".repeat(256)]);
+    }
+
+    #[test]
+    fn dkim_splits_long_values() -> Result<()> {
+        let public_key = "A".repeat(400);
+        let record = Record::dkim("mail", &FQDN::TEST_DOMAIN, &public_key);
+        let Record::TXT(txt) = record else {
+            panic!("expected a TXT record")
+        };
+
+        assert_eq!("mail._domainkey.hickory-dns.testing.", txt.zone.as_str());
+        assert_eq!(
+            vec![255, "v=DKIM1; k=rsa; p=".len() + 400 - 255],
+            txt.character_strings
+                .iter()
+                .map(String::len)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(format!("v=DKIM1; k=rsa; p={public_key}"), txt.value());
+
+        // the split record survives a round trip through the zone file format
+        let reparsed: TXT = txt.to_string().parse()?;
+        assert_eq!(txt, reparsed);
+
+        Ok(())
+    }
+
     const CAA_INPUT: &str = "certs.example.com.	86400	IN	CAA	0 issue ca1.example.net";
 
     #[test]
@@ -1768,6 +3742,251 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn caa_quoted_value() -> Result<()> {
+        // as printed by `dig`
+        let caa: CAA = "example.com.\t86400\tIN\tCAA\t0 issue \"letsencrypt.org\"".parse()?;
+        assert_eq!("letsencrypt.org", caa.value);
+
+        let caa: CAA =
+            "example.com.\t86400\tIN\tCAA\t128 iodef \"mailto:security@example.com\"".parse()?;
+        assert_eq!(128, caa.flags);
+        assert_eq!("iodef", caa.tag);
+        assert_eq!("mailto:security@example.com", caa.value);
+
+        let caa: CAA =
+            "example.com.\t86400\tIN\tCAA\t0 issue \"ca.example.net; account=123\"".parse()?;
+        assert_eq!("ca.example.net; account=123", caa.value);
+        assert_eq!(caa, caa.to_string().parse()?);
+
+        assert!(
+            "example.com.\t86400\tIN\tCAA\t0 issue \"letsencrypt.org"
+                .parse::<CAA>()
+                .is_err()
+        );
+
+        Ok(())
+    }
+
+    // `dig` splits the certificate association data in 56-character chunks
+    const TLSA_INPUT: &str = "_443._tcp.www.example.com.	3600	IN	TLSA	3 1 1 0C72AC70B745AC19998811B131D662C9AC69DBDBE7CB23E5B514B566 64C5D3D6";
+
+    #[test]
+    fn tlsa() -> Result<()> {
+        let tlsa @ TLSA {
+            fqdn,
+            ttl,
+            usage,
+            selector,
+            matching_type,
+            data,
+        } = &TLSA_INPUT.parse()?;
+
+        assert_eq!("_443._tcp.www.example.com.", fqdn.as_str());
+        assert_eq!(3600, *ttl);
+        assert_eq!(3, *usage);
+        assert_eq!(1, *selector);
+        assert_eq!(1, *matching_type);
+        assert_eq!(
+            "0C72AC70B745AC19998811B131D662C9AC69DBDBE7CB23E5B514B56664C5D3D6",
+            hex::encode_upper(data)
+        );
+
+        let output = tlsa.to_string();
+        assert_eq!(*tlsa, output.parse()?);
+        assert!(matches!(output.parse()?, Record::TLSA(..)));
+
+        Ok(())
+    }
+
+    // as printed by `dig`
+    const NAPTR_INPUT: &str = r#"4.3.2.1.5.5.5.0.0.8.1.e164.arpa.	3600	IN	NAPTR	100 10 "u" "E2U+sip" "!^\\+1800555(.*)$!sip:\\1@example.com!" ."#;
+
+    #[test]
+    fn naptr() -> Result<()> {
+        let naptr @ NAPTR {
+            fqdn,
+            ttl,
+            order,
+            preference,
+            flags,
+            services,
+            regexp,
+            replacement,
+        } = &NAPTR_INPUT.parse()?;
+
+        assert_eq!("4.3.2.1.5.5.5.0.0.8.1.e164.arpa.", fqdn.as_str());
+        assert_eq!(3600, *ttl);
+        assert_eq!(100, *order);
+        assert_eq!(10, *preference);
+        assert_eq!("u", flags);
+        assert_eq!("E2U+sip", services);
+        assert_eq!(r"!^\+1800555(.*)$!sip:\1@example.com!", regexp);
+        assert_eq!(FQDN::ROOT, *replacement);
+
+        let output = naptr.to_string();
+        assert_eq!(NAPTR_INPUT, output);
+
+        Ok(())
+    }
+
+    #[test]
+    fn naptr_escapes() -> Result<()> {
+        let input =
+            r#"example.com.	3600	IN	NAPTR	10 20 "" "" "!\"quoted\" \\ \009tab!!" sip.example.com."#;
+        let naptr: NAPTR = input.parse()?;
+
+        assert_eq!("", naptr.flags);
+        assert_eq!("", naptr.services);
+        assert_eq!("!\"quoted\" \\ \ttab!!", naptr.regexp);
+        assert_eq!("sip.example.com.", naptr.replacement.as_str());
+        assert_eq!(naptr, naptr.to_string().parse()?);
+
+        let unterminated = r#"example.com.	3600	IN	NAPTR	10 20 "u" "E2U+sip" "!^.*$!sip:x! ."#;
+        assert!(unterminated.parse::<NAPTR>().is_err());
+
+        Ok(())
+    }
+
+    // dig MX google.com.
+    const MX_INPUT: &str = "google.com.\t300\tIN\tMX\t10 smtp.google.com.";
+
+    #[test]
+    fn mx() -> Result<()> {
+        let mx @ MX {
+            fqdn,
+            ttl,
+            preference,
+            exchange,
+        } = &MX_INPUT.parse()?;
+
+        assert_eq!("google.com.", fqdn.as_str());
+        assert_eq!(300, *ttl);
+        assert_eq!(10, *preference);
+        assert_eq!("smtp.google.com.", exchange.as_str());
+
+        let output = mx.to_string();
+        assert_eq!(MX_INPUT, output);
+
+        Ok(())
+    }
+
+    // dig SRV _sip._tcp.sip.voice.google.com.
+    const SRV_INPUT: &str =
+        "_sip._tcp.sip.voice.google.com.\t300\tIN\tSRV\t20 1 5060 sip-anycast-2.voice.google.com.";
+
+    #[test]
+    fn srv() -> Result<()> {
+        let srv @ SRV {
+            fqdn,
+            ttl,
+            priority,
+            weight,
+            port,
+            target,
+        } = &SRV_INPUT.parse()?;
+
+        assert_eq!("_sip._tcp.sip.voice.google.com.", fqdn.as_str());
+        assert_eq!(300, *ttl);
+        assert_eq!(20, *priority);
+        assert_eq!(1, *weight);
+        assert_eq!(5060, *port);
+        assert_eq!("sip-anycast-2.voice.google.com.", target.as_str());
+
+        let output = srv.to_string();
+        assert_eq!(SRV_INPUT, output);
+
+        Ok(())
+    }
+
+    const SSHFP_INPUT: &str = "host.example.com.	3600	IN	SSHFP	4 2 3A5E8F0C4D2B1A7E6F9C8B0D2E4F6A8C1B3D5E7F9A0C2E4B6D8F1A3C5E7B9D0F";
+
+    #[test]
+    fn sshfp() -> Result<()> {
+        let sshfp @ SSHFP {
+            fqdn,
+            ttl,
+            algorithm,
+            fp_type,
+            fingerprint,
+        } = &SSHFP_INPUT.parse()?;
+
+        assert_eq!("host.example.com.", fqdn.as_str());
+        assert_eq!(3600, *ttl);
+        assert_eq!(4, *algorithm);
+        assert_eq!(2, *fp_type);
+        assert_eq!(32, fingerprint.len());
+
+        let output = sshfp.to_string();
+        assert_eq!(*sshfp, output.parse()?);
+        assert!(matches!(output.parse()?, Record::SSHFP(..)));
+
+        Ok(())
+    }
+
+    const HTTPS_INPUT: &str = "example.com.	300	IN	HTTPS	1 . alpn=\"h2,h3\" port=8443 ipv4hint=192.0.2.1,192.0.2.2 ipv6hint=2001:db8::1";
+
+    #[test]
+    fn https() -> Result<()> {
+        let https @ HTTPS { fqdn, ttl, rdata } = &HTTPS_INPUT.parse()?;
+
+        assert_eq!("example.com.", fqdn.as_str());
+        assert_eq!(300, *ttl);
+        assert_eq!(1, rdata.priority);
+        assert!(!rdata.is_alias());
+        assert_eq!(FQDN::ROOT, rdata.target);
+        assert_eq!(
+            [
+                SvcParam::Alpn(vec!["h2".to_string(), "h3".to_string()]),
+                SvcParam::Port(8443),
+                SvcParam::Ipv4Hint(vec![
+                    Ipv4Addr::new(192, 0, 2, 1),
+                    Ipv4Addr::new(192, 0, 2, 2)
+                ]),
+                SvcParam::Ipv6Hint(vec!["2001:db8::1".parse()?]),
+            ],
+            rdata.params.as_slice()
+        );
+
+        let output = https.to_string();
+        assert_eq!(HTTPS_INPUT, output);
+
+        Ok(())
+    }
+
+    #[test]
+    fn svcb_alias_mode() -> Result<()> {
+        let input = "_dns.example.com.\t300\tIN\tSVCB\t0 svc.example.net.";
+        let svcb: SVCB = input.parse()?;
+
+        assert!(svcb.rdata.is_alias());
+        assert_eq!("svc.example.net.", svcb.rdata.target.as_str());
+        assert!(svcb.rdata.params.is_empty());
+        assert_eq!(input, svcb.to_string());
+
+        Ok(())
+    }
+
+    #[test]
+    fn svcb_params_keep_their_order() -> Result<()> {
+        let input = "_dns.example.com.\t300\tIN\tSVCB\t1 dns.example.net. mandatory=alpn,port port=853 alpn=\"dot\" no-default-alpn";
+        let svcb: SVCB = input.parse()?;
+
+        let keys = svcb
+            .rdata
+            .params
+            .iter()
+            .map(SvcParam::key)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ["mandatory", "port", "alpn", "no-default-alpn"],
+            keys.as_slice()
+        );
+        assert_eq!(input, svcb.to_string());
+
+        Ok(())
+    }
+
     #[test]
     fn any() -> Result<()> {
         assert!(matches!(A_INPUT.parse()?, Record::A(..)));
@@ -1792,4 +4011,101 @@ mod tests {
         assert_eq!(RecordType::Unknown(1000).as_name(), "type1000");
         Ok(())
     }
+
+    #[test]
+    fn loc() -> Result<()> {
+        // RFC1876: cambridge-net.kei.com. LOC 42 21 54 N 71 06 18 W -24m 30m
+        let fqdn = FQDN("cambridge-net.kei.com.")?;
+        let loc = Loc {
+            size: 3_000,
+            ..Loc::new(
+                (42 * 3600 + 21 * 60 + 54) * 1000,
+                -(71 * 3600 + 6 * 60 + 18) * 1000,
+                -2_400,
+            )
+        };
+
+        let record = Record::loc(fqdn.clone(), &loc).try_into_unknown().unwrap();
+        assert_eq!(29, record.r#type);
+        assert_eq!(
+            "0033161389172dd070be15f000988d20",
+            hex::encode(&record.rdata)
+        );
+
+        // `dig +unknownformat`
+        let input = "cambridge-net.kei.com.	3600	IN	LOC	\\# 16 0033161389172DD070BE15F000988D20";
+        let parsed = input.parse::<Record>()?.try_into_unknown().unwrap();
+        assert_eq!(record.rdata, parsed.rdata);
+        assert_eq!(29, parsed.r#type);
+        assert_eq!(fqdn, parsed.zone);
+
+        Ok(())
+    }
+
+    #[test]
+    fn loc_precision_is_rounded_up() {
+        assert_eq!(0x00, encode_loc_centimeters(0));
+        assert_eq!(0x90, encode_loc_centimeters(9));
+        assert_eq!(0x11, encode_loc_centimeters(10));
+        assert_eq!(0x21, encode_loc_centimeters(11));
+        assert_eq!(0x99, encode_loc_centimeters(u64::MAX));
+    }
+
+    #[test]
+    fn apl() -> Result<()> {
+        let items = [
+            AplItem {
+                negation: false,
+                address: "192.0.2.0".parse()?,
+                prefix: 24,
+            },
+            AplItem {
+                negation: true,
+                address: "2001::".parse()?,
+                prefix: 32,
+            },
+        ];
+
+        let record = Record::apl(FQDN::TEST_DOMAIN, &items)
+            .try_into_unknown()
+            .unwrap();
+        assert_eq!(42, record.r#type);
+        assert_eq!("00011803c00002000220822001", hex::encode(&record.rdata));
+
+        Ok(())
+    }
+
+    #[test]
+    fn rp_and_afsdb() -> Result<()> {
+        let mbox = FQDN("admin.example.")?;
+        let hostname = FQDN("afs.example.")?;
+
+        let rp = Record::rp(FQDN::TEST_DOMAIN, &mbox, &FQDN::ROOT)
+            .try_into_unknown()
+            .unwrap();
+        assert_eq!(17, rp.r#type);
+        assert_eq!(b"\x05admin\x07example\x00\x00", rp.rdata.as_slice());
+
+        let afsdb = Record::afsdb(FQDN::TEST_DOMAIN, 1, &hostname)
+            .try_into_unknown()
+            .unwrap();
+        assert_eq!(18, afsdb.r#type);
+        assert_eq!(b"\x00\x01\x03afs\x07example\x00", afsdb.rdata.as_slice());
+
+        // round trip through a zone file
+        let parsed = afsdb.to_string().parse::<Record>()?;
+        assert_eq!(Record::Unknown(afsdb), parsed);
+
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_format_of_known_type() -> Result<()> {
+        let input = "a.root-servers.net.	77859	IN	A	\\# 4 C6290004";
+        let record = input.parse::<Record>()?.try_into_unknown().unwrap();
+        assert_eq!(RecordType::A.code(), record.r#type);
+        assert_eq!([198, 41, 0, 4], record.rdata.as_slice());
+
+        Ok(())
+    }
 }
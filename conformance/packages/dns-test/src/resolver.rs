@@ -1,5 +1,8 @@
 use core::fmt::Write;
-use std::net::Ipv4Addr;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::time::Duration;
+
+use ipnet::Ipv6Net;
 
 use crate::container::{Child, Container, Network};
 use crate::implementation::{Config, Role};
@@ -25,6 +28,17 @@ impl Resolver {
             trust_anchor: TrustAnchor::empty(),
             custom_config: None,
             case_randomization: false,
+            prefetch: None,
+            servfail_cache_ttl: None,
+            edns_buffer_size: None,
+            tls: false,
+            https: false,
+            dns64_prefix: None,
+            qname_minimization: None,
+            aggressive_nsec: None,
+            serve_stale: None,
+            upstream_timeout: None,
+            ipv6_only: false,
         }
     }
 
@@ -48,6 +62,19 @@ impl Resolver {
         self.container.ipv4_addr()
     }
 
+    /// Returns `None` unless the resolver's network was created with [`Network::with_ipv6`]
+    pub fn ipv6_addr(&self) -> Option<Ipv6Addr> {
+        self.container.ipv6_addr()
+    }
+
+    pub(crate) fn container(&self) -> &Container {
+        &self.container
+    }
+
+    pub(crate) fn implementation(&self) -> &Implementation {
+        &self.implementation
+    }
+
     /// Returns the logs collected so far
     pub fn logs(&self) -> Result<String> {
         if self.implementation.is_hickory() {
@@ -76,6 +103,17 @@ pub struct ResolverSettings {
     trust_anchor: TrustAnchor,
     custom_config: Option<String>,
     case_randomization: bool,
+    prefetch: Option<bool>,
+    servfail_cache_ttl: Option<Duration>,
+    edns_buffer_size: Option<u16>,
+    tls: bool,
+    https: bool,
+    dns64_prefix: Option<Ipv6Net>,
+    qname_minimization: Option<bool>,
+    aggressive_nsec: Option<bool>,
+    serve_stale: Option<Duration>,
+    upstream_timeout: Option<Duration>,
+    ipv6_only: bool,
 }
 
 impl ResolverSettings {
@@ -109,6 +147,20 @@ impl ResolverSettings {
                 netmask: self.network.netmask(),
                 ede: self.ede,
                 case_randomization: self.case_randomization,
+                prefetch: self.prefetch,
+                servfail_cache_ttl: self.servfail_cache_ttl.map(|ttl| ttl.as_secs()),
+                edns_buffer_size: self.edns_buffer_size,
+                tls: self.tls,
+                https: self.https,
+                dns64_prefix: self.dns64_prefix.map(|prefix| prefix.to_string()),
+                qname_minimization: self.qname_minimization,
+                aggressive_nsec: self.aggressive_nsec,
+                serve_stale_ttl: self.serve_stale.map(|max_stale| max_stale.as_secs()),
+                upstream_timeout_ms: self
+                    .upstream_timeout
+                    .map(|timeout| timeout.as_millis() as u64),
+                ipv6_netmask: self.network.ipv6_netmask(),
+                ipv6_only: self.ipv6_only,
             };
             &implementation.format_config(config)
         };
@@ -116,7 +168,29 @@ impl ResolverSettings {
             container.cp(conf_file_path, config_contents)?;
         }
 
+        if (self.tls || self.https) && implementation.is_unbound() {
+            container.status_ok(&[
+                "openssl",
+                "req",
+                "-x509",
+                "-newkey",
+                "rsa:2048",
+                "-nodes",
+                "-subj",
+                "/CN=resolver",
+                "-keyout",
+                "/etc/unbound/tls.key",
+                "-out",
+                "/etc/unbound/tls.pem",
+            ])?;
+        }
+
         if use_dnssec {
+            assert!(
+                !implementation.is_hickory() || self.trust_anchor.ds().is_empty(),
+                "hickory does not support DS records in the trust anchor"
+            );
+
             let path = if implementation.is_bind() {
                 "/etc/bind/bind.keys"
             } else {
@@ -158,11 +232,9 @@ impl ResolverSettings {
         self
     }
 
-    /// Adds all the keys in the `other` trust anchor to ours
+    /// Adds all the keys, and DS records, in the `other` trust anchor to ours
     pub fn trust_anchor(&mut self, other: &TrustAnchor) -> &mut Self {
-        for key in other.keys() {
-            self.trust_anchor.add(key.clone());
-        }
+        self.trust_anchor.extend(other);
         self
     }
 
@@ -177,6 +249,112 @@ impl ResolverSettings {
         self.case_randomization = true;
         self
     }
+
+    /// Enables or disables refreshing cached records that are about to expire
+    ///
+    /// If this is never called, the implementation's default behavior is used.
+    pub fn prefetch(&mut self, enabled: bool) -> &mut Self {
+        self.prefetch = Some(enabled);
+        self
+    }
+
+    /// Sets how long a resolution failure (SERVFAIL) is cached for (RFC9520); rounded down to
+    /// whole seconds
+    ///
+    /// Only supported by BIND, which caps this value at 30 seconds.
+    pub fn servfail_cache_ttl(&mut self, ttl: Duration) -> &mut Self {
+        self.servfail_cache_ttl = Some(ttl);
+        self
+    }
+
+    /// Sets the UDP payload size, in bytes, that the resolver advertises in the EDNS OPT record
+    /// of its queries to authoritative servers. Responses to clients are also capped at this size.
+    ///
+    /// Not supported by hickory.
+    pub fn edns_buffer_size(&mut self, size: u16) -> &mut Self {
+        self.edns_buffer_size = Some(size);
+        self
+    }
+
+    /// Makes the resolver also accept DNS-over-TLS (RFC7858) queries on port 853, using a
+    /// self-signed certificate
+    ///
+    /// Not supported by hickory.
+    pub fn tls(&mut self) -> &mut Self {
+        self.tls = true;
+        self
+    }
+
+    /// Makes the resolver also accept DNS-over-HTTPS (RFC8484) queries on port 443, at the
+    /// `/dns-query` endpoint, using a self-signed certificate
+    ///
+    /// Not supported by hickory.
+    pub fn https(&mut self) -> &mut Self {
+        self.https = true;
+        self
+    }
+
+    /// Makes the resolver synthesize AAAA records from A records for names that have no AAAA
+    /// records (DNS64, RFC6147) by embedding the IPv4 address in `prefix`, e.g. the well-known
+    /// prefix `64:ff9b::/96`
+    ///
+    /// Not supported by hickory.
+    pub fn dns64(&mut self, prefix: Ipv6Net) -> &mut Self {
+        self.dns64_prefix = Some(prefix);
+        self
+    }
+
+    /// Enables or disables QNAME minimization (RFC9156): only sending name servers as many labels
+    /// of the query name as they need to answer with a referral
+    ///
+    /// If this is never called, the implementation's default behavior is used. BIND's relaxed
+    /// mode is used when enabled. Hickory always minimizes so it can't be disabled.
+    pub fn qname_minimization(&mut self, enabled: bool) -> &mut Self {
+        self.qname_minimization = Some(enabled);
+        self
+    }
+
+    /// Enables or disables synthesizing negative answers from cached, validated NSEC records
+    /// (RFC8198) instead of querying the authoritative servers
+    ///
+    /// If this is never called, the implementation's default behavior is used. Not supported by
+    /// hickory.
+    pub fn aggressive_nsec(&mut self, enabled: bool) -> &mut Self {
+        self.aggressive_nsec = Some(enabled);
+        self
+    }
+
+    /// Answers with expired cache entries, for up to `max_stale` past their expiration, when the
+    /// authoritative servers can't be reached (RFC8767); rounded down to whole seconds
+    ///
+    /// Not supported by hickory.
+    pub fn serve_stale(&mut self, max_stale: Duration) -> &mut Self {
+        self.serve_stale = Some(max_stale);
+        self
+    }
+
+    /// Caps how long the resolver waits for upstream name servers; rounded down to whole
+    /// milliseconds
+    ///
+    /// With unbound this is the longest it waits for a single query to be answered. With BIND
+    /// this is the time it spends resolving a client query, with a lower bound of 301ms. Not
+    /// supported by hickory.
+    pub fn upstream_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.upstream_timeout = Some(timeout);
+        self
+    }
+
+    /// Only use IPv6, both to serve clients and to query upstream name servers
+    ///
+    /// The network must have been created with [`Network::with_ipv6`]. Not supported by hickory.
+    pub fn ipv6_only(&mut self) -> &mut Self {
+        assert!(
+            self.network.ipv6_netmask().is_some(),
+            "the network does not support IPv6"
+        );
+        self.ipv6_only = true;
+        self
+    }
 }
 
 #[cfg(test)]
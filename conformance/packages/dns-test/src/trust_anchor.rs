@@ -2,16 +2,20 @@ use core::fmt;
 
 use crate::{
     DEFAULT_TTL, FQDN,
-    record::{DNSKEY, DNSKEYRData},
+    record::{DNSKEY, DNSKEYRData, DS},
 };
 
 pub struct TrustAnchor {
     keys: Vec<DNSKEY>,
+    ds: Vec<DS>,
 }
 
 impl TrustAnchor {
     pub fn empty() -> Self {
-        Self { keys: Vec::new() }
+        Self {
+            keys: Vec::new(),
+            ds: Vec::new(),
+        }
     }
 
     pub fn public_dns() -> Self {
@@ -40,7 +44,7 @@ impl TrustAnchor {
     }
 
     pub fn is_empty(&self) -> bool {
-        self.keys.is_empty()
+        self.keys.is_empty() && self.ds.is_empty()
     }
 
     pub fn add(&mut self, key: DNSKEY) -> &mut Self {
@@ -48,10 +52,28 @@ impl TrustAnchor {
         self
     }
 
+    /// Trusts the zone's DNSKEY that `ds` is the digest of, rather than a key given in full
+    ///
+    /// The validator must then authenticate the zone's DNSKEY RRset against the DS record itself.
+    pub fn add_ds(&mut self, ds: DS) -> &mut Self {
+        self.ds.push(ds);
+        self
+    }
+
     pub(crate) fn keys(&self) -> &[DNSKEY] {
         &self.keys
     }
 
+    pub(crate) fn ds(&self) -> &[DS] {
+        &self.ds
+    }
+
+    /// Adds all the keys and DS records in `other` to ours
+    pub(crate) fn extend(&mut self, other: &TrustAnchor) {
+        self.keys.extend_from_slice(&other.keys);
+        self.ds.extend_from_slice(&other.ds);
+    }
+
     /// formats the `TrustAnchor` in the format `delv` expects
     pub(super) fn delv(&self) -> String {
         let mut buf = "trust-anchors {".to_string();
@@ -60,6 +82,10 @@ impl TrustAnchor {
             buf.push_str(&key.delv());
         }
 
+        for ds in &self.ds {
+            buf.push_str(&ds.delv());
+        }
+
         buf.push_str("};");
         buf
     }
@@ -70,6 +96,9 @@ impl fmt::Display for TrustAnchor {
         for key in &self.keys {
             writeln!(f, "{key}")?;
         }
+        for ds in &self.ds {
+            writeln!(f, "{ds}")?;
+        }
         Ok(())
     }
 }
@@ -78,6 +107,7 @@ impl FromIterator<DNSKEY> for TrustAnchor {
     fn from_iter<T: IntoIterator<Item = DNSKEY>>(iter: T) -> Self {
         Self {
             keys: iter.into_iter().collect(),
+            ds: Vec::new(),
         }
     }
 }
@@ -4,17 +4,18 @@ use core::result::Result as CoreResult;
 use std::fmt;
 use std::io::{self, BufRead, BufReader};
 use std::net::Ipv4Addr;
+use std::str::FromStr;
 use std::sync::atomic::{self, AtomicUsize};
 use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender, channel};
 use std::thread::{self, JoinHandle};
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 
 use serde::de::{DeserializeSeed, Error as _, SeqAccess, Visitor};
 use serde::{Deserialize, Deserializer};
 use serde_with::{DisplayFromStr, serde_as};
 
-use crate::Result;
 use crate::container::{Child, Container};
+use crate::{FQDN, Result};
 
 static ID: AtomicUsize = AtomicUsize::new(0);
 
@@ -195,10 +196,50 @@ pub struct Capture {
     pub direction: Direction,
 }
 
+impl Capture {
+    /// When `tshark` captured the packet
+    ///
+    /// All containers share the host's clock so timestamps of packets captured in different
+    /// containers can be compared
+    pub fn timestamp(&self) -> SystemTime {
+        self.message.timestamp
+    }
+
+    /// Returns `true` if this is a query, received by the eavesdropped container, whose question
+    /// is about `fqdn`
+    pub fn is_incoming_query_for(&self, fqdn: &FQDN) -> bool {
+        matches!(self.direction, Direction::Incoming { .. }) && self.message.is_query_for(fqdn)
+    }
+
+    /// Returns `true` if this is a query, sent by the eavesdropped container, whose question is
+    /// about `fqdn`
+    pub fn is_outgoing_query_for(&self, fqdn: &FQDN) -> bool {
+        matches!(self.direction, Direction::Outgoing { .. }) && self.message.is_query_for(fqdn)
+    }
+
+    /// Counts the `captures` that are queries, received by the eavesdropped container, about
+    /// `fqdn`
+    pub fn count_incoming_queries_for(captures: &[Capture], fqdn: &FQDN) -> usize {
+        captures
+            .iter()
+            .filter(|capture| capture.is_incoming_query_for(fqdn))
+            .count()
+    }
+
+    /// Counts the `captures` that are queries, sent by the eavesdropped container, about `fqdn`
+    pub fn count_outgoing_queries_for(captures: &[Capture], fqdn: &FQDN) -> usize {
+        captures
+            .iter()
+            .filter(|capture| capture.is_outgoing_query_for(fqdn))
+            .count()
+    }
+}
+
 #[derive(Debug)]
 pub struct Message {
     // TODO this should be more "cooked", i.e. be deserialized into a `struct`
     inner: serde_json::Value,
+    timestamp: SystemTime,
 }
 
 impl Message {
@@ -259,6 +300,43 @@ impl Message {
         None
     }
 
+    /// Returns `true` if the QR flag is set
+    pub fn is_response(&self) -> bool {
+        self.inner["dns.flags_tree"]
+            .get("dns.flags.response")
+            .and_then(|response| response.as_str())
+            == Some("1")
+    }
+
+    /// Returns `true` if this is a query whose question is about `fqdn`, in any case
+    pub fn is_query_for(&self, fqdn: &FQDN) -> bool {
+        !self.is_response()
+            && self
+                .query_name()
+                .is_some_and(|name| fqdn.eq_ignore_case(name))
+    }
+
+    /// Returns the domain name in the question section, without the trailing dot
+    ///
+    /// Returns `None` if there's no question
+    pub fn query_name(&self) -> Option<&str> {
+        self.inner
+            .get("Queries")?
+            .as_object()?
+            .values()
+            .next()?
+            .get("dns.qry.name")?
+            .as_str()
+    }
+
+    /// Returns `true` if the TC (truncation) flag is set
+    pub fn is_tc_flag_set(&self) -> bool {
+        self.inner["dns.flags_tree"]
+            .get("dns.flags.truncated")
+            .and_then(|truncated| truncated.as_str())
+            == Some("1")
+    }
+
     pub fn is_rd_flag_set(&self) -> bool {
         let Some(recursion_desired) = self.inner["dns.flags_tree"]
             .as_object()
@@ -321,10 +399,45 @@ struct Source {
 
 #[derive(Deserialize)]
 struct Layers {
+    frame: Frame,
     ip: Ip,
     dns: serde_json::Value,
 }
 
+#[serde_as]
+#[derive(Deserialize)]
+struct Frame {
+    #[serde(rename = "frame.time_epoch")]
+    #[serde_as(as = "DisplayFromStr")]
+    time_epoch: EpochTime,
+}
+
+/// A `frame.time_epoch` value: seconds since the UNIX epoch with up to nanosecond precision,
+/// e.g. `1700000000.123456789`
+struct EpochTime(SystemTime);
+
+impl FromStr for EpochTime {
+    type Err = String;
+
+    fn from_str(input: &str) -> CoreResult<Self, Self::Err> {
+        let error = || format!("invalid frame.time_epoch: {input}");
+
+        let (secs, fraction) = input.split_once('.').unwrap_or((input, ""));
+        if fraction.len() > 9 || !fraction.bytes().all(|byte| byte.is_ascii_digit()) {
+            return Err(error());
+        }
+
+        let secs: u64 = secs.parse().map_err(|_| error())?;
+        let nanos = if fraction.is_empty() {
+            0
+        } else {
+            fraction.parse::<u32>().map_err(|_| error())? * 10u32.pow(9 - fraction.len() as u32)
+        };
+
+        Ok(Self(SystemTime::UNIX_EPOCH + Duration::new(secs, nanos)))
+    }
+}
+
 #[serde_as]
 #[derive(Debug, Deserialize)]
 struct Ip {
@@ -389,7 +502,7 @@ impl<'de> Visitor<'de> for StreamingCaptureVisitor {
         A: SeqAccess<'de>,
     {
         while let Some(entry) = seq.next_element::<Entry>()? {
-            let Layers { ip, dns } = entry._source.layers;
+            let Layers { frame, ip, dns } = entry._source.layers;
 
             let direction = if ip.dst == self.own_addr {
                 Direction::Incoming { source: ip.src }
@@ -404,7 +517,10 @@ impl<'de> Visitor<'de> for StreamingCaptureVisitor {
             };
 
             let _ = self.sender.send(Capture {
-                message: Message { inner: dns },
+                message: Message {
+                    inner: dns,
+                    timestamp: frame.time_epoch.0,
+                },
                 direction,
             });
         }
@@ -422,6 +538,25 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn parses_frame_time_epoch() -> Result<()> {
+        let EpochTime(time) = "1700000000.123456789".parse()?;
+        assert_eq!(
+            Duration::new(1_700_000_000, 123_456_789),
+            time.duration_since(SystemTime::UNIX_EPOCH)?
+        );
+
+        let EpochTime(time) = "1700000000.5".parse()?;
+        assert_eq!(
+            Duration::new(1_700_000_000, 500_000_000),
+            time.duration_since(SystemTime::UNIX_EPOCH)?
+        );
+
+        assert!("1700000000.1234567890".parse::<EpochTime>().is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn nameserver() -> Result<()> {
         let network = &Network::new()?;
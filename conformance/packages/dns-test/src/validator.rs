@@ -0,0 +1,696 @@
+//! A minimal DNSSEC validator that is independent of the implementations under test
+//!
+//! It checks the RRSIGs of the answer section of a single response against the keys of a trust
+//! anchor and against the DNSKEY RRsets, in that same answer section, that those keys
+//! authenticate. It does not follow chains of trust across zone cuts nor does it validate denial
+//! of existence; those cases produce an [`Verdict::Indeterminate`] verdict. A DS record of the
+//! trust anchor makes the DNSKEY, in the answer section, that it is the digest of a trusted key.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use base64::prelude::*;
+use ring::digest;
+use ring::signature::{self, RsaPublicKeyComponents, UnparsedPublicKey};
+
+use crate::FQDN;
+use crate::record::{DNSKEY, DNSKEYRData, DS, HTTPS, RRSIG, Record, RecordType, SVCB, SvcParam};
+use crate::trust_anchor::TrustAnchor;
+
+/// The smallest RSA modulus that `ring` verifies signatures with
+const MIN_RSA_MODULUS_BITS: usize = 1_024;
+
+/// Outcome of validating a response with the reference validator
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    /// Every RRset in the answer section is covered by a valid signature made with a trusted key
+    Secure,
+    /// Some RRset only has signatures, made with trusted keys, that are invalid
+    Bogus(String),
+    /// The reference validator cannot reach a verdict
+    Indeterminate(String),
+}
+
+impl Verdict {
+    pub fn is_secure(&self) -> bool {
+        matches!(self, Self::Secure)
+    }
+
+    pub fn is_bogus(&self) -> bool {
+        matches!(self, Self::Bogus(_))
+    }
+}
+
+pub(crate) fn validate(answer: &[Record], trust_anchor: &TrustAnchor, now: SystemTime) -> Verdict {
+    let now = now
+        .duration_since(UNIX_EPOCH)
+        .expect("system time before UNIX epoch")
+        .as_secs();
+
+    let rrsigs = answer
+        .iter()
+        .filter_map(|record| match record {
+            Record::RRSIG(rrsig) => Some(rrsig),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let rrsets = rrsets(answer);
+
+    if rrsets.is_empty() {
+        return Verdict::Indeterminate("the answer section is empty".into());
+    }
+
+    // keys authenticated by the trust anchor, directly or through a DNSKEY RRset in the answer
+    let mut trusted_keys = trust_anchor.keys().to_vec();
+    trusted_keys.extend(answer.iter().filter_map(|record| match record {
+        Record::DNSKEY(dnskey) if trust_anchor.ds().iter().any(|ds| ds_matches(ds, dnskey)) => {
+            Some(dnskey.clone())
+        }
+        _ => None,
+    }));
+    loop {
+        let mut new_keys = vec![];
+        for rrset in rrsets
+            .iter()
+            .filter(|rrset| rrset.record_type == RecordType::DNSKEY)
+        {
+            if rrset_verdict(rrset, &rrsigs, &trusted_keys, now).is_secure() {
+                new_keys.extend(rrset.records.iter().filter_map(|record| match record {
+                    Record::DNSKEY(dnskey) if !trusted_keys.contains(dnskey) => {
+                        Some(dnskey.clone())
+                    }
+                    _ => None,
+                }));
+            }
+        }
+
+        if new_keys.is_empty() {
+            break;
+        }
+        trusted_keys.extend(new_keys);
+    }
+
+    let mut verdict = Verdict::Secure;
+    for rrset in &rrsets {
+        match rrset_verdict(rrset, &rrsigs, &trusted_keys, now) {
+            Verdict::Secure => {}
+            bogus @ Verdict::Bogus(_) => return bogus,
+            indeterminate @ Verdict::Indeterminate(_) => verdict = indeterminate,
+        }
+    }
+    verdict
+}
+
+/// Whether `ds` is the digest of `dnskey` (RFC4034 section 5.1.4)
+fn ds_matches(ds: &DS, dnskey: &DNSKEY) -> bool {
+    if !eq_ignore_case(&ds.zone, &dnskey.zone)
+        || ds.algorithm != dnskey.rdata.algorithm
+        || ds.key_tag != dnskey.rdata.calculate_key_tag()
+    {
+        return false;
+    }
+
+    let algorithm = match ds.digest_type {
+        1 => &digest::SHA1_FOR_LEGACY_USE_ONLY,
+        2 => &digest::SHA256,
+        4 => &digest::SHA384,
+        _ => return false,
+    };
+
+    let (Ok(mut data), Ok(rdata), Ok(expected)) = (
+        canonical_name(&dnskey.zone),
+        rdata_to_wire(&Record::DNSKEY(dnskey.clone())),
+        hex::decode(&ds.digest),
+    ) else {
+        return false;
+    };
+    data.extend(rdata);
+
+    digest::digest(algorithm, &data).as_ref() == expected
+}
+
+struct RRset<'a> {
+    owner: &'a FQDN,
+    record_type: RecordType,
+    records: Vec<&'a Record>,
+}
+
+fn rrsets(answer: &[Record]) -> Vec<RRset<'_>> {
+    let mut rrsets: Vec<RRset> = vec![];
+    for record in answer {
+        if matches!(record, Record::RRSIG(_)) {
+            continue;
+        }
+
+        let owner = record.owner();
+        let record_type = record.record_type();
+        if let Some(rrset) = rrsets
+            .iter_mut()
+            .find(|rrset| rrset.record_type == record_type && eq_ignore_case(rrset.owner, owner))
+        {
+            rrset.records.push(record);
+        } else {
+            rrsets.push(RRset {
+                owner,
+                record_type,
+                records: vec![record],
+            });
+        }
+    }
+    rrsets
+}
+
+fn rrset_verdict(rrset: &RRset, rrsigs: &[&RRSIG], trusted_keys: &[DNSKEY], now: u64) -> Verdict {
+    let desc = format!("{} {}", rrset.owner, rrset.record_type);
+
+    let mut failure = None;
+    for rrsig in rrsigs.iter().filter(|rrsig| {
+        rrsig.type_covered == rrset.record_type && eq_ignore_case(&rrsig.fqdn, rrset.owner)
+    }) {
+        let candidates = trusted_keys.iter().filter(|key| {
+            eq_ignore_case(&key.zone, &rrsig.signer_name)
+                && key.rdata.algorithm == rrsig.algorithm
+                && key.rdata.calculate_key_tag() == rrsig.key_tag
+        });
+
+        for key in candidates {
+            match verify(rrset, rrsig, &key.rdata, now) {
+                Ok(true) => return Verdict::Secure,
+                Ok(false) => {}
+                Err(reason) => return Verdict::Indeterminate(format!("{desc}: {reason}")),
+            }
+
+            let inception = rrsig_timestamp(rrsig.signature_inception);
+            let expiration = rrsig_timestamp(rrsig.signature_expiration);
+            failure = Some(if now < inception {
+                format!("{desc}: RRSIG (key tag {}) is not yet valid", rrsig.key_tag)
+            } else if now > expiration {
+                format!("{desc}: RRSIG (key tag {}) has expired", rrsig.key_tag)
+            } else {
+                format!("{desc}: RRSIG (key tag {}) does not verify", rrsig.key_tag)
+            });
+        }
+    }
+
+    match failure {
+        Some(reason) => Verdict::Bogus(reason),
+        None => Verdict::Indeterminate(format!("{desc}: no RRSIG made with a trusted key")),
+    }
+}
+
+/// Returns `Ok(false)` if the signature is invalid or outside its validity period and `Err` if
+/// the reference validator does not support the records or the algorithm involved
+fn verify(rrset: &RRset, rrsig: &RRSIG, key: &DNSKEYRData, now: u64) -> Result<bool, String> {
+    let inception = rrsig_timestamp(rrsig.signature_inception);
+    let expiration = rrsig_timestamp(rrsig.signature_expiration);
+    if now < inception || now > expiration {
+        return Ok(false);
+    }
+
+    let message = signed_data(rrset, rrsig)?;
+    let signature = decode_base64(&rrsig.signature)?;
+    let public_key = decode_base64(&key.public_key)?;
+
+    let rsa = |params| {
+        let (e, n) = split_rsa_public_key(&public_key)?;
+        // `ring` rejects these keys as it would an invalid signature; the zone is not bogus
+        // though, its keys are just too weak for the reference validator
+        let modulus_bits = rsa_modulus_bits(n);
+        if modulus_bits < MIN_RSA_MODULUS_BITS {
+            return Err(format!(
+                "unsupported RSA key size of {modulus_bits} bits; the minimum is {MIN_RSA_MODULUS_BITS}"
+            ));
+        }
+
+        Ok(RsaPublicKeyComponents { n, e }
+            .verify(params, &message, &signature)
+            .is_ok())
+    };
+    let ecdsa = |algorithm| {
+        let mut uncompressed = vec![0x04];
+        uncompressed.extend_from_slice(&public_key);
+        UnparsedPublicKey::new(algorithm, uncompressed)
+            .verify(&message, &signature)
+            .is_ok()
+    };
+
+    match key.algorithm {
+        5 | 7 => rsa(&signature::RSA_PKCS1_1024_8192_SHA1_FOR_LEGACY_USE_ONLY),
+        8 => rsa(&signature::RSA_PKCS1_1024_8192_SHA256_FOR_LEGACY_USE_ONLY),
+        10 => rsa(&signature::RSA_PKCS1_1024_8192_SHA512_FOR_LEGACY_USE_ONLY),
+        13 => Ok(ecdsa(&signature::ECDSA_P256_SHA256_FIXED)),
+        14 => Ok(ecdsa(&signature::ECDSA_P384_SHA384_FIXED)),
+        15 => Ok(UnparsedPublicKey::new(&signature::ED25519, &public_key)
+            .verify(&message, &signature)
+            .is_ok()),
+        algorithm => Err(format!("unsupported algorithm {algorithm}")),
+    }
+}
+
+/// The data covered by an RRSIG (RFC4034 section 3.1.8.1), with the RRset in canonical form and
+/// order (RFC4034 section 6)
+fn signed_data(rrset: &RRset, rrsig: &RRSIG) -> Result<Vec<u8>, String> {
+    let type_code = rrsig.type_covered.code();
+
+    let mut data = vec![];
+    data.extend_from_slice(&type_code.to_be_bytes());
+    data.push(rrsig.algorithm);
+    data.push(rrsig.labels);
+    data.extend_from_slice(&rrsig.original_ttl.to_be_bytes());
+    data.extend_from_slice(&(rrsig_timestamp(rrsig.signature_expiration) as u32).to_be_bytes());
+    data.extend_from_slice(&(rrsig_timestamp(rrsig.signature_inception) as u32).to_be_bytes());
+    data.extend_from_slice(&rrsig.key_tag.to_be_bytes());
+    data.extend_from_slice(&canonical_name(&rrsig.signer_name)?);
+
+    // wildcard expansion (RFC4035 section 5.3.2)
+    let mut owner = canonical_name(rrset.owner)?;
+    if usize::from(rrsig.labels) < rrset.owner.num_labels() {
+        let labels = rrset
+            .owner
+            .as_str()
+            .split('.')
+            .filter(|label| !label.is_empty());
+        let skip = rrset.owner.num_labels() - usize::from(rrsig.labels);
+        let suffix = labels.skip(skip).collect::<Vec<_>>().join(".");
+        owner = canonical_name(&FQDN(format!("*.{suffix}.")).map_err(|e| e.to_string())?)?;
+    }
+
+    let mut rdatas = rrset
+        .records
+        .iter()
+        .map(|record| rdata_to_wire(record))
+        .collect::<Result<Vec<_>, _>>()?;
+    rdatas.sort();
+    rdatas.dedup();
+
+    for rdata in rdatas {
+        data.extend_from_slice(&owner);
+        data.extend_from_slice(&type_code.to_be_bytes());
+        data.extend_from_slice(&1u16.to_be_bytes()); // class IN
+        data.extend_from_slice(&rrsig.original_ttl.to_be_bytes());
+        data.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+        data.extend_from_slice(&rdata);
+    }
+
+    Ok(data)
+}
+
+fn canonical_name(name: &FQDN) -> Result<Vec<u8>, String> {
+    name.canonical_wire_format().map_err(|e| e.to_string())
+}
+
+fn rdata_to_wire(record: &Record) -> Result<Vec<u8>, String> {
+    let mut rdata = vec![];
+    match record {
+        Record::A(a) => rdata.extend_from_slice(&a.ipv4_addr.octets()),
+
+        Record::AAAA(aaaa) => rdata.extend_from_slice(&aaaa.ipv6_addr.octets()),
+
+        Record::NS(ns) => rdata.extend(canonical_name(&ns.nameserver)?),
+
+        Record::CNAME(cname) => rdata.extend(canonical_name(&cname.target)?),
+        Record::DNAME(dname) => rdata.extend(canonical_name(&dname.target)?),
+
+        Record::PTR(ptr) => rdata.extend(canonical_name(&ptr.target)?),
+
+        Record::SOA(soa) => {
+            rdata.extend(canonical_name(&soa.nameserver)?);
+            rdata.extend(canonical_name(&soa.admin)?);
+            for field in [
+                soa.settings.serial,
+                soa.settings.refresh,
+                soa.settings.retry,
+                soa.settings.expire,
+                soa.settings.minimum,
+            ] {
+                rdata.extend_from_slice(&field.to_be_bytes());
+            }
+        }
+
+        Record::TXT(txt) => {
+            for string in &txt.character_strings {
+                rdata.push(string.len() as u8);
+                rdata.extend_from_slice(string.as_bytes());
+            }
+        }
+
+        Record::DNSKEY(dnskey) => {
+            rdata.extend_from_slice(&dnskey.rdata.flags.to_be_bytes());
+            rdata.push(dnskey.rdata.protocol);
+            rdata.push(dnskey.rdata.algorithm);
+            rdata.extend(decode_base64(&dnskey.rdata.public_key)?);
+        }
+
+        Record::DS(ds) => {
+            rdata.extend_from_slice(&ds.key_tag.to_be_bytes());
+            rdata.push(ds.algorithm);
+            rdata.push(ds.digest_type);
+            rdata.extend(hex::decode(&ds.digest).map_err(|e| e.to_string())?);
+        }
+
+        Record::CDS(cds) => {
+            rdata.extend_from_slice(&cds.key_tag.to_be_bytes());
+            rdata.push(cds.algorithm);
+            rdata.push(cds.digest_type);
+            rdata.extend(hex::decode(&cds.digest).map_err(|e| e.to_string())?);
+        }
+
+        Record::CDNSKEY(cdnskey) => {
+            rdata.extend_from_slice(&cdnskey.rdata.flags.to_be_bytes());
+            rdata.push(cdnskey.rdata.protocol);
+            rdata.push(cdnskey.rdata.algorithm);
+            rdata.extend(decode_base64(&cdnskey.rdata.public_key)?);
+        }
+
+        Record::CAA(caa) => {
+            rdata.push(caa.flags);
+            rdata.push(caa.tag.len() as u8);
+            rdata.extend_from_slice(caa.tag.as_bytes());
+            rdata.extend_from_slice(caa.value.as_bytes());
+        }
+
+        Record::SVCB(SVCB { rdata: svcb, .. }) | Record::HTTPS(HTTPS { rdata: svcb, .. }) => {
+            rdata.extend_from_slice(&svcb.priority.to_be_bytes());
+            // SVCB and HTTPS are not in the list of RFC4034 section 6.2 so the case of the
+            // target is preserved
+            rdata.extend(svcb.target.wire_format().map_err(|e| e.to_string())?);
+            rdata.extend(svc_params_to_wire(&svcb.params)?);
+        }
+
+        Record::NAPTR(naptr) => {
+            rdata.extend_from_slice(&naptr.order.to_be_bytes());
+            rdata.extend_from_slice(&naptr.preference.to_be_bytes());
+            for string in [&naptr.flags, &naptr.services, &naptr.regexp] {
+                rdata.push(string.len() as u8);
+                rdata.extend_from_slice(string.as_bytes());
+            }
+            rdata.extend(canonical_name(&naptr.replacement)?);
+        }
+
+        Record::MX(mx) => {
+            rdata.extend_from_slice(&mx.preference.to_be_bytes());
+            rdata.extend(canonical_name(&mx.exchange)?);
+        }
+
+        Record::SRV(srv) => {
+            for value in [srv.priority, srv.weight, srv.port] {
+                rdata.extend_from_slice(&value.to_be_bytes());
+            }
+            rdata.extend(canonical_name(&srv.target)?);
+        }
+
+        Record::SSHFP(sshfp) => {
+            rdata.extend([sshfp.algorithm, sshfp.fp_type]);
+            rdata.extend_from_slice(&sshfp.fingerprint);
+        }
+
+        Record::TLSA(tlsa) => {
+            rdata.extend([tlsa.usage, tlsa.selector, tlsa.matching_type]);
+            rdata.extend_from_slice(&tlsa.data);
+        }
+
+        Record::Unknown(unknown) => rdata.extend_from_slice(&unknown.rdata),
+
+        Record::NSEC(_) | Record::NSEC3(_) | Record::NSEC3PARAM(_) | Record::RRSIG(_) => {
+            return Err(format!("unsupported record type {}", record.record_type()));
+        }
+    }
+    Ok(rdata)
+}
+
+/// The SvcParams of an SVCB RR in wire format, sorted by key as per RFC9460 section 2.2
+fn svc_params_to_wire(params: &[SvcParam]) -> Result<Vec<u8>, String> {
+    let key_code =
+        |key: &str| SvcParam::key_code(key).ok_or_else(|| format!("unknown SvcParamKey {key}"));
+
+    let mut encoded = vec![];
+    for param in params {
+        let mut value = vec![];
+        match param {
+            SvcParam::Mandatory(keys) => {
+                let mut codes = keys
+                    .iter()
+                    .map(|key| key_code(key))
+                    .collect::<Result<Vec<_>, _>>()?;
+                codes.sort_unstable();
+                for code in codes {
+                    value.extend_from_slice(&code.to_be_bytes());
+                }
+            }
+            SvcParam::Alpn(ids) => {
+                for id in ids {
+                    value.push(id.len() as u8);
+                    value.extend_from_slice(id.as_bytes());
+                }
+            }
+            SvcParam::Port(port) => value.extend_from_slice(&port.to_be_bytes()),
+            SvcParam::Ipv4Hint(addrs) => {
+                for addr in addrs {
+                    value.extend_from_slice(&addr.octets());
+                }
+            }
+            SvcParam::Ipv6Hint(addrs) => {
+                for addr in addrs {
+                    value.extend_from_slice(&addr.octets());
+                }
+            }
+            SvcParam::Other { value: None, .. } => {}
+            SvcParam::Other {
+                key,
+                value: Some(_),
+            } => return Err(format!("unsupported SvcParamKey {key}")),
+        }
+        encoded.push((key_code(param.key())?, value));
+    }
+    encoded.sort_by_key(|(code, _)| *code);
+
+    let mut wire = vec![];
+    for (code, value) in encoded {
+        wire.extend_from_slice(&code.to_be_bytes());
+        wire.extend_from_slice(&(value.len() as u16).to_be_bytes());
+        wire.extend(value);
+    }
+    Ok(wire)
+}
+
+/// Returns the size in bits of a big-endian RSA modulus
+fn rsa_modulus_bits(modulus: &[u8]) -> usize {
+    match modulus.iter().position(|byte| *byte != 0) {
+        Some(first) => (modulus.len() - first) * 8 - modulus[first].leading_zeros() as usize,
+        None => 0,
+    }
+}
+
+/// Splits a RSA public key in the format of RFC3110 section 2 into its exponent and modulus
+fn split_rsa_public_key(public_key: &[u8]) -> Result<(&[u8], &[u8]), String> {
+    let (exponent_len, rest) = match public_key {
+        [0, high, low, rest @ ..] => (usize::from(u16::from_be_bytes([*high, *low])), rest),
+        [len, rest @ ..] => (usize::from(*len), rest),
+        [] => return Err("empty RSA public key".into()),
+    };
+
+    if rest.len() <= exponent_len {
+        return Err("truncated RSA public key".into());
+    }
+
+    Ok(rest.split_at(exponent_len))
+}
+
+/// Converts the text representation of an RRSIG timestamp, `YYYYMMDDHHmmSS`, into a UNIX
+/// timestamp
+fn rrsig_timestamp(value: u64) -> u64 {
+    let (date, time) = (value / 1_000_000, value % 1_000_000);
+    let (year, month, day) = (date / 10_000, (date / 100) % 100, date % 100);
+    let (hours, minutes, seconds) = (time / 10_000, (time / 100) % 100, time % 100);
+
+    // days since the UNIX epoch of the given civil date; see
+    // http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let year_of_era = year - era * 400;
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    days * 86_400 + hours * 3_600 + minutes * 60 + seconds
+}
+
+fn decode_base64(input: &str) -> Result<Vec<u8>, String> {
+    let input = input
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace())
+        .collect::<String>();
+    BASE64_STANDARD.decode(input).map_err(|e| e.to_string())
+}
+
+fn eq_ignore_case(a: &FQDN, b: &FQDN) -> bool {
+    a.as_str().eq_ignore_ascii_case(b.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::zone_file::ZoneFile;
+
+    // the `muster.zone` signatures are valid from 2024-02-20 to 2024-03-19
+    const DURING_VALIDITY: u64 = 1_709_251_200; // 2024-03-01
+    const AFTER_EXPIRATION: u64 = 1_711_929_600; // 2024-04-01
+
+    fn muster() -> (Vec<Record>, TrustAnchor) {
+        let zone: ZoneFile = include_str!("zone_file/muster.zone").parse().unwrap();
+        let ksk = zone
+            .records
+            .iter()
+            .find_map(|record| match record {
+                Record::DNSKEY(dnskey) if dnskey.is_key_signing_key() => Some(dnskey.clone()),
+                _ => None,
+            })
+            .unwrap();
+
+        // keep the RRsets the validator supports, plus their signatures. NOTE the SOA record of
+        // this zone file was edited after signing so its RRSIG does not verify
+        let records = zone
+            .records
+            .into_iter()
+            .filter(|record| {
+                let record_type = match record {
+                    Record::RRSIG(rrsig) => &rrsig.type_covered,
+                    _ => &record.record_type(),
+                };
+                matches!(record_type, RecordType::NS | RecordType::DNSKEY)
+            })
+            .collect();
+
+        (records, TrustAnchor::from_iter([ksk]))
+    }
+
+    fn at(timestamp: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(timestamp)
+    }
+
+    #[test]
+    fn rrsig_timestamps() {
+        assert_eq!(0, rrsig_timestamp(19700101000000));
+        assert_eq!(1_710_845_119, rrsig_timestamp(20240319104519));
+    }
+
+    #[test]
+    fn secure() {
+        let (records, trust_anchor) = muster();
+
+        assert_eq!(
+            Verdict::Secure,
+            validate(&records, &trust_anchor, at(DURING_VALIDITY))
+        );
+    }
+
+    #[test]
+    fn bogus_when_tampered() {
+        let (mut records, trust_anchor) = muster();
+        for record in &mut records {
+            if let Record::NS(ns) = record {
+                ns.nameserver = FQDN("primary1.nameservers.com.").unwrap();
+            }
+        }
+
+        let verdict = validate(&records, &trust_anchor, at(DURING_VALIDITY));
+        assert!(verdict.is_bogus(), "{verdict:?}");
+    }
+
+    #[test]
+    fn bogus_when_expired() {
+        let (records, trust_anchor) = muster();
+
+        let verdict = validate(&records, &trust_anchor, at(AFTER_EXPIRATION));
+        assert!(verdict.is_bogus(), "{verdict:?}");
+    }
+
+    #[test]
+    fn indeterminate_without_trusted_keys() {
+        let (records, _) = muster();
+
+        let verdict = validate(&records, &TrustAnchor::empty(), at(DURING_VALIDITY));
+        assert!(matches!(verdict, Verdict::Indeterminate(_)), "{verdict:?}");
+    }
+
+    #[test]
+    fn secure_with_ds_trust_anchor() {
+        let (records, _) = muster();
+        let ds = |digest_type, digest: &str| DS {
+            zone: FQDN::ROOT,
+            ttl: 86_400,
+            key_tag: 11_245,
+            algorithm: 7,
+            digest_type,
+            digest: digest.to_string(),
+        };
+
+        let mut trust_anchor = TrustAnchor::empty();
+        trust_anchor.add_ds(ds(
+            2,
+            "29C46212D81E4BCAD014EB1D535B91777CA25ACDF5C6B762328FDA563C64AEB7",
+        ));
+        assert_eq!(
+            Verdict::Secure,
+            validate(&records, &trust_anchor, at(DURING_VALIDITY))
+        );
+
+        let mut trust_anchor = TrustAnchor::empty();
+        trust_anchor.add_ds(ds(1, "8476BFC7F26DB5BF7168FB0D981A7802DB5FC667"));
+        assert_eq!(
+            Verdict::Secure,
+            validate(&records, &trust_anchor, at(DURING_VALIDITY))
+        );
+
+        // a digest of some other key
+        let mut trust_anchor = TrustAnchor::empty();
+        trust_anchor.add_ds(ds(1, "0000BFC7F26DB5BF7168FB0D981A7802DB5FC667"));
+        let verdict = validate(&records, &trust_anchor, at(DURING_VALIDITY));
+        assert!(matches!(verdict, Verdict::Indeterminate(_)), "{verdict:?}");
+    }
+
+    #[test]
+    fn rsa_modulus_size() {
+        assert_eq!(0, rsa_modulus_bits(&[0, 0]));
+        assert_eq!(9, rsa_modulus_bits(&[0, 1, 0xff]));
+        assert_eq!(512, rsa_modulus_bits(&[0xc0; 64]));
+    }
+
+    #[test]
+    fn svcb_target_keeps_its_case() -> Result<(), String> {
+        let target = FQDN("Svc.Example.").unwrap();
+        let https = Record::https(FQDN::ROOT, 1, target.clone(), vec![]);
+
+        let mut expected = vec![0, 1];
+        expected.extend(target.wire_format().unwrap());
+        assert_eq!(expected, rdata_to_wire(&https)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn svc_params_are_sorted_by_key() -> Result<(), String> {
+        // figure 6 of RFC9460 appendix D.2, minus the ECH param
+        let params = [
+            SvcParam::Port(8004),
+            SvcParam::Mandatory(vec!["ipv4hint".to_string(), "alpn".to_string()]),
+            SvcParam::Alpn(vec!["h2".to_string(), "h3-19".to_string()]),
+            SvcParam::Ipv4Hint(vec![Ipv4Addr::new(192, 0, 2, 1)]),
+        ];
+
+        let expected = [
+            &[0x00, 0x00, 0x00, 0x04, 0x00, 0x01, 0x00, 0x04][..],
+            &[0x00, 0x01, 0x00, 0x09, 0x02, b'h', b'2'],
+            &[0x05, b'h', b'3', b'-', b'1', b'9'],
+            &[0x00, 0x03, 0x00, 0x02, 0x1f, 0x44],
+            &[0x00, 0x04, 0x00, 0x04, 0xc0, 0x00, 0x02, 0x01],
+        ]
+        .concat();
+        assert_eq!(expected, svc_params_to_wire(&params)?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,469 @@
+//! Encoding of DNS queries and decoding of DNS responses in the wire format (RFC1035 section 4)
+//!
+//! This is for the few cases where `dig` can't be used, e.g. pipelining queries. Only the
+//! record types the harness commonly asserts on are decoded into their own [`Record`] variant;
+//! everything else becomes a [`Record::Unknown`]
+
+use core::fmt::Write as _;
+use std::collections::{BTreeMap, BTreeSet};
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use crate::client::{DigFlags, DigOutput, DigStatus, EdnsInfo};
+use crate::record::{
+    A, AAAA, CNAME, DNAME, MX, NS, PTR, Record, RecordType, SOA, SoaSettings, UnknownRdata,
+};
+use crate::{FQDN, Result};
+
+const HEADER_LEN: usize = 12;
+/// Advertised in the OPT pseudo-record of queries; same as `dig`'s default
+const UDP_PAYLOAD_SIZE: u16 = 1232;
+
+const OPT: u16 = 41;
+const NSID: u16 = 3;
+const CLIENT_SUBNET: u16 = 8;
+const COOKIE: u16 = 10;
+const EDE: u16 = 15;
+
+/// Encodes a query for the `record_type` RRset at `fqdn`, in the IN class, with an EDNS(0) OPT
+/// pseudo-record
+pub fn encode_query(
+    id: u16,
+    record_type: RecordType,
+    fqdn: &FQDN,
+    recursion_desired: bool,
+) -> Result<Vec<u8>> {
+    let flags: u16 = if recursion_desired { 0x0100 } else { 0 };
+
+    let mut message = vec![];
+    message.extend(id.to_be_bytes());
+    message.extend(flags.to_be_bytes());
+    // QDCOUNT, ANCOUNT, NSCOUNT, ARCOUNT
+    message.extend([0, 1, 0, 0, 0, 0, 0, 1]);
+
+    message.extend(fqdn.wire_format()?);
+    message.extend(record_type.code().to_be_bytes());
+    // IN class
+    message.extend(1u16.to_be_bytes());
+
+    // OPT pseudo-record: root owner, no extended RCODE, version 0, no flags and no options
+    message.push(0);
+    message.extend(OPT.to_be_bytes());
+    message.extend(UDP_PAYLOAD_SIZE.to_be_bytes());
+    message.extend([0, 0, 0, 0, 0, 0]);
+
+    Ok(message)
+}
+
+/// Returns the ID of `message`
+pub fn message_id(message: &[u8]) -> Result<u16> {
+    read_u16(message, 0)
+}
+
+/// Returns the name and type of the first question of `message`; `None` if it has no questions
+pub(crate) fn first_question(message: &[u8]) -> Result<Option<(FQDN, RecordType)>> {
+    if read_u16(message, 4)? == 0 {
+        return Ok(None);
+    }
+
+    let (qname, next) = read_name(message, HEADER_LEN)?;
+    let qtype = read_u16(message, next)?;
+    Ok(Some((qname, RecordType::from_code(qtype))))
+}
+
+/// Decodes a response into the same representation that parsing `dig`'s output produces
+pub fn decode_response(message: &[u8]) -> Result<DigOutput> {
+    let flags = read_u16(message, 2)?;
+    let question_count = read_u16(message, 4)?;
+    let section_counts = [
+        read_u16(message, 6)?,
+        read_u16(message, 8)?,
+        read_u16(message, 10)?,
+    ];
+
+    let mut offset = HEADER_LEN;
+    for _ in 0..question_count {
+        let (_qname, next) = read_name(message, offset)?;
+        // QTYPE and QCLASS
+        offset = next + 4;
+    }
+
+    let mut sections: [Vec<Record>; 3] = Default::default();
+    let mut opt = None;
+    for (section, count) in sections.iter_mut().zip(section_counts) {
+        for _ in 0..count {
+            let (owner, next) = read_name(message, offset)?;
+            let record_type = read_u16(message, next)?;
+            let class = read_u16(message, next + 2)?;
+            let ttl = read_u32(message, next + 4)?;
+            let rdata_len = usize::from(read_u16(message, next + 8)?);
+            let rdata_offset = next + 10;
+            let rdata = message
+                .get(rdata_offset..rdata_offset + rdata_len)
+                .ok_or("truncated RDATA")?;
+            offset = rdata_offset + rdata_len;
+
+            if record_type == OPT {
+                if opt.is_some() {
+                    return Err("more than one OPT pseudo-record".into());
+                }
+                opt = Some((class, ttl, rdata));
+            } else {
+                section.push(decode_record(
+                    message,
+                    &owner,
+                    record_type,
+                    ttl,
+                    rdata_offset,
+                    rdata,
+                )?);
+            }
+        }
+    }
+    let [answer, authority, additional] = sections;
+
+    let mut output = DigOutput {
+        ede: BTreeSet::new(),
+        ede_extra_text: BTreeMap::new(),
+        flags: DigFlags {
+            qr: flags & 0x8000 != 0,
+            authoritative_answer: flags & 0x0400 != 0,
+            truncation: flags & 0x0200 != 0,
+            recursion_desired: flags & 0x0100 != 0,
+            recursion_available: flags & 0x0080 != 0,
+            authenticated_data: flags & 0x0020 != 0,
+            checking_disabled: flags & 0x0010 != 0,
+        },
+        status: DigStatus::from_code(flags & 0x000f),
+        answer,
+        authority,
+        additional,
+        opt: opt.is_some(),
+        options: vec![],
+        must_be_zero: flags & 0x0040 != 0,
+        opcode: opcode_name((flags >> 11) & 0x0f),
+        nsid: None,
+        cookie: None,
+        ecs_scope: None,
+        query_time: None,
+        msg_size: Some(message.len()),
+        edns: None,
+        raw: Some(message.to_vec()),
+    };
+
+    if let Some((udp_payload_size, ttl, rdata)) = opt {
+        let [extended_rcode, version, ..] = ttl.to_be_bytes();
+        let dnssec_ok = ttl & 0x8000 != 0;
+        let must_be_zero = (ttl & 0x7fff) as u16;
+
+        output.status = DigStatus::from_code((u16::from(extended_rcode) << 4) | (flags & 0x000f));
+        output.edns = Some(EdnsInfo {
+            version,
+            dnssec_ok,
+            udp_payload_size,
+            must_be_zero,
+        });
+
+        decode_options(rdata, &mut output)?;
+    }
+
+    Ok(output)
+}
+
+fn decode_options(mut rdata: &[u8], output: &mut DigOutput) -> Result<()> {
+    while !rdata.is_empty() {
+        let code = read_u16(rdata, 0)?;
+        let len = usize::from(read_u16(rdata, 2)?);
+        let data = rdata.get(4..4 + len).ok_or("truncated EDNS option")?;
+        rdata = &rdata[4 + len..];
+
+        match code {
+            NSID => output.nsid = Some(String::from_utf8_lossy(data).into_owned()),
+            COOKIE => output.cookie = Some(data.to_vec()),
+            CLIENT_SUBNET => {
+                // FAMILY (2 bytes), SOURCE PREFIX-LENGTH, SCOPE PREFIX-LENGTH, ADDRESS
+                output.ecs_scope = Some(*data.get(3).ok_or("truncated CLIENT-SUBNET option")?);
+            }
+            EDE => {
+                let info_code = read_u16(data, 0)?;
+                let ede = info_code.to_string().parse()?;
+                output.ede.insert(ede);
+
+                let extra_text = String::from_utf8_lossy(&data[2..]);
+                if !extra_text.is_empty() {
+                    output.ede_extra_text.insert(ede, extra_text.into_owned());
+                }
+            }
+            _ => {
+                let mut hex = String::new();
+                for byte in data {
+                    if !hex.is_empty() {
+                        hex.push(' ');
+                    }
+                    write!(hex, "{byte:02x}")?;
+                }
+                output.options.push((code, hex));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Decodes the record types that have their own [`Record`] variant; every other type, and
+/// RDATA of an unexpected length, becomes a [`Record::Unknown`]
+fn decode_record(
+    message: &[u8],
+    owner: &FQDN,
+    record_type: u16,
+    ttl: u32,
+    rdata_offset: usize,
+    rdata: &[u8],
+) -> Result<Record> {
+    let fqdn = owner.clone();
+    let name_at = |offset| read_name(message, rdata_offset + offset).map(|(name, _)| name);
+
+    let record = match RecordType::from_code(record_type) {
+        RecordType::A if rdata.len() == 4 => Record::A(A {
+            fqdn,
+            ttl,
+            ipv4_addr: Ipv4Addr::from(<[u8; 4]>::try_from(rdata)?),
+        }),
+        RecordType::AAAA if rdata.len() == 16 => Record::AAAA(AAAA {
+            fqdn,
+            ttl,
+            ipv6_addr: Ipv6Addr::from(<[u8; 16]>::try_from(rdata)?),
+        }),
+        RecordType::CNAME => Record::CNAME(CNAME {
+            fqdn,
+            ttl,
+            target: name_at(0)?,
+        }),
+        RecordType::DNAME => Record::DNAME(DNAME {
+            fqdn,
+            ttl,
+            target: name_at(0)?,
+        }),
+        RecordType::NS => Record::NS(NS {
+            zone: fqdn,
+            ttl,
+            nameserver: name_at(0)?,
+        }),
+        RecordType::PTR => Record::PTR(PTR {
+            fqdn,
+            ttl,
+            target: name_at(0)?,
+        }),
+        RecordType::MX => Record::MX(MX {
+            fqdn,
+            ttl,
+            preference: read_u16(rdata, 0)?,
+            exchange: name_at(2)?,
+        }),
+        RecordType::SOA => {
+            let (nameserver, next) = read_name(message, rdata_offset)?;
+            let (admin, next) = read_name(message, next)?;
+            Record::SOA(SOA {
+                zone: fqdn,
+                ttl,
+                nameserver,
+                admin,
+                settings: SoaSettings {
+                    serial: read_u32(message, next)?,
+                    refresh: read_u32(message, next + 4)?,
+                    retry: read_u32(message, next + 8)?,
+                    expire: read_u32(message, next + 12)?,
+                    minimum: read_u32(message, next + 16)?,
+                },
+            })
+        }
+        _ => Record::Unknown(UnknownRdata {
+            zone: fqdn,
+            ttl,
+            r#type: record_type,
+            rdata: rdata.to_vec(),
+        }),
+    };
+
+    Ok(record)
+}
+
+/// Reads the, possibly compressed, domain name at `offset`
+///
+/// Returns the name and the offset of the byte that follows it in the record
+fn read_name(message: &[u8], mut offset: usize) -> Result<(FQDN, usize)> {
+    // each pointer must point backwards so this bounds the number of jumps
+    let mut lowest_offset = offset;
+    let mut end = None;
+    let mut labels = vec![];
+
+    loop {
+        let len = *message.get(offset).ok_or("truncated domain name")?;
+        match len & 0xc0 {
+            0x00 if len == 0 => {
+                let end = end.unwrap_or(offset + 1);
+                return Ok((FQDN::from_wire_labels(labels), end));
+            }
+            0x00 => {
+                let len = usize::from(len);
+                let label = message
+                    .get(offset + 1..offset + 1 + len)
+                    .ok_or("truncated label")?;
+                labels.push(label);
+                offset += 1 + len;
+            }
+            0xc0 => {
+                let pointer = usize::from(read_u16(message, offset)? & 0x3fff);
+                if pointer >= lowest_offset {
+                    return Err(format!("invalid compression pointer at offset {offset}").into());
+                }
+                end.get_or_insert(offset + 2);
+                lowest_offset = pointer;
+                offset = pointer;
+            }
+            _ => return Err(format!("unsupported label type at offset {offset}").into()),
+        }
+    }
+}
+
+fn opcode_name(opcode: u16) -> String {
+    match opcode {
+        0 => "QUERY".into(),
+        1 => "IQUERY".into(),
+        2 => "STATUS".into(),
+        4 => "NOTIFY".into(),
+        5 => "UPDATE".into(),
+        _ => format!("RESERVED{opcode}"),
+    }
+}
+
+pub(crate) fn read_u16(bytes: &[u8], offset: usize) -> Result<u16> {
+    let bytes = bytes
+        .get(offset..offset + 2)
+        .ok_or("unexpected end of message")?;
+    Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32> {
+    let bytes = bytes
+        .get(offset..offset + 4)
+        .ok_or("unexpected end of message")?;
+    Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_compressed_response() -> Result<()> {
+        let fqdn = FQDN("example.testing.")?;
+        let query = encode_query(0xabcd, RecordType::MX, &fqdn, false)?;
+
+        let mut response = query[..HEADER_LEN].to_vec();
+        // QR and AA; NOERROR
+        response[2..4].copy_from_slice(&0x8400u16.to_be_bytes());
+        // 1 question, 2 answers, no authority, no additional records
+        response[4..12].copy_from_slice(&[0, 1, 0, 2, 0, 0, 0, 0]);
+        // the question, at offset 12
+        response.extend(&query[HEADER_LEN..query.len() - 11]);
+        // example.testing. 300 IN MX 10 mail.example.testing.
+        response.extend([0xc0, 12, 0, 15, 0, 1, 0, 0, 1, 44, 0, 9, 0, 10]);
+        response.extend([4, b'm', b'a', b'i', b'l', 0xc0, 12]);
+        // mail.example.testing. 300 IN A 1.2.3.4
+        let mail_offset = (response.len() - 7) as u8;
+        response.extend([0xc0, mail_offset, 0, 1, 0, 1, 0, 0, 1, 44, 0, 4, 1, 2, 3, 4]);
+
+        assert_eq!(0xabcd, message_id(&response)?);
+        let output = decode_response(&response)?;
+
+        assert_eq!(Some(&response), output.raw.as_ref());
+
+        assert!(output.status.is_noerror());
+        assert!(output.flags.qr);
+        assert!(output.flags.authoritative_answer);
+        assert!(!output.opt);
+
+        let [mx, a] = output.answer.try_into().unwrap();
+        let mx = mx.try_into_mx().unwrap();
+        assert_eq!(fqdn, mx.fqdn);
+        assert_eq!(10, mx.preference);
+        assert_eq!(FQDN("mail.example.testing.")?, mx.exchange);
+        let a = a.try_into_a().unwrap();
+        assert_eq!(FQDN("mail.example.testing.")?, a.fqdn);
+        assert_eq!(Ipv4Addr::new(1, 2, 3, 4), a.ipv4_addr);
+
+        Ok(())
+    }
+
+    #[test]
+    fn decodes_soa_and_unknown_records() -> Result<()> {
+        let mut response = encode_query(1, RecordType::SOA, &FQDN::ROOT, false)?;
+        // QR; no additional records
+        response[2..4].copy_from_slice(&0x8000u16.to_be_bytes());
+        response[4..12].copy_from_slice(&[0, 1, 0, 2, 0, 0, 0, 0]);
+        response.truncate(response.len() - 11);
+
+        // . 300 IN SOA a\.b. c. 1 2 3 4 5
+        response.extend([0, 0, 6, 0, 1, 0, 0, 1, 44, 0, 28]);
+        response.extend([3, b'a', b'.', b'b', 0, 1, b'c', 0]);
+        for field in 1..=5u32 {
+            response.extend(field.to_be_bytes());
+        }
+        // . 300 IN TXT "hi"
+        response.extend([0, 0, 16, 0, 1, 0, 0, 1, 44, 0, 3, 2, b'h', b'i']);
+
+        let output = decode_response(&response)?;
+
+        let [soa, txt] = output.answer.try_into().unwrap();
+        let Record::SOA(soa) = soa else {
+            panic!("not an SOA record: {soa:?}");
+        };
+        assert_eq!(FQDN(r"a\.b.")?, soa.nameserver);
+        assert_eq!(FQDN("c.")?, soa.admin);
+        assert_eq!(1, soa.settings.serial);
+        assert_eq!(5, soa.settings.minimum);
+
+        let Record::Unknown(txt) = txt else {
+            panic!("not an unknown record: {txt:?}");
+        };
+        assert_eq!(16, txt.r#type);
+        assert_eq!(300, txt.ttl);
+        assert_eq!(vec![2, b'h', b'i'], txt.rdata);
+
+        Ok(())
+    }
+
+    #[test]
+    fn decodes_opt_pseudo_record() -> Result<()> {
+        let mut response = encode_query(1, RecordType::A, &FQDN::ROOT, false)?;
+        // QR; header RCODE 0
+        response[2..4].copy_from_slice(&0x8000u16.to_be_bytes());
+        // extended RCODE 1, i.e. BADVERS, version 0 and the DO bit
+        let opt_ttl = response.len() - 6;
+        response[opt_ttl..opt_ttl + 4].copy_from_slice(&[1, 0, 0x80, 0]);
+
+        let output = decode_response(&response)?;
+
+        assert_eq!(DigStatus::BADVERS, output.status);
+        assert_eq!(
+            Some(EdnsInfo {
+                version: 0,
+                dnssec_ok: true,
+                udp_payload_size: UDP_PAYLOAD_SIZE,
+                must_be_zero: 0,
+            }),
+            output.edns
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_compression_loop() {
+        let mut message = encode_query(1, RecordType::A, &FQDN::ROOT, false).unwrap();
+        // the QNAME points to itself
+        message.splice(HEADER_LEN..HEADER_LEN + 1, [0xc0, 12]);
+
+        assert!(decode_response(&message).is_err());
+    }
+}
@@ -6,7 +6,7 @@
 
 use core::fmt;
 use std::array;
-use std::net::Ipv4Addr;
+use std::net::{Ipv4Addr, Ipv6Addr};
 use std::str::FromStr;
 
 use crate::record::{self, DNSKEYRData, RRSIG, Record, RecordType, SOA, write_split_long_string};
@@ -14,7 +14,7 @@ use crate::{DEFAULT_TTL, Error, FQDN, Result};
 
 mod signer;
 
-pub use signer::{Nsec, SignSettings, Signer};
+pub use signer::{DsDigest, Nsec, SignAlgorithm, SignSettings, Signer};
 
 #[derive(Clone)]
 pub struct ZoneFile {
@@ -52,9 +52,126 @@ impl ZoneFile {
         self.add(Record::a(nameserver, ipv4_addr));
     }
 
+    /// Compares this zone file against `other`
+    ///
+    /// Records present in `other` but not in `self` are reported as `added`; records present in
+    /// `self` but not in `other` are reported as `removed`. The SOA record takes part in the
+    /// comparison; the order of the records does not.
+    ///
+    /// A typical use is diffing an unsigned zone file against its signed version
+    pub fn diff(&self, other: &ZoneFile) -> ZoneFileDiff {
+        let ours = self.all_records();
+        let theirs = other.all_records();
+
+        let added = theirs
+            .iter()
+            .filter(|record| !ours.contains(record))
+            .map(|record| (*record).clone())
+            .collect();
+        let removed = ours
+            .iter()
+            .filter(|record| !theirs.contains(record))
+            .map(|record| (*record).clone())
+            .collect();
+
+        ZoneFileDiff { added, removed }
+    }
+
+    /// Checks that this zone file and `other` differ only in their DNSKEY and RRSIG records, as is
+    /// the case when the same zone is signed before and after a key rollover
+    ///
+    /// On failure, the returned diff holds the records that were not expected to change
+    pub fn check_differs_only_in_signatures(
+        &self,
+        other: &ZoneFile,
+    ) -> core::result::Result<(), ZoneFileDiff> {
+        let is_key_or_signature = |record: &Record| {
+            matches!(record.record_type(), RecordType::DNSKEY | RecordType::RRSIG)
+        };
+
+        let mut diff = self.diff(other);
+        diff.added.retain(|record| !is_key_or_signature(record));
+        diff.removed.retain(|record| !is_key_or_signature(record));
+
+        if diff.is_empty() { Ok(()) } else { Err(diff) }
+    }
+
     pub(crate) fn origin(&self) -> &FQDN {
         &self.origin
     }
+
+    /// Checks that the Original TTL field of every RRSIG matches the TTL of the RRset it covers, as
+    /// required by section 3.1.4 of RFC4034
+    ///
+    /// RRSIGs that cover an RRset that's not in the zone file are not checked
+    pub fn check_rrsig_original_ttls(&self) -> core::result::Result<(), Vec<OriginalTtlMismatch>> {
+        let records = self.all_records();
+
+        let mut mismatches = vec![];
+        for record in &records {
+            let Record::RRSIG(rrsig) = record else {
+                continue;
+            };
+
+            let mismatched_ttl = records
+                .iter()
+                .filter(|covered| {
+                    covered.owner() == &rrsig.fqdn && covered.record_type() == rrsig.type_covered
+                })
+                .map(Record::ttl)
+                .find(|ttl| *ttl != rrsig.original_ttl);
+
+            if let Some(rrset_ttl) = mismatched_ttl {
+                mismatches.push(OriginalTtlMismatch {
+                    rrsig: rrsig.clone(),
+                    rrset_ttl,
+                });
+            }
+        }
+
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(mismatches)
+        }
+    }
+
+    fn all_records(&self) -> Vec<Record> {
+        let mut records = Vec::with_capacity(self.records.len() + 1);
+        records.push(Record::SOA(self.soa.clone()));
+        records.extend(self.records.iter().cloned());
+        records
+    }
+}
+
+/// The output of [`ZoneFile::diff`]
+#[derive(Debug, Default)]
+pub struct ZoneFileDiff {
+    pub added: Vec<Record>,
+    pub removed: Vec<Record>,
+}
+
+impl ZoneFileDiff {
+    /// Returns `true` if both zone files contain the same records
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+
+    /// Returns `true` if all `added` records are of the given types and no record was removed
+    pub fn only_added(&self, record_types: &[RecordType]) -> bool {
+        self.removed.is_empty()
+            && self
+                .added
+                .iter()
+                .all(|record| record_types.contains(&record.record_type()))
+    }
+}
+
+/// An RRSIG whose Original TTL field differs from the TTL of the RRset it covers
+#[derive(Debug)]
+pub struct OriginalTtlMismatch {
+    pub rrsig: RRSIG,
+    pub rrset_ttl: u32,
 }
 
 impl fmt::Display for ZoneFile {
@@ -114,6 +231,8 @@ impl FromStr for ZoneFile {
 #[derive(Clone)]
 pub struct Root {
     pub ipv4_addr: Ipv4Addr,
+    /// Also written out as an AAAA record when set
+    pub ipv6_addr: Option<Ipv6Addr>,
     pub ns: FQDN,
     pub ttl: u32,
 }
@@ -123,6 +242,7 @@ impl Root {
     pub fn new(ns: FQDN, ipv4_addr: Ipv4Addr) -> Self {
         Self {
             ipv4_addr,
+            ipv6_addr: None,
             ns,
             ttl: DEFAULT_TTL,
         }
@@ -131,6 +251,7 @@ impl Root {
     pub fn public_dns() -> Root {
         Root {
             ipv4_addr: Ipv4Addr::new(198, 41, 0, 4),
+            ipv6_addr: None,
             ns: FQDN("a.root-servers.net.").unwrap(),
             ttl: DEFAULT_TTL,
         }
@@ -139,10 +260,20 @@ impl Root {
 
 impl fmt::Display for Root {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let Self { ipv4_addr, ns, ttl } = self;
+        let Self {
+            ipv4_addr,
+            ipv6_addr,
+            ns,
+            ttl,
+        } = self;
 
         writeln!(f, ".\t{ttl}\tNS\t{ns}")?;
-        write!(f, "{ns}\t{ttl}\tA\t{ipv4_addr}")
+        write!(f, "{ns}\t{ttl}\tA\t{ipv4_addr}")?;
+        if let Some(ipv6_addr) = ipv6_addr {
+            write!(f, "\n{ns}\t{ttl}\tAAAA\t{ipv6_addr}")?;
+        }
+
+        Ok(())
     }
 }
 
@@ -166,6 +297,16 @@ impl DNSKEY {
     pub(crate) fn rdata(&self) -> &DNSKEYRData {
         &self.rdata
     }
+
+    pub fn calculate_key_tag(&self) -> u16 {
+        self.rdata.calculate_key_tag()
+    }
+
+    /// Returns `true` if the Secure Entry Point bit is set
+    pub fn is_key_signing_key(&self) -> bool {
+        let mask = record::DNSKEY::KSK_BIT;
+        self.rdata.flags & mask == mask
+    }
 }
 
 impl FromStr for DNSKEY {
@@ -244,6 +385,10 @@ pub struct Keypair {
 pub struct SigningKeys {
     pub ksk: Keypair,
     pub zsk: Keypair,
+    /// Additional KSKs, e.g. to roll over the KSK; these also sign the DNSKEY RRset
+    pub extra_ksks: Vec<Keypair>,
+    /// Additional ZSKs, e.g. to roll over the ZSK; these also sign every RRset
+    pub extra_zsks: Vec<Keypair>,
 }
 
 #[cfg(test)]
@@ -277,6 +422,23 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn per_record_ttl() -> Result<()> {
+        let mut zone: ZoneFile = include_str!("muster.zone").parse()?;
+        let record = Record::a(FQDN::EXAMPLE_SUBDOMAIN, Ipv4Addr::new(1, 2, 3, 4)).with_ttl(5);
+        zone.add(record.clone());
+
+        let output = zone.to_string();
+        assert!(output.lines().any(|line| line == record.to_string()));
+        assert!(record.to_string().contains("\t5\t"));
+
+        let zone: ZoneFile = output.parse()?;
+        assert_eq!(Some(&record), zone.records.last());
+        assert_eq!(5, zone.records.last().unwrap().ttl());
+
+        Ok(())
+    }
+
     #[test]
     fn roundtrip() -> Result<()> {
         // `ldns-signzone`'s output minus trailing comments; long trailing fields have been split as well
@@ -287,4 +449,106 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn diff_signed_against_unsigned() -> Result<()> {
+        let signed: ZoneFile = include_str!("muster.zone").parse()?;
+        let dnssec_types = [
+            RecordType::DNSKEY,
+            RecordType::NSEC3,
+            RecordType::NSEC3PARAM,
+            RecordType::RRSIG,
+        ];
+
+        let mut unsigned = signed.clone();
+        unsigned
+            .records
+            .retain(|record| !dnssec_types.contains(&record.record_type()));
+
+        assert!(unsigned.diff(&unsigned).is_empty());
+
+        let diff = unsigned.diff(&signed);
+        assert!(diff.only_added(&dnssec_types));
+        assert_eq!(
+            signed.records.len() - unsigned.records.len(),
+            diff.added.len()
+        );
+
+        let diff = signed.diff(&unsigned);
+        assert!(diff.added.is_empty());
+        assert_eq!(
+            diff.removed.len(),
+            signed.records.len() - unsigned.records.len()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn differs_only_in_signatures() -> Result<()> {
+        let old: ZoneFile = include_str!("muster.zone").parse()?;
+        assert!(old.check_differs_only_in_signatures(&old).is_ok());
+
+        // what a key rollover looks like: new keys and new signatures
+        let mut new = old.clone();
+        for record in &mut new.records {
+            match record {
+                Record::DNSKEY(dnskey) => dnskey.rdata.public_key.insert(0, 'A'),
+                Record::RRSIG(rrsig) => rrsig.key_tag = rrsig.key_tag.wrapping_add(1),
+                _ => {}
+            }
+        }
+        assert!(!old.diff(&new).is_empty());
+        assert!(old.check_differs_only_in_signatures(&new).is_ok());
+
+        // unsigned data must not change
+        let Some(Record::NS(ns)) = new
+            .records
+            .iter_mut()
+            .find(|record| matches!(record, Record::NS(_)))
+        else {
+            panic!("muster.zone has no NS record");
+        };
+        ns.nameserver = ns.nameserver.push_label("changed");
+
+        let diff = old.check_differs_only_in_signatures(&new).unwrap_err();
+        let [added] = diff.added.try_into().unwrap();
+        let [removed] = diff.removed.try_into().unwrap();
+        assert_eq!(RecordType::NS, added.record_type());
+        assert_eq!(RecordType::NS, removed.record_type());
+
+        Ok(())
+    }
+
+    #[test]
+    fn rrsig_original_ttls() -> Result<()> {
+        let mut zone: ZoneFile = include_str!("muster.zone").parse()?;
+        assert!(zone.check_rrsig_original_ttls().is_ok());
+
+        let rrsig = zone
+            .rrsig_mut(RecordType::SOA)
+            .expect("RRSIG covering the SOA");
+        rrsig.original_ttl += 1;
+        let soa_ttl = zone.soa.ttl;
+
+        let mismatches = zone.check_rrsig_original_ttls().unwrap_err();
+        let [mismatch] = mismatches.try_into().expect("exactly one mismatch");
+        assert_eq!(RecordType::SOA, mismatch.rrsig.type_covered);
+        assert_eq!(soa_ttl, mismatch.rrset_ttl);
+
+        Ok(())
+    }
+
+    #[test]
+    fn root_hint_with_ipv6_addr() -> Result<()> {
+        let mut root = Root::new(FQDN("a.nic.")?, Ipv4Addr::new(172, 18, 0, 2));
+        root.ipv6_addr = Some(Ipv6Addr::new(0xfd00, 0, 0, 1, 0, 0, 0, 2));
+
+        let expected = ".\t86400\tNS\ta.nic.
+a.nic.\t86400\tA\t172.18.0.2
+a.nic.\t86400\tAAAA\tfd00:0:0:1::2";
+        assert_eq!(expected, root.to_string());
+
+        Ok(())
+    }
 }
@@ -7,7 +7,7 @@ use crate::{
     FQDN,
     container::Container,
     name_server::{DS2, Signed},
-    record::DS,
+    record::{CDNSKEY, CDS, DS},
 };
 
 use super::{DNSKEY, Keypair, SigningKeys, ZoneFile};
@@ -24,87 +24,69 @@ fn zone_file_path() -> String {
 pub struct SignSettings {
     zsk_bits: Option<u16>,
     ksk_bits: Option<u16>,
-    algorithm: Algorithm,
+    rsa_key_bits: Option<u16>,
+    algorithm: SignAlgorithm,
     expiration: Option<u64>,
     inception: Option<u64>,
     nsec: Nsec,
+    publish_cds: bool,
+    ds_digest: DsDigest,
+    extra_ksks: u8,
+    extra_zsks: u8,
     implementation: Implementation,
 }
 
 impl SignSettings {
     pub fn rsasha1_nsec3() -> Self {
-        Self {
-            algorithm: Algorithm::RSASHA1_NSEC3,
-            zsk_bits: Some(1_024),
-            ksk_bits: Some(2_048),
-            expiration: None,
-            inception: None,
-            nsec: Nsec::default(),
-            implementation: Implementation::default(),
-        }
+        Self::new(SignAlgorithm::RSASHA1_NSEC3)
     }
 
     pub fn dsa() -> Self {
-        Self {
-            algorithm: Algorithm::DSA,
-            zsk_bits: Some(1_024),
-            ksk_bits: Some(1_024),
-            expiration: None,
-            inception: None,
-            nsec: Nsec::default(),
-            implementation: Implementation::default(),
-        }
+        Self::new(SignAlgorithm::DSA)
     }
 
     pub fn rsamd5() -> Self {
-        Self {
-            algorithm: Algorithm::RSAMD5,
-            zsk_bits: Some(2_048),
-            ksk_bits: Some(2_048),
-            expiration: None,
-            inception: None,
-            nsec: Nsec::default(),
-            implementation: Implementation::default(),
-        }
+        Self::new(SignAlgorithm::RSAMD5)
     }
 
     pub fn rsasha256_nsec3_optout() -> Self {
         Self {
-            algorithm: Algorithm::RSASHA256,
-            // 2048-bit SHA256 matches `$ dig DNSKEY .` in length
-            zsk_bits: Some(2_048),
-            ksk_bits: Some(2_048),
-            expiration: None,
-            inception: None,
             nsec: Nsec::_3 {
                 salt: None,
                 opt_out: true,
+                iterations: None,
             },
             implementation: Implementation::Bindutils,
+            ..Self::new(SignAlgorithm::RSASHA256)
         }
     }
 
     pub fn rsasha256() -> Self {
-        Self {
-            algorithm: Algorithm::RSASHA256,
-            // 2048-bit SHA256 matches `$ dig DNSKEY .` in length
-            zsk_bits: Some(2_048),
-            ksk_bits: Some(2_048),
-            expiration: None,
-            inception: None,
-            nsec: Nsec::default(),
-            implementation: Implementation::default(),
-        }
+        Self::new(SignAlgorithm::RSASHA256)
     }
 
     pub fn ecdsap256sha256() -> Self {
+        Self::new(SignAlgorithm::ECDSAP256SHA256)
+    }
+
+    pub fn ed25519() -> Self {
+        Self::new(SignAlgorithm::ED25519)
+    }
+
+    fn new(algorithm: SignAlgorithm) -> Self {
+        let (zsk_bits, ksk_bits) = algorithm.key_bits();
         Self {
-            algorithm: Algorithm::ECDSAP256SHA256,
-            zsk_bits: None,
-            ksk_bits: None,
+            algorithm,
+            zsk_bits,
+            ksk_bits,
+            rsa_key_bits: None,
             expiration: None,
             inception: None,
             nsec: Nsec::default(),
+            publish_cds: false,
+            ds_digest: DsDigest::default(),
+            extra_ksks: 0,
+            extra_zsks: 0,
             implementation: Implementation::default(),
         }
     }
@@ -127,11 +109,122 @@ impl SignSettings {
         self
     }
 
+    /// Sets the window in which the RRSIGs are valid
+    pub fn signature_validity(self, inception: SystemTime, expiration: SystemTime) -> Self {
+        self.inception(inception).expiration(expiration)
+    }
+
+    /// Changes the signing algorithm and the key sizes to the ones that go with it
+    pub fn algorithm(mut self, algorithm: SignAlgorithm) -> Self {
+        (self.zsk_bits, self.ksk_bits) = algorithm.key_bits();
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Sets the size of both the ZSK and the KSK for RSA algorithms, overriding the algorithm's
+    /// default
+    ///
+    /// Signing fails if the algorithm is not RSA-based, whether it was chosen before or after
+    /// calling this
+    pub fn rsa_key_bits(mut self, bits: u16) -> Self {
+        self.rsa_key_bits = Some(bits);
+        self
+    }
+
     /// Changes the NSEC policy (default is NSEC3; see `Nsec::default`)
     pub fn nsec(mut self, nsec: Nsec) -> Self {
         self.nsec = nsec;
         self
     }
+
+    /// Sets the NSEC3 salt; `None` or an empty salt means no salt
+    ///
+    /// Switches to NSEC3 if NSEC was in use
+    pub fn nsec3_salt(mut self, salt: Option<Vec<u8>>) -> Self {
+        if let Nsec::_3 { salt: current, .. } = self.nsec3_mut() {
+            *current = salt.filter(|salt| !salt.is_empty()).map(hex::encode);
+        }
+        self
+    }
+
+    /// Sets whether the NSEC3 chain skips unsigned delegations (RFC5155 section 6)
+    ///
+    /// Switches to NSEC3 if NSEC was in use
+    pub fn nsec3_opt_out(mut self, enabled: bool) -> Self {
+        if let Nsec::_3 { opt_out, .. } = self.nsec3_mut() {
+            *opt_out = enabled;
+        }
+        self
+    }
+
+    /// Sets the number of additional NSEC3 hash iterations
+    ///
+    /// Switches to NSEC3 if NSEC was in use
+    pub fn nsec3_iterations(mut self, iterations: u16) -> Self {
+        if let Nsec::_3 {
+            iterations: current,
+            ..
+        } = self.nsec3_mut()
+        {
+            *current = Some(iterations);
+        }
+        self
+    }
+
+    /// Adds CDS and CDNSKEY records (RFC7344) derived from the KSK to the zone before it's signed
+    pub fn publish_cds(mut self, enabled: bool) -> Self {
+        self.publish_cds = enabled;
+        self
+    }
+
+    /// Sets the digest type of the DS records computed from the zone's keys, and of the CDS
+    /// record if [`SignSettings::publish_cds`] is enabled
+    pub fn ds_digest(mut self, digest: DsDigest) -> Self {
+        self.ds_digest = digest;
+        self
+    }
+
+    /// Generates one more KSK in [`Signer::generate_keys`], e.g. to test KSK rollovers
+    pub fn extra_ksk(mut self) -> Self {
+        self.extra_ksks += 1;
+        self
+    }
+
+    /// Generates one more ZSK in [`Signer::generate_keys`], e.g. to test ZSK rollovers
+    ///
+    /// Hickory name servers re-sign the zone with a single ZSK of their own so this has no effect
+    /// on them
+    pub fn extra_zsk(mut self) -> Self {
+        self.extra_zsks += 1;
+        self
+    }
+
+    fn validate(&self) -> crate::Result<()> {
+        let algorithm = self.algorithm;
+        if self.rsa_key_bits.is_some() && !algorithm.is_rsa() {
+            return Err(
+                format!("an RSA key size was set but {algorithm} is not an RSA algorithm").into(),
+            );
+        }
+
+        if let (Some(inception), Some(expiration)) = (self.inception, self.expiration) {
+            if inception >= expiration {
+                return Err(format!(
+                    "signature inception ({inception}) is not before its expiration ({expiration})"
+                )
+                .into());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn nsec3_mut(&mut self) -> &mut Nsec {
+        if let Nsec::_1 = self.nsec {
+            self.nsec = Nsec::default();
+        }
+        &mut self.nsec
+    }
 }
 
 impl Default for SignSettings {
@@ -143,7 +236,13 @@ impl Default for SignSettings {
 #[derive(Clone)]
 pub enum Nsec {
     _1,
-    _3 { opt_out: bool, salt: Option<String> },
+    _3 {
+        opt_out: bool,
+        /// Hex encoded
+        salt: Option<String>,
+        /// `None` uses the signer's default, which is 1 for `ldns-signzone`
+        iterations: Option<u16>,
+    },
 }
 
 impl Default for Nsec {
@@ -151,27 +250,100 @@ impl Default for Nsec {
         Self::_3 {
             opt_out: false,
             salt: None,
+            iterations: None,
         }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+/// DNSSEC signing algorithms, named as in the IANA "DNS Security Algorithm Numbers" registry
+#[derive(Debug, Clone, Copy, PartialEq)]
 #[allow(non_camel_case_types)]
 #[allow(clippy::upper_case_acronyms)]
-enum Algorithm {
+pub enum SignAlgorithm {
     DSA,
     RSAMD5,
     RSASHA1_NSEC3,
     RSASHA256,
     ECDSAP256SHA256,
+    ED25519,
+}
+
+impl SignAlgorithm {
+    /// The number that identifies the algorithm in DNSKEY, DS and RRSIG records
+    pub fn number(&self) -> u8 {
+        match self {
+            Self::RSAMD5 => 1,
+            Self::DSA => 3,
+            Self::RSASHA1_NSEC3 => 7,
+            Self::RSASHA256 => 8,
+            Self::ECDSAP256SHA256 => 13,
+            Self::ED25519 => 15,
+        }
+    }
+
+    /// Whether the key size of the algorithm can be chosen with [`SignSettings::rsa_key_bits`]
+    pub fn is_rsa(&self) -> bool {
+        matches!(self, Self::RSAMD5 | Self::RSASHA1_NSEC3 | Self::RSASHA256)
+    }
+
+    /// The (ZSK, KSK) sizes in bits, for the algorithms whose key size is not fixed
+    fn key_bits(&self) -> (Option<u16>, Option<u16>) {
+        match self {
+            Self::DSA => (Some(1_024), Some(1_024)),
+            Self::RSASHA1_NSEC3 => (Some(1_024), Some(2_048)),
+            // 2048-bit SHA256 matches `$ dig DNSKEY .` in length
+            Self::RSAMD5 | Self::RSASHA256 => (Some(2_048), Some(2_048)),
+            Self::ECDSAP256SHA256 | Self::ED25519 => (None, None),
+        }
+    }
 }
 
-impl fmt::Display for Algorithm {
+impl fmt::Display for SignAlgorithm {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt::Debug::fmt(self, f)
     }
 }
 
+/// DS record digest types, named as in the IANA "Delegation Signer (DS) Resource Record (RR)
+/// Type Digest Algorithms" registry
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum DsDigest {
+    SHA1,
+    #[default]
+    SHA256,
+    SHA384,
+}
+
+impl DsDigest {
+    /// The number that identifies the digest type in DS records
+    pub fn number(&self) -> u8 {
+        match self {
+            Self::SHA1 => 1,
+            Self::SHA256 => 2,
+            Self::SHA384 => 4,
+        }
+    }
+
+    /// The length of the digest, in bytes
+    pub fn digest_len(&self) -> usize {
+        match self {
+            Self::SHA1 => 20,
+            Self::SHA256 => 32,
+            Self::SHA384 => 48,
+        }
+    }
+
+    /// The `ldns-key2ds` flag that selects this digest type
+    fn key2ds_flag(&self) -> &'static str {
+        match self {
+            Self::SHA1 => "-1",
+            Self::SHA256 => "-2",
+            Self::SHA384 => "-4",
+        }
+    }
+}
+
 #[derive(Debug, Default, Clone, Copy)]
 enum Implementation {
     #[default]
@@ -182,7 +354,7 @@ enum Implementation {
 /// Generates the command string to generate ZSK using `ldns-keygen`
 pub fn ldns_keygen_zsk(settings: &SignSettings, zone: &FQDN) -> String {
     let algorithm = settings.algorithm;
-    match settings.zsk_bits {
+    match settings.rsa_key_bits.or(settings.zsk_bits) {
         Some(bits) => format!("ldns-keygen -a {algorithm} -b {bits} {zone}"),
         None => format!("ldns-keygen -a {algorithm} {zone}"),
     }
@@ -191,7 +363,7 @@ pub fn ldns_keygen_zsk(settings: &SignSettings, zone: &FQDN) -> String {
 /// Generates the command string to generate KSK using `ldns-keygen`
 pub fn ldns_keygen_ksk(settings: &SignSettings, zone: &FQDN) -> String {
     let algorithm = settings.algorithm;
-    match settings.ksk_bits {
+    match settings.rsa_key_bits.or(settings.ksk_bits) {
         Some(bits) => format!("ldns-keygen -k -a {algorithm} -b {bits} {zone}"),
         None => format!("ldns-keygen -k -a {algorithm} {zone}"),
     }
@@ -213,56 +385,175 @@ pub struct Signer<'a> {
 
 impl<'a> Signer<'a> {
     pub fn new(container: &'a Container, settings: SignSettings) -> crate::Result<Self> {
+        settings.validate()?;
+
         Ok(Self {
             container,
             settings,
         })
     }
 
-    /// Generates ZSK and KSK keys.
+    /// Generates ZSK and KSK keys, plus the extra ones requested in the [`SignSettings`].
     pub fn generate_keys(&self, zone: &FQDN) -> crate::Result<SigningKeys> {
+        self.generate_keys_with_ksk(zone, None)
+    }
+
+    /// Uses an existing key pair as the KSK of `zone` and generates the other keys like
+    /// [`Signer::generate_keys`] does
+    ///
+    /// `private` is the contents of the `.private` file that `ldns-keygen` produced along with
+    /// `public`. The owner of `public` is replaced with `zone` so the same key material can sign
+    /// several zones.
+    pub fn import_key(
+        &self,
+        zone: &FQDN,
+        private: &str,
+        public: DNSKEY,
+    ) -> crate::Result<SigningKeys> {
+        if !public.is_key_signing_key() {
+            return Err("the imported key is not a key signing key".into());
+        }
+
+        let algorithm = self.settings.algorithm.number();
+        if public.rdata.algorithm != algorithm {
+            return Err(format!(
+                "the imported key uses algorithm {} but the settings use {algorithm}",
+                public.rdata.algorithm
+            )
+            .into());
+        }
+
+        let ksk = Keypair {
+            public: DNSKEY {
+                zone: zone.clone(),
+                ..public
+            },
+            private: private.to_string(),
+        };
+        self.generate_keys_with_ksk(zone, Some(ksk))
+    }
+
+    fn generate_keys_with_ksk(
+        &self,
+        zone: &FQDN,
+        ksk: Option<Keypair>,
+    ) -> crate::Result<SigningKeys> {
         self.container.status_ok(&["mkdir", "-p", KEYS_DIR])?;
-        let zsk = self.gen_zsk_key(zone)?;
-        let ksk = self.gen_ksk_key(zone, zsk.public.rdata.calculate_key_tag())?;
-        Ok(SigningKeys { ksk, zsk })
+        let mut key_tags = vec![];
+        let ksk = match ksk {
+            Some(ksk) => {
+                key_tags.push(ksk.public.rdata.calculate_key_tag());
+                ksk
+            }
+            None => {
+                self.gen_key_with_unique_tag(&ldns_keygen_ksk(&self.settings, zone), &mut key_tags)?
+            }
+        };
+        let zsk =
+            self.gen_key_with_unique_tag(&ldns_keygen_zsk(&self.settings, zone), &mut key_tags)?;
+
+        let extra_zsks = (0..self.settings.extra_zsks)
+            .map(|_| {
+                self.gen_key_with_unique_tag(&ldns_keygen_zsk(&self.settings, zone), &mut key_tags)
+            })
+            .collect::<crate::Result<_>>()?;
+        let extra_ksks = (0..self.settings.extra_ksks)
+            .map(|_| {
+                self.gen_key_with_unique_tag(&ldns_keygen_ksk(&self.settings, zone), &mut key_tags)
+            })
+            .collect::<crate::Result<_>>()?;
+
+        Ok(SigningKeys {
+            ksk,
+            zsk,
+            extra_ksks,
+            extra_zsks,
+        })
     }
 
     /// Signs the [`ZoneFile`] with the [`SignSettings`].
     pub fn sign_zone(&self, zone_file: &ZoneFile, keys: &SigningKeys) -> crate::Result<Signed> {
+        self.sign_zone_with_additional_keys(zone_file, keys, &[])
+    }
+
+    /// Like [`Signer::sign_zone`] but the zone is also signed with `additional_keys`, which may
+    /// use algorithms other than the one in the [`SignSettings`]
+    ///
+    /// The DS records and keys in the returned value correspond to `keys`
+    pub fn sign_zone_with_additional_keys(
+        &self,
+        zone_file: &ZoneFile,
+        keys: &SigningKeys,
+        additional_keys: &[SigningKeys],
+    ) -> crate::Result<Signed> {
         self.container.status_ok(&["mkdir", "-p", ZONES_DIR])?;
         let zone_file_path = zone_file_path();
-        self.container.cp(&zone_file_path, &zone_file.to_string())?;
 
         let zone = zone_file.origin();
         // inherit SOA's TTL value
         let ttl = zone_file.soa.ttl;
 
-        let zsk_filename = "zsk".to_owned();
-        let ksk_filename = "ksk".to_owned();
-        self.container.cp(
-            &format!("{ZONES_DIR}/{zsk_filename}.key"),
-            &format!("{}\n", keys.zsk.public),
-        )?;
-        self.container.cp(
-            &format!("{ZONES_DIR}/{zsk_filename}.private"),
-            &format!("{}\n", keys.zsk.private),
-        )?;
-        self.container.cp(
-            &format!("{ZONES_DIR}/{ksk_filename}.key"),
-            &format!("{}\n", keys.ksk.public),
-        )?;
-        self.container.cp(
-            &format!("{ZONES_DIR}/{ksk_filename}.private"),
-            &format!("{}\n", keys.ksk.private),
-        )?;
-
-        let signzone_cmd = self.sign_zone_cmd(zone, [zsk_filename, ksk_filename].iter().cloned());
+        let mut key_filenames = vec![];
+        for (index, keys) in [keys].into_iter().chain(additional_keys).enumerate() {
+            let suffix = if index == 0 {
+                String::new()
+            } else {
+                index.to_string()
+            };
+
+            let extra_zsks = keys
+                .extra_zsks
+                .iter()
+                .enumerate()
+                .map(|(n, keypair)| (format!("zsk{suffix}-{}", n + 1), keypair));
+            let extra_ksks = keys
+                .extra_ksks
+                .iter()
+                .enumerate()
+                .map(|(n, keypair)| (format!("ksk{suffix}-{}", n + 1), keypair));
+            let keypairs = [
+                (format!("zsk{suffix}"), &keys.zsk),
+                (format!("ksk{suffix}"), &keys.ksk),
+            ]
+            .into_iter()
+            .chain(extra_zsks)
+            .chain(extra_ksks);
+            for (filename, keypair) in keypairs {
+                self.container.cp(
+                    &format!("{ZONES_DIR}/{filename}.key"),
+                    &format!("{}\n", keypair.public),
+                )?;
+                self.container.cp(
+                    &format!("{ZONES_DIR}/{filename}.private"),
+                    &format!("{}\n", keypair.private),
+                )?;
+                key_filenames.push(filename);
+            }
+        }
+
+        // the CDS and CDNSKEY RRsets need to be in the zone file before it's signed
+        if self.settings.publish_cds {
+            let mut zone_file = zone_file.clone();
+            let key2ds = format!(
+                "cd {ZONES_DIR} && ldns-key2ds -n {} ksk.key",
+                self.settings.ds_digest.key2ds_flag()
+            );
+            let ds: DS = self.container.stdout(&["sh", "-c", &key2ds])?.parse()?;
+            zone_file.add(CDS { ttl, ..ds.into() });
+            zone_file.add(CDNSKEY::from(keys.ksk.public.clone().with_ttl(ttl)));
+            self.container.cp(&zone_file_path, &zone_file.to_string())?;
+        } else {
+            self.container.cp(&zone_file_path, &zone_file.to_string())?;
+        }
+
+        let signzone_cmd = self.sign_zone_cmd(zone, key_filenames.into_iter());
         let signzone = format!("cd {ZONES_DIR} && {}", signzone_cmd);
         self.container.status_ok(&["sh", "-c", &signzone])?;
 
-        // TODO do we want to make the hashing algorithm configurable?
-        // -2 = use SHA256 for the DS hash
-        let key2ds = format!("cd {ZONES_DIR} && ldns-key2ds -f -n -2 {ZONE_FILENAME}.signed");
+        let key2ds = format!(
+            "cd {ZONES_DIR} && ldns-key2ds -f -n {} {ZONE_FILENAME}.signed",
+            self.settings.ds_digest.key2ds_flag()
+        );
         let dses = self
             .container
             .stdout(&["sh", "-c", &key2ds])?
@@ -288,24 +579,31 @@ impl<'a> Signer<'a> {
         })
     }
 
-    fn gen_zsk_key(&self, zone: &FQDN) -> crate::Result<Keypair> {
-        self.gen_key(&ldns_keygen_zsk(&self.settings, zone))
-    }
-
-    fn gen_ksk_key(&self, zone: &FQDN, zsk_keytag: u16) -> crate::Result<Keypair> {
-        // ldns-signzone will not accept a KSK that has either the same
-        // keytag as the ZSK, or a keytag one higher than the ZSK.
+    /// Generates a key whose keytag does not collide with any of `key_tags`, and adds its keytag
+    /// to them
+    fn gen_key_with_unique_tag(
+        &self,
+        command: &str,
+        key_tags: &mut Vec<u16>,
+    ) -> crate::Result<Keypair> {
+        // ldns-signzone will not accept two keys that have either the same
+        // keytag, or keytags one apart.
         // See https://github.com/hickory-dns/hickory-dns/issues/2555
         for _ in 0..100 {
-            let keypair = self.gen_key(&ldns_keygen_ksk(&self.settings, zone))?;
-            let ksk_keytag = keypair.public.rdata.calculate_key_tag();
-            if ksk_keytag != zsk_keytag && ksk_keytag != zsk_keytag.wrapping_add(1) {
+            let keypair = self.gen_key(command)?;
+            let key_tag = keypair.public.rdata.calculate_key_tag();
+            if key_tags.iter().all(|other| {
+                key_tag != *other
+                    && key_tag != other.wrapping_add(1)
+                    && *other != key_tag.wrapping_add(1)
+            }) {
+                key_tags.push(key_tag);
                 return Ok(keypair);
             }
         }
 
         Err(
-            format!("could not generate collision-free KSK for ZSK with keytag {zsk_keytag}")
+            format!("could not generate a key whose keytag does not collide with {key_tags:?}")
                 .into(),
         )
     }
@@ -341,13 +639,22 @@ impl<'a> Signer<'a> {
 
                 // NSEC3 related options
                 // -n = use NSEC3 instead of NSEC
-                if let Nsec::_3 { salt, opt_out } = &self.settings.nsec {
+                if let Nsec::_3 {
+                    salt,
+                    opt_out,
+                    iterations,
+                } = &self.settings.nsec
+                {
                     args.push("-n".to_string());
 
                     if *opt_out {
                         args.push("-p".to_string());
                     }
 
+                    if let Some(iterations) = iterations {
+                        args.push(format!("-t {iterations}"));
+                    }
+
                     if let Some(salt) = salt {
                         args.push(format!("-s {}", salt));
                     }
@@ -373,7 +680,12 @@ impl<'a> Signer<'a> {
 
                 // Set -3 for NSEC3, optionally followed by a salt.
                 // -A sets opt-out
-                if let Nsec::_3 { salt, opt_out } = &self.settings.nsec {
+                if let Nsec::_3 {
+                    salt,
+                    opt_out,
+                    iterations,
+                } = &self.settings.nsec
+                {
                     args.push("-3".to_string());
 
                     if let Some(salt) = salt {
@@ -387,6 +699,10 @@ impl<'a> Signer<'a> {
                     if *opt_out {
                         args.push("-A".to_string());
                     }
+
+                    if let Some(iterations) = iterations {
+                        args.push(format!("-H {iterations}"));
+                    }
                 }
 
                 // We must pass dnssec-signzone the origin of the zone, and specify
@@ -402,3 +718,115 @@ impl<'a> Signer<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::name_server::NameServer;
+    use crate::{FQDN, Implementation, Network};
+
+    use super::*;
+
+    #[test]
+    fn key_rollover_only_changes_signatures() -> crate::Result<()> {
+        let network = Network::new()?;
+        let mut ns = NameServer::new(&Implementation::test_peer(), FQDN::TEST_DOMAIN, &network)?;
+        ns.add(crate::record::Record::a(
+            FQDN::EXAMPLE_SUBDOMAIN,
+            std::net::Ipv4Addr::new(1, 2, 3, 4),
+        ));
+
+        let signer = Signer::new(ns.container(), SignSettings::default())?;
+        let old_keys = signer.generate_keys(&FQDN::TEST_DOMAIN)?;
+        let new_keys = signer.generate_keys(&FQDN::TEST_DOMAIN)?;
+
+        let old = signer.sign_zone(ns.zone_file(), &old_keys)?.signed;
+        let new = signer.sign_zone(ns.zone_file(), &new_keys)?.signed;
+
+        assert!(!old.diff(&new).is_empty());
+        if let Err(diff) = old.check_differs_only_in_signatures(&new) {
+            panic!("unsigned data changed: {diff:#?}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn import_key_signs_two_zones_with_the_same_ksk() -> crate::Result<()> {
+        let network = Network::new()?;
+        let leaf_zone = FQDN::TEST_TLD.push_label("other");
+        let ns = NameServer::new(&Implementation::test_peer(), FQDN::TEST_DOMAIN, &network)?;
+        let other_ns = NameServer::new(&Implementation::test_peer(), leaf_zone.clone(), &network)?;
+
+        let signer = Signer::new(ns.container(), SignSettings::default())?;
+        let keys = signer.generate_keys(&FQDN::TEST_DOMAIN)?;
+        let signed = signer.sign_zone(ns.zone_file(), &keys)?;
+
+        let other_signer = Signer::new(other_ns.container(), SignSettings::default())?;
+        let imported_keys =
+            other_signer.import_key(&leaf_zone, &keys.ksk.private, keys.ksk.public.clone())?;
+        let other_signed = other_signer.sign_zone(other_ns.zone_file(), &imported_keys)?;
+
+        assert_eq!(leaf_zone, other_signed.ksk.zone);
+        assert_eq!(signed.ksk.rdata, other_signed.ksk.rdata);
+        assert_ne!(signed.zsk.rdata, other_signed.zsk.rdata);
+        assert_eq!(signed.ds.ksk.key_tag, other_signed.ds.ksk.key_tag);
+
+        let zsk = keys.zsk.public;
+        assert!(
+            other_signer
+                .import_key(&leaf_zone, &keys.zsk.private, zsk)
+                .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn rsa_key_bits() -> crate::Result<()> {
+        let settings = SignSettings::rsasha256().rsa_key_bits(4_096);
+        assert_eq!(
+            "ldns-keygen -a RSASHA256 -b 4096 example.",
+            ldns_keygen_zsk(&settings, &FQDN("example.")?)
+        );
+        assert_eq!(
+            "ldns-keygen -k -a RSASHA256 -b 4096 example.",
+            ldns_keygen_ksk(&settings, &FQDN("example.")?)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn rsa_key_bits_requires_rsa_algorithm() {
+        assert!(
+            SignSettings::rsasha256()
+                .rsa_key_bits(4_096)
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            SignSettings::ed25519()
+                .rsa_key_bits(4_096)
+                .validate()
+                .is_err()
+        );
+        assert!(SignSettings::dsa().rsa_key_bits(1_024).validate().is_err());
+
+        // the order of the calls does not matter
+        assert!(
+            SignSettings::rsasha256()
+                .rsa_key_bits(4_096)
+                .algorithm(SignAlgorithm::ED25519)
+                .validate()
+                .is_err()
+        );
+        let settings = SignSettings::dsa()
+            .rsa_key_bits(4_096)
+            .algorithm(SignAlgorithm::RSASHA256);
+        assert!(settings.validate().is_ok());
+        assert_eq!(
+            "ldns-keygen -k -a RSASHA256 -b 4096 example.",
+            ldns_keygen_ksk(&settings, &FQDN("example.").unwrap())
+        );
+    }
+}